@@ -0,0 +1,54 @@
+#!/usr/bin/env python3
+"""Compare benchmark throughput against benches/baseline.json
+
+Run after `cargo bench --bench parser`. Fails if the throughput of any
+benchmark listed in the baseline dropped by more than 10%. With `--update`
+the baseline is rewritten from the latest results instead.
+"""
+import json
+import os
+import sys
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+BASELINE = os.path.join(ROOT, "benches", "baseline.json")
+CRITERION = os.path.join(ROOT, "target", "criterion")
+TOLERANCE = 0.10
+
+
+def throughput(name):
+    """Throughput of benchmark `name` (`group/id`) in MB/s"""
+    directory = os.path.join(CRITERION, *name.split("/"), "new")
+    with open(os.path.join(directory, "benchmark.json")) as f:
+        size = json.load(f)["throughput"]["Bytes"]
+    with open(os.path.join(directory, "estimates.json")) as f:
+        nanos = json.load(f)["mean"]["point_estimate"]
+    return size / nanos * 1e3
+
+
+def main():
+    with open(BASELINE) as f:
+        baseline = json.load(f)
+
+    results = {name: throughput(name) for name in baseline}
+
+    if "--update" in sys.argv[1:]:
+        with open(BASELINE, "w") as f:
+            json.dump({name: round(value, 1) for name, value in results.items()}, f, indent=2)
+            f.write("\n")
+        return 0
+
+    failed = False
+    for name, expected in baseline.items():
+        actual = results[name]
+        change = actual / expected - 1
+        regressed = change < -TOLERANCE
+        failed |= regressed
+
+        status = "REGRESSED" if regressed else "ok"
+        print("{:<24} {:>10.1f} MB/s {:>+7.1%}  {}".format(name, actual, change, status))
+
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,127 @@
+//! Parser throughput benchmarks
+//!
+//! Run with `cargo bench`, optionally with `--features simd` to compare the
+//! vectorized fast path.
+//!
+//! Every workload is fed to the parser in chunks of 1, 64 and 4096 bytes.
+//! `benches/compare.py` checks the results against `benches/baseline.json`.
+#[macro_use]
+extern crate criterion;
+extern crate vte;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+
+/// Input chunk sizes every workload is measured with
+const CHUNK_SIZES: [usize; 3] = [1, 64, 4096];
+
+/// Terminal output of a short `vim` session, recorded with `script`
+static VIM_CAPTURE: &[u8] = include_bytes!("data/vim.log");
+
+/// A `Perform` which does nothing with the parsed data
+struct Noop;
+
+impl vte::Perform for Noop {
+    fn print(&mut self, c: char) {
+        criterion::black_box(c);
+    }
+    fn execute(&mut self, _byte: u8) {}
+    fn hook(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    fn csi_dispatch(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+    fn esc_dispatch(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+}
+
+/// Build 1 MiB of mostly printable text with the occasional escape sequence
+fn mixed_text() -> Vec<u8> {
+    static LINE: &[u8] = b"\x1b[1;32mok\x1b[0m  The quick brown fox jumps over the lazy dog, \
+        then runs around the block a couple of times.\r\n";
+
+    LINE.iter().cloned().cycle().take(1024 * 1024).collect()
+}
+
+/// Build 1 MiB of printable ASCII without any control characters
+fn ascii_text() -> Vec<u8> {
+    (0..1024 * 1024).map(|i| b' ' + (i % 95) as u8).collect()
+}
+
+/// Build 100k short cursor movement sequences
+fn cursor_movement() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for i in 0..100_000 {
+        match i % 4 {
+            0 => bytes.extend_from_slice(format!("\x1b[{};{}H", i % 50 + 1, i % 120 + 1).as_bytes()),
+            1 => bytes.extend_from_slice(b"\x1b[A"),
+            2 => bytes.extend_from_slice(b"\x1b[3C"),
+            _ => bytes.extend_from_slice(b"\x1b[K"),
+        }
+    }
+    bytes
+}
+
+/// Build a stream of 16 sixel images of 256x120 pixels
+fn sixel_images() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for image in 0..16 {
+        bytes.extend_from_slice(b"\x1bP0;1;0q\"1;1;256;120");
+        for color in 0..4 {
+            bytes.extend_from_slice(format!("#{};2;{};{};{}", color, color * 25, 100 - image, 50).as_bytes());
+        }
+
+        for band in 0..20 {
+            for color in 0..4 {
+                bytes.extend_from_slice(format!("#{}", color).as_bytes());
+                for column in 0..64 {
+                    bytes.push(b'?' + ((band + color + column) % 63) as u8);
+                }
+                bytes.extend_from_slice(format!("!{}~", 192 - color).as_bytes());
+                bytes.push(if color == 3 { b'-' } else { b'$' });
+            }
+        }
+        bytes.extend_from_slice(b"\x1b\\");
+    }
+    bytes
+}
+
+fn bench_mixed_text(c: &mut Criterion) {
+    let input = mixed_text();
+
+    let mut group = c.benchmark_group("mixed_text");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("advance_slice", |b| {
+        b.iter(|| {
+            let mut parser = vte::Parser::new();
+            parser.advance_slice(&mut Noop, &input);
+        })
+    });
+    group.finish();
+}
+
+/// Measure parsing `input` once for every chunk size
+fn bench_chunked(c: &mut Criterion, name: &str, input: &[u8]) {
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(input.len() as u64));
+
+    for &chunk_size in &CHUNK_SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(chunk_size), &chunk_size, |b, &chunk_size| {
+            b.iter(|| {
+                let mut parser = vte::Parser::new();
+                for chunk in input.chunks(chunk_size) {
+                    parser.advance_slice(&mut Noop, chunk);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_workloads(c: &mut Criterion) {
+    bench_chunked(c, "ascii", &ascii_text());
+    bench_chunked(c, "cursor_movement", &cursor_movement());
+    bench_chunked(c, "vim", VIM_CAPTURE);
+    bench_chunked(c, "sixel", &sixel_images());
+}
+
+criterion_group!(benches, bench_mixed_text, bench_workloads);
+criterion_main!(benches);
@@ -1,2 +1 @@
 pub mod utf8;
-pub mod vt;
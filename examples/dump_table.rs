@@ -0,0 +1,8 @@
+//! Print the state machine of the parser as a Graphviz graph
+//!
+//! Render it with `cargo run --example dump_table | dot -Tsvg > table.svg`.
+extern crate vte;
+
+fn main() {
+    print!("{}", vte::Table::default().to_dot());
+}
@@ -0,0 +1,40 @@
+//! Parse input from stdin and write a line for every action on stdout
+//!
+//! Each line shows the action, the position of the byte which triggered it
+//! and the bytes it was parsed from:
+//!
+//! ```text
+//! printf 'a\e[1m' | cargo run --example hexdump_vte
+//! ```
+extern crate vte;
+
+use std::io::{self, Read};
+
+use vte::{HexdumpPerform, Parser};
+
+fn main() {
+    let input = io::stdin();
+    let mut handle = input.lock();
+    let output = io::stdout();
+
+    let mut parser = Parser::new();
+    let mut hexdump = HexdumpPerform::new(output.lock());
+
+    let mut buf = [0; 2048];
+
+    loop {
+        match handle.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => hexdump.advance(&mut parser, &buf[..n]),
+            Err(err) => {
+                eprintln!("err: {}", err);
+                break;
+            },
+        }
+
+        if let Some(err) = hexdump.io_error() {
+            eprintln!("err: {}", err);
+            break;
+        }
+    }
+}
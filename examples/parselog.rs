@@ -15,9 +15,9 @@ impl vte::Perform for Log {
         println!("[execute] {:02x}", byte);
     }
 
-    fn hook(&mut self, params: &[i64], intermediates: &[u8], ignore: bool) {
-        println!("[hook] params={:?}, intermediates={:?}, ignore={:?}",
-                 params, intermediates, ignore);
+    fn hook(&mut self, params: &vte::Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        println!("[hook] params={:?}, intermediates={:?}, ignore={:?}, char={:?}",
+                 params, intermediates, ignore, final_byte as char);
     }
 
     fn put(&mut self, byte: u8) {
@@ -28,16 +28,16 @@ impl vte::Perform for Log {
         println!("[unhook]");
     }
 
-    fn osc_dispatch(&mut self, params: &[&[u8]]) {
-        println!("[csi_dispatch] params={:?}", params);
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        println!("[osc_dispatch] params={:?} bell_terminated={}", params, bell_terminated);
     }
 
-    fn csi_dispatch(&mut self, params: &[i64], intermediates: &[u8], ignore: bool, c: char) {
-        println!("[csi_dispatch] params={:?}, intermediates={:?}, ignore={:?}, char={:?}",
-                 params, intermediates, ignore, c);
+    fn csi_dispatch(&mut self, params: &vte::Params, intermediates: &[u8], ignore: bool, c: char, private: Option<u8>) {
+        println!("[csi_dispatch] params={:?}, intermediates={:?}, ignore={:?}, char={:?}, private={:?}",
+                 params, intermediates, ignore, c, private.map(char::from));
     }
 
-    fn esc_dispatch(&mut self, params: &[i64], intermediates: &[u8], ignore: bool, byte: u8) {
+    fn esc_dispatch(&mut self, params: &vte::Params, intermediates: &[u8], ignore: bool, byte: u8) {
         println!("[esc_dispatch] params={:?}, intermediates={:?}, ignore={:?}, byte={:02x}",
                  params, intermediates, ignore, byte);
     }
@@ -51,16 +51,12 @@ fn main() {
     let mut statemachine = vte::Parser::new();
     let mut parser = Log;
 
-    let mut buf: [u8; 2048] = unsafe { std::mem::uninitialized() };
+    let mut buf = [0; 2048];
 
     loop {
         match handle.read(&mut buf) {
             Ok(0) => break,
-            Ok(n) => {
-                for byte in &buf[..n] {
-                    statemachine.advance(&mut parser, *byte);
-                }
-            },
+            Ok(n) => statemachine.advance_slice(&mut parser, &buf[..n]),
             Err(err) => {
                 println!("err: {}", err);
                 break;
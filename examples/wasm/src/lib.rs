@@ -0,0 +1,5 @@
+//! Exports `vte::wasm::WasmParser` for _index.html_
+extern crate vte;
+extern crate wasm_bindgen;
+
+pub use vte::wasm::WasmParser;
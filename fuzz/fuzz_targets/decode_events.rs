@@ -0,0 +1,20 @@
+//! Replays structured events into the layers built on the parser
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate vte;
+
+use vte::decoder::TerminalDecoder;
+use vte::{ScreenBuffer, VteEvent};
+
+fuzz_target!(|input: (u8, u8, Vec<VteEvent>)| {
+    let (rows, cols, events) = input;
+    let mut decoder = TerminalDecoder::new(Vec::new());
+    let mut screen = ScreenBuffer::new(rows.into(), cols.into());
+    for event in &events {
+        event.replay(&mut decoder);
+        event.replay(&mut screen);
+    }
+    let _ = screen.render_to_string();
+});
@@ -0,0 +1,30 @@
+//! Feeds the input to a parser one byte at a time with `Parser::advance`
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate vte;
+
+use vte::{Params, Parser, Perform};
+
+/// A `Perform` which ignores every action
+struct Noop;
+
+impl Perform for Noop {
+    fn print(&mut self, _c: char) {}
+    fn execute(&mut self, _byte: u8) {}
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+    fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut parser = Parser::new();
+    for &byte in data {
+        parser.advance(&mut Noop, byte);
+    }
+    parser.flush(&mut Noop);
+});
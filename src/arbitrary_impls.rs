@@ -0,0 +1,139 @@
+//! `Arbitrary` implementations for structure-aware fuzzing
+//!
+//! The generated values have the shape of the parser's: parameters fit into
+//! `Params`, intermediates and final bytes are in the ranges of their
+//! sequences and controls are C0 controls. They aren't all values the parser
+//! produces though, since strings, printed characters, errors and state
+//! changes are unrestricted and the `ignore` flags are arbitrary.
+use std::convert::TryFrom;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use {Params, State, VteEvent, MAX_INTERMEDIATES, MAX_PARAMS, MAX_SUBPARAMS};
+
+/// Up to 32 parameters of 1 to 8 values each
+impl<'a> Arbitrary<'a> for Params {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Params> {
+        let mut params = Params::new();
+        for _ in 0..u.int_in_range(0..=MAX_PARAMS)? {
+            params.push(u.arbitrary()?);
+            for _ in 1..u.int_in_range(1..=MAX_SUBPARAMS)? {
+                params.extend(u.arbitrary()?);
+            }
+        }
+        Ok(params)
+    }
+}
+
+impl<'a> Arbitrary<'a> for State {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<State> {
+        let discriminant = u.int_in_range(State::Anywhere as u8..=State::Utf8 as u8)?;
+        Ok(State::try_from(discriminant).expect("discriminant in range"))
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(1))
+    }
+}
+
+impl<'a> Arbitrary<'a> for VteEvent {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<VteEvent> {
+        let event = match u.int_in_range(0..=13)? {
+            0 => VteEvent::Print(u.arbitrary()?),
+            1 => VteEvent::Execute(u.int_in_range(0x00..=0x1f)?),
+            2 => VteEvent::DcsHook {
+                params: u.arbitrary()?,
+                intermediates: intermediates(u)?,
+                ignore: u.arbitrary()?,
+                final_byte: u.int_in_range(0x40..=0x7e)?,
+            },
+            3 => VteEvent::DcsPut(u.arbitrary()?),
+            4 => VteEvent::DcsUnhook,
+            5 => VteEvent::OscDispatch { params: u.arbitrary()?, bell_terminated: u.arbitrary()? },
+            6 => VteEvent::SosDispatch(u.arbitrary()?),
+            7 => VteEvent::PmDispatch(u.arbitrary()?),
+            8 => VteEvent::ApcDispatch(u.arbitrary()?),
+            9 => VteEvent::CsiDispatch {
+                params: u.arbitrary()?,
+                intermediates: intermediates(u)?,
+                ignore: u.arbitrary()?,
+                final_byte: char::from(u.int_in_range(0x40..=0x7e)?),
+                private: if u.arbitrary()? { Some(u.int_in_range(b'<'..=b'?')?) } else { None },
+            },
+            10 => VteEvent::EscDispatch {
+                params: Params::new(),
+                intermediates: intermediates(u)?,
+                ignore: u.arbitrary()?,
+                byte: u.int_in_range(0x30..=0x7e)?,
+            },
+            11 => VteEvent::StateChange { from: u.arbitrary()?, to: u.arbitrary()? },
+            _ => VteEvent::Error(u.arbitrary()?),
+        };
+        Ok(event)
+    }
+}
+
+/// Up to `MAX_INTERMEDIATES` intermediate bytes, as many as the parser collects
+fn intermediates(u: &mut Unstructured) -> Result<Vec<u8>> {
+    let len = u.int_in_range(0..=MAX_INTERMEDIATES)?;
+    (0..len).map(|_| u.int_in_range(0x20..=0x2f)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use {Params, State, VteEvent, MAX_INTERMEDIATES, MAX_PARAMS, MAX_SUBPARAMS};
+
+    /// Deterministic bytes which cover every choice eventually
+    fn data() -> Vec<u8> {
+        (0..16384u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect()
+    }
+
+    #[test]
+    fn params_fit() {
+        let data = data();
+        let mut u = Unstructured::new(&data);
+        let mut most = 0;
+        while !u.is_empty() {
+            let params = Params::arbitrary(&mut u).unwrap();
+            assert!(params.len() <= MAX_PARAMS);
+            assert!(params.iter().all(|param| !param.is_empty() && param.len() <= MAX_SUBPARAMS));
+            most = most.max(params.len());
+        }
+        assert!(most > 1);
+
+        // Running out of data ends the parameters
+        assert!(Params::arbitrary(&mut Unstructured::new(&[])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn events_are_valid() {
+        let data = data();
+        let mut u = Unstructured::new(&data);
+        let mut states = Vec::new();
+        let mut most_intermediates = 0;
+        while !u.is_empty() {
+            match VteEvent::arbitrary(&mut u).unwrap() {
+                VteEvent::Execute(byte) => assert!(byte < 0x20),
+                VteEvent::CsiDispatch { intermediates, final_byte, private, .. } => {
+                    assert!(intermediates.len() <= MAX_INTERMEDIATES);
+                    most_intermediates = most_intermediates.max(intermediates.len());
+                    assert!(intermediates.iter().all(|byte| (0x20..=0x2f).contains(byte)));
+                    assert!(('\x40'..='\x7e').contains(&final_byte));
+                    assert!(matches!(private, None | Some(b'<'..=b'?')));
+                },
+                VteEvent::EscDispatch { params, byte, .. } => {
+                    assert!(params.is_empty());
+                    assert!((0x30..=0x7e).contains(&byte));
+                },
+                VteEvent::StateChange { from, to } => states.extend([from, to]),
+                _ => (),
+            }
+        }
+        assert!(most_intermediates > 2);
+        assert!(states.contains(&State::Anywhere));
+        assert!(states.contains(&State::Utf8));
+    }
+}
@@ -0,0 +1,132 @@
+//! Recording terminal output in the asciicast v2 format
+use std::fmt::Write as FmtWrite;
+use std::io::{self, Write};
+use std::time::Instant;
+
+use {Params, Perform};
+
+/// A [`Perform`] which records printed text and controls as an asciicast v2
+/// recording, the format used by [asciinema]
+///
+/// The header line is written by [`new`], followed by one `"o"` (output)
+/// event line for every call to `print` and `execute`. Event times are the
+/// seconds since `start_time`. Sequences other than C0 and C1 controls are
+/// not recorded, so a replay only shows the text.
+///
+/// `Perform` methods can't fail, so the first error returned by the writer
+/// is kept and later events are dropped; check [`io_error`] before calling
+/// [`finish`].
+///
+/// ```
+/// # use std::time::Instant;
+/// # use vte::{Parser, TerminalRecorder};
+/// let mut recorder = TerminalRecorder::new(Vec::new(), Instant::now(), 80, 24).unwrap();
+/// Parser::new().advance_slice(&mut recorder, b"\x1b[1mhi\r\n");
+///
+/// assert!(recorder.io_error().is_none());
+/// let recording = String::from_utf8(recorder.finish()).unwrap();
+/// assert_eq!(recording.lines().count(), 5);
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [asciinema]: https://asciinema.org
+/// [`new`]: struct.TerminalRecorder.html#method.new
+/// [`io_error`]: struct.TerminalRecorder.html#method.io_error
+/// [`finish`]: struct.TerminalRecorder.html#method.finish
+pub struct TerminalRecorder<W: Write> {
+    writer: W,
+    start_time: Instant,
+    error: Option<io::Error>,
+
+    /// Reused for encoding event data
+    buffer: String,
+}
+
+impl<W: Write> TerminalRecorder<W> {
+    /// Write the header of a `width` by `height` terminal to `writer`
+    ///
+    /// Events are timed relative to `start_time`.
+    pub fn new(mut writer: W, start_time: Instant, width: u16, height: u16) -> io::Result<TerminalRecorder<W>> {
+        writeln!(writer, "{{\"version\": 2, \"width\": {}, \"height\": {}}}", width, height)?;
+
+        Ok(TerminalRecorder {
+            writer,
+            start_time,
+            error: None,
+            buffer: String::new(),
+        })
+    }
+
+    /// The first error returned by the writer, if any
+    #[inline]
+    #[must_use]
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Stop recording and return the writer
+    #[inline]
+    #[must_use]
+    pub fn finish(self) -> W {
+        self.writer
+    }
+
+    /// Write an output event with `data`
+    fn record(&mut self, data: char) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let time = self.start_time.elapsed().as_secs_f64();
+
+        self.buffer.clear();
+        escape_json(&mut self.buffer, data);
+
+        if let Err(error) = writeln!(self.writer, "[{:.6}, \"o\", \"{}\"]", time, self.buffer) {
+            self.error = Some(error);
+        }
+    }
+}
+
+/// Append `c` to `buffer` escaped for use in a JSON string
+fn escape_json(buffer: &mut String, c: char) {
+    match c {
+        '"' => buffer.push_str("\\\""),
+        '\\' => buffer.push_str("\\\\"),
+        '\n' => buffer.push_str("\\n"),
+        '\r' => buffer.push_str("\\r"),
+        '\t' => buffer.push_str("\\t"),
+        '\0'..='\x1f' | '\x7f' => {
+            let _ = write!(buffer, "\\u{:04x}", c as u32);
+        },
+        _ => buffer.push(c),
+    }
+}
+
+impl<W: Write> Perform for TerminalRecorder<W> {
+    fn print(&mut self, c: char) {
+        self.record(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        // C1 controls are the code points U+0080 to U+009F
+        self.record(byte as char);
+    }
+
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+
+    fn csi_dispatch(
+        &mut self,
+        _params: &Params,
+        _intermediates: &[u8],
+        _ignore: bool,
+        _action: char,
+        _private: Option<u8>,
+    ) {
+    }
+
+    fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+}
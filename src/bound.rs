@@ -0,0 +1,153 @@
+//! A parser which owns its `Perform`
+use core::mem;
+
+use {Parser, Perform};
+
+/// A [`Parser`] bundled with the [`Perform`] it dispatches to
+///
+/// This is convenient when both are stored together anyway, and lets the
+/// `Perform` be replaced between bytes with [`swap_perform`], for example to
+/// route output to another pane. The parser state is kept, so a sequence
+/// started before the swap is finished by the new `Perform`.
+///
+/// ```
+/// # use vte::{BoundParser, Params, Perform};
+/// #[derive(Default)]
+/// struct Text(String);
+///
+/// impl Perform for Text {
+///     fn print(&mut self, c: char) {
+///         self.0.push(c);
+///     }
+///     # fn execute(&mut self, _byte: u8) {}
+///     # fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+///     # fn put(&mut self, _byte: u8) {}
+///     # fn unhook(&mut self) {}
+///     # fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+///     # fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+///     # fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// }
+///
+/// let mut parser = BoundParser::new(Text::default());
+/// parser.advance_slice(b"left");
+/// let left = parser.swap_perform(Text::default());
+/// parser.advance_slice(b"right");
+///
+/// assert_eq!(left.0, "left");
+/// assert_eq!(parser.performer().0, "right");
+/// ```
+///
+/// [`Parser`]: struct.Parser.html
+/// [`Perform`]: trait.Perform.html
+/// [`swap_perform`]: struct.BoundParser.html#method.swap_perform
+#[derive(Clone, Default)]
+pub struct BoundParser<P: Perform> {
+    parser: Parser,
+    performer: P,
+}
+
+impl<P: Perform> BoundParser<P> {
+    /// Create a new parser dispatching to `performer`
+    #[inline]
+    #[must_use]
+    pub fn new(performer: P) -> BoundParser<P> {
+        BoundParser::with_parser(Parser::new(), performer)
+    }
+
+    /// Bundle an existing parser, for example one from [`ParserBuilder`],
+    /// with `performer`
+    ///
+    /// [`ParserBuilder`]: struct.ParserBuilder.html
+    #[inline]
+    #[must_use]
+    pub fn with_parser(parser: Parser, performer: P) -> BoundParser<P> {
+        BoundParser { parser, performer }
+    }
+
+    /// Advance the parser with `byte`
+    #[inline]
+    pub fn advance(&mut self, byte: u8) {
+        self.parser.advance(&mut self.performer, byte);
+    }
+
+    /// Advance the parser with every byte in `bytes`
+    #[inline]
+    pub fn advance_slice(&mut self, bytes: &[u8]) {
+        self.parser.advance_slice(&mut self.performer, bytes);
+    }
+
+    /// Replace the `Perform`, returning the previous one
+    ///
+    /// All actions of later bytes are dispatched to `performer`.
+    #[inline]
+    pub fn swap_perform(&mut self, performer: P) -> P {
+        mem::replace(&mut self.performer, performer)
+    }
+
+    /// The parser
+    #[inline]
+    #[must_use]
+    pub fn parser(&self) -> &Parser {
+        &self.parser
+    }
+
+    /// The parser
+    #[inline]
+    pub fn parser_mut(&mut self) -> &mut Parser {
+        &mut self.parser
+    }
+
+    /// The `Perform`
+    #[inline]
+    #[must_use]
+    pub fn performer(&self) -> &P {
+        &self.performer
+    }
+
+    /// The `Perform`
+    #[inline]
+    pub fn performer_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Split into the parser and the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_parts(self) -> (Parser, P) {
+        (self.parser, self.performer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundParser;
+    use test_utils::RecordingPerform;
+    use tests::describe;
+
+    #[test]
+    fn swap_perform() {
+        let mut parser = BoundParser::new(RecordingPerform::new());
+        parser.advance_slice(b"a\x1b[1");
+        let old = parser.swap_perform(RecordingPerform::new());
+        parser.advance_slice(b"mb");
+        parser.advance(b'c');
+
+        assert_eq!(describe(old.events()), vec!["print 'a'"]);
+        assert_eq!(describe(parser.performer().events()), vec![
+            "csi_dispatch [[1]] [] false 'm'",
+            "print 'b'",
+            "print 'c'",
+        ]);
+    }
+
+    #[test]
+    fn into_parts() {
+        let mut parser = BoundParser::new(RecordingPerform::new());
+        parser.advance_slice(b"\x1b]0;t");
+
+        let (mut parser, mut performer) = parser.into_parts();
+        assert!(describe(performer.events()).is_empty());
+        parser.advance(&mut performer, 0x07);
+        assert_eq!(describe(performer.events()), vec!["osc_dispatch [[48], [116]] true"]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Buffering strings of untrusted length
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cmp;
+use core::ops::Deref;
+
+#[cfg(not(feature = "alloc"))]
+use MAX_OSC_RAW;
+
+/// Default limit of a [`BoundedByteBuffer`], 4 MiB
+#[cfg(feature = "alloc")]
+pub(crate) const DEFAULT_LIMIT: usize = 4 * 1024 * 1024;
+
+/// Default limit of a [`BoundedByteBuffer`], the size of its array
+#[cfg(not(feature = "alloc"))]
+pub(crate) const DEFAULT_LIMIT: usize = MAX_OSC_RAW;
+
+/// Smallest allocation of a [`BoundedByteBuffer`]
+#[cfg(feature = "alloc")]
+const MIN_CAPACITY: usize = 64;
+
+/// Growable buffer which holds at most `limit` bytes
+///
+/// Bytes pushed beyond the limit are dropped and mark the buffer as
+/// truncated. The capacity grows by doubling like a `Vec`, but never past
+/// the limit, so a string which never ends can't exhaust memory.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BoundedByteBuffer {
+    data: Vec<u8>,
+    limit: usize,
+    truncated: bool,
+}
+
+/// Fixed buffer which holds at most `limit` bytes
+///
+/// Without `alloc` the bytes are kept in an array of [`DEFAULT_LIMIT`]
+/// bytes, which the limit can't exceed.
+#[cfg(not(feature = "alloc"))]
+#[derive(Clone)]
+pub(crate) struct BoundedByteBuffer {
+    data: [u8; MAX_OSC_RAW],
+    len: usize,
+    limit: usize,
+    truncated: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl BoundedByteBuffer {
+    #[inline]
+    pub fn new(limit: usize) -> BoundedByteBuffer {
+        BoundedByteBuffer { data: Vec::new(), limit, truncated: false }
+    }
+
+    /// Append `byte`, returning `false` if it was dropped
+    #[inline]
+    pub fn push(&mut self, byte: u8) -> bool {
+        let len = self.data.len();
+        if len >= self.limit {
+            self.truncated = true;
+            return false;
+        }
+
+        if len == self.data.capacity() {
+            let additional = cmp::max(len, MIN_CAPACITY);
+            self.data.reserve_exact(cmp::min(additional, self.limit - len));
+        }
+        self.data.push(byte);
+        true
+    }
+
+    /// Remove all bytes, keeping the allocation
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.truncated = false;
+    }
+
+    /// Replace the contents with `data`, truncated to the limit
+    pub fn replace(&mut self, mut data: Vec<u8>) {
+        self.truncated = data.len() > self.limit;
+        data.truncate(self.limit);
+        self.data = data;
+    }
+
+    #[inline]
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.data.len() >= self.limit
+    }
+
+    #[cfg(test)]
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl BoundedByteBuffer {
+    #[inline]
+    pub fn new(limit: usize) -> BoundedByteBuffer {
+        BoundedByteBuffer { data: [0; MAX_OSC_RAW], len: 0, limit: cmp::min(limit, MAX_OSC_RAW), truncated: false }
+    }
+
+    /// Append `byte`, returning `false` if it was dropped
+    #[inline]
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.is_full() {
+            self.truncated = true;
+            return false;
+        }
+
+        self.data[self.len] = byte;
+        self.len += 1;
+        true
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.truncated = false;
+    }
+
+    /// Set the limit, which is clamped to the size of the array
+    #[inline]
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = cmp::min(limit, MAX_OSC_RAW);
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len >= self.limit
+    }
+}
+
+impl BoundedByteBuffer {
+    /// Bytes were dropped since the buffer was last cleared
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Mark the buffer as truncated without pushing a byte
+    #[inline]
+    pub fn set_truncated(&mut self) {
+        self.truncated = true;
+    }
+}
+
+impl Deref for BoundedByteBuffer {
+    type Target = [u8];
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::{BoundedByteBuffer, MIN_CAPACITY};
+
+    #[test]
+    fn limit() {
+        let mut buffer = BoundedByteBuffer::new(3);
+        assert!(buffer.push(b'a'));
+        assert!(buffer.push(b'b'));
+        assert!(buffer.push(b'c'));
+        assert!(!buffer.is_truncated());
+        assert!(!buffer.push(b'd'));
+        assert!(buffer.is_truncated());
+        assert_eq!(&*buffer, b"abc");
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert!(!buffer.is_truncated());
+
+        buffer.replace(b"wxyz".to_vec());
+        assert_eq!(&*buffer, b"wxy");
+        assert!(buffer.is_truncated());
+    }
+
+    #[test]
+    fn capacity() {
+        let limit = 1000;
+        let mut buffer = BoundedByteBuffer::new(limit);
+        for _ in 0..limit * 10 {
+            buffer.push(0);
+        }
+        assert_eq!(buffer.len(), limit);
+        assert!(buffer.capacity() >= limit && buffer.capacity() < limit + MIN_CAPACITY);
+    }
+}
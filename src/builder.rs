@@ -0,0 +1,417 @@
+//! Configuration of a `Parser`
+use core::cmp;
+
+use buffer;
+use {Parser, ParserMode, MAX_INTERMEDIATES, MAX_PARAMS, MAX_SUBPARAMS};
+#[cfg(not(feature = "alloc"))]
+use MAX_OSC_RAW;
+
+/// Interpretation of bytes at or above `0x80` in the ground state
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Utf8Mode {
+    /// Text is decoded as UTF-8
+    #[default]
+    Utf8,
+
+    /// Every byte in `0xa0..=0xff` is printed as the ISO 8859-1 character
+    /// with the same value; UTF-8 is not decoded
+    Latin1,
+}
+
+/// Handling of malformed UTF-8
+///
+/// A sequence which is interrupted or can't encode a character is malformed.
+/// The byte which interrupted it is processed again afterwards, so a control
+/// character or escape sequence is never lost.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Utf8ErrorMode {
+    /// Print `U+FFFD` for the incomplete sequence and every byte which can't
+    /// start a sequence
+    #[default]
+    Replace,
+
+    /// Drop malformed bytes silently
+    Skip,
+}
+
+/// Handling of CSI and DCS sequences with more parameters than the parser
+/// keeps, see [`ParserBuilder::max_params`]
+///
+/// The sequence overflows at the byte which starts or ends the first
+/// parameter that doesn't fit.
+///
+/// [`ParserBuilder::max_params`]: struct.ParserBuilder.html#method.max_params
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ParamOverflowMode {
+    /// Drop the parameters which don't fit and dispatch the sequence with the
+    /// rest; `Perform::error` is called with the final byte before the
+    /// sequence is dispatched
+    #[default]
+    Truncate,
+
+    /// Ignore the rest of the sequence like a malformed one, calling
+    /// `Perform::error` with the byte it overflowed at; nothing is dispatched
+    IgnoreSequence,
+
+    /// Call `Perform::error` with the byte the sequence overflowed at, then
+    /// drop the parameters which don't fit and dispatch it with the rest
+    Callback,
+}
+
+/// Builder for a [`Parser`] with non-default configuration
+///
+/// Every limit is capped at the capacity the parser is compiled with, which
+/// is also the default. A parser built without calling any methods behaves
+/// exactly like [`Parser::new`].
+///
+/// ```
+/// # use vte::ParserBuilder;
+/// let parser = ParserBuilder::new()
+///     .max_params(4)
+///     .c1_controls(true)
+///     .build();
+/// assert!(parser.c1_controls());
+/// ```
+///
+/// [`Parser`]: struct.Parser.html
+/// [`Parser::new`]: struct.Parser.html#method.new
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParserBuilder {
+    max_params: usize,
+    max_subparams: usize,
+    max_intermediates: usize,
+    max_osc_len: usize,
+    #[cfg(feature = "alloc")]
+    max_dcs_len: usize,
+    c1_controls: bool,
+    utf8_mode: Utf8Mode,
+    utf8_error_mode: Utf8ErrorMode,
+    param_overflow_mode: ParamOverflowMode,
+    mode: ParserMode,
+}
+
+impl ParserBuilder {
+    /// Create a builder with the default configuration
+    #[must_use]
+    pub fn new() -> ParserBuilder {
+        ParserBuilder {
+            max_params: MAX_PARAMS,
+            max_subparams: MAX_SUBPARAMS,
+            max_intermediates: MAX_INTERMEDIATES,
+            max_osc_len: buffer::DEFAULT_LIMIT,
+            #[cfg(feature = "alloc")]
+            max_dcs_len: buffer::DEFAULT_LIMIT,
+            c1_controls: false,
+            utf8_mode: Utf8Mode::Utf8,
+            utf8_error_mode: Utf8ErrorMode::Replace,
+            param_overflow_mode: ParamOverflowMode::Truncate,
+            mode: ParserMode::AnsiVt,
+        }
+    }
+
+    /// Maximum number of CSI and DCS parameters, at most 32 (the default)
+    ///
+    /// Once the limit is reached any further parameters, including their
+    /// subparameters, are dropped. The sequence is still dispatched with the
+    /// parameters that fit, unless [`param_overflow_mode`] says otherwise.
+    ///
+    /// [`param_overflow_mode`]: struct.ParserBuilder.html#method.param_overflow_mode
+    #[must_use]
+    pub fn max_params(mut self, n: usize) -> ParserBuilder {
+        self.max_params = cmp::min(n, MAX_PARAMS);
+        self
+    }
+
+    /// Maximum number of values of a single parameter, counting the value
+    /// itself and its subparameters, between 1 and [`MAX_SUBPARAMS`] (the
+    /// default)
+    ///
+    /// Additional subparameters are dropped.
+    ///
+    /// [`MAX_SUBPARAMS`]: constant.MAX_SUBPARAMS.html
+    #[must_use]
+    pub fn max_subparams(mut self, n: usize) -> ParserBuilder {
+        self.max_subparams = n.clamp(1, MAX_SUBPARAMS);
+        self
+    }
+
+    /// Maximum number of intermediates, at most 8 (the default)
+    ///
+    /// Additional intermediates are dropped and the sequence is dispatched
+    /// with `ignore` set to `true`.
+    #[must_use]
+    pub fn max_intermediates(mut self, n: usize) -> ParserBuilder {
+        self.max_intermediates = cmp::min(n, MAX_INTERMEDIATES);
+        self
+    }
+
+    /// Maximum length in bytes of OSC, SOS, PM and APC strings, 4 MiB by
+    /// default
+    ///
+    /// Bytes beyond the limit are dropped and the buffer never grows past it.
+    /// The truncated string is still dispatched once it is terminated, and
+    /// [`Perform::error`] is called with the terminating byte right after.
+    /// Parameter separators of OSC strings don't count towards the limit.
+    ///
+    /// Without the `alloc` feature the string is collected in a fixed buffer
+    /// of 1024 bytes inside the parser. That is also the default then, and
+    /// larger limits are silently clamped to it.
+    ///
+    /// [`Perform::error`]: trait.Perform.html#method.error
+    #[must_use]
+    pub fn max_osc_len(mut self, bytes: usize) -> ParserBuilder {
+        #[cfg(not(feature = "alloc"))]
+        let bytes = cmp::min(bytes, MAX_OSC_RAW);
+        self.max_osc_len = bytes;
+        self
+    }
+
+    /// Maximum number of bytes buffered for a DCS string, 4 MiB by default
+    ///
+    /// Strings are only buffered for performers which return `true` from
+    /// [`Perform::wants_dcs_dispatch`]. Bytes beyond the limit are dropped
+    /// and the buffer never grows past it, so a string which is never
+    /// terminated can't exhaust memory. The truncated string is still passed
+    /// to `dcs_dispatch` once it is terminated, and [`Perform::error`] is
+    /// called with the terminating byte right after. Strings streamed to
+    /// `hook`, `put` and `unhook` or claimed by a [`DcsHandler`] aren't
+    /// buffered and have no limit.
+    ///
+    /// [`Perform::wants_dcs_dispatch`]: trait.Perform.html#method.wants_dcs_dispatch
+    /// [`Perform::error`]: trait.Perform.html#method.error
+    /// [`DcsHandler`]: trait.DcsHandler.html
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn max_dcs_len(mut self, bytes: usize) -> ParserBuilder {
+        self.max_dcs_len = bytes;
+        self
+    }
+
+    /// Enable 8-bit C1 controls, disabled by default
+    ///
+    /// See [`Parser::set_c1_controls`].
+    ///
+    /// [`Parser::set_c1_controls`]: struct.Parser.html#method.set_c1_controls
+    #[must_use]
+    pub fn c1_controls(mut self, enabled: bool) -> ParserBuilder {
+        self.c1_controls = enabled;
+        self
+    }
+
+    /// Set how bytes at or above `0x80` are printed, `Utf8Mode::Utf8` by
+    /// default
+    #[must_use]
+    pub fn utf8_mode(mut self, mode: Utf8Mode) -> ParserBuilder {
+        self.utf8_mode = mode;
+        self
+    }
+
+    /// Set how malformed UTF-8 is handled, `Utf8ErrorMode::Replace` by default
+    #[must_use]
+    pub fn utf8_error_mode(mut self, mode: Utf8ErrorMode) -> ParserBuilder {
+        self.utf8_error_mode = mode;
+        self
+    }
+
+    /// Set how sequences with more than [`max_params`] parameters are
+    /// handled, `ParamOverflowMode::Truncate` by default
+    ///
+    /// [`max_params`]: struct.ParserBuilder.html#method.max_params
+    #[must_use]
+    pub fn param_overflow_mode(mut self, mode: ParamOverflowMode) -> ParserBuilder {
+        self.param_overflow_mode = mode;
+        self
+    }
+
+    /// Set the escape sequence syntax, `ParserMode::AnsiVt` by default
+    #[must_use]
+    pub fn mode(mut self, mode: ParserMode) -> ParserBuilder {
+        self.mode = mode;
+        self
+    }
+
+    /// Create a parser with this configuration
+    #[must_use]
+    pub fn build(self) -> Parser {
+        let mut parser = Parser::new();
+        parser.max_params = self.max_params;
+        parser.max_subparams = self.max_subparams;
+        parser.max_intermediates = self.max_intermediates;
+        parser.osc_raw.set_limit(self.max_osc_len);
+        #[cfg(feature = "alloc")]
+        {
+            parser.dcs_data.set_limit(self.max_dcs_len);
+        }
+        parser.c1_controls = self.c1_controls;
+        parser.utf8_mode = self.utf8_mode;
+        parser.utf8_error_mode = self.utf8_error_mode;
+        parser.param_overflow_mode = self.param_overflow_mode;
+        parser.mode = self.mode;
+        parser
+    }
+}
+
+impl Default for ParserBuilder {
+    fn default() -> ParserBuilder {
+        ParserBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParamOverflowMode, ParserBuilder, Utf8ErrorMode, Utf8Mode};
+    use test_utils::RecordingPerform;
+    #[cfg(feature = "alloc")]
+    use tests::DcsRecorder;
+    use tests::describe;
+    use ParserMode;
+
+    fn calls(builder: ParserBuilder, bytes: &[u8]) -> Vec<String> {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = builder.build();
+        parser.advance_slice(&mut recorder, bytes);
+        describe(recorder.events())
+    }
+
+    #[test]
+    fn defaults_match_new() {
+        let input = b"a\x1b[1;2;3;4;5;6;7;8;9;10;11;12;13;14;15;16;17;18;19;20;21;22;23;24;25;26;27;28;29;30;31;32;33m\x1b[1 !#q\x1b]0;t\x07\x9b";
+        assert_eq!(calls(ParserBuilder::new(), input), calls(ParserBuilder::default(), input));
+
+        let mut recorder = RecordingPerform::new();
+        ::Parser::new().advance_slice(&mut recorder, input);
+        assert_eq!(calls(ParserBuilder::new(), input), describe(recorder.events()));
+    }
+
+    #[test]
+    fn max_params_zero() {
+        let builder = ParserBuilder::new().max_params(0);
+        assert_eq!(calls(builder, b"\x1b[1;2m"), vec!["error 6d", "csi_dispatch [] [] false 'm'"]);
+        assert_eq!(calls(builder, b"\x1b[38:5:1m"), vec!["error 6d", "csi_dispatch [] [] false 'm'"]);
+        assert_eq!(calls(builder, b"\x1b[m"), vec!["csi_dispatch [] [] false 'm'"]);
+    }
+
+    #[test]
+    fn max_params() {
+        let builder = ParserBuilder::new().max_params(2);
+        assert_eq!(calls(builder, b"\x1b[1;2;3:4m"), vec!["error 6d", "csi_dispatch [[1], [2]] [] false 'm'"]);
+        assert_eq!(calls(builder, b"\x1bP1;2;3q\x1b\\")[1], "hook [[1], [2]] [] false 'q'");
+    }
+
+    #[test]
+    fn param_overflow_mode() {
+        let truncate = ParserBuilder::new().max_params(2);
+        let ignore = truncate.param_overflow_mode(ParamOverflowMode::IgnoreSequence);
+        let callback = truncate.param_overflow_mode(ParamOverflowMode::Callback);
+
+        assert_eq!(calls(truncate, b"\x1b[1;2;3;4mx"), vec!["error 6d", "csi_dispatch [[1], [2]] [] false 'm'", "print 'x'"]);
+        assert_eq!(calls(ignore, b"\x1b[1;2;3;4mx"), vec!["error 33", "print 'x'"]);
+        assert_eq!(calls(callback, b"\x1b[1;2;3;4mx"), vec!["error 33", "csi_dispatch [[1], [2]] [] false 'm'", "print 'x'"]);
+
+        // Empty and trailing parameters
+        assert_eq!(calls(callback, b"\x1b[1;2;;m"), vec!["error 3b", "csi_dispatch [[1], [2]] [] false 'm'"]);
+        assert_eq!(calls(callback, b"\x1b[1;2;m"), vec!["csi_dispatch [[1], [2]] [] false 'm'"]);
+
+        // Sequences which fit are unaffected
+        assert_eq!(calls(ignore, b"\x1b[1;2:3m"), vec!["csi_dispatch [[1], [2, 3]] [] false 'm'"]);
+
+        // Device control strings are ignored up to the string terminator
+        assert_eq!(calls(ignore, b"\x1bP1;2;3qdata\x1b\\x"), vec!["error 33", "esc_dispatch [] [] false 5c", "print 'x'"]);
+        assert_eq!(calls(callback, b"\x1bP1;2;3q\x1b\\")[..2], ["error 33", "hook [[1], [2]] [] false 'q'"]);
+
+        // Overflowing in the entry state
+        let none = ParserBuilder::new().max_params(0).param_overflow_mode(ParamOverflowMode::IgnoreSequence);
+        assert_eq!(calls(none, b"\x1b[1mx"), vec!["error 31", "print 'x'"]);
+    }
+
+    #[test]
+    fn max_subparams() {
+        let builder = ParserBuilder::new().max_subparams(3);
+        assert_eq!(calls(builder, b"\x1b[38:2:1:2:3;1m"), vec!["csi_dispatch [[38, 2, 1], [1]] [] false 'm'"]);
+        assert_eq!(calls(builder.max_subparams(0), b"\x1b[4:3m"), vec!["csi_dispatch [[4]] [] false 'm'"]);
+    }
+
+    #[test]
+    fn max_intermediates() {
+        let builder = ParserBuilder::new().max_intermediates(1);
+        assert_eq!(calls(builder, b"\x1b[ q"), vec!["csi_dispatch [] [32] false 'q'"]);
+        assert_eq!(calls(builder, b"\x1b[ !q"), vec!["error 71", "csi_dispatch [] [32] true 'q'"]);
+    }
+
+    #[test]
+    fn max_osc_len() {
+        let builder = ParserBuilder::new().max_osc_len(3);
+        assert_eq!(calls(builder, b"\x1b]0;abcd\x07"), vec!["osc_dispatch [[48], [97, 98]] true", "error 07"]);
+        assert_eq!(calls(builder, b"\x1b_abcd\x1b\\")[..2], ["apc_dispatch [97, 98, 99]", "error 1b"]);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn max_osc_len_is_clamped() {
+        let mut apc = b"\x1b_".to_vec();
+        apc.resize(2048, b'a');
+        apc.extend_from_slice(b"\x1b\\");
+
+        let clamped = calls(ParserBuilder::new().max_osc_len(usize::MAX), &apc);
+        assert_eq!(clamped, calls(ParserBuilder::new(), &apc));
+        assert_eq!(clamped[1], "error 1b");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn max_dcs_len() {
+        let builder = ParserBuilder::new().max_dcs_len(1);
+        let mut recorder = DcsRecorder::default();
+        builder.build().advance_slice(&mut recorder, b"\x1bPqabc\x1b\\");
+        assert_eq!(recorder.calls, vec!["hook [] [] false 'q'", "put 61", "unhook", "final 'q'", "error 1b"]);
+
+        let mut recorder = DcsRecorder::default();
+        builder.build().advance_slice(&mut recorder, b"\x1bPqa\x1b\\");
+        assert_eq!(recorder.calls[0], "hook [] [] false 'q'");
+
+        // Streamed strings aren't buffered, so they aren't limited either
+        assert_eq!(calls(builder, b"\x1bPqabc\x1b\\")[..5], [
+            "hook [] [] false 'q'", "put 61", "put 62", "put 63", "unhook",
+        ]);
+    }
+
+    #[test]
+    fn c1_controls_and_mode() {
+        assert_eq!(calls(ParserBuilder::new().c1_controls(true), b"\x9b4m"), vec!["csi_dispatch [[4]] [] false 'm'"]);
+
+        let parser = ParserBuilder::new().mode(ParserMode::Vt52).build();
+        assert_eq!(parser.mode(), ParserMode::Vt52);
+    }
+
+    #[test]
+    fn latin1() {
+        let builder = ParserBuilder::new().utf8_mode(Utf8Mode::Latin1);
+        assert_eq!(calls(builder, b"\xe9\xa0\xff"), vec!["print 'é'", "print '\\u{a0}'", "print 'ÿ'"]);
+        assert_eq!(calls(builder, b"\x85"), vec!["execute 85"]);
+    }
+
+    #[test]
+    fn utf8_error_mode() {
+        let replace = ParserBuilder::new();
+        let skip = ParserBuilder::new().utf8_error_mode(Utf8ErrorMode::Skip);
+
+        // Truncated 2-byte sequence
+        assert_eq!(calls(replace, b"\xc3a"), vec!["print '\u{fffd}'", "print 'a'"]);
+        assert_eq!(calls(skip, b"\xc3a"), vec!["print 'a'"]);
+
+        // Overlong encodings
+        assert_eq!(calls(replace, b"\xc0\xaf"), vec!["print '\u{fffd}'"; 2]);
+        assert_eq!(calls(replace, b"\xe0\x80\xaf"), vec!["print '\u{fffd}'"; 3]);
+        assert_eq!(calls(replace, b"\xf0\x8f\xbf\xbf"), vec!["print '\u{fffd}'"; 4]);
+        assert!(calls(skip, b"\xc0\xaf\xe0\x80\xaf\xf0\x8f\xbf\xbf").is_empty());
+
+        // Interrupted by an escape sequence
+        let expected = vec!["print '\u{fffd}'", "csi_dispatch [[1]] [] false 'm'", "print '─'"];
+        assert_eq!(calls(replace, b"\xe2\x94\x1b[1m\xe2\x94\x80"), expected);
+        assert_eq!(calls(skip, b"\xe2\x94\x1b[1m\xe2\x94\x80"), expected[1..].to_vec());
+
+        // Interrupted by the start of another character, or a control
+        assert_eq!(calls(replace, b"\xe2\xc3\xa9"), vec!["print '\u{fffd}'", "print 'é'"]);
+        assert_eq!(calls(replace, b"\xf0\x9f\r"), vec!["print '\u{fffd}'", "execute 0d"]);
+    }
+}
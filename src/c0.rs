@@ -0,0 +1,147 @@
+//! The C0 control functions
+use core::convert::TryFrom;
+
+use InvalidVariant;
+
+/// A C0 control function, the bytes `0x00` to `0x1f`
+///
+/// Passed to [`Perform::execute_c0`]. The discriminant of every variant is
+/// its byte, so `c0 as u8` gets the byte back.
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use vte::{C0, InvalidVariant};
+/// assert_eq!(C0::try_from(0x1b), Ok(C0::Esc));
+/// assert_eq!(C0::Lf as u8, b'\n');
+/// assert_eq!(C0::try_from(0x7f), Err(InvalidVariant(0x7f)));
+/// ```
+///
+/// [`Perform::execute_c0`]: trait.Perform.html#method.execute_c0
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum C0 {
+    /// Null
+    Nul = 0x00,
+    /// Start of heading
+    Soh = 0x01,
+    /// Start of text
+    Stx = 0x02,
+    /// End of text
+    Etx = 0x03,
+    /// End of transmission
+    Eot = 0x04,
+    /// Enquiry, which some terminals answer with an answerback message
+    Enq = 0x05,
+    /// Acknowledge
+    Ack = 0x06,
+    /// Bell
+    Bel = 0x07,
+    /// Backspace
+    Bs = 0x08,
+    /// Horizontal tab
+    Ht = 0x09,
+    /// Line feed
+    Lf = 0x0a,
+    /// Vertical tab, usually treated like LF
+    Vt = 0x0b,
+    /// Form feed, usually treated like LF
+    Ff = 0x0c,
+    /// Carriage return
+    Cr = 0x0d,
+    /// Shift out, invokes the G1 character set
+    So = 0x0e,
+    /// Shift in, invokes the G0 character set
+    Si = 0x0f,
+    /// Data link escape
+    Dle = 0x10,
+    /// Device control 1, XON
+    Dc1 = 0x11,
+    /// Device control 2
+    Dc2 = 0x12,
+    /// Device control 3, XOFF
+    Dc3 = 0x13,
+    /// Device control 4
+    Dc4 = 0x14,
+    /// Negative acknowledge
+    Nak = 0x15,
+    /// Synchronous idle
+    Syn = 0x16,
+    /// End of transmission block
+    Etb = 0x17,
+    /// Cancel, aborts the sequence being parsed
+    Can = 0x18,
+    /// End of medium
+    Em = 0x19,
+    /// Substitute, aborts the sequence being parsed
+    Sub = 0x1a,
+    /// Escape, which starts an escape sequence and is never executed
+    Esc = 0x1b,
+    /// File separator
+    Fs = 0x1c,
+    /// Group separator
+    Gs = 0x1d,
+    /// Record separator
+    Rs = 0x1e,
+    /// Unit separator
+    Us = 0x1f,
+}
+
+/// All C0 controls, indexed by their byte
+const C0S: [C0; 32] = [
+    C0::Nul, C0::Soh, C0::Stx, C0::Etx, C0::Eot, C0::Enq, C0::Ack, C0::Bel,
+    C0::Bs, C0::Ht, C0::Lf, C0::Vt, C0::Ff, C0::Cr, C0::So, C0::Si,
+    C0::Dle, C0::Dc1, C0::Dc2, C0::Dc3, C0::Dc4, C0::Nak, C0::Syn, C0::Etb,
+    C0::Can, C0::Em, C0::Sub, C0::Esc, C0::Fs, C0::Gs, C0::Rs, C0::Us,
+];
+
+/// The C0 control `byte`, if it is one
+impl TryFrom<u8> for C0 {
+    type Error = InvalidVariant;
+
+    #[inline]
+    fn try_from(byte: u8) -> Result<C0, InvalidVariant> {
+        C0S.get(byte as usize).copied().ok_or(InvalidVariant(byte))
+    }
+}
+
+impl From<C0> for u8 {
+    #[inline]
+    fn from(c0: C0) -> u8 {
+        c0 as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+
+    use super::C0;
+    use InvalidVariant;
+
+    #[test]
+    fn every_byte() {
+        for byte in 0x00..=0x1fu8 {
+            let c0 = C0::try_from(byte).unwrap();
+            assert_eq!(c0 as u8, byte);
+            assert_eq!(u8::from(c0), byte);
+        }
+
+        let named = [
+            (0x00, C0::Nul), (0x05, C0::Enq), (0x07, C0::Bel), (0x08, C0::Bs),
+            (0x09, C0::Ht), (0x0a, C0::Lf), (0x0b, C0::Vt), (0x0c, C0::Ff),
+            (0x0d, C0::Cr), (0x0e, C0::So), (0x0f, C0::Si), (0x11, C0::Dc1),
+            (0x13, C0::Dc3), (0x18, C0::Can), (0x1a, C0::Sub), (0x1b, C0::Esc),
+            (0x1f, C0::Us),
+        ];
+        for &(byte, c0) in &named {
+            assert_eq!(C0::try_from(byte), Ok(c0));
+        }
+    }
+
+    #[test]
+    fn other_bytes() {
+        for byte in 0x20..=0xffu8 {
+            assert_eq!(C0::try_from(byte), Err(InvalidVariant(byte)));
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! Common control functions by name
+#[cfg(any(feature = "alloc", test))]
+use event::VteEvent;
+
+/// A control function with a fixed sequence, as named by terminal
+/// capability databases like terminfo
+///
+/// Only the forms without a count are included, like `cuu1` rather than
+/// `cuu`; [`identify_sequence`] recognizes them with an explicit count of
+/// one as well.
+///
+/// [`identify_sequence`]: fn.identify_sequence.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// `cuu1`, `CSI A`
+    CursorUp,
+
+    /// `cud1`, `CSI B`
+    CursorDown,
+
+    /// `cuf1`, `CSI C`
+    CursorForward,
+
+    /// `cub1`, `CSI D`
+    CursorBackward,
+
+    /// `home`, `CSI H`
+    CursorHome,
+
+    /// `sc`, `ESC 7`
+    SaveCursor,
+
+    /// `rc`, `ESC 8`
+    RestoreCursor,
+
+    /// `civis`, `CSI ? 25 l`
+    HideCursor,
+
+    /// `cnorm`, `CSI ? 25 h`
+    ShowCursor,
+
+    /// `ed`, `CSI J`
+    ClearToEndOfScreen,
+
+    /// `CSI 2 J`, which is `clear` without moving the cursor home
+    ClearScreen,
+
+    /// `el`, `CSI K`
+    ClearToEndOfLine,
+
+    /// `el1`, `CSI 1 K`
+    ClearToStartOfLine,
+
+    /// `CSI 2 K`
+    ClearLine,
+
+    /// `il1`, `CSI L`
+    InsertLine,
+
+    /// `dl1`, `CSI M`
+    DeleteLine,
+
+    /// `ich1`, `CSI @`
+    InsertCharacter,
+
+    /// `dch1`, `CSI P`
+    DeleteCharacter,
+
+    /// `indn` with a count of one, `CSI S`
+    ScrollUp,
+
+    /// `rin` with a count of one, `CSI T`
+    ScrollDown,
+
+    /// `ind`, `ESC D`
+    Index,
+
+    /// `ri`, `ESC M`
+    ReverseIndex,
+
+    /// `sgr0`, `CSI m`
+    ExitAttributeMode,
+
+    /// `bold`, `CSI 1 m`
+    EnterBoldMode,
+
+    /// `dim`, `CSI 2 m`
+    EnterDimMode,
+
+    /// `sitm`, `CSI 3 m`
+    EnterItalicsMode,
+
+    /// `smul`, `CSI 4 m`
+    EnterUnderlineMode,
+
+    /// `blink`, `CSI 5 m`
+    EnterBlinkMode,
+
+    /// `rev`, `CSI 7 m`
+    EnterReverseMode,
+
+    /// `invis`, `CSI 8 m`
+    EnterSecureMode,
+
+    /// `smcup`, `CSI ? 1049 h`
+    EnterCaMode,
+
+    /// `rmcup`, `CSI ? 1049 l`
+    ExitCaMode,
+
+    /// `bel`, BEL
+    Bell,
+
+    /// `rs1`, `ESC c`
+    FullReset,
+}
+
+impl Capability {
+    /// Every capability
+    pub const ALL: [Capability; 34] = [
+        Capability::CursorUp,
+        Capability::CursorDown,
+        Capability::CursorForward,
+        Capability::CursorBackward,
+        Capability::CursorHome,
+        Capability::SaveCursor,
+        Capability::RestoreCursor,
+        Capability::HideCursor,
+        Capability::ShowCursor,
+        Capability::ClearToEndOfScreen,
+        Capability::ClearScreen,
+        Capability::ClearToEndOfLine,
+        Capability::ClearToStartOfLine,
+        Capability::ClearLine,
+        Capability::InsertLine,
+        Capability::DeleteLine,
+        Capability::InsertCharacter,
+        Capability::DeleteCharacter,
+        Capability::ScrollUp,
+        Capability::ScrollDown,
+        Capability::Index,
+        Capability::ReverseIndex,
+        Capability::ExitAttributeMode,
+        Capability::EnterBoldMode,
+        Capability::EnterDimMode,
+        Capability::EnterItalicsMode,
+        Capability::EnterUnderlineMode,
+        Capability::EnterBlinkMode,
+        Capability::EnterReverseMode,
+        Capability::EnterSecureMode,
+        Capability::EnterCaMode,
+        Capability::ExitCaMode,
+        Capability::Bell,
+        Capability::FullReset,
+    ];
+
+    /// The canonical sequence, with 7-bit controls
+    #[must_use]
+    pub fn sequence(self) -> &'static [u8] {
+        match self {
+            Capability::CursorUp => b"\x1b[A",
+            Capability::CursorDown => b"\x1b[B",
+            Capability::CursorForward => b"\x1b[C",
+            Capability::CursorBackward => b"\x1b[D",
+            Capability::CursorHome => b"\x1b[H",
+            Capability::SaveCursor => b"\x1b7",
+            Capability::RestoreCursor => b"\x1b8",
+            Capability::HideCursor => b"\x1b[?25l",
+            Capability::ShowCursor => b"\x1b[?25h",
+            Capability::ClearToEndOfScreen => b"\x1b[J",
+            Capability::ClearScreen => b"\x1b[2J",
+            Capability::ClearToEndOfLine => b"\x1b[K",
+            Capability::ClearToStartOfLine => b"\x1b[1K",
+            Capability::ClearLine => b"\x1b[2K",
+            Capability::InsertLine => b"\x1b[L",
+            Capability::DeleteLine => b"\x1b[M",
+            Capability::InsertCharacter => b"\x1b[@",
+            Capability::DeleteCharacter => b"\x1b[P",
+            Capability::ScrollUp => b"\x1b[S",
+            Capability::ScrollDown => b"\x1b[T",
+            Capability::Index => b"\x1bD",
+            Capability::ReverseIndex => b"\x1bM",
+            Capability::ExitAttributeMode => b"\x1b[m",
+            Capability::EnterBoldMode => b"\x1b[1m",
+            Capability::EnterDimMode => b"\x1b[2m",
+            Capability::EnterItalicsMode => b"\x1b[3m",
+            Capability::EnterUnderlineMode => b"\x1b[4m",
+            Capability::EnterBlinkMode => b"\x1b[5m",
+            Capability::EnterReverseMode => b"\x1b[7m",
+            Capability::EnterSecureMode => b"\x1b[8m",
+            Capability::EnterCaMode => b"\x1b[?1049h",
+            Capability::ExitCaMode => b"\x1b[?1049l",
+            Capability::Bell => b"\x07",
+            Capability::FullReset => b"\x1bc",
+        }
+    }
+}
+
+/// The sequences of common capabilities by name
+///
+/// ```
+/// # use vte::Capabilities;
+/// let mut output = Vec::new();
+/// output.extend_from_slice(Capabilities::clear_screen());
+/// output.extend_from_slice(Capabilities::cursor_home());
+/// assert_eq!(output, b"\x1b[2J\x1b[H");
+/// ```
+///
+/// See [`Capability`] for the meaning of every sequence.
+///
+/// [`Capability`]: enum.Capability.html
+#[derive(Debug, Copy, Clone)]
+pub struct Capabilities;
+
+macro_rules! capabilities {
+    ($($name:ident => $capability:ident,)*) => {
+        impl Capabilities {
+            $(
+                #[doc = concat!("The sequence of [`Capability::", stringify!($capability), "`]")]
+                #[doc = ""]
+                #[doc = concat!("[`Capability::", stringify!($capability), "`]: enum.Capability.html#variant.", stringify!($capability))]
+                #[inline]
+                #[must_use]
+                pub fn $name() -> &'static [u8] {
+                    Capability::$capability.sequence()
+                }
+            )*
+        }
+    };
+}
+
+capabilities! {
+    cursor_up => CursorUp,
+    cursor_down => CursorDown,
+    cursor_forward => CursorForward,
+    cursor_backward => CursorBackward,
+    cursor_home => CursorHome,
+    save_cursor => SaveCursor,
+    restore_cursor => RestoreCursor,
+    hide_cursor => HideCursor,
+    show_cursor => ShowCursor,
+    clear_to_end_of_screen => ClearToEndOfScreen,
+    clear_screen => ClearScreen,
+    clear_to_end_of_line => ClearToEndOfLine,
+    clear_to_start_of_line => ClearToStartOfLine,
+    clear_line => ClearLine,
+    insert_line => InsertLine,
+    delete_line => DeleteLine,
+    insert_character => InsertCharacter,
+    delete_character => DeleteCharacter,
+    scroll_up => ScrollUp,
+    scroll_down => ScrollDown,
+    index => Index,
+    reverse_index => ReverseIndex,
+    exit_attribute_mode => ExitAttributeMode,
+    enter_bold_mode => EnterBoldMode,
+    enter_dim_mode => EnterDimMode,
+    enter_italics_mode => EnterItalicsMode,
+    enter_underline_mode => EnterUnderlineMode,
+    enter_blink_mode => EnterBlinkMode,
+    enter_reverse_mode => EnterReverseMode,
+    enter_secure_mode => EnterSecureMode,
+    enter_ca_mode => EnterCaMode,
+    exit_ca_mode => ExitCaMode,
+    bell => Bell,
+    full_reset => FullReset,
+}
+
+/// The capability `event` is the sequence of
+///
+/// Parameters with their default value are recognized as well, so
+/// `CSI 1 A` and `CSI 0 m` are [`CursorUp`] and [`ExitAttributeMode`], and
+/// `CSI 1 ; 1 H` is [`CursorHome`]. Other parameters, like the count of
+/// `CSI 5 A`, aren't part of any capability.
+///
+/// ```
+/// # use vte::{identify_sequence, Capability, Parser};
+/// let mut parser = Parser::new();
+/// let capabilities: Vec<_> = parser.parse(b"\x1b[A\x1b[5A\x1b[?25l")
+///     .filter_map(|event| identify_sequence(&event))
+///     .collect();
+/// assert_eq!(capabilities, [Capability::CursorUp, Capability::HideCursor]);
+/// ```
+///
+/// [`CursorUp`]: enum.Capability.html#variant.CursorUp
+/// [`ExitAttributeMode`]: enum.Capability.html#variant.ExitAttributeMode
+/// [`CursorHome`]: enum.Capability.html#variant.CursorHome
+#[cfg(any(feature = "alloc", test))]
+#[must_use]
+pub fn identify_sequence(event: &VteEvent) -> Option<Capability> {
+    match *event {
+        VteEvent::Execute(0x07) => Some(Capability::Bell),
+        VteEvent::EscDispatch { ref intermediates, ignore: false, byte, .. } if intermediates.is_empty() => {
+            match byte {
+                b'7' => Some(Capability::SaveCursor),
+                b'8' => Some(Capability::RestoreCursor),
+                b'D' => Some(Capability::Index),
+                b'M' => Some(Capability::ReverseIndex),
+                b'c' => Some(Capability::FullReset),
+                _ => None,
+            }
+        },
+        VteEvent::CsiDispatch { ref params, ref intermediates, ignore: false, final_byte, private }
+            if intermediates.is_empty() =>
+        {
+            // Subparameters aren't part of any capability
+            if params.iter().any(|param| param.len() > 1) {
+                return None;
+            }
+
+            // Both rows and columns default to one
+            if final_byte == 'H' && private.is_none() {
+                let home = params.len() <= 2 && params.iter().all(|param| param[0] <= 1);
+                return if home { Some(Capability::CursorHome) } else { None };
+            }
+
+            if params.len() > 1 {
+                return None;
+            }
+            let param = params.get_or_default(0, 0, 0);
+
+            let capability = match (private, final_byte, param) {
+                (None, 'A', 0..=1) => Capability::CursorUp,
+                (None, 'B', 0..=1) => Capability::CursorDown,
+                (None, 'C', 0..=1) => Capability::CursorForward,
+                (None, 'D', 0..=1) => Capability::CursorBackward,
+                (None, 'J', 0) => Capability::ClearToEndOfScreen,
+                (None, 'J', 2) => Capability::ClearScreen,
+                (None, 'K', 0) => Capability::ClearToEndOfLine,
+                (None, 'K', 1) => Capability::ClearToStartOfLine,
+                (None, 'K', 2) => Capability::ClearLine,
+                (None, 'L', 0..=1) => Capability::InsertLine,
+                (None, 'M', 0..=1) => Capability::DeleteLine,
+                (None, '@', 0..=1) => Capability::InsertCharacter,
+                (None, 'P', 0..=1) => Capability::DeleteCharacter,
+                (None, 'S', 0..=1) => Capability::ScrollUp,
+                (None, 'T', 0..=1) => Capability::ScrollDown,
+                (None, 'm', 0) => Capability::ExitAttributeMode,
+                (None, 'm', 1) => Capability::EnterBoldMode,
+                (None, 'm', 2) => Capability::EnterDimMode,
+                (None, 'm', 3) => Capability::EnterItalicsMode,
+                (None, 'm', 4) => Capability::EnterUnderlineMode,
+                (None, 'm', 5) => Capability::EnterBlinkMode,
+                (None, 'm', 7) => Capability::EnterReverseMode,
+                (None, 'm', 8) => Capability::EnterSecureMode,
+                (Some(b'?'), 'l', 25) => Capability::HideCursor,
+                (Some(b'?'), 'h', 25) => Capability::ShowCursor,
+                (Some(b'?'), 'h', 1049) => Capability::EnterCaMode,
+                (Some(b'?'), 'l', 1049) => Capability::ExitCaMode,
+                _ => return None,
+            };
+            Some(capability)
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{identify_sequence, Capabilities, Capability};
+    use test_utils::RecordingPerform;
+    use Parser;
+
+    fn identify(bytes: &[u8]) -> Vec<Capability> {
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_slice(&mut recorder, bytes);
+        recorder.events().iter().filter_map(identify_sequence).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        for &capability in &Capability::ALL {
+            assert_eq!(identify(capability.sequence()), [capability], "{:?}", capability);
+        }
+
+        assert_eq!(identify(Capabilities::cursor_up()), [Capability::CursorUp]);
+        assert_eq!(identify(Capabilities::exit_ca_mode()), [Capability::ExitCaMode]);
+    }
+
+    #[test]
+    fn unique_sequences() {
+        for (i, a) in Capability::ALL.iter().enumerate() {
+            for b in &Capability::ALL[i + 1..] {
+                assert_ne!(a.sequence(), b.sequence());
+            }
+        }
+    }
+
+    #[test]
+    fn default_parameters() {
+        assert_eq!(identify(b"\x1b[1A\x1b[0B\x1b[0m\x1b[0J"), [
+            Capability::CursorUp,
+            Capability::CursorDown,
+            Capability::ExitAttributeMode,
+            Capability::ClearToEndOfScreen,
+        ]);
+        assert_eq!(identify(b"\x1b[1;1H\x1b[;H\x1b[0;1H"), [Capability::CursorHome; 3]);
+    }
+
+    #[test]
+    fn other_sequences() {
+        assert!(identify(b"\x1b[5A\x1b[2;1H\x1b[1;4m\x1b[3J\x1b[4:3m\x1b[?25;1049h\x1b[>4m\x1b[ q\x1b(B\n").is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+//! Translating printed characters through the designated character sets
+use {Params, Perform, State, C0};
+
+/// A character set which can be designated as G0 to G3
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Charset {
+    /// US ASCII, designated by final byte `B`
+    #[default]
+    Ascii,
+
+    /// DEC Special Graphics, designated by final byte `0`
+    ///
+    /// Maps `0x60..=0x7e` to line-drawing and other symbols.
+    DecSpecialGraphics,
+
+    /// United Kingdom, designated by final byte `A`
+    ///
+    /// Like ASCII, but `#` is `£`.
+    Uk,
+}
+
+impl Charset {
+    /// The charset designated by `final_byte`, or `None` if it is unknown
+    #[must_use]
+    pub fn from_final_byte(final_byte: u8) -> Option<Charset> {
+        match final_byte {
+            b'B' => Some(Charset::Ascii),
+            b'0' => Some(Charset::DecSpecialGraphics),
+            b'A' => Some(Charset::Uk),
+            _ => None,
+        }
+    }
+
+    /// Translate a printed character through the charset
+    ///
+    /// ```
+    /// # use vte::Charset;
+    /// assert_eq!(Charset::DecSpecialGraphics.map('q'), '─');
+    /// assert_eq!(Charset::Uk.map('#'), '£');
+    /// assert_eq!(Charset::Ascii.map('q'), 'q');
+    /// ```
+    #[must_use]
+    pub fn map(self, c: char) -> char {
+        match (self, c) {
+            (Charset::DecSpecialGraphics, '\x60'..='\x7e') => DEC_SPECIAL_GRAPHICS[c as usize - 0x60],
+            (Charset::Uk, '#') => '£',
+            _ => c,
+        }
+    }
+}
+
+/// Characters of DEC Special Graphics for `0x60..=0x7e`
+const DEC_SPECIAL_GRAPHICS: [char; 31] = [
+    '◆', '▒', '␉', '␌', '␍', '␊', '°', '±', '␤', '␋', '┘', '┐', '┌', '└', '┼', '⎺',
+    '⎻', '─', '⎼', '⎽', '├', '┤', '┴', '┬', '│', '≤', '≥', 'π', '≠', '£', '·',
+];
+
+/// A [`Perform`] which tracks character set designations and translates
+/// printed characters before forwarding everything else to `P`
+///
+/// `ESC (`, `ESC )`, `ESC *` and `ESC +` designate G0 to G3. SI and SO
+/// invoke G0 and G1 into GL, and `ESC n` (LS2) and `ESC o` (LS3) invoke G2
+/// and G3. These sequences are not passed on; `P` receives the characters
+/// already translated through the invoked charset. Designations of unknown
+/// charsets select ASCII. A full reset, `ESC c`, restores the initial state
+/// and is passed on.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// # use vte::{CharsetDecoder, Parser, TextStripper};
+/// let mut decoder = CharsetDecoder::new(TextStripper::new());
+/// Parser::new().advance_slice(&mut decoder, b"\x1b(0lqqk\x1b(B ok");
+/// assert_eq!(decoder.inner().text(), "┌──┐ ok");
+/// # }
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CharsetDecoder<P: Perform> {
+    performer: P,
+    charsets: [Charset; 4],
+
+    /// Index of the charset invoked into GL
+    active: usize,
+}
+
+impl<P: Perform> CharsetDecoder<P> {
+    /// Wrap `performer`, starting with ASCII in G0 to G3
+    #[inline]
+    #[must_use]
+    pub fn new(performer: P) -> CharsetDecoder<P> {
+        CharsetDecoder { performer, charsets: [Charset::Ascii; 4], active: 0 }
+    }
+
+    /// The charset designated as G0 to G3, selected by `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than 3.
+    #[inline]
+    #[must_use]
+    pub fn charset(&self, index: usize) -> Charset {
+        self.charsets[index]
+    }
+
+    /// The charset invoked into GL, which printed characters go through
+    #[inline]
+    #[must_use]
+    pub fn active_charset(&self) -> Charset {
+        self.charsets[self.active]
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+}
+
+impl<P: Perform> Perform for CharsetDecoder<P> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        let c = self.active_charset().map(c);
+        self.performer.print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        let c = self.active_charset().map(c);
+        self.performer.print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            0x0e => self.active = 1,
+            0x0f => self.active = 0,
+            _ => self.performer.execute(byte),
+        }
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        match c0 {
+            C0::So => self.active = 1,
+            C0::Si => self.active = 0,
+            _ => self.performer.execute_c0(c0),
+        }
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.performer.carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.performer.linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        self.performer.backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        self.performer.tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.performer.bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.performer.hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.performer.put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.performer.unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.performer.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.performer.osc_dispatch(params, bell_terminated);
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.apc_dispatch(bytes);
+    }
+
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.performer.csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        match (intermediates, byte) {
+            // `(`, `)`, `*` and `+` designate G0 to G3
+            (&[designator @ b'('..=b'+'], _) => {
+                let charset = Charset::from_final_byte(byte).unwrap_or(Charset::Ascii);
+                self.charsets[(designator - b'(') as usize] = charset;
+            },
+            ([], b'n') => self.active = 2,
+            ([], b'o') => self.active = 3,
+            ([], b'c') => {
+                self.charsets = [Charset::Ascii; 4];
+                self.active = 0;
+                self.performer.esc_dispatch(params, intermediates, ignore, byte);
+            },
+            _ => self.performer.esc_dispatch(params, intermediates, ignore, byte),
+        }
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.performer.on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.performer.error(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Charset, CharsetDecoder};
+    use test_utils::{RecordedEvent, RecordingPerform};
+    use tests::describe;
+    use Parser;
+
+    fn decode(bytes: &[u8]) -> CharsetDecoder<RecordingPerform> {
+        let mut decoder = CharsetDecoder::new(RecordingPerform::default());
+        Parser::new().advance_slice(&mut decoder, bytes);
+        decoder
+    }
+
+    fn printed(decoder: &CharsetDecoder<RecordingPerform>) -> String {
+        decoder.inner().events().iter()
+            .filter_map(|event| match *event {
+                RecordedEvent::Print(c) => Some(c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn line_drawing() {
+        let decoder = decode(b"\x1b(0`abcdefghijklmnopqrstuvwxyz{|}~");
+        assert_eq!(printed(&decoder), "◆▒␉␌␍␊°±␤␋┘┐┌└┼⎺⎻─⎼⎽├┤┴┬│≤≥π≠£·");
+        assert_eq!(describe(decoder.inner().events()).len(), 31);
+
+        let decoder = decode(b"\x1b(0_AZ09 \x1b(Bq");
+        assert_eq!(printed(&decoder), "_AZ09 q");
+    }
+
+    #[test]
+    fn shifts() {
+        let decoder = decode(b"\x1b)0q\x0eq\x0fq\x1b*Aq#\x1bn#\x1b+0\x1boq");
+        assert_eq!(printed(&decoder), "q─qq#£─");
+        assert_eq!(decoder.charset(2), Charset::Uk);
+        assert_eq!(decoder.active_charset(), Charset::DecSpecialGraphics);
+        assert_eq!(describe(decoder.inner().events()).len(), 7);
+    }
+
+    #[test]
+    fn reset_and_other_sequences() {
+        let decoder = decode(b"\x1b(0\x1b(Zq\x1b)0\x0e\x1bcq\x1b7\x07");
+        assert_eq!(decoder.active_charset(), Charset::Ascii);
+        assert_eq!(decoder.charset(1), Charset::Ascii);
+        assert_eq!(describe(decoder.inner().events()), vec![
+            "print 'q'",
+            "esc_dispatch [] [] false 63",
+            "print 'q'",
+            "esc_dispatch [] [] false 37",
+            "execute 07",
+        ]);
+    }
+}
@@ -0,0 +1,576 @@
+//! Translating terminal input into crossterm events
+//!
+//! Terminals send keys, mouse events and some notifications to applications
+//! as escape sequences. [`CrosstermEventBridge`] decodes the [`VteEvent`]s
+//! the parser produces from this input into the [`Event`]s of crossterm,
+//! the same way crossterm decodes input in raw mode itself. Applications
+//! built on crossterm's event types can then read input through the parser,
+//! for example when it doesn't come from the controlling terminal.
+//!
+//! ```
+//! # extern crate crossterm;
+//! # extern crate vte;
+//! use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+//! use vte::compat::crossterm::CrosstermEventBridge;
+//! use vte::Parser;
+//!
+//! let mut parser = Parser::new();
+//! let mut bridge = CrosstermEventBridge::new();
+//! let events: Vec<Event> = parser.parse(b"a\x1b[1;5A\x1b[I")
+//!     .filter_map(|event| bridge.push(&event))
+//!     .collect();
+//!
+//! assert_eq!(events, [
+//!     Event::Key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE)),
+//!     Event::Key(KeyEvent::new(KeyCode::Up, KeyModifiers::CONTROL)),
+//!     Event::FocusGained,
+//! ]);
+//! ```
+//!
+//! A lone Escape key is never reported, since the parser waits for the rest
+//! of the sequence it might start.
+//!
+//! [`CrosstermEventBridge`]: struct.CrosstermEventBridge.html
+//! [`VteEvent`]: ../../enum.VteEvent.html
+//! [`Event`]: https://docs.rs/crossterm/0.28/crossterm/event/enum.Event.html
+use core::mem;
+use std::string::String;
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton as CrosstermButton,
+    MouseEvent as CrosstermMouseEvent, MouseEventKind as CrosstermMouseKind,
+};
+
+use mouse::{parse_mouse_csi, parse_mouse_dec, MouseButton, MouseEvent, MouseEventKind, MouseModifiers};
+use {Params, VteEvent};
+
+/// Keys sent as `CSI 1 ; modifiers final`, or `CSI final` without modifiers
+///
+/// `CSI 1 ; modifiers R` isn't F3, since it can't be told apart from a
+/// cursor position report.
+const CSI_KEYS: &[(char, KeyCode)] = &[
+    ('A', KeyCode::Up),
+    ('B', KeyCode::Down),
+    ('C', KeyCode::Right),
+    ('D', KeyCode::Left),
+    ('H', KeyCode::Home),
+    ('F', KeyCode::End),
+    ('P', KeyCode::F(1)),
+    ('Q', KeyCode::F(2)),
+    ('S', KeyCode::F(4)),
+];
+
+/// Keys sent as `SS3 final`, which is `ESC O final` with 7-bit controls
+const SS3_KEYS: &[(char, KeyCode)] = &[
+    ('A', KeyCode::Up),
+    ('B', KeyCode::Down),
+    ('C', KeyCode::Right),
+    ('D', KeyCode::Left),
+    ('H', KeyCode::Home),
+    ('F', KeyCode::End),
+    ('P', KeyCode::F(1)),
+    ('Q', KeyCode::F(2)),
+    ('R', KeyCode::F(3)),
+    ('S', KeyCode::F(4)),
+];
+
+/// Keys sent as `CSI number ; modifiers ~`
+const TILDE_KEYS: &[(u16, KeyCode)] = &[
+    (1, KeyCode::Home),
+    (2, KeyCode::Insert),
+    (3, KeyCode::Delete),
+    (4, KeyCode::End),
+    (5, KeyCode::PageUp),
+    (6, KeyCode::PageDown),
+    (7, KeyCode::Home),
+    (8, KeyCode::End),
+    (11, KeyCode::F(1)),
+    (12, KeyCode::F(2)),
+    (13, KeyCode::F(3)),
+    (14, KeyCode::F(4)),
+    (15, KeyCode::F(5)),
+    (17, KeyCode::F(6)),
+    (18, KeyCode::F(7)),
+    (19, KeyCode::F(8)),
+    (20, KeyCode::F(9)),
+    (21, KeyCode::F(10)),
+    (23, KeyCode::F(11)),
+    (24, KeyCode::F(12)),
+    (25, KeyCode::F(13)),
+    (26, KeyCode::F(14)),
+    (28, KeyCode::F(15)),
+    (29, KeyCode::F(16)),
+    (31, KeyCode::F(17)),
+    (32, KeyCode::F(18)),
+    (33, KeyCode::F(19)),
+    (34, KeyCode::F(20)),
+];
+
+/// Modifier bits of xterm's modifier parameter, which is the sum plus one
+const MODIFIERS: &[(u16, KeyModifiers)] = &[
+    (1, KeyModifiers::SHIFT),
+    (2, KeyModifiers::ALT),
+    (4, KeyModifiers::CONTROL),
+    (8, KeyModifiers::SUPER),
+    (16, KeyModifiers::HYPER),
+    (32, KeyModifiers::META),
+];
+
+/// Start and end of a bracketed paste, `CSI 200 ~` and `CSI 201 ~`
+const PASTE_START: u16 = 200;
+const PASTE_END: u16 = 201;
+
+/// Decoder of input [`VteEvent`]s into crossterm [`Event`]s
+///
+/// Some input is split over several `VteEvent`s, so they have to be pushed
+/// in the order the parser produced them. The following input is decoded:
+///
+/// * Characters, controls as `Ctrl` with a letter, and characters after
+///   `ESC` as `Alt` with the character
+/// * Cursor, editing and function keys, with xterm's modifier parameter,
+///   in their `CSI` and `SS3` forms, and keys in the `CSI u` form of the
+///   kitty keyboard protocol
+/// * Mouse events in the X10, SGR and URXVT encodings
+/// * Focus reports, `CSI I` and `CSI O`, and bracketed pastes
+/// * The window size reported by `CSI 8 ; rows ; columns t` in reply to
+///   `CSI 18 t`, and the in-band resize notifications of mode 2048, which
+///   terminals send instead of `SIGWINCH` when the application isn't local
+///
+/// Everything else, including replies which crossterm doesn't report as
+/// events, like cursor positions, is dropped.
+///
+/// [`VteEvent`]: ../../enum.VteEvent.html
+/// [`Event`]: https://docs.rs/crossterm/0.28/crossterm/event/enum.Event.html
+#[derive(Debug, Default, Clone)]
+pub struct CrosstermEventBridge {
+    /// `ESC O` was received, so the next character selects a key
+    ss3: bool,
+
+    /// A string was dispatched, so an `ESC \` is its terminator
+    string_ended: bool,
+
+    /// Characters received after `CSI M`, which starts an X10 mouse event
+    x10_mouse: Option<([char; 3], usize)>,
+
+    /// Text of the bracketed paste which is being received
+    paste: Option<String>,
+}
+
+impl CrosstermEventBridge {
+    /// Create a bridge expecting the start of an event
+    #[must_use]
+    pub fn new() -> CrosstermEventBridge {
+        CrosstermEventBridge::default()
+    }
+
+    /// Decode the next event
+    ///
+    /// Returns `None` if `event` doesn't complete a crossterm event.
+    pub fn push(&mut self, event: &VteEvent) -> Option<Event> {
+        if let VteEvent::StateChange { .. } = *event {
+            return None;
+        }
+
+        if self.paste.is_some() {
+            return self.paste(event);
+        }
+
+        let ss3 = mem::replace(&mut self.ss3, false);
+        let string_ended = mem::replace(&mut self.string_ended, false);
+        if let Some((mut chars, len)) = self.x10_mouse.take() {
+            if let VteEvent::Print(c) = *event {
+                chars[len] = c;
+                if len < 2 {
+                    self.x10_mouse = Some((chars, len + 1));
+                    return None;
+                }
+                return parse_mouse_dec(chars).and_then(mouse_event);
+            }
+        }
+
+        match *event {
+            VteEvent::Print(c) if ss3 => lookup(SS3_KEYS, c).map(|code| key(code, KeyModifiers::NONE)),
+            VteEvent::Print('\x7f') => Some(key(KeyCode::Backspace, KeyModifiers::NONE)),
+            VteEvent::Print(c) => Some(char_key(c, KeyModifiers::NONE)),
+            VteEvent::Execute(byte) => control(byte),
+            VteEvent::OscDispatch { .. }
+            | VteEvent::DcsUnhook
+            | VteEvent::SosDispatch(_)
+            | VteEvent::PmDispatch(_)
+            | VteEvent::ApcDispatch(_) => {
+                self.string_ended = true;
+                None
+            },
+            VteEvent::EscDispatch { byte: b'\\', .. } if string_ended => None,
+            VteEvent::EscDispatch { ref intermediates, ignore: false, byte, .. } if intermediates.is_empty() => {
+                if byte == b'O' {
+                    self.ss3 = true;
+                    return None;
+                }
+                Some(char_key(byte as char, KeyModifiers::ALT))
+            },
+            VteEvent::CsiDispatch { ref params, ref intermediates, ignore: false, final_byte, private }
+                if intermediates.is_empty() =>
+            {
+                self.csi(params, private, final_byte)
+            },
+            _ => None,
+        }
+    }
+
+    /// Collect the text of a bracketed paste until it ends
+    fn paste(&mut self, event: &VteEvent) -> Option<Event> {
+        let c = match *event {
+            VteEvent::Print(c) => c,
+            VteEvent::Execute(byte) => byte as char,
+            VteEvent::CsiDispatch { ref params, final_byte: '~', private: None, .. }
+                if params.get_or_default(0, 0, 0) == PASTE_END =>
+            {
+                return self.paste.take().map(Event::Paste);
+            },
+            _ => return None,
+        };
+
+        if let Some(ref mut paste) = self.paste {
+            paste.push(c);
+        }
+        None
+    }
+
+    fn csi(&mut self, params: &Params, private: Option<u8>, final_byte: char) -> Option<Event> {
+        if let Some(event) = parse_mouse_csi(params, private, final_byte as u8) {
+            return mouse_event(event);
+        }
+        if private.is_some() {
+            return None;
+        }
+
+        match final_byte {
+            'M' if params.is_empty() => {
+                self.x10_mouse = Some((['\0'; 3], 0));
+                None
+            },
+            'I' if params.is_empty() => Some(Event::FocusGained),
+            'O' if params.is_empty() => Some(Event::FocusLost),
+            'Z' if params.is_empty() => Some(key(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            '~' => match params.get_or_default(0, 0, 0) {
+                PASTE_START => {
+                    self.paste = Some(String::new());
+                    None
+                },
+                number => modified_key(lookup(TILDE_KEYS, number)?, params),
+            },
+            'u' => {
+                let code = match params.get_or_default(0, 0, 0) {
+                    9 => KeyCode::Tab,
+                    13 => KeyCode::Enter,
+                    27 => KeyCode::Esc,
+                    127 => KeyCode::Backspace,
+                    code => KeyCode::Char(char::from_u32(u32::from(code))?),
+                };
+                modified_key(code, params)
+            },
+            't' => resize(params),
+            c => {
+                // Only `1` or nothing may precede the modifiers
+                if params.get_or_default(0, 0, 1) != 1 {
+                    return None;
+                }
+                modified_key(lookup(CSI_KEYS, c)?, params)
+            },
+        }
+    }
+}
+
+fn lookup<T: PartialEq>(table: &[(T, KeyCode)], value: T) -> Option<KeyCode> {
+    table.iter().find(|entry| entry.0 == value).map(|entry| entry.1)
+}
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+/// A character key, with `Shift` for uppercase characters
+fn char_key(c: char, mut modifiers: KeyModifiers) -> Event {
+    if c.is_uppercase() {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    key(KeyCode::Char(c), modifiers)
+}
+
+/// A key with the modifiers and event type of the second parameter
+fn modified_key(code: KeyCode, params: &Params) -> Option<Event> {
+    let bits = params.get_or_default(1, 0, 1).saturating_sub(1);
+    let modifiers = MODIFIERS.iter()
+        .filter(|&&(bit, _)| bits & bit != 0)
+        .fold(KeyModifiers::NONE, |modifiers, &(_, modifier)| modifiers | modifier);
+
+    let kind = match params.get_or_default(1, 1, 1) {
+        1 => KeyEventKind::Press,
+        2 => KeyEventKind::Repeat,
+        3 => KeyEventKind::Release,
+        _ => return None,
+    };
+
+    Some(Event::Key(KeyEvent::new_with_kind(code, modifiers, kind)))
+}
+
+/// Keys sent as C0 controls
+fn control(byte: u8) -> Option<Event> {
+    let (code, modifiers) = match byte {
+        b'\r' => (KeyCode::Enter, KeyModifiers::NONE),
+        b'\t' => (KeyCode::Tab, KeyModifiers::NONE),
+        0x00 => (KeyCode::Char(' '), KeyModifiers::CONTROL),
+        0x01..=0x1a => (KeyCode::Char((byte - 0x01 + b'a') as char), KeyModifiers::CONTROL),
+        0x1c..=0x1f => (KeyCode::Char((byte - 0x1c + b'4') as char), KeyModifiers::CONTROL),
+        _ => return None,
+    };
+    Some(key(code, modifiers))
+}
+
+/// The window size, `CSI 8 ; rows ; columns t` or
+/// `CSI 48 ; rows ; columns ; height ; width t`
+fn resize(params: &Params) -> Option<Event> {
+    match params.get_or_default(0, 0, 0) {
+        8 | 48 => (),
+        _ => return None,
+    }
+
+    let rows = *params.get(1)?.first()?;
+    let columns = *params.get(2)?.first()?;
+    Some(Event::Resize(columns, rows))
+}
+
+fn mouse_event(event: MouseEvent) -> Option<Event> {
+    let button = match event.button {
+        MouseButton::Left => Some(CrosstermButton::Left),
+        MouseButton::Middle => Some(CrosstermButton::Middle),
+        MouseButton::Right => Some(CrosstermButton::Right),
+        _ => None,
+    };
+
+    let kind = match (event.kind, event.button) {
+        (MouseEventKind::Press, MouseButton::WheelUp) => CrosstermMouseKind::ScrollUp,
+        (MouseEventKind::Press, MouseButton::WheelDown) => CrosstermMouseKind::ScrollDown,
+        (MouseEventKind::Press, MouseButton::WheelLeft) => CrosstermMouseKind::ScrollLeft,
+        (MouseEventKind::Press, MouseButton::WheelRight) => CrosstermMouseKind::ScrollRight,
+        (MouseEventKind::Press, _) => CrosstermMouseKind::Down(button?),
+
+        // Crossterm reports releases of unknown buttons as the left button
+        (MouseEventKind::Release, MouseButton::None) => CrosstermMouseKind::Up(CrosstermButton::Left),
+        (MouseEventKind::Release, _) => CrosstermMouseKind::Up(button?),
+        (MouseEventKind::Motion, MouseButton::None) => CrosstermMouseKind::Moved,
+        (MouseEventKind::Motion, _) => CrosstermMouseKind::Drag(button?),
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    if event.modifiers.contains(MouseModifiers::SHIFT) {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if event.modifiers.contains(MouseModifiers::ALT) {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if event.modifiers.contains(MouseModifiers::CTRL) {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+
+    Some(Event::Mouse(CrosstermMouseEvent { kind, column: event.column, row: event.row, modifiers }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+    use std::vec::Vec;
+
+    use crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+
+    use super::{CrosstermEventBridge, CSI_KEYS, SS3_KEYS, TILDE_KEYS};
+    use Parser;
+
+    fn events(bytes: &[u8]) -> Vec<Event> {
+        let mut bridge = CrosstermEventBridge::new();
+        Parser::new().parse(bytes).filter_map(|event| bridge.push(&event)).collect()
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Vec<Event> {
+        vec![Event::Key(KeyEvent::new(code, modifiers))]
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Vec<Event> {
+        vec![Event::Mouse(MouseEvent { kind, column, row, modifiers: KeyModifiers::NONE })]
+    }
+
+    /// Every combination of `Shift`, `Alt` and `Ctrl` with its parameter
+    fn modifier_params() -> Vec<(u16, KeyModifiers)> {
+        (0..8).map(|bits| {
+            let mut modifiers = KeyModifiers::NONE;
+            if bits & 1 != 0 {
+                modifiers |= KeyModifiers::SHIFT;
+            }
+            if bits & 2 != 0 {
+                modifiers |= KeyModifiers::ALT;
+            }
+            if bits & 4 != 0 {
+                modifiers |= KeyModifiers::CONTROL;
+            }
+            (bits + 1, modifiers)
+        }).collect()
+    }
+
+    #[test]
+    fn csi_keys() {
+        for &(final_byte, code) in CSI_KEYS {
+            assert_eq!(events(format!("\x1b[{}", final_byte).as_bytes()), key(code, KeyModifiers::NONE));
+            for (param, modifiers) in modifier_params() {
+                let input = format!("\x1b[1;{}{}", param, final_byte);
+                assert_eq!(events(input.as_bytes()), key(code, modifiers), "{:?}", input);
+            }
+        }
+
+        // Other parameters are a different sequence
+        assert_eq!(events(b"\x1b[2A"), []);
+
+        // A cursor position report
+        assert_eq!(events(b"\x1b[1;5R"), []);
+    }
+
+    #[test]
+    fn ss3_keys() {
+        for &(final_byte, code) in SS3_KEYS {
+            let input = format!("\x1bO{}", final_byte);
+            assert_eq!(events(input.as_bytes()), key(code, KeyModifiers::NONE), "{:?}", input);
+        }
+
+        assert_eq!(events(b"\x1bOx"), []);
+        assert_eq!(events(b"\x1bOAa"), [key(KeyCode::Up, KeyModifiers::NONE), key(KeyCode::Char('a'), KeyModifiers::NONE)].concat());
+    }
+
+    #[test]
+    fn tilde_keys() {
+        for &(number, code) in TILDE_KEYS {
+            assert_eq!(events(format!("\x1b[{}~", number).as_bytes()), key(code, KeyModifiers::NONE));
+            for (param, modifiers) in modifier_params() {
+                let input = format!("\x1b[{};{}~", number, param);
+                assert_eq!(events(input.as_bytes()), key(code, modifiers), "{:?}", input);
+            }
+        }
+
+        for number in (0..100).filter(|number| TILDE_KEYS.iter().all(|entry| entry.0 != *number)) {
+            assert_eq!(events(format!("\x1b[{}~", number).as_bytes()), [], "{}", number);
+        }
+    }
+
+    #[test]
+    fn csi_u_keys() {
+        assert_eq!(events(b"\x1b[97;5u"), key(KeyCode::Char('a'), KeyModifiers::CONTROL));
+        assert_eq!(events(b"\x1b[13u"), key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(events(b"\x1b[27u"), key(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(events(b"\x1b[9;2u"), key(KeyCode::Tab, KeyModifiers::SHIFT));
+        assert_eq!(events(b"\x1b[127;3u"), key(KeyCode::Backspace, KeyModifiers::ALT));
+        assert_eq!(events(b"\x1b[97;17u"), key(KeyCode::Char('a'), KeyModifiers::HYPER));
+        assert_eq!(events(b"\x1b[97;33u"), key(KeyCode::Char('a'), KeyModifiers::META));
+
+        assert_eq!(events(b"\x1b[97;1:3u"), vec![
+            Event::Key(KeyEvent::new_with_kind(KeyCode::Char('a'), KeyModifiers::NONE, KeyEventKind::Release)),
+        ]);
+        assert_eq!(events(b"\x1b[3;9:2~"), vec![
+            Event::Key(KeyEvent::new_with_kind(KeyCode::Delete, KeyModifiers::SUPER, KeyEventKind::Repeat)),
+        ]);
+        assert_eq!(events(b"\x1b[97;1:4u"), []);
+    }
+
+    #[test]
+    fn characters() {
+        assert_eq!(events(b"a"), key(KeyCode::Char('a'), KeyModifiers::NONE));
+        assert_eq!(events(b"A"), key(KeyCode::Char('A'), KeyModifiers::SHIFT));
+        assert_eq!(events("\u{e9}".as_bytes()), key(KeyCode::Char('\u{e9}'), KeyModifiers::NONE));
+        assert_eq!(events(b"\x7f"), key(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(events(b"\x1b[Z"), key(KeyCode::BackTab, KeyModifiers::SHIFT));
+
+        // Alt
+        assert_eq!(events(b"\x1bb"), key(KeyCode::Char('b'), KeyModifiers::ALT));
+        assert_eq!(events(b"\x1bB"), key(KeyCode::Char('B'), KeyModifiers::ALT | KeyModifiers::SHIFT));
+        assert_eq!(events(b"\x1b1"), key(KeyCode::Char('1'), KeyModifiers::ALT));
+    }
+
+    #[test]
+    fn controls() {
+        assert_eq!(events(b"\r"), key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(events(b"\t"), key(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(events(b"\x00"), key(KeyCode::Char(' '), KeyModifiers::CONTROL));
+        assert_eq!(events(b"\n"), key(KeyCode::Char('j'), KeyModifiers::CONTROL));
+        assert_eq!(events(b"\x08"), key(KeyCode::Char('h'), KeyModifiers::CONTROL));
+
+        for byte in (0x01..=0x1a).filter(|&byte| byte != b'\r' && byte != b'\t') {
+            let c = (b'a' + byte - 1) as char;
+            assert_eq!(events(&[byte]), key(KeyCode::Char(c), KeyModifiers::CONTROL), "{:02x}", byte);
+        }
+        for (byte, c) in (0x1c..=0x1f).zip("4567".chars()) {
+            assert_eq!(events(&[byte]), key(KeyCode::Char(c), KeyModifiers::CONTROL), "{:02x}", byte);
+        }
+    }
+
+    #[test]
+    fn mouse_events() {
+        // SGR
+        assert_eq!(events(b"\x1b[<0;10;5M"), mouse(MouseEventKind::Down(MouseButton::Left), 9, 4));
+        assert_eq!(events(b"\x1b[<2;10;5m"), mouse(MouseEventKind::Up(MouseButton::Right), 9, 4));
+        assert_eq!(events(b"\x1b[<33;1;2M"), mouse(MouseEventKind::Drag(MouseButton::Middle), 0, 1));
+        assert_eq!(events(b"\x1b[<35;1;2M"), mouse(MouseEventKind::Moved, 0, 1));
+        assert_eq!(events(b"\x1b[<64;1;1M"), mouse(MouseEventKind::ScrollUp, 0, 0));
+        assert_eq!(events(b"\x1b[<65;1;1M"), mouse(MouseEventKind::ScrollDown, 0, 0));
+        assert_eq!(events(b"\x1b[<66;1;1M"), mouse(MouseEventKind::ScrollLeft, 0, 0));
+        assert_eq!(events(b"\x1b[<67;1;1M"), mouse(MouseEventKind::ScrollRight, 0, 0));
+        assert_eq!(events(b"\x1b[<128;1;1M"), []);
+
+        assert_eq!(events(b"\x1b[<20;1;1M"), vec![Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::SHIFT | KeyModifiers::CONTROL,
+        })]);
+
+        // URXVT
+        assert_eq!(events(b"\x1b[32;10;5M"), mouse(MouseEventKind::Down(MouseButton::Left), 9, 4));
+        assert_eq!(events(b"\x1b[35;10;5M"), mouse(MouseEventKind::Up(MouseButton::Left), 9, 4));
+
+        // X10, with the characters printed after `CSI M`
+        assert_eq!(events(b"\x1b[M *%"), mouse(MouseEventKind::Down(MouseButton::Left), 9, 4));
+        assert_eq!(events(b"\x1b[M#*%a"), [
+            mouse(MouseEventKind::Up(MouseButton::Left), 9, 4),
+            key(KeyCode::Char('a'), KeyModifiers::NONE),
+        ].concat());
+        assert_eq!(events(b"\x1b[M *\ra"), [
+            key(KeyCode::Enter, KeyModifiers::NONE),
+            key(KeyCode::Char('a'), KeyModifiers::NONE),
+        ].concat());
+    }
+
+    #[test]
+    fn focus() {
+        assert_eq!(events(b"\x1b[I\x1b[O"), [Event::FocusGained, Event::FocusLost]);
+    }
+
+    #[test]
+    fn paste() {
+        assert_eq!(events(b"\x1b[200~a\x1b[1mb\r\nc\x1b[201~d"), [
+            vec![Event::Paste("ab\r\nc".to_string())],
+            key(KeyCode::Char('d'), KeyModifiers::NONE),
+        ].concat());
+        assert_eq!(events(b"\x1b[200~\x1b[201~"), [Event::Paste(String::new())]);
+    }
+
+    #[test]
+    fn resize() {
+        assert_eq!(events(b"\x1b[8;24;80t"), [Event::Resize(80, 24)]);
+        assert_eq!(events(b"\x1b[48;50;132;900;1200t"), [Event::Resize(132, 50)]);
+        assert_eq!(events(b"\x1b[8;24t\x1b[4;24;80t"), []);
+    }
+
+    #[test]
+    fn other_sequences() {
+        assert_eq!(events(b"\x1b]0;title\x07\x1bP1$r0m\x1b\\\x1b[?1;2c\x1b[ q\x1b#8"), []);
+    }
+}
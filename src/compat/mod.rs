@@ -0,0 +1,3 @@
+//! Bridges to other terminal libraries
+#[cfg(feature = "crossterm")]
+pub mod crossterm;
@@ -0,0 +1,242 @@
+//! Forwarding actions to several `Perform` implementations
+use {Params, Perform, State, C0};
+
+/// A [`Perform`] which forwards every action to `A` and then to `B`
+///
+/// Use [`compose!`] to combine more than two implementations.
+///
+/// [`Perform`]: trait.Perform.html
+/// [`compose!`]: macro.compose.html
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CompositePerform<A: Perform, B: Perform>(pub A, pub B);
+
+impl<A: Perform, B: Perform> Perform for CompositePerform<A, B> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.0.print(c);
+        self.1.print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.0.print_wide(c, cell_width);
+        self.1.print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        self.0.execute(byte);
+        self.1.execute(byte);
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        self.0.execute_c0(c0);
+        self.1.execute_c0(c0);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.0.carriage_return();
+        self.1.carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.0.linefeed();
+        self.1.linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        self.0.backspace();
+        self.1.backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        self.0.tab();
+        self.1.tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.0.bell();
+        self.1.bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.0.hook(params, intermediates, ignore, final_byte);
+        self.1.hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.0.put(byte);
+        self.1.put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.0.unhook();
+        self.1.unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.0.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+        self.1.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.0.wants_dcs_dispatch() || self.1.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.0.osc_dispatch(params, bell_terminated);
+        self.1.osc_dispatch(params, bell_terminated);
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.0.sos_dispatch(bytes);
+        self.1.sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.0.pm_dispatch(bytes);
+        self.1.pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.0.apc_dispatch(bytes);
+        self.1.apc_dispatch(bytes);
+    }
+
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.0.csi_dispatch(params, intermediates, ignore, action, private);
+        self.1.csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.0.esc_dispatch(params, intermediates, ignore, byte);
+        self.1.esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.0.resize_event(rows, cols);
+        self.1.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.0.on_state_change(from, to);
+        self.1.on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.0.error(byte);
+        self.1.error(byte);
+    }
+}
+
+/// Combine several [`Perform`] implementations into one
+///
+/// Actions are forwarded to every implementation in the order they are
+/// listed, by nesting [`CompositePerform`] pairs.
+///
+/// ```
+/// # #[macro_use] extern crate vte;
+/// # use vte::{Params, Perform};
+/// # #[derive(Default)]
+/// # struct Count(usize);
+/// # impl Perform for Count {
+/// #     fn print(&mut self, _c: char) { self.0 += 1; }
+/// #     fn execute(&mut self, _byte: u8) {}
+/// #     fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+/// #     fn put(&mut self, _byte: u8) {}
+/// #     fn unhook(&mut self) {}
+/// #     fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+/// #     fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+/// #     fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// # }
+/// # fn main() {
+/// let mut performer = compose!(Count::default(), Count::default(), Count::default());
+/// let mut parser = vte::Parser::new();
+/// parser.advance_slice(&mut performer, b"ab");
+///
+/// let vte::CompositePerform(a, vte::CompositePerform(b, c)) = performer;
+/// assert_eq!((a.0, b.0, c.0), (2, 2, 2));
+/// # }
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`CompositePerform`]: struct.CompositePerform.html
+#[macro_export]
+macro_rules! compose {
+    ($performer:expr $(,)*) => {
+        $performer
+    };
+    ($first:expr, $($rest:expr),+ $(,)*) => {
+        $crate::CompositePerform($first, $crate::compose!($($rest),+))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompositePerform;
+    use test_utils::RecordingPerform;
+    use Parser;
+
+    static INPUT: &[u8] = b"a\xe2\x94\x80\r\n\x1b[1;38:2::1:2:3m\x1b[?25h\x1b(B\x1b]0;title\x07\
+                            \x1bP1$qm\x1b\\\x1b_apc\x1b\\\x1b[1<x\x1b[ !#q";
+
+    #[test]
+    fn forwards_to_both() {
+        let mut performer = CompositePerform(RecordingPerform::new(), RecordingPerform::new());
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut performer, INPUT);
+
+        let mut expected = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut expected, INPUT);
+
+        assert!(!expected.events().is_empty());
+        assert_eq!(performer.0.events(), expected.events());
+        assert_eq!(performer.1.events(), expected.events());
+    }
+
+    #[test]
+    fn compose_nests_pairs() {
+        let mut performer = compose!(RecordingPerform::new(), RecordingPerform::new(), RecordingPerform::new());
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut performer, INPUT);
+
+        let CompositePerform(a, CompositePerform(b, c)) = performer;
+        assert_eq!(a.events(), b.events());
+        assert_eq!(b.events(), c.events());
+    }
+}
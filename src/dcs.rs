@@ -0,0 +1,335 @@
+//! Handlers which take over device control strings from the `Perform`
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp;
+
+use Params;
+
+/// Receives device control strings before they reach the [`Perform`]
+///
+/// A handler is installed with [`Parser::set_dcs_handler`]. It is offered
+/// every DCS sequence which is not ignored; if `hook` returns `true` the
+/// handler claims the sequence, receives its data through `put` and `unhook`
+/// and the `Perform` is not called for it. Otherwise the sequence is passed
+/// to the `Perform` as usual.
+///
+/// [`Perform`]: trait.Perform.html
+/// [`Parser::set_dcs_handler`]: struct.Parser.html#method.set_dcs_handler
+pub trait DcsHandler {
+    /// A DCS sequence has started; return `true` to claim it
+    fn hook(&mut self, params: &Params, intermediates: &[u8], final_byte: u8) -> bool;
+
+    /// Data of a claimed sequence
+    fn put(&mut self, byte: u8);
+
+    /// A claimed sequence has been terminated
+    fn unhook(&mut self);
+
+    /// Whether ESC is escaped as `ESC ESC` in the data of claimed sequences
+    ///
+    /// If this returns `true` when a sequence is claimed, a doubled ESC is
+    /// passed to `put` as a single `0x1b` instead of starting the string
+    /// terminator. Defaults to `false`.
+    fn doubles_escapes(&self) -> bool {
+        false
+    }
+}
+
+/// Storage for the DCS handler of a parser
+///
+/// The handler can't be cloned, so a clone of the parser has none.
+#[derive(Default)]
+pub(crate) struct DcsHandlerSlot {
+    pub(crate) handler: Option<Box<dyn DcsHandler>>,
+
+    /// The current sequence was claimed by the handler
+    pub(crate) claimed: bool,
+
+    /// The claimed sequence escapes ESC by doubling it
+    pub(crate) doubles_escapes: bool,
+
+    /// An ESC of the claimed sequence may be the first of a doubled ESC
+    pub(crate) escape_pending: bool,
+}
+
+impl Clone for DcsHandlerSlot {
+    fn clone(&self) -> DcsHandlerSlot {
+        // The rest of a claimed sequence is dropped by the clone
+        DcsHandlerSlot {
+            handler: None,
+            claimed: self.claimed,
+            doubles_escapes: self.doubles_escapes,
+            escape_pending: self.escape_pending,
+        }
+    }
+}
+
+/// A [`DcsHandler`] which collects sixel images
+///
+/// Claims `DCS P1 ; P2 ; P3 q` sequences without intermediates. When an
+/// image is complete the callback is called with its width and height in
+/// pixels and the raw sixel data following the `q`.
+///
+/// The size is taken from the raster attributes (`" Pan ; Pad ; Ph ; Pv`)
+/// at the start of the data if they specify one, and otherwise computed from
+/// the sixels the image consists of.
+///
+/// ```
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// # use vte::{Params, Parser, Perform, SixelDcsHandler};
+/// # struct Ignore;
+/// # impl Perform for Ignore {
+/// #     fn print(&mut self, _c: char) {}
+/// #     fn execute(&mut self, _byte: u8) {}
+/// #     fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+/// #     fn put(&mut self, _byte: u8) {}
+/// #     fn unhook(&mut self) {}
+/// #     fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+/// #     fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+/// #     fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// # }
+/// let sizes = Rc::new(RefCell::new(Vec::new()));
+/// let images = sizes.clone();
+///
+/// let mut parser = Parser::new();
+/// parser.set_dcs_handler(Box::new(SixelDcsHandler::new(move |width, height, _data: &[u8]| {
+///     images.borrow_mut().push((width, height));
+/// })));
+/// parser.advance_slice(&mut Ignore, b"\x1bPq#0;2;0;0;0#0~~@@-!3~\x1b\\");
+/// assert_eq!(*sizes.borrow(), [(4, 12)]);
+/// ```
+///
+/// [`DcsHandler`]: trait.DcsHandler.html
+pub struct SixelDcsHandler<F: FnMut(u32, u32, &[u8])> {
+    callback: F,
+    data: Vec<u8>,
+}
+
+impl<F: FnMut(u32, u32, &[u8])> SixelDcsHandler<F> {
+    /// Create a handler calling `callback` for every sixel image
+    #[must_use]
+    pub fn new(callback: F) -> SixelDcsHandler<F> {
+        SixelDcsHandler {
+            callback,
+            data: Vec::new(),
+        }
+    }
+}
+
+impl<F: FnMut(u32, u32, &[u8])> DcsHandler for SixelDcsHandler<F> {
+    fn hook(&mut self, _params: &Params, intermediates: &[u8], final_byte: u8) -> bool {
+        self.data.clear();
+        final_byte == b'q' && intermediates.is_empty()
+    }
+
+    fn put(&mut self, byte: u8) {
+        self.data.push(byte);
+    }
+
+    fn unhook(&mut self) {
+        let (width, height) = sixel_size(&self.data);
+        (self.callback)(width, height, &self.data);
+        self.data.clear();
+    }
+}
+
+/// Collects the numeric parameters following an introducer
+struct Numbers {
+    values: [u32; 4],
+    len: usize,
+}
+
+impl Numbers {
+    /// Parse numbers separated by `;` at the start of `data`, returning them
+    /// and the number of bytes consumed
+    fn parse(data: &[u8]) -> (Numbers, usize) {
+        let mut numbers = Numbers { values: [0; 4], len: 1 };
+        let mut consumed = 0;
+
+        for &byte in data {
+            match byte {
+                b'0'..=b'9' => {
+                    if numbers.len <= numbers.values.len() {
+                        let value = &mut numbers.values[numbers.len - 1];
+                        *value = value.saturating_mul(10).saturating_add(u32::from(byte - b'0'));
+                    }
+                },
+                b';' => numbers.len += 1,
+                _ => break,
+            }
+            consumed += 1;
+        }
+
+        numbers.len = cmp::min(numbers.len, numbers.values.len());
+        (numbers, consumed)
+    }
+}
+
+/// Width and height in pixels of sixel `data`
+fn sixel_size(data: &[u8]) -> (u32, u32) {
+    let mut index = 0;
+
+    // Raster attributes
+    if data.first() == Some(&b'"') {
+        let (numbers, consumed) = Numbers::parse(&data[1..]);
+        index = consumed + 1;
+
+        let (width, height) = (numbers.values[2], numbers.values[3]);
+        if numbers.len == 4 && width > 0 && height > 0 {
+            return (width, height);
+        }
+    }
+
+    let mut width = 0u32;
+    let mut column = 0u32;
+    let mut bands = 0u32;
+    let mut band_used = false;
+    let mut repeat = 1u32;
+
+    while index < data.len() {
+        let byte = data[index];
+        index += 1;
+
+        match byte {
+            b'?'..=b'~' => {
+                column = column.saturating_add(repeat);
+                width = cmp::max(width, column);
+                band_used = true;
+                repeat = 1;
+            },
+            b'!' => {
+                let (numbers, consumed) = Numbers::parse(&data[index..]);
+                repeat = cmp::max(numbers.values[0], 1);
+                index += consumed;
+            },
+            // Colors don't change the size
+            b'#' => index += Numbers::parse(&data[index..]).1,
+            b'$' => column = 0,
+            b'-' => {
+                bands += 1;
+                band_used = false;
+                column = 0;
+            },
+            _ => (),
+        }
+    }
+
+    if band_used {
+        bands += 1;
+    }
+    (width, bands.saturating_mul(6))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::{sixel_size, DcsHandler, SixelDcsHandler};
+    use test_utils::RecordingPerform;
+    use tests::describe;
+    use {Params, Parser};
+
+    type Images = Rc<RefCell<Vec<(u32, u32, Vec<u8>)>>>;
+
+    fn sixel_parser() -> (Parser, Images) {
+        let images = Images::default();
+        let collected = images.clone();
+
+        let mut parser = Parser::new();
+        parser.set_dcs_handler(Box::new(SixelDcsHandler::new(move |width, height, data: &[u8]| {
+            collected.borrow_mut().push((width, height, data.to_vec()));
+        })));
+        (parser, images)
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(sixel_size(b""), (0, 0));
+        assert_eq!(sixel_size(b"\"1;1;20;30#0~"), (20, 30));
+        assert_eq!(sixel_size(b"\"1;1#0~~"), (2, 6));
+        assert_eq!(sixel_size(b"#1;2;100;0;0~~$~~~-!10?"), (10, 12));
+        assert_eq!(sixel_size(b"~-"), (1, 6));
+    }
+
+    #[test]
+    fn claims_sixel_sequences() {
+        let (mut parser, images) = sixel_parser();
+        let mut recorder = RecordingPerform::new();
+        parser.advance_slice(&mut recorder, b"a\x1bP0;1q\"1;1;3;6#0~~~\x1b\\b");
+
+        assert_eq!(*images.borrow(), vec![(3, 6, b"\"1;1;3;6#0~~~".to_vec())]);
+        assert_eq!(describe(recorder.events()), vec!["print 'a'", "esc_dispatch [] [] false 5c", "print 'b'"]);
+    }
+
+    #[test]
+    fn passes_other_sequences_on() {
+        let (mut parser, images) = sixel_parser();
+        let mut recorder = RecordingPerform::new();
+        parser.advance_slice(&mut recorder, b"\x1bP$qm\x1b\\");
+
+        assert!(images.borrow().is_empty());
+        assert_eq!(describe(recorder.events())[..3], ["hook [] [36] false 'q'", "put 6d", "unhook"]);
+    }
+
+    #[test]
+    fn handler_can_be_taken() {
+        let (mut parser, images) = sixel_parser();
+        assert!(parser.take_dcs_handler().is_some());
+        assert!(parser.take_dcs_handler().is_none());
+
+        let mut recorder = RecordingPerform::new();
+        parser.advance_slice(&mut recorder, b"\x1bPq~\x1b\\");
+        assert!(images.borrow().is_empty());
+        assert_eq!(describe(recorder.events())[0], "hook [] [] false 'q'");
+    }
+
+    #[test]
+    fn clone_drops_claimed_sequence() {
+        let (mut parser, images) = sixel_parser();
+        let mut recorder = RecordingPerform::new();
+        parser.advance_slice(&mut recorder, b"\x1bPq~");
+
+        let mut clone = parser.clone();
+        clone.advance_slice(&mut recorder, b"~\x1b\\\x1bPq~\x1b\\");
+        assert!(images.borrow().is_empty());
+        assert_eq!(describe(recorder.events())[1..4], ["hook [] [] false 'q'", "put 7e", "unhook"]);
+
+        parser.advance_slice(&mut recorder, b"~\x1b\\");
+        assert_eq!(images.borrow().len(), 1);
+    }
+
+    /// Claims everything and counts the calls
+    #[derive(Default)]
+    struct Counter(Rc<RefCell<(usize, usize, usize)>>);
+
+    impl DcsHandler for Counter {
+        fn hook(&mut self, _params: &Params, _intermediates: &[u8], _final_byte: u8) -> bool {
+            self.0.borrow_mut().0 += 1;
+            true
+        }
+
+        fn put(&mut self, _byte: u8) {
+            self.0.borrow_mut().1 += 1;
+        }
+
+        fn unhook(&mut self) {
+            self.0.borrow_mut().2 += 1;
+        }
+    }
+
+    #[test]
+    fn ignored_sequences_are_not_offered() {
+        let counter = Counter::default();
+        let counts = counter.0.clone();
+
+        let mut parser = Parser::new();
+        parser.set_dcs_handler(Box::new(counter));
+        let mut recorder = RecordingPerform::new();
+        parser.advance_slice(&mut recorder, b"\x1bP1;2         q~~\x1b\\\x1bPpabc\x1b\\");
+
+        assert_eq!(*counts.borrow(), (1, 3, 1));
+        assert_eq!(describe(recorder.events())[1], "hook [[1], [2]] [32, 32, 32, 32, 32, 32, 32, 32] true 'q'");
+    }
+}
@@ -0,0 +1,814 @@
+//! Decoding parser actions into terminal commands
+//!
+//! [`TerminalDecoder`] is a [`Perform`] which assigns meaning to the
+//! sequences most terminal emulators implement, like cursor movement,
+//! erasing and colors, and passes them to a [`TerminalSink`] as
+//! [`TerminalCommand`]s. Actions it doesn't decode are passed on unchanged as
+//! [`VteEventRef`]s, so the sink can still handle them itself.
+//!
+//! ```
+//! # use vte::{Parser, SgrAttribute};
+//! # use vte::decoder::{TerminalCommand, TerminalDecoder};
+//! let mut decoder = TerminalDecoder::new(Vec::new());
+//! Parser::new().advance_slice(&mut decoder, b"\x1b[2;5H\x1b[1mx");
+//!
+//! assert_eq!(decoder.into_inner(), [
+//!     TerminalCommand::MoveCursor(1, 4),
+//!     TerminalCommand::SetGraphicsRendition(vec![SgrAttribute::Bold]),
+//!     TerminalCommand::Print('x'),
+//! ]);
+//! ```
+//!
+//! [`TerminalDecoder`]: struct.TerminalDecoder.html
+//! [`TerminalSink`]: trait.TerminalSink.html
+//! [`TerminalCommand`]: enum.TerminalCommand.html
+//! [`Perform`]: ../trait.Perform.html
+//! [`VteEventRef`]: ../enum.VteEventRef.html
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use osc::{join, parse_osc8, Osc8};
+use {parse_sgr, Params, Perform, SgrAttribute, State, VteEventRef};
+
+/// Zero-based row of the screen
+pub type Row = u16;
+
+/// Zero-based column of the screen
+pub type Col = u16;
+
+/// Part of the screen or line to erase
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EraseMode {
+    /// From the cursor to the end, `0`
+    ToEnd,
+
+    /// From the start to the cursor, `1`
+    ToStart,
+
+    /// Everything, `2`
+    All,
+
+    /// The scrollback buffer, `3`; only used by ED
+    Scrollback,
+}
+
+/// Shape and blinking of the cursor, set with DECSCUSR (`CSI Ps SP q`)
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CursorStyle {
+    /// The style configured by the user, `0`
+    #[default]
+    Default,
+
+    /// `1`
+    BlinkingBlock,
+
+    /// `2`
+    SteadyBlock,
+
+    /// `3`
+    BlinkingUnderline,
+
+    /// `4`
+    SteadyUnderline,
+
+    /// `5`
+    BlinkingBar,
+
+    /// `6`
+    SteadyBar,
+}
+
+impl CursorStyle {
+    /// The style selected by parameter `value` of DECSCUSR, or `None` if it
+    /// is unknown
+    #[must_use]
+    pub fn from_param(value: u16) -> Option<CursorStyle> {
+        let style = match value {
+            0 => CursorStyle::Default,
+            1 => CursorStyle::BlinkingBlock,
+            2 => CursorStyle::SteadyBlock,
+            3 => CursorStyle::BlinkingUnderline,
+            4 => CursorStyle::SteadyUnderline,
+            5 => CursorStyle::BlinkingBar,
+            6 => CursorStyle::SteadyBar,
+            _ => return None,
+        };
+        Some(style)
+    }
+}
+
+/// An operation of a terminal, decoded by [`TerminalDecoder`]
+///
+/// Counts are at least 1, since a missing or zero count means 1 for these
+/// sequences. Rows and columns are zero-based, unlike in the sequences.
+///
+/// [`TerminalDecoder`]: struct.TerminalDecoder.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TerminalCommand {
+    /// Print a character at the cursor
+    Print(char),
+
+    /// `BEL`
+    Bell,
+
+    /// `BS`
+    Backspace,
+
+    /// `HT`
+    HorizontalTab,
+
+    /// `LF`, `VT` or `FF`
+    LineFeed,
+
+    /// `CR`
+    CarriageReturn,
+
+    /// `IND`, move down and scroll at the bottom margin
+    Index,
+
+    /// `RI`, move up and scroll at the top margin
+    ReverseIndex,
+
+    /// `NEL`, a carriage return and index
+    NextLine,
+
+    /// `HTS`, set a tab stop at the cursor column
+    SetTabStop,
+
+    /// `TBC 0`, clear the tab stop at the cursor column
+    ClearTabStop,
+
+    /// `TBC 3`, clear all tab stops
+    ClearAllTabStops,
+
+    /// `SGR`, the attributes in order as decoded by [`parse_sgr`]
+    ///
+    /// [`parse_sgr`]: ../fn.parse_sgr.html
+    SetGraphicsRendition(Vec<SgrAttribute>),
+
+    /// `CUP` or `HVP`
+    MoveCursor(Row, Col),
+
+    /// `CUU`
+    CursorUp(u16),
+
+    /// `CUD`
+    CursorDown(u16),
+
+    /// `CUF`
+    CursorForward(u16),
+
+    /// `CUB`
+    CursorBackward(u16),
+
+    /// `CNL`, move down and to the first column
+    CursorNextLine(u16),
+
+    /// `CPL`, move up and to the first column
+    CursorPrecedingLine(u16),
+
+    /// `CHA`, move to a column of the current row
+    CursorColumn(Col),
+
+    /// `VPA`, move to a row keeping the column
+    CursorRow(Row),
+
+    /// `DECSC` or `CSI s`
+    SaveCursor,
+
+    /// `DECRC` or `CSI u`
+    RestoreCursor,
+
+    /// `ED`
+    EraseInDisplay(EraseMode),
+
+    /// `EL`
+    EraseInLine(EraseMode),
+
+    /// `ECH`
+    EraseCharacters(u16),
+
+    /// `ICH`
+    InsertCharacters(u16),
+
+    /// `DCH`
+    DeleteCharacters(u16),
+
+    /// `IL`
+    InsertLines(u16),
+
+    /// `DL`
+    DeleteLines(u16),
+
+    /// `SU`
+    ScrollUp(u16),
+
+    /// `SD`
+    ScrollDown(u16),
+
+    /// `DECSTBM`, the top and bottom row of the scrolling region
+    ///
+    /// A missing bottom row means the last row of the screen.
+    SetScrollingRegion(Row, Option<Row>),
+
+    /// `SM`, one command per mode
+    SetMode(u16),
+
+    /// `RM`, one command per mode
+    ResetMode(u16),
+
+    /// `DECSET`, one command per mode
+    SetPrivateMode(u16),
+
+    /// `DECRST`, one command per mode
+    ResetPrivateMode(u16),
+
+    /// `DECSCUSR`
+    SetCursorStyle(CursorStyle),
+
+    /// `DECKPAM`
+    ApplicationKeypad,
+
+    /// `DECKPNM`
+    NormalKeypad,
+
+    /// `DSR 6`, report the cursor position
+    ReportCursorPosition,
+
+    /// `DA`, report the primary device attributes
+    ReportDeviceAttributes,
+
+    /// `RIS`
+    FullReset,
+
+    /// `OSC 0` or `OSC 2`
+    SetTitle(String),
+
+    /// `OSC 0` or `OSC 1`
+    SetIconName(String),
+
+    /// `OSC 8`, the id and the URI of the link
+    ///
+    /// A link without URI ends the current link, see [`Osc8`].
+    ///
+    /// [`Osc8`]: ../osc/enum.Osc8.html
+    SetHyperlink(Option<String>, Option<String>),
+
+    /// `XTWINOPS 8`, resize the window to rows and columns
+    ///
+    /// Sent for [`Perform::resize_event`], so the values are not zero-based
+    /// and 0 keeps the current size.
+    ///
+    /// [`Perform::resize_event`]: ../trait.Perform.html#method.resize_event
+    ResizeWindow(u16, u16),
+}
+
+/// Receives the commands decoded by a [`TerminalDecoder`]
+///
+/// ```
+/// # use vte::decoder::{TerminalCommand, TerminalDecoder, TerminalSink};
+/// # use vte::{Parser, VteEventRef};
+/// #[derive(Default)]
+/// struct Sink {
+///     text: String,
+///     unhandled: usize,
+/// }
+///
+/// impl TerminalSink for Sink {
+///     fn command(&mut self, command: TerminalCommand) {
+///         if let TerminalCommand::Print(c) = command {
+///             self.text.push(c);
+///         }
+///     }
+///
+///     fn unhandled(&mut self, _event: VteEventRef) {
+///         self.unhandled += 1;
+///     }
+/// }
+///
+/// let mut decoder = TerminalDecoder::new(Sink::default());
+/// Parser::new().advance_slice(&mut decoder, b"a\x1b[5xb");
+/// assert_eq!(decoder.sink().text, "ab");
+/// assert_eq!(decoder.sink().unhandled, 1);
+/// ```
+///
+/// [`TerminalDecoder`]: struct.TerminalDecoder.html
+pub trait TerminalSink {
+    /// Handle a decoded command
+    fn command(&mut self, command: TerminalCommand);
+
+    /// Handle an action which isn't decoded
+    ///
+    /// This includes sequences with the ignore flag set and string data other
+    /// than OSC. Device control strings are passed through `hook`, `put` and
+    /// `unhook` events. State changes are not passed on.
+    fn unhandled(&mut self, _event: VteEventRef) {}
+}
+
+/// Collects the commands, dropping other actions
+impl TerminalSink for Vec<TerminalCommand> {
+    #[inline]
+    fn command(&mut self, command: TerminalCommand) {
+        self.push(command);
+    }
+}
+
+impl<S: TerminalSink + ?Sized> TerminalSink for &mut S {
+    #[inline]
+    fn command(&mut self, command: TerminalCommand) {
+        (**self).command(command);
+    }
+
+    #[inline]
+    fn unhandled(&mut self, event: VteEventRef) {
+        (**self).unhandled(event);
+    }
+}
+
+/// A [`Perform`] which decodes actions into [`TerminalCommand`]s
+///
+/// The decoder keeps no state apart from the cursor style, which is set by
+/// DECSCUSR and returned to the default by a full reset.
+///
+/// See the [module documentation](index.html) for an example.
+///
+/// [`Perform`]: ../trait.Perform.html
+/// [`TerminalCommand`]: enum.TerminalCommand.html
+#[derive(Debug, Clone, Default)]
+pub struct TerminalDecoder<S: TerminalSink> {
+    sink: S,
+    cursor_style: CursorStyle,
+}
+
+impl<S: TerminalSink> TerminalDecoder<S> {
+    /// Create a decoder passing the commands to `sink`
+    #[inline]
+    #[must_use]
+    pub fn new(sink: S) -> TerminalDecoder<S> {
+        TerminalDecoder { sink, cursor_style: CursorStyle::Default }
+    }
+
+    /// The cursor style set by the last DECSCUSR
+    ///
+    /// ```
+    /// # use vte::Parser;
+    /// # use vte::decoder::{CursorStyle, TerminalDecoder};
+    /// let mut decoder = TerminalDecoder::new(Vec::new());
+    /// Parser::new().advance_slice(&mut decoder, b"\x1b[5 q");
+    /// assert_eq!(decoder.cursor_style(), CursorStyle::BlinkingBar);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn cursor_style(&self) -> CursorStyle {
+        self.cursor_style
+    }
+
+    /// The `TerminalSink`
+    #[inline]
+    #[must_use]
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// The `TerminalSink`
+    #[inline]
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Unwrap the `TerminalSink`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    #[inline]
+    fn command(&mut self, command: TerminalCommand) {
+        self.sink.command(command);
+    }
+
+    /// Decode a CSI sequence without private marker or intermediates
+    fn csi(&mut self, params: &Params, action: char) -> bool {
+        let command = match action {
+            'm' => TerminalCommand::SetGraphicsRendition(parse_sgr(params)),
+            'H' | 'f' => TerminalCommand::MoveCursor(position(params, 0), position(params, 1)),
+            'A' => TerminalCommand::CursorUp(count(params)),
+            'B' | 'e' => TerminalCommand::CursorDown(count(params)),
+            'C' | 'a' => TerminalCommand::CursorForward(count(params)),
+            'D' => TerminalCommand::CursorBackward(count(params)),
+            'E' => TerminalCommand::CursorNextLine(count(params)),
+            'F' => TerminalCommand::CursorPrecedingLine(count(params)),
+            'G' | '`' => TerminalCommand::CursorColumn(position(params, 0)),
+            'd' => TerminalCommand::CursorRow(position(params, 0)),
+            'J' => match erase_mode(params) {
+                Some(mode) => TerminalCommand::EraseInDisplay(mode),
+                None => return false,
+            },
+            'K' => match erase_mode(params) {
+                Some(EraseMode::Scrollback) | None => return false,
+                Some(mode) => TerminalCommand::EraseInLine(mode),
+            },
+            'X' => TerminalCommand::EraseCharacters(count(params)),
+            '@' => TerminalCommand::InsertCharacters(count(params)),
+            'P' => TerminalCommand::DeleteCharacters(count(params)),
+            'L' => TerminalCommand::InsertLines(count(params)),
+            'M' => TerminalCommand::DeleteLines(count(params)),
+            'S' => TerminalCommand::ScrollUp(count(params)),
+            'T' => TerminalCommand::ScrollDown(count(params)),
+            'r' => {
+                let bottom = params.get(1).map(|param| param[0]).filter(|&row| row != 0);
+                TerminalCommand::SetScrollingRegion(position(params, 0), bottom.map(|row| row - 1))
+            },
+            's' => TerminalCommand::SaveCursor,
+            'u' => TerminalCommand::RestoreCursor,
+            'g' => match params.get_or_default(0, 0, 0) {
+                0 => TerminalCommand::ClearTabStop,
+                3 => TerminalCommand::ClearAllTabStops,
+                _ => return false,
+            },
+            't' if params.get_or_default(0, 0, 0) == 8 => {
+                self.resize_event(params.get_or_default(1, 0, 0), params.get_or_default(2, 0, 0));
+                return true;
+            },
+            'n' if params.get_or_default(0, 0, 0) == 6 => TerminalCommand::ReportCursorPosition,
+            'c' if params.get_or_default(0, 0, 0) == 0 => TerminalCommand::ReportDeviceAttributes,
+            'h' | 'l' => {
+                for param in params {
+                    self.command(if action == 'h' {
+                        TerminalCommand::SetMode(param[0])
+                    } else {
+                        TerminalCommand::ResetMode(param[0])
+                    });
+                }
+                return true;
+            },
+            _ => return false,
+        };
+
+        self.command(command);
+        true
+    }
+}
+
+/// First parameter as count, treating zero and a missing value as 1
+#[inline]
+fn count(params: &Params) -> u16 {
+    params.get_or_default(0, 0, 1).max(1)
+}
+
+/// Parameter at `index` as zero-based row or column
+#[inline]
+fn position(params: &Params, index: usize) -> u16 {
+    params.get_or_default(index, 0, 1).max(1) - 1
+}
+
+#[inline]
+fn erase_mode(params: &Params) -> Option<EraseMode> {
+    match params.get_or_default(0, 0, 0) {
+        0 => Some(EraseMode::ToEnd),
+        1 => Some(EraseMode::ToStart),
+        2 => Some(EraseMode::All),
+        3 => Some(EraseMode::Scrollback),
+        _ => None,
+    }
+}
+
+impl<S: TerminalSink> Perform for TerminalDecoder<S> {
+    fn print(&mut self, c: char) {
+        self.command(TerminalCommand::Print(c));
+    }
+
+    fn execute(&mut self, byte: u8) {
+        let command = match byte {
+            0x07 => TerminalCommand::Bell,
+            0x08 => TerminalCommand::Backspace,
+            0x09 => TerminalCommand::HorizontalTab,
+            0x0a..=0x0c => TerminalCommand::LineFeed,
+            0x0d => TerminalCommand::CarriageReturn,
+            0x84 => TerminalCommand::Index,
+            0x85 => TerminalCommand::NextLine,
+            0x88 => TerminalCommand::SetTabStop,
+            0x8d => TerminalCommand::ReverseIndex,
+            _ => return self.sink.unhandled(VteEventRef::Execute(byte)),
+        };
+        self.command(command);
+    }
+
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.sink.unhandled(VteEventRef::DcsHook { params, intermediates, ignore, final_byte });
+    }
+
+    fn put(&mut self, byte: u8) {
+        self.sink.unhandled(VteEventRef::DcsPut(byte));
+    }
+
+    fn unhook(&mut self) {
+        self.sink.unhandled(VteEventRef::DcsUnhook);
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        match params {
+            [b"0", ..] if params.len() > 1 => {
+                let title = join(&params[1..]);
+                self.command(TerminalCommand::SetIconName(title.clone()));
+                self.command(TerminalCommand::SetTitle(title));
+            },
+            [b"1", ..] if params.len() > 1 => self.command(TerminalCommand::SetIconName(join(&params[1..]))),
+            [b"2", ..] if params.len() > 1 => self.command(TerminalCommand::SetTitle(join(&params[1..]))),
+            [b"8", ..] => match parse_osc8(params) {
+                Some(Osc8::Open { id, uri }) => self.command(TerminalCommand::SetHyperlink(id, Some(uri))),
+                Some(Osc8::Close) => self.command(TerminalCommand::SetHyperlink(None, None)),
+                None => self.sink.unhandled(VteEventRef::OscDispatch { params, bell_terminated }),
+            },
+            _ => self.sink.unhandled(VteEventRef::OscDispatch { params, bell_terminated }),
+        }
+    }
+
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.sink.unhandled(VteEventRef::SosDispatch(bytes));
+    }
+
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.sink.unhandled(VteEventRef::PmDispatch(bytes));
+    }
+
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.sink.unhandled(VteEventRef::ApcDispatch(bytes));
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        let handled = match (ignore, private, intermediates) {
+            (false, None, []) => self.csi(params, action),
+            (false, None, [b' ']) if action == 'q' => match CursorStyle::from_param(params.get_or_default(0, 0, 0)) {
+                Some(style) => {
+                    self.cursor_style = style;
+                    self.command(TerminalCommand::SetCursorStyle(style));
+                    true
+                },
+                None => false,
+            },
+            (false, Some(b'?'), []) if action == 'h' || action == 'l' => {
+                for param in params {
+                    self.command(if action == 'h' {
+                        TerminalCommand::SetPrivateMode(param[0])
+                    } else {
+                        TerminalCommand::ResetPrivateMode(param[0])
+                    });
+                }
+                true
+            },
+            _ => false,
+        };
+
+        if !handled {
+            let event = VteEventRef::CsiDispatch { params, intermediates, ignore, final_byte: action, private };
+            self.sink.unhandled(event);
+        }
+    }
+
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        let command = match (ignore, intermediates, byte) {
+            (false, [], b'D') => TerminalCommand::Index,
+            (false, [], b'E') => TerminalCommand::NextLine,
+            (false, [], b'H') => TerminalCommand::SetTabStop,
+            (false, [], b'M') => TerminalCommand::ReverseIndex,
+            (false, [], b'7') => TerminalCommand::SaveCursor,
+            (false, [], b'8') => TerminalCommand::RestoreCursor,
+            (false, [], b'=') => TerminalCommand::ApplicationKeypad,
+            (false, [], b'>') => TerminalCommand::NormalKeypad,
+            (false, [], b'c') => {
+                self.cursor_style = CursorStyle::Default;
+                TerminalCommand::FullReset
+            },
+            _ => return self.sink.unhandled(VteEventRef::EscDispatch { params, intermediates, ignore, byte }),
+        };
+        self.command(command);
+    }
+
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.command(TerminalCommand::ResizeWindow(rows, cols));
+    }
+
+    fn on_state_change(&mut self, _from: State, _to: State) {}
+
+    fn error(&mut self, byte: u8) {
+        self.sink.unhandled(VteEventRef::Error(byte));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    use super::{CursorStyle, EraseMode, TerminalCommand, TerminalDecoder, TerminalSink};
+    use {Parser, Perform, SgrAttribute, TermColor, VteEventRef};
+
+    #[derive(Default)]
+    struct Sink {
+        commands: Vec<TerminalCommand>,
+        unhandled: Vec<String>,
+    }
+
+    impl TerminalSink for Sink {
+        fn command(&mut self, command: TerminalCommand) {
+            self.commands.push(command);
+        }
+
+        fn unhandled(&mut self, event: VteEventRef) {
+            self.unhandled.push(format!("{:?}", event));
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Vec<TerminalCommand> {
+        let mut decoder = TerminalDecoder::new(Vec::new());
+        Parser::new().advance_slice(&mut decoder, bytes);
+        decoder.into_inner()
+    }
+
+    #[test]
+    fn cursor_movement() {
+        assert_eq!(decode(b"\x1b[H\x1b[3;7f\x1b[;2H"), vec![
+            TerminalCommand::MoveCursor(0, 0),
+            TerminalCommand::MoveCursor(2, 6),
+            TerminalCommand::MoveCursor(0, 1),
+        ]);
+        assert_eq!(decode(b"\x1b[A\x1b[0B\x1b[5C\x1b[2D\x1b[E\x1b[3F\x1b[10G\x1b[4d"), vec![
+            TerminalCommand::CursorUp(1),
+            TerminalCommand::CursorDown(1),
+            TerminalCommand::CursorForward(5),
+            TerminalCommand::CursorBackward(2),
+            TerminalCommand::CursorNextLine(1),
+            TerminalCommand::CursorPrecedingLine(3),
+            TerminalCommand::CursorColumn(9),
+            TerminalCommand::CursorRow(3),
+        ]);
+        assert_eq!(decode(b"\x1b7\x1b[s\x1b8\x1b[u"), vec![
+            TerminalCommand::SaveCursor,
+            TerminalCommand::SaveCursor,
+            TerminalCommand::RestoreCursor,
+            TerminalCommand::RestoreCursor,
+        ]);
+    }
+
+    #[test]
+    fn erase() {
+        assert_eq!(decode(b"\x1b[J\x1b[1J\x1b[2J\x1b[3J\x1b[K\x1b[1K\x1b[2K\x1b[X\x1b[4X"), vec![
+            TerminalCommand::EraseInDisplay(EraseMode::ToEnd),
+            TerminalCommand::EraseInDisplay(EraseMode::ToStart),
+            TerminalCommand::EraseInDisplay(EraseMode::All),
+            TerminalCommand::EraseInDisplay(EraseMode::Scrollback),
+            TerminalCommand::EraseInLine(EraseMode::ToEnd),
+            TerminalCommand::EraseInLine(EraseMode::ToStart),
+            TerminalCommand::EraseInLine(EraseMode::All),
+            TerminalCommand::EraseCharacters(1),
+            TerminalCommand::EraseCharacters(4),
+        ]);
+
+        // Unknown modes are not decoded
+        assert!(decode(b"\x1b[3K\x1b[4J").is_empty());
+    }
+
+    #[test]
+    fn editing_and_scrolling() {
+        assert_eq!(decode(b"\x1b[2@\x1b[P\x1b[3L\x1b[M\x1b[S\x1b[2T\x1b[5;20r\x1b[r"), vec![
+            TerminalCommand::InsertCharacters(2),
+            TerminalCommand::DeleteCharacters(1),
+            TerminalCommand::InsertLines(3),
+            TerminalCommand::DeleteLines(1),
+            TerminalCommand::ScrollUp(1),
+            TerminalCommand::ScrollDown(2),
+            TerminalCommand::SetScrollingRegion(4, Some(19)),
+            TerminalCommand::SetScrollingRegion(0, None),
+        ]);
+    }
+
+    #[test]
+    fn modes() {
+        assert_eq!(decode(b"\x1b[4h\x1b[?25;1049l\x1b[?1h\x1b=\x1b>"), vec![
+            TerminalCommand::SetMode(4),
+            TerminalCommand::ResetPrivateMode(25),
+            TerminalCommand::ResetPrivateMode(1049),
+            TerminalCommand::SetPrivateMode(1),
+            TerminalCommand::ApplicationKeypad,
+            TerminalCommand::NormalKeypad,
+        ]);
+    }
+
+    #[test]
+    fn cursor_style() {
+        let styles = [
+            (&b"\x1b[ q"[..], CursorStyle::Default),
+            (b"\x1b[0 q", CursorStyle::Default),
+            (b"\x1b[1 q", CursorStyle::BlinkingBlock),
+            (b"\x1b[2 q", CursorStyle::SteadyBlock),
+            (b"\x1b[3 q", CursorStyle::BlinkingUnderline),
+            (b"\x1b[4 q", CursorStyle::SteadyUnderline),
+            (b"\x1b[5 q", CursorStyle::BlinkingBar),
+            (b"\x1b[6 q", CursorStyle::SteadyBar),
+        ];
+        for &(bytes, style) in &styles {
+            let mut decoder = TerminalDecoder::new(Vec::new());
+            Parser::new().advance_slice(&mut decoder, b"\x1b[2 q");
+            Parser::new().advance_slice(&mut decoder, bytes);
+            assert_eq!(decoder.cursor_style(), style, "{:?}", bytes);
+            assert_eq!(decoder.into_inner().last(), Some(&TerminalCommand::SetCursorStyle(style)));
+        }
+
+        // Unknown styles and other sequences ending in `q` keep the style
+        let mut decoder = TerminalDecoder::new(Sink::default());
+        Parser::new().advance_slice(&mut decoder, b"\x1b[4 q\x1b[7 q\x1b[1q\x1b[?2 q\x1b[2\"q");
+        assert_eq!(decoder.cursor_style(), CursorStyle::SteadyUnderline);
+        assert_eq!(decoder.sink().unhandled.len(), 4);
+
+        Parser::new().advance_slice(&mut decoder, b"\x1bc");
+        assert_eq!(decoder.cursor_style(), CursorStyle::Default);
+    }
+
+    #[test]
+    fn resize() {
+        assert_eq!(decode(b"\x1b[8;24;80t\x1b[8;;132t"), vec![
+            TerminalCommand::ResizeWindow(24, 80),
+            TerminalCommand::ResizeWindow(0, 132),
+        ]);
+
+        // Other window operations aren't decoded
+        assert!(decode(b"\x1b[18t\x1b[22;0;0t").is_empty());
+
+        let mut decoder = TerminalDecoder::new(Vec::new());
+        decoder.resize_event(50, 100);
+        assert_eq!(decoder.into_inner(), vec![TerminalCommand::ResizeWindow(50, 100)]);
+    }
+
+    #[test]
+    fn controls() {
+        assert_eq!(decode(b"a\x07\x08\t\n\x0b\r\x1bD\x1bE\x1bH\x1bM\x1b[g\x1b[3g\x1b[6n\x1b[c\x1bc"), vec![
+            TerminalCommand::Print('a'),
+            TerminalCommand::Bell,
+            TerminalCommand::Backspace,
+            TerminalCommand::HorizontalTab,
+            TerminalCommand::LineFeed,
+            TerminalCommand::LineFeed,
+            TerminalCommand::CarriageReturn,
+            TerminalCommand::Index,
+            TerminalCommand::NextLine,
+            TerminalCommand::SetTabStop,
+            TerminalCommand::ReverseIndex,
+            TerminalCommand::ClearTabStop,
+            TerminalCommand::ClearAllTabStops,
+            TerminalCommand::ReportCursorPosition,
+            TerminalCommand::ReportDeviceAttributes,
+            TerminalCommand::FullReset,
+        ]);
+    }
+
+    #[test]
+    fn sgr() {
+        assert_eq!(decode(b"\x1b[m\x1b[1;38;5;208m"), vec![
+            TerminalCommand::SetGraphicsRendition(vec![SgrAttribute::Reset]),
+            TerminalCommand::SetGraphicsRendition(vec![
+                SgrAttribute::Bold,
+                SgrAttribute::Foreground(TermColor::Indexed(208)),
+            ]),
+        ]);
+    }
+
+    #[test]
+    fn osc() {
+        assert_eq!(decode(b"\x1b]0;a;b\x07\x1b]1;icon\x07\x1b]2;title\x1b\\"), vec![
+            TerminalCommand::SetIconName("a;b".to_string()),
+            TerminalCommand::SetTitle("a;b".to_string()),
+            TerminalCommand::SetIconName("icon".to_string()),
+            TerminalCommand::SetTitle("title".to_string()),
+        ]);
+        assert_eq!(decode(b"\x1b]8;;http://a.b/\x07x\x1b]8;;\x07\x1b]8;id=1:k=v;file:///x;y\x07"), vec![
+            TerminalCommand::SetHyperlink(None, Some("http://a.b/".to_string())),
+            TerminalCommand::Print('x'),
+            TerminalCommand::SetHyperlink(None, None),
+            TerminalCommand::SetHyperlink(Some("1".to_string()), Some("file:///x;y".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn unhandled() {
+        let mut decoder = TerminalDecoder::new(Sink::default());
+        Parser::new().advance_slice(&mut decoder, b"\x1b[>c\x1b[1$p\x1b]52;c;x\x07\x1b(B\x1bPqx\x1b\\\x01a");
+
+        let sink = decoder.into_inner();
+        assert_eq!(sink.commands, vec![TerminalCommand::Print('a')]);
+        assert_eq!(sink.unhandled.len(), 9);
+        assert!(sink.unhandled[0].starts_with("CsiDispatch"));
+        assert!(sink.unhandled[2].starts_with("OscDispatch"));
+        assert!(sink.unhandled[3].starts_with("EscDispatch"));
+        assert_eq!(sink.unhandled[5], "DcsPut(120)");
+        assert_eq!(sink.unhandled[8], "Execute(1)");
+    }
+}
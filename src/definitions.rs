@@ -1,5 +1,20 @@
-#[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+use core::convert::TryFrom;
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// States of the parser
+///
+/// These correspond to the states in Paul Williams' ANSI parser state machine,
+/// with the addition of `Utf8` for decoding multi-byte characters.
+///
+/// The numeric discriminants are part of the stable API since they are used
+/// to encode the state table; see [`unpack`].
+///
+/// [`unpack`]: fn.unpack.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum State {
    Anywhere = 0,
    CsiEntry = 1,
@@ -19,8 +34,14 @@ pub enum State {
    Utf8 = 15,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+/// Actions performed by the parser on state transitions
+///
+/// The numeric discriminants are part of the stable API since they are used
+/// to encode the state table; see [`unpack`].
+///
+/// [`unpack`]: fn.unpack.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Action {
    None = 0,
    Clear = 1,
@@ -40,27 +61,137 @@ pub enum Action {
    BeginUtf8 = 15,
 }
 
+/// Every state, indexed by discriminant
+const STATES: [State; 16] = [
+    State::Anywhere,
+    State::CsiEntry,
+    State::CsiIgnore,
+    State::CsiIntermediate,
+    State::CsiParam,
+    State::DcsEntry,
+    State::DcsIgnore,
+    State::DcsIntermediate,
+    State::DcsParam,
+    State::DcsPassthrough,
+    State::Escape,
+    State::EscapeIntermediate,
+    State::Ground,
+    State::OscString,
+    State::SosPmApcString,
+    State::Utf8,
+];
+
+/// Every action, indexed by discriminant
+const ACTIONS: [Action; 16] = [
+    Action::None,
+    Action::Clear,
+    Action::Collect,
+    Action::CsiDispatch,
+    Action::EscDispatch,
+    Action::Execute,
+    Action::Hook,
+    Action::Ignore,
+    Action::OscEnd,
+    Action::OscPut,
+    Action::OscStart,
+    Action::Param,
+    Action::Print,
+    Action::Put,
+    Action::Unhook,
+    Action::BeginUtf8,
+];
+
+/// A byte which is not the discriminant of any variant
+///
+/// Returned when converting a byte into a [`State`], an [`Action`] or a
+/// [`C0`] with `TryFrom`, holding the byte.
+///
+/// [`State`]: enum.State.html
+/// [`Action`]: enum.Action.html
+/// [`C0`]: enum.C0.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct InvalidVariant(pub u8);
+
+impl fmt::Display for InvalidVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid discriminant 0x{:02x}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for InvalidVariant {}
+
+/// The state with discriminant `byte`, for example from a packed table entry
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use vte::{InvalidVariant, State};
+/// assert_eq!(State::try_from(State::Ground as u8), Ok(State::Ground));
+/// assert_eq!(State::try_from(16), Err(InvalidVariant(16)));
+/// ```
+impl TryFrom<u8> for State {
+    type Error = InvalidVariant;
+
+    #[inline]
+    fn try_from(byte: u8) -> Result<State, InvalidVariant> {
+        STATES.get(byte as usize).copied().ok_or(InvalidVariant(byte))
+    }
+}
+
+/// The action with discriminant `byte`, for example from a packed table entry
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use vte::{Action, InvalidVariant};
+/// assert_eq!(Action::try_from(Action::Print as u8), Ok(Action::Print));
+/// assert_eq!(Action::try_from(0xff), Err(InvalidVariant(0xff)));
+/// ```
+impl TryFrom<u8> for Action {
+    type Error = InvalidVariant;
+
+    #[inline]
+    fn try_from(byte: u8) -> Result<Action, InvalidVariant> {
+        ACTIONS.get(byte as usize).copied().ok_or(InvalidVariant(byte))
+    }
+}
+
 /// Unpack a u8 into a State and Action
 ///
 /// The implementation of this assumes that there are *precisely* 16 variants for both Action and
 /// State. Furthermore, it assumes that the enums are tag-only; that is, there is no data in any
 /// variant.
 ///
-/// Bad things will happen if those invariants are violated.
+/// Bad things will happen if those invariants are violated. Every byte is a
+/// valid table entry; use `TryFrom<u8>` to convert a single discriminant.
+///
+/// # Examples
+///
+/// ```
+/// use vte::{unpack, Action, State};
+///
+/// // Action in the top 4 bits, state in the bottom 4 bits
+/// let byte = ((Action::Execute as u8) << 4) | State::Ground as u8;
+/// assert_eq!(byte, 0x5c);
+///
+/// assert_eq!(unpack(byte), (State::Ground, Action::Execute));
+/// ```
 #[inline(always)]
+#[must_use]
 pub fn unpack(delta: u8) -> (State, Action) {
     (
         // State is stored in bottom 4 bits
-        unsafe { ::std::mem::transmute(delta & 0x0f) },
+        unsafe { ::core::mem::transmute::<u8, State>(delta & 0x0f) },
 
         // Action is stored in top 4 bits
-        unsafe { ::std::mem::transmute(delta >> 4) },
+        unsafe { ::core::mem::transmute::<u8, Action>(delta >> 4) },
     )
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{State, Action, unpack};
+    use core::convert::TryFrom;
+
+    use super::{InvalidVariant, State, Action, unpack};
     #[test]
     fn unpack_state_action() {
         match unpack(0xee) {
@@ -78,4 +209,31 @@ mod tests {
             _ => panic!("unpack failed"),
         }
     }
+
+    #[test]
+    fn try_from_u8() {
+        for byte in 0..=255u8 {
+            match (State::try_from(byte), Action::try_from(byte)) {
+                (Ok(state), Ok(action)) => {
+                    assert!(byte < 16);
+                    assert_eq!(state as u8, byte);
+                    assert_eq!(action as u8, byte);
+                },
+                (state, action) => {
+                    assert!(byte >= 16);
+                    assert_eq!(state, Err(InvalidVariant(byte)));
+                    assert_eq!(action, Err(InvalidVariant(byte)));
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn unpack_every_byte() {
+        for byte in 0..=255u8 {
+            let state = State::try_from(byte & 0x0f).unwrap();
+            let action = Action::try_from(byte >> 4).unwrap();
+            assert_eq!(unpack(byte), (state, action));
+        }
+    }
 }
@@ -0,0 +1,363 @@
+//! Following the cursor of a VT100 compatible terminal
+use {Params, Perform};
+
+/// Distance of the tab stops
+const TAB_WIDTH: u32 = 8;
+
+/// A [`Perform`] which tracks the cursor position of a terminal
+///
+/// Implements the cursor movement of a VT100 on a viewport of `rows` by
+/// `cols` cells, as a starting point for a terminal emulator:
+///
+/// * Printing moves the cursor right and wraps at the end of the line.
+///   Characters passed to `print` are one cell wide and those passed to
+///   `print_wide` take their width.
+/// * CR, LF, VT, FF, BS and HT, which moves to the next multiple of 8.
+/// * CUU, CUD, CUF, CUB, CNL, CPL, CHA, HPA, VPA, CUP and HVP.
+/// * IND, NEL and RI, and DECSC and DECRC for saving the cursor.
+/// * DECSTBM sets the scroll region. LF at its bottom margin and RI at its
+///   top margin scroll the region instead of moving the cursor, and CUU and
+///   CUD stop at the margins when starting inside the region.
+///
+/// The cursor never leaves the viewport; movements past its edges are
+/// clamped. Positions are counted from zero. A resize event changes the
+/// viewport size and resets the scroll region.
+///
+/// ```
+/// # use vte::{LineEditor, Parser};
+/// let mut editor = LineEditor::new(24, 80);
+/// Parser::new().advance_slice(&mut editor, b"\x1b[10;20Hab\r\n\x1b[3A\x1b[100C");
+/// assert_eq!(editor.cursor_position(), (7, 79));
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LineEditor {
+    rows: u32,
+    cols: u32,
+    cursor_row: u32,
+    cursor_col: u32,
+
+    /// The last column has been printed to, so the next character goes to
+    /// the next line
+    wrap_pending: bool,
+    scroll_top: u32,
+    scroll_bottom: u32,
+    saved_cursor: (u32, u32),
+}
+
+impl LineEditor {
+    /// Create an editor for a viewport of `rows` by `cols` cells, with the
+    /// cursor at the top left
+    ///
+    /// A viewport without rows or columns is treated as one cell.
+    #[must_use]
+    pub fn new(rows: u32, cols: u32) -> LineEditor {
+        let rows = rows.max(1);
+        LineEditor {
+            rows,
+            cols: cols.max(1),
+            cursor_row: 0,
+            cursor_col: 0,
+            wrap_pending: false,
+            scroll_top: 0,
+            scroll_bottom: rows - 1,
+            saved_cursor: (0, 0),
+        }
+    }
+
+    /// The row and column of the cursor
+    #[inline]
+    #[must_use]
+    pub fn cursor_position(&self) -> (u32, u32) {
+        (self.cursor_row, self.cursor_col)
+    }
+
+    /// The number of rows and columns of the viewport
+    #[inline]
+    #[must_use]
+    pub fn viewport_size(&self) -> (u32, u32) {
+        (self.rows, self.cols)
+    }
+
+    /// The first and last row of the scroll region
+    #[inline]
+    #[must_use]
+    pub fn scroll_region(&self) -> (u32, u32) {
+        (self.scroll_top, self.scroll_bottom)
+    }
+
+    /// Change the size of the viewport
+    ///
+    /// The cursor is clamped to the new size and the scroll region covers
+    /// all rows again.
+    pub fn resize(&mut self, rows: u32, cols: u32) {
+        let (row, col) = (self.cursor_row, self.cursor_col);
+        *self = LineEditor { saved_cursor: self.saved_cursor, ..LineEditor::new(rows, cols) };
+        self.move_to(row, col);
+    }
+
+    /// Move the cursor to `row` and `col`, clamped to the viewport
+    fn move_to(&mut self, row: u32, col: u32) {
+        self.cursor_row = row.min(self.rows - 1);
+        self.cursor_col = col.min(self.cols - 1);
+        self.wrap_pending = false;
+    }
+
+    fn move_to_col(&mut self, col: u32) {
+        self.move_to(self.cursor_row, col);
+    }
+
+    /// Move up, stopping at the top margin if the cursor is below it
+    fn up(&mut self, count: u32) {
+        let limit = if self.cursor_row >= self.scroll_top { self.scroll_top } else { 0 };
+        let row = self.cursor_row.saturating_sub(count).max(limit);
+        self.move_to(row, self.cursor_col);
+    }
+
+    /// Move down, stopping at the bottom margin if the cursor is above it
+    fn down(&mut self, count: u32) {
+        let limit = if self.cursor_row <= self.scroll_bottom { self.scroll_bottom } else { self.rows - 1 };
+        let row = self.cursor_row.saturating_add(count).min(limit);
+        self.move_to(row, self.cursor_col);
+    }
+
+    /// IND: move down, scrolling at the bottom margin
+    fn index(&mut self) {
+        if self.cursor_row == self.scroll_bottom {
+            self.wrap_pending = false;
+        } else {
+            self.move_to(self.cursor_row + 1, self.cursor_col);
+        }
+    }
+
+    /// RI: move up, scrolling at the top margin
+    fn reverse_index(&mut self) {
+        if self.cursor_row == self.scroll_top {
+            self.wrap_pending = false;
+        } else {
+            self.move_to(self.cursor_row.saturating_sub(1), self.cursor_col);
+        }
+    }
+
+    /// Move past a character `width` cells wide
+    fn advance(&mut self, width: u32) {
+        if width == 0 {
+            return;
+        }
+        if self.wrap_pending {
+            self.index();
+            self.move_to_col(0);
+        }
+
+        let next = self.cursor_col.saturating_add(width);
+        if next >= self.cols {
+            self.cursor_col = self.cols - 1;
+            self.wrap_pending = true;
+        } else {
+            self.cursor_col = next;
+        }
+    }
+}
+
+impl Perform for LineEditor {
+    #[inline]
+    fn print(&mut self, _c: char) {
+        self.advance(1);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, _c: char, cell_width: u8) {
+        self.advance(cell_width.into());
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            0x0a..=0x0c => self.index(),
+            0x0d => self.move_to_col(0),
+            0x08 => {
+                let col = self.cursor_col.saturating_sub(1);
+                self.move_to_col(col);
+            },
+            0x09 => {
+                let col = (self.cursor_col / TAB_WIDTH + 1) * TAB_WIDTH;
+                self.move_to_col(col);
+            },
+            // IND and NEL as C1 controls
+            0x84 => self.index(),
+            0x85 => {
+                self.index();
+                self.move_to_col(0);
+            },
+            0x8d => self.reverse_index(),
+            _ => (),
+        }
+    }
+
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        if ignore || !intermediates.is_empty() || private.is_some() {
+            return;
+        }
+
+        let arg = |index: usize| u32::from(params.get_or_default(index, 0, 1).max(1));
+        match action {
+            'A' => self.up(arg(0)),
+            'B' | 'e' => self.down(arg(0)),
+            'C' | 'a' => self.move_to_col(self.cursor_col.saturating_add(arg(0))),
+            'D' => self.move_to_col(self.cursor_col.saturating_sub(arg(0))),
+            'E' => {
+                self.down(arg(0));
+                self.move_to_col(0);
+            },
+            'F' => {
+                self.up(arg(0));
+                self.move_to_col(0);
+            },
+            'G' | '`' => self.move_to_col(arg(0) - 1),
+            'd' => self.move_to(arg(0) - 1, self.cursor_col),
+            'H' | 'f' => self.move_to(arg(0) - 1, arg(1) - 1),
+            'r' => {
+                let top = arg(0) - 1;
+                let bottom = match params.get_or_default(1, 0, 0) {
+                    0 => self.rows,
+                    bottom => u32::from(bottom).min(self.rows),
+                } - 1;
+                if top < bottom {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                    self.move_to(0, 0);
+                }
+            },
+            's' => self.saved_cursor = (self.cursor_row, self.cursor_col),
+            'u' => self.move_to(self.saved_cursor.0, self.saved_cursor.1),
+            _ => (),
+        }
+    }
+
+    fn esc_dispatch(&mut self, _params: &Params, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if !intermediates.is_empty() {
+            return;
+        }
+
+        match byte {
+            b'D' => self.index(),
+            b'E' => {
+                self.index();
+                self.move_to_col(0);
+            },
+            b'M' => self.reverse_index(),
+            b'7' => self.saved_cursor = (self.cursor_row, self.cursor_col),
+            b'8' => self.move_to(self.saved_cursor.0, self.saved_cursor.1),
+            _ => (),
+        }
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.resize(rows.into(), cols.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineEditor;
+    use {Parser, Perform};
+
+    fn edit(rows: u32, cols: u32, bytes: &[u8]) -> LineEditor {
+        let mut editor = LineEditor::new(rows, cols);
+        Parser::new().advance_slice(&mut editor, bytes);
+        editor
+    }
+
+    #[test]
+    fn cursor_movement() {
+        assert_eq!(edit(24, 80, b"").cursor_position(), (0, 0));
+        assert_eq!(edit(24, 80, b"\x1b[5;10H").cursor_position(), (4, 9));
+        assert_eq!(edit(24, 80, b"\x1b[5;10f\x1b[2A\x1b[3C").cursor_position(), (2, 12));
+        assert_eq!(edit(24, 80, b"\x1b[5;10H\x1b[B\x1b[4D").cursor_position(), (5, 5));
+        assert_eq!(edit(24, 80, b"\x1b[5;10H\x1b[2E").cursor_position(), (6, 0));
+        assert_eq!(edit(24, 80, b"\x1b[5;10H\x1b[0F").cursor_position(), (3, 0));
+        assert_eq!(edit(24, 80, b"\x1b[5;10H\x1b[30G\x1b[12d").cursor_position(), (11, 29));
+        assert_eq!(edit(24, 80, b"\x1b[5;10Habc\x08\x08\t\t").cursor_position(), (4, 24));
+        assert_eq!(edit(24, 80, b"\x1b[5;10H\x1b7\x1b[H\x1b8").cursor_position(), (4, 9));
+        assert_eq!(edit(24, 80, b"\x1b[5;10H\x1b[s\x1b[H\x1b[u").cursor_position(), (4, 9));
+    }
+
+    #[test]
+    fn clamping() {
+        assert_eq!(edit(24, 80, b"\x1b[100;200H").cursor_position(), (23, 79));
+        assert_eq!(edit(24, 80, b"\x1b[100A\x1b[100D\x08").cursor_position(), (0, 0));
+        assert_eq!(edit(24, 80, b"\x1b[100B\x1b[100C\t").cursor_position(), (23, 79));
+        assert_eq!(edit(24, 80, b"\x1b[24H\n\n\x1bD").cursor_position(), (23, 0));
+        assert_eq!(edit(24, 80, b"\x1bM\x1bM").cursor_position(), (0, 0));
+    }
+
+    #[test]
+    fn wrapping() {
+        let editor = edit(3, 4, b"abcd");
+        assert_eq!(editor.cursor_position(), (0, 3));
+        assert_eq!(edit(3, 4, b"abcde").cursor_position(), (1, 1));
+        assert_eq!(edit(3, 4, b"abcd\r").cursor_position(), (0, 0));
+
+        let editor = edit(3, 4, b"abcdefghijklm");
+        assert_eq!(editor.cursor_position(), (2, 1));
+
+        let mut editor = edit(3, 4, b"ab");
+        editor.print_wide('\u{6f22}', 2);
+        assert_eq!(editor.cursor_position(), (0, 3));
+        editor.print_wide('\u{301}', 0);
+        editor.print_wide('\u{5b57}', 2);
+        assert_eq!(editor.cursor_position(), (1, 2));
+    }
+
+    #[test]
+    fn scroll_region() {
+        let editor = edit(24, 80, b"\x1b[5;10H\x1b[10;20r");
+        assert_eq!(editor.scroll_region(), (9, 19));
+        assert_eq!(editor.cursor_position(), (0, 0));
+
+        // Movement inside the region stops at its margins
+        let editor = edit(24, 80, b"\x1b[10;20r\x1b[15H\x1b[100A");
+        assert_eq!(editor.cursor_position(), (9, 0));
+        let editor = edit(24, 80, b"\x1b[10;20r\x1b[15H\x1b[100B\n\n");
+        assert_eq!(editor.cursor_position(), (19, 0));
+        let editor = edit(24, 80, b"\x1b[10;20r\x1b[10H\x1bM\x1bM");
+        assert_eq!(editor.cursor_position(), (9, 0));
+
+        // Outside of it only at the viewport
+        let editor = edit(24, 80, b"\x1b[10;20r\x1b[5H\x1b[100A\x1b[22H\x1b[100B\n");
+        assert_eq!(editor.cursor_position(), (23, 0));
+
+        // Invalid regions are ignored, and an empty one is the whole viewport
+        let editor = edit(24, 80, b"\x1b[10;20r\x1b[5H\x1b[20;10r");
+        assert_eq!(editor.scroll_region(), (9, 19));
+        assert_eq!(editor.cursor_position(), (4, 0));
+        assert_eq!(edit(24, 80, b"\x1b[10;20r\x1b[r").scroll_region(), (0, 23));
+        assert_eq!(edit(24, 80, b"\x1b[10;99r").scroll_region(), (9, 23));
+    }
+
+    #[test]
+    fn replay() {
+        let bytes = b"\x1b[?1049h\x1b[1;24r\x1b[H\x1b[2J\x1b[1mvim\x1b[m\r\n~\r\n~\x1b[24;1H\"file\" 3L, 10B\x1b[1;4H";
+        let mut editor = edit(24, 80, bytes);
+        assert_eq!(editor.cursor_position(), (0, 3));
+        assert_eq!(editor.viewport_size(), (24, 80));
+
+        editor.resize_event(10, 2);
+        assert_eq!(editor.viewport_size(), (10, 2));
+        assert_eq!(editor.cursor_position(), (0, 1));
+        assert_eq!(editor.scroll_region(), (0, 9));
+    }
+}
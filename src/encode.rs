@@ -0,0 +1,284 @@
+//! Encoding control sequences, the inverse of parsing them
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Reason a control sequence could not be encoded
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// Final byte which doesn't end a sequence of this kind
+    FinalByte(u8),
+
+    /// Intermediate byte outside of `0x20..=0x2f`, or a private marker in
+    /// `0x3c..=0x3f` which is not the first intermediate
+    Intermediate(u8),
+
+    /// Empty CSI parameter, which can't be told apart from `0`
+    EmptyParam,
+
+    /// OSC parameter byte which is a control character or `;`
+    OscByte(u8),
+
+    /// OSC string without any parameters
+    NoOscParams,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EncodeError::FinalByte(byte) => write!(f, "invalid final byte 0x{:02x}", byte),
+            EncodeError::Intermediate(byte) => write!(f, "invalid intermediate byte 0x{:02x}", byte),
+            EncodeError::EmptyParam => f.write_str("empty CSI parameter"),
+            EncodeError::OscByte(byte) => write!(f, "invalid OSC parameter byte 0x{:02x}", byte),
+            EncodeError::NoOscParams => f.write_str("OSC string without parameters"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for EncodeError {}
+
+fn is_private_marker(byte: u8) -> bool {
+    matches!(byte, 0x3c..=0x3f)
+}
+
+/// Split CSI intermediates into a leading private marker and the rest
+fn split_intermediates(intermediates: &[u8]) -> Result<(&[u8], &[u8]), EncodeError> {
+    let markers = match intermediates.first() {
+        Some(&byte) if is_private_marker(byte) => 1,
+        _ => 0,
+    };
+    let (markers, rest) = intermediates.split_at(markers);
+
+    match rest.iter().find(|&&byte| !matches!(byte, 0x20..=0x2f)) {
+        Some(&byte) => Err(EncodeError::Intermediate(byte)),
+        None => Ok((markers, rest)),
+    }
+}
+
+fn push_number(bytes: &mut Vec<u8>, mut value: u16) {
+    let mut digits = [0u8; 5];
+    let mut start = digits.len();
+    loop {
+        start -= 1;
+        digits[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    bytes.extend_from_slice(&digits[start..]);
+}
+
+/// Encode a CSI sequence
+///
+/// Every parameter is a slice of its value followed by its subparameters,
+/// like the parameters passed to [`Perform::csi_dispatch`]. Parameters are
+/// separated by `;` and subparameters by `:`.
+///
+/// A private marker (`<`, `=`, `>` or `?`) at the start of `intermediates`
+/// is placed before the parameters and the remaining intermediates after
+/// them. The parser reports the marker as the `private` argument of
+/// [`Perform::csi_dispatch`] and the rest as its intermediates.
+///
+/// ```
+/// let bytes = vte::encode_csi(&[&[38, 5, 196], &[1]], b"", b'm').unwrap();
+/// assert_eq!(bytes, b"\x1b[38:5:196;1m");
+///
+/// let bytes = vte::encode_csi(&[&[25]], b"?", b'h').unwrap();
+/// assert_eq!(bytes, b"\x1b[?25h");
+/// ```
+///
+/// [`Perform::csi_dispatch`]: trait.Perform.html#tymethod.csi_dispatch
+pub fn encode_csi(params: &[&[u16]], intermediates: &[u8], final_byte: u8) -> Result<Vec<u8>, EncodeError> {
+    if !matches!(final_byte, 0x40..=0x7e) {
+        return Err(EncodeError::FinalByte(final_byte));
+    }
+    let (markers, intermediates) = split_intermediates(intermediates)?;
+
+    let mut bytes = Vec::with_capacity(4 + markers.len() + intermediates.len() + params.len() * 4);
+    bytes.extend_from_slice(b"\x1b[");
+    bytes.extend_from_slice(markers);
+
+    for (index, param) in params.iter().enumerate() {
+        if param.is_empty() {
+            return Err(EncodeError::EmptyParam);
+        }
+        if index > 0 {
+            bytes.push(b';');
+        }
+
+        for (sub_index, &value) in param.iter().enumerate() {
+            if sub_index > 0 {
+                bytes.push(b':');
+            }
+            push_number(&mut bytes, value);
+        }
+    }
+
+    bytes.extend_from_slice(intermediates);
+    bytes.push(final_byte);
+    Ok(bytes)
+}
+
+/// Encode an OSC string, terminated by `ST`
+///
+/// Parameters are separated by `;`, so they may not contain it; control
+/// characters are not allowed either.
+///
+/// ```
+/// let bytes = vte::encode_osc(&[b"2", b"title"]).unwrap();
+/// assert_eq!(bytes, b"\x1b]2;title\x1b\\");
+/// ```
+pub fn encode_osc(params: &[&[u8]]) -> Result<Vec<u8>, EncodeError> {
+    if params.is_empty() {
+        return Err(EncodeError::NoOscParams);
+    }
+
+    let len = params.iter().map(|param| param.len() + 1).sum::<usize>();
+    let mut bytes = Vec::with_capacity(len + 4);
+    bytes.extend_from_slice(b"\x1b]");
+
+    for (index, param) in params.iter().enumerate() {
+        if let Some(&byte) = param.iter().find(|&&byte| byte < 0x20 || byte == b';') {
+            return Err(EncodeError::OscByte(byte));
+        }
+        if index > 0 {
+            bytes.push(b';');
+        }
+        bytes.extend_from_slice(param);
+    }
+
+    bytes.extend_from_slice(b"\x1b\\");
+    Ok(bytes)
+}
+
+/// Encode an escape sequence
+///
+/// Final bytes which introduce a control string or CSI sequence (`P`, `X`,
+/// `[`, `]`, `^` and `_`) are only allowed after an intermediate.
+///
+/// ```
+/// let bytes = vte::encode_esc(b"(", b'B').unwrap();
+/// assert_eq!(bytes, b"\x1b(B");
+/// ```
+pub fn encode_esc(intermediates: &[u8], final_byte: u8) -> Result<Vec<u8>, EncodeError> {
+    let introducer = matches!(final_byte, b'P' | b'X' | b'[' | b']' | b'^' | b'_');
+    if !matches!(final_byte, 0x30..=0x7e) || (introducer && intermediates.is_empty()) {
+        return Err(EncodeError::FinalByte(final_byte));
+    }
+    if let Some(&byte) = intermediates.iter().find(|&&byte| !matches!(byte, 0x20..=0x2f)) {
+        return Err(EncodeError::Intermediate(byte));
+    }
+
+    let mut bytes = Vec::with_capacity(intermediates.len() + 2);
+    bytes.push(0x1b);
+    bytes.extend_from_slice(intermediates);
+    bytes.push(final_byte);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_csi, encode_esc, encode_osc, EncodeError};
+    use test_utils::{RecordedEvent, RecordingPerform};
+    use Parser;
+
+    fn parse(bytes: &[u8]) -> Vec<RecordedEvent> {
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_slice(&mut recorder, bytes);
+        recorder.events().iter()
+            .filter(|event| !matches!(**event, RecordedEvent::StateChange { .. }))
+            .cloned()
+            .collect()
+    }
+
+    type CsiCase<'a> = (&'a [&'a [u16]], &'a [u8], u8, Option<u8>);
+
+    #[test]
+    fn csi_round_trip() {
+        let cases: &[CsiCase] = &[
+            (&[], b"", b'm', None),
+            (&[&[0]], b"", b'm', None),
+            (&[&[1], &[38, 2, 0, 255, 128, 0], &[65535]], b"", b'm', None),
+            (&[&[25]], b"?", b'h', Some(b'?')),
+            (&[&[2]], b" ", b'q', None),
+            (&[&[1], &[2]], b">$", b'p', Some(b'>')),
+        ];
+
+        for &(params, intermediates, final_byte, marker) in cases {
+            let bytes = encode_csi(params, intermediates, final_byte).unwrap();
+            match parse(&bytes)[..] {
+                [RecordedEvent::CsiDispatch { params: ref parsed, intermediates: ref parsed_intermediates, ignore, final_byte: c, private }] => {
+                    assert_eq!(parsed.iter().collect::<Vec<_>>(), params);
+                    assert_eq!(&parsed_intermediates[..], &intermediates[marker.iter().count()..]);
+                    assert_eq!(private, marker);
+                    assert!(!ignore);
+                    assert_eq!(c, final_byte as char);
+                },
+                ref events => panic!("unexpected events {:?} for {:?}", events, bytes),
+            }
+        }
+    }
+
+    #[test]
+    fn csi_errors() {
+        assert_eq!(encode_csi(&[], b"", 0x7f), Err(EncodeError::FinalByte(0x7f)));
+        assert_eq!(encode_csi(&[], b"", b'0'), Err(EncodeError::FinalByte(b'0')));
+        assert_eq!(encode_csi(&[], b" ?", b'm'), Err(EncodeError::Intermediate(b'?')));
+        assert_eq!(encode_csi(&[], b"?>", b'c'), Err(EncodeError::Intermediate(b'>')));
+        assert_eq!(encode_csi(&[], b"1", b'm'), Err(EncodeError::Intermediate(b'1')));
+        assert_eq!(encode_csi(&[&[1], &[]], b"", b'm'), Err(EncodeError::EmptyParam));
+    }
+
+    #[test]
+    fn osc_round_trip() {
+        let cases: &[&[&[u8]]] = &[
+            &[b""],
+            &[b"0", b"title"],
+            &[b"8", b"", b"https://example.com"],
+            &[b"2", "t\u{ee}tle".as_bytes()],
+        ];
+
+        for &params in cases {
+            let bytes = encode_osc(params).unwrap();
+            match parse(&bytes)[..] {
+                [RecordedEvent::OscDispatch { params: ref parsed, bell_terminated: false }, RecordedEvent::EscDispatch { byte: b'\\', .. }] => {
+                    assert_eq!(parsed, params);
+                },
+                ref events => panic!("unexpected events {:?} for {:?}", events, bytes),
+            }
+        }
+    }
+
+    #[test]
+    fn osc_errors() {
+        assert_eq!(encode_osc(&[]), Err(EncodeError::NoOscParams));
+        assert_eq!(encode_osc(&[b"0", b"a;b"]), Err(EncodeError::OscByte(b';')));
+        assert_eq!(encode_osc(&[b"0", b"a\x07"]), Err(EncodeError::OscByte(0x07)));
+    }
+
+    #[test]
+    fn esc_round_trip() {
+        let cases: &[(&[u8], u8)] = &[(b"", b'7'), (b"", b'c'), (b"(", b'B'), (b"#", b'8'), (b" ", b'P')];
+
+        for &(intermediates, final_byte) in cases {
+            let bytes = encode_esc(intermediates, final_byte).unwrap();
+            match parse(&bytes)[..] {
+                [RecordedEvent::EscDispatch { ref params, intermediates: ref parsed, ignore: false, byte }] => {
+                    assert!(params.is_empty());
+                    assert_eq!(&parsed[..], intermediates);
+                    assert_eq!(byte, final_byte);
+                },
+                ref events => panic!("unexpected events {:?} for {:?}", events, bytes),
+            }
+        }
+    }
+
+    #[test]
+    fn esc_errors() {
+        assert_eq!(encode_esc(b"", b'['), Err(EncodeError::FinalByte(b'[')));
+        assert_eq!(encode_esc(b"", 0x7f), Err(EncodeError::FinalByte(0x7f)));
+        assert_eq!(encode_esc(b"", b'/'), Err(EncodeError::FinalByte(b'/')));
+        assert_eq!(encode_esc(b"0", b'B'), Err(EncodeError::Intermediate(b'0')));
+    }
+}
@@ -0,0 +1,508 @@
+//! Owned representation of the actions performed by the parser
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use {Params, Perform, State, C0};
+
+/// An action performed by the parser
+///
+/// Every variant mirrors a method of [`Perform`], with borrowed arguments
+/// copied into owned values.
+///
+/// With the `serde` feature, `Print` is serialized as the codepoint of the
+/// character and byte strings as base64 strings in human-readable formats.
+///
+/// [`Perform`]: trait.Perform.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VteEvent {
+    /// See [`Perform::print`](trait.Perform.html#tymethod.print)
+    Print(#[cfg_attr(feature = "serde", serde(with = "::serde_impls::codepoint"))] char),
+
+    /// See [`Perform::execute`](trait.Perform.html#tymethod.execute)
+    Execute(u8),
+
+    /// See [`Perform::hook`](trait.Perform.html#tymethod.hook)
+    DcsHook {
+        params: Params,
+        #[cfg_attr(feature = "serde", serde(with = "::serde_impls::bytes"))]
+        intermediates: Vec<u8>,
+        ignore: bool,
+        final_byte: u8,
+    },
+
+    /// See [`Perform::put`](trait.Perform.html#tymethod.put)
+    DcsPut(u8),
+
+    /// See [`Perform::unhook`](trait.Perform.html#tymethod.unhook)
+    DcsUnhook,
+
+    /// See [`Perform::osc_dispatch`](trait.Perform.html#tymethod.osc_dispatch)
+    OscDispatch {
+        #[cfg_attr(feature = "serde", serde(with = "::serde_impls::byte_lists"))]
+        params: Vec<Vec<u8>>,
+        bell_terminated: bool,
+    },
+
+    /// See [`Perform::sos_dispatch`](trait.Perform.html#method.sos_dispatch)
+    SosDispatch(#[cfg_attr(feature = "serde", serde(with = "::serde_impls::bytes"))] Vec<u8>),
+
+    /// See [`Perform::pm_dispatch`](trait.Perform.html#method.pm_dispatch)
+    PmDispatch(#[cfg_attr(feature = "serde", serde(with = "::serde_impls::bytes"))] Vec<u8>),
+
+    /// See [`Perform::apc_dispatch`](trait.Perform.html#method.apc_dispatch)
+    ApcDispatch(#[cfg_attr(feature = "serde", serde(with = "::serde_impls::bytes"))] Vec<u8>),
+
+    /// See [`Perform::csi_dispatch`](trait.Perform.html#tymethod.csi_dispatch)
+    CsiDispatch {
+        params: Params,
+        #[cfg_attr(feature = "serde", serde(with = "::serde_impls::bytes"))]
+        intermediates: Vec<u8>,
+        ignore: bool,
+        final_byte: char,
+        private: Option<u8>,
+    },
+
+    /// See [`Perform::esc_dispatch`](trait.Perform.html#tymethod.esc_dispatch)
+    EscDispatch {
+        params: Params,
+        #[cfg_attr(feature = "serde", serde(with = "::serde_impls::bytes"))]
+        intermediates: Vec<u8>,
+        ignore: bool,
+        byte: u8,
+    },
+
+    /// See [`Perform::on_state_change`](trait.Perform.html#method.on_state_change)
+    StateChange {
+        from: State,
+        to: State,
+    },
+
+    /// See [`Perform::error`](trait.Perform.html#method.error)
+    Error(u8),
+}
+
+impl VteEvent {
+    /// Call the `Perform` method the event was recorded from
+    ///
+    /// CR, LF, BS, HT and BEL are passed to the dedicated methods like the
+    /// parser does, so recorded events can be replayed to any `Perform`.
+    pub fn replay<P: Perform + ?Sized>(&self, performer: &mut P) {
+        match *self {
+            VteEvent::Print(c) => performer.print(c),
+            VteEvent::Execute(0x07) => performer.bell(),
+            VteEvent::Execute(0x08) => performer.backspace(),
+            VteEvent::Execute(0x09) => performer.tab(),
+            VteEvent::Execute(0x0a) => performer.linefeed(),
+            VteEvent::Execute(0x0d) => performer.carriage_return(),
+            VteEvent::Execute(byte) => match C0::try_from(byte) {
+                Ok(c0) => performer.execute_c0(c0),
+                Err(_) => performer.execute(byte),
+            },
+            VteEvent::DcsHook { ref params, ref intermediates, ignore, final_byte } => {
+                performer.hook(params, intermediates, ignore, final_byte);
+            },
+            VteEvent::DcsPut(byte) => performer.put(byte),
+            VteEvent::DcsUnhook => performer.unhook(),
+            VteEvent::OscDispatch { ref params, bell_terminated } => {
+                let params: Vec<&[u8]> = params.iter().map(|param| &param[..]).collect();
+                performer.osc_dispatch(&params, bell_terminated);
+            },
+            VteEvent::SosDispatch(ref bytes) => performer.sos_dispatch(bytes),
+            VteEvent::PmDispatch(ref bytes) => performer.pm_dispatch(bytes),
+            VteEvent::ApcDispatch(ref bytes) => performer.apc_dispatch(bytes),
+            VteEvent::CsiDispatch { ref params, ref intermediates, ignore, final_byte, private } => {
+                performer.csi_dispatch(params, intermediates, ignore, final_byte, private);
+            },
+            VteEvent::EscDispatch { ref params, ref intermediates, ignore, byte } => {
+                performer.esc_dispatch(params, intermediates, ignore, byte);
+            },
+            VteEvent::StateChange { from, to } => performer.on_state_change(from, to),
+            VteEvent::Error(byte) => performer.error(byte),
+        }
+    }
+}
+
+/// Writes the escape sequence which causes the event
+///
+/// Bytes outside of printable ASCII are written as `\x` and two hex digits,
+/// like `\x1b` for `ESC`, and `\` as `\\`, so the result is readable.
+/// Printed characters outside of ASCII are written as they are. Unescaped,
+/// the bytes parse back into the same event, except for `ignore` flags and
+/// DCS data, which depend on the surrounding bytes.
+/// OSC strings keep their terminator and the other strings end with `ST`.
+/// `StateChange` writes nothing and `Error` the byte which caused it.
+///
+/// ```
+/// # use vte::{Parser, VteEvent};
+/// let events: Vec<_> = Parser::new().parse(b"\x1b[?25h\x1b]0;title\x07")
+///     .filter(|event| !matches!(*event, VteEvent::StateChange { .. }))
+///     .map(|event| event.to_string())
+///     .collect();
+/// assert_eq!(events, [r"\x1b[?25h", r"\x1b]0;title\x07"]);
+/// ```
+impl fmt::Display for VteEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VteEvent::Print(c) if c.is_ascii() => write_byte(f, c as u8),
+            VteEvent::Print(c) => f.write_char(c),
+            VteEvent::Execute(byte) | VteEvent::DcsPut(byte) | VteEvent::Error(byte) => write_byte(f, byte),
+            VteEvent::DcsHook { ref params, ref intermediates, final_byte, .. } => {
+                f.write_str("\\x1bP")?;
+                write_params(f, params)?;
+                write_bytes(f, intermediates)?;
+                write_byte(f, final_byte)
+            },
+            VteEvent::DcsUnhook => f.write_str("\\x1b\\\\"),
+            VteEvent::OscDispatch { ref params, bell_terminated } => {
+                f.write_str("\\x1b]")?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(';')?;
+                    }
+                    write_bytes(f, param)?;
+                }
+                f.write_str(if bell_terminated { "\\x07" } else { "\\x1b\\\\" })
+            },
+            VteEvent::SosDispatch(ref bytes) => write_string(f, 'X', bytes),
+            VteEvent::PmDispatch(ref bytes) => write_string(f, '^', bytes),
+            VteEvent::ApcDispatch(ref bytes) => write_string(f, '_', bytes),
+            VteEvent::CsiDispatch { ref params, ref intermediates, final_byte, private, .. } => {
+                f.write_str("\\x1b[")?;
+                if let Some(private) = private {
+                    write_byte(f, private)?;
+                }
+                write_params(f, params)?;
+                write_bytes(f, intermediates)?;
+                f.write_char(final_byte)
+            },
+            VteEvent::EscDispatch { ref intermediates, byte, .. } => {
+                f.write_str("\\x1b")?;
+                write_bytes(f, intermediates)?;
+                write_byte(f, byte)
+            },
+            VteEvent::StateChange { .. } => Ok(()),
+        }
+    }
+}
+
+fn write_byte(f: &mut fmt::Formatter, byte: u8) -> fmt::Result {
+    match byte {
+        b'\\' => f.write_str("\\\\"),
+        0x20..=0x7e => f.write_char(byte as char),
+        _ => write!(f, "\\x{:02x}", byte),
+    }
+}
+
+fn write_bytes(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+    bytes.iter().try_for_each(|&byte| write_byte(f, byte))
+}
+
+fn write_params(f: &mut fmt::Formatter, params: &Params) -> fmt::Result {
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            f.write_char(';')?;
+        }
+        for (j, value) in param.iter().enumerate() {
+            if j > 0 {
+                f.write_char(':')?;
+            }
+            write!(f, "{}", value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write an SOS, PM or APC string
+fn write_string(f: &mut fmt::Formatter, introducer: char, bytes: &[u8]) -> fmt::Result {
+    write!(f, "\\x1b{}", introducer)?;
+    write_bytes(f, bytes)?;
+    f.write_str("\\x1b\\\\")
+}
+
+/// A `VteEvent` with the offset of the byte which caused it
+///
+/// Returned by [`Parser::parse_with_positions`].
+///
+/// [`Parser::parse_with_positions`]: struct.Parser.html#method.parse_with_positions
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParsedEvent {
+    /// The action
+    pub event: VteEvent,
+
+    /// Offset of the final byte of the action within everything passed to
+    /// the parser, see [`Parser::byte_position`]
+    ///
+    /// [`Parser::byte_position`]: struct.Parser.html#method.byte_position
+    pub byte_position: u64,
+}
+
+/// A `Perform` which records every action as a `VteEvent`
+///
+/// This is used by [`Parser::parse`] and is available for testing through
+/// the `test_utils` module.
+///
+/// [`Parser::parse`]: ../struct.Parser.html#method.parse
+#[derive(Debug, Default, Clone)]
+pub struct RecordingPerform {
+    pub(crate) events: Vec<VteEvent>,
+}
+
+impl Perform for RecordingPerform {
+    fn print(&mut self, c: char) {
+        self.events.push(VteEvent::Print(c));
+    }
+
+    fn execute(&mut self, byte: u8) {
+        self.events.push(VteEvent::Execute(byte));
+    }
+
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.events.push(VteEvent::DcsHook {
+            params: *params,
+            intermediates: intermediates.to_vec(),
+            ignore,
+            final_byte,
+        });
+    }
+
+    fn put(&mut self, byte: u8) {
+        self.events.push(VteEvent::DcsPut(byte));
+    }
+
+    fn unhook(&mut self) {
+        self.events.push(VteEvent::DcsUnhook);
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.events.push(VteEvent::OscDispatch {
+            params: params.iter().map(|param| param.to_vec()).collect(),
+            bell_terminated,
+        });
+    }
+
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.events.push(VteEvent::SosDispatch(bytes.to_vec()));
+    }
+
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.events.push(VteEvent::PmDispatch(bytes.to_vec()));
+    }
+
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.events.push(VteEvent::ApcDispatch(bytes.to_vec()));
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.events.push(VteEvent::CsiDispatch {
+            params: *params,
+            intermediates: intermediates.to_vec(),
+            ignore,
+            final_byte: action,
+            private,
+        });
+    }
+
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.events.push(VteEvent::EscDispatch {
+            params: *params,
+            intermediates: intermediates.to_vec(),
+            ignore,
+            byte,
+        });
+    }
+
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.events.push(VteEvent::StateChange { from, to });
+    }
+
+    fn error(&mut self, byte: u8) {
+        self.events.push(VteEvent::Error(byte));
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::{ParsedEvent, RecordingPerform, VteEvent};
+    use {Params, Parser, State};
+
+    /// Undo the escaping of `VteEvent`'s `Display`
+    fn unescape(text: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut rest = text.as_bytes();
+        while let Some((&byte, tail)) = rest.split_first() {
+            rest = match (byte, tail) {
+                (b'\\', [b'\\', tail @ ..]) => {
+                    bytes.push(b'\\');
+                    tail
+                },
+                (b'\\', [b'x', tail @ ..]) => {
+                    let hex = core::str::from_utf8(&tail[..2]).unwrap();
+                    bytes.push(u8::from_str_radix(hex, 16).unwrap());
+                    &tail[2..]
+                },
+                _ => {
+                    bytes.push(byte);
+                    tail
+                },
+            };
+        }
+        bytes
+    }
+
+    /// Parse `bytes`, dropping state changes and the `ST` ending strings
+    fn parse_events(bytes: &[u8]) -> Vec<VteEvent> {
+        let mut events: Vec<_> = Parser::new().parse(bytes)
+            .filter(|event| !matches!(*event, VteEvent::StateChange { .. }))
+            .collect();
+        if events.len() > 1 && events.last().unwrap().to_string() == "\\x1b\\\\" {
+            events.pop();
+        }
+        events
+    }
+
+    #[test]
+    fn parse_collects_events() {
+        let mut parser = Parser::new();
+        let events: Vec<_> = parser.parse(b"a\x1b[4m\x1b]0;t\x07")
+            .filter(|event| !matches!(*event, VteEvent::StateChange { .. }))
+            .collect();
+
+        let mut params = Params::new();
+        params.push(4);
+
+        assert_eq!(events, vec![
+            VteEvent::Print('a'),
+            VteEvent::CsiDispatch {
+                params,
+                intermediates: vec![],
+                ignore: false,
+                final_byte: 'm',
+                private: None,
+            },
+            VteEvent::OscDispatch {
+                params: vec![b"0".to_vec(), b"t".to_vec()],
+                bell_terminated: true,
+            },
+        ]);
+    }
+
+    #[test]
+    fn parse_records_dcs_and_state_changes() {
+        let mut parser = Parser::new();
+        let events: Vec<_> = parser.parse(b"\x1bPqx\x1b\\").collect();
+
+        assert_eq!(events, vec![
+            VteEvent::StateChange { from: State::Ground, to: State::Escape },
+            VteEvent::StateChange { from: State::Escape, to: State::DcsEntry },
+            VteEvent::DcsHook { params: Params::new(), intermediates: vec![], ignore: false, final_byte: b'q' },
+            VteEvent::StateChange { from: State::DcsEntry, to: State::DcsPassthrough },
+            VteEvent::DcsPut(b'x'),
+            VteEvent::DcsUnhook,
+            VteEvent::StateChange { from: State::DcsPassthrough, to: State::Escape },
+            VteEvent::EscDispatch {
+                params: Params::new(),
+                intermediates: vec![],
+                ignore: false,
+                byte: b'\\',
+            },
+            VteEvent::StateChange { from: State::Escape, to: State::Ground },
+        ]);
+    }
+
+    #[test]
+    fn replay() {
+        let input = b"a\r\n\x1b[1;2m\x1b]0;t\x07\x1bPqx\x1b\\\x1b_apc\x1b\\\x1b7\x07\xe2\x94";
+        let events: Vec<_> = Parser::new().parse(input).collect();
+
+        let mut recorder = RecordingPerform::default();
+        for event in &events {
+            event.replay(&mut recorder);
+        }
+        assert_eq!(recorder.events, events);
+    }
+
+    #[test]
+    fn display() {
+        let sequences: &[(&[u8], &str)] = &[
+            (b"\x1b[3m", r"\x1b[3m"),
+            (b"\x1b[1;38:2::255:0:0m", r"\x1b[1;38:2:0:255:0:0m"),
+            (b"\x1b[m", r"\x1b[m"),
+            (b"\x1b[?25h", r"\x1b[?25h"),
+            (b"\x1b[?1049l", r"\x1b[?1049l"),
+            (b"\x1b[>4;2m", r"\x1b[>4;2m"),
+            (b"\x1b[10;20H", r"\x1b[10;20H"),
+            (b"\x1b[2J", r"\x1b[2J"),
+            (b"\x1b[2 q", r"\x1b[2 q"),
+            (b"\x1b[!p", r"\x1b[!p"),
+            (b"\x1b]0;title\x07", r"\x1b]0;title\x07"),
+            (b"\x1b]2;a;b\x1b\\", r"\x1b]2;a;b\x1b\\"),
+            (b"\x1b]8;;http://example.com\x1b\\", r"\x1b]8;;http://example.com\x1b\\"),
+            (b"\x1b]52;c;dGV4dA==\x07", r"\x1b]52;c;dGV4dA==\x07"),
+            (b"\x1b]0;\xe2\x94\x80\x07", r"\x1b]0;\xe2\x94\x80\x07"),
+            (b"\x1b(B", r"\x1b(B"),
+            (b"\x1b7\x1b8", r"\x1b7\x1b8"),
+            (b"\x1bM", r"\x1bM"),
+            (b"\x1bPq#0!3~\x1b\\", r"\x1bPq#0!3~\x1b\\"),
+            (b"\x1bP1$r0m\x1b\\", r"\x1bP1$r0m\x1b\\"),
+            (b"\x1b_Gf=1;AAAA\x1b\\", r"\x1b_Gf=1;AAAA\x1b\\"),
+            (b"\x1b^private\x1b\\", r"\x1b^private\x1b\\"),
+            (b"\x1bXstring\x1b\\", r"\x1bXstring\x1b\\"),
+            (b"a\\b \xe2\x94\x80", r"a\\b ─"),
+            (b"\r\n\t\x08\x07", r"\x0d\x0a\x09\x08\x07"),
+        ];
+
+        for &(input, expected) in sequences {
+            let events = parse_events(input);
+            let display: String = events.iter().map(|event| event.to_string()).collect();
+            assert_eq!(display, expected, "{:?}", events);
+            assert_eq!(parse_events(&unescape(&display)), events, "{}", display);
+        }
+    }
+
+    #[test]
+    fn parse_with_positions() {
+        let mut parser = Parser::new();
+        let events: Vec<_> = parser.parse_with_positions(b"ab\x1b[4")
+            .map(|parsed| parsed.byte_position)
+            .collect();
+        assert_eq!(events, [0, 1, 2, 3, 4]);
+
+        // Positions continue across calls
+        let events: Vec<_> = parser.parse_with_positions(b"m\xe2\x94\x80")
+            .filter(|parsed| !matches!(parsed.event, VteEvent::StateChange { .. }))
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].byte_position, 5);
+        assert_eq!(events[1], ParsedEvent { event: VteEvent::Print('─'), byte_position: 8 });
+    }
+
+    #[test]
+    fn parse_keeps_partial_sequences() {
+        let mut parser = Parser::new();
+        assert_eq!(parser.parse(b"\x1b[3").count(), 3);
+        assert_eq!(parser.current_state(), State::CsiParam);
+
+        let events: Vec<_> = parser.parse(b"A").collect();
+        assert_eq!(events.len(), 2);
+        match events[0] {
+            VteEvent::CsiDispatch { ref params, final_byte: 'A', .. } => {
+                assert_eq!(params.get(0), Some(&[3][..]));
+            },
+            ref event => panic!("unexpected event {:?}", event),
+        }
+    }
+}
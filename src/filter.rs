@@ -0,0 +1,686 @@
+//! Dropping actions before they reach a `Perform` implementation
+use {Params, Perform, State, C0};
+
+/// An action performed by the parser, borrowing its arguments
+///
+/// This mirrors `VteEvent` without copying any data, so it is available
+/// without the `alloc` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VteEventRef<'a> {
+    /// See [`Perform::print`](trait.Perform.html#tymethod.print)
+    Print(char),
+
+    /// See [`Perform::execute`](trait.Perform.html#tymethod.execute), and
+    /// the named controls like [`Perform::bell`](trait.Perform.html#method.bell)
+    Execute(u8),
+
+    /// See [`Perform::hook`](trait.Perform.html#tymethod.hook)
+    DcsHook {
+        params: &'a Params,
+        intermediates: &'a [u8],
+        ignore: bool,
+        final_byte: u8,
+    },
+
+    /// See [`Perform::put`](trait.Perform.html#tymethod.put)
+    DcsPut(u8),
+
+    /// See [`Perform::unhook`](trait.Perform.html#tymethod.unhook)
+    DcsUnhook,
+
+    /// See [`Perform::osc_dispatch`](trait.Perform.html#tymethod.osc_dispatch)
+    OscDispatch {
+        params: &'a [&'a [u8]],
+        bell_terminated: bool,
+    },
+
+    /// See [`Perform::sos_dispatch`](trait.Perform.html#method.sos_dispatch)
+    SosDispatch(&'a [u8]),
+
+    /// See [`Perform::pm_dispatch`](trait.Perform.html#method.pm_dispatch)
+    PmDispatch(&'a [u8]),
+
+    /// See [`Perform::apc_dispatch`](trait.Perform.html#method.apc_dispatch)
+    ApcDispatch(&'a [u8]),
+
+    /// See [`Perform::csi_dispatch`](trait.Perform.html#tymethod.csi_dispatch)
+    CsiDispatch {
+        params: &'a Params,
+        intermediates: &'a [u8],
+        ignore: bool,
+        final_byte: char,
+        private: Option<u8>,
+    },
+
+    /// See [`Perform::esc_dispatch`](trait.Perform.html#tymethod.esc_dispatch)
+    EscDispatch {
+        params: &'a Params,
+        intermediates: &'a [u8],
+        ignore: bool,
+        byte: u8,
+    },
+
+    /// See [`Perform::on_state_change`](trait.Perform.html#method.on_state_change)
+    StateChange {
+        from: State,
+        to: State,
+    },
+
+    /// See [`Perform::error`](trait.Perform.html#method.error)
+    Error(u8),
+}
+
+/// A [`Perform`] which only forwards the actions accepted by a filter
+///
+/// Every action is passed to the filter as a [`VteEventRef`] first and is
+/// dropped if the filter returns `false`.
+///
+/// A complete device control string is forwarded through `dcs_dispatch` if
+/// the filter accepts its `DcsHook`, every `DcsPut` and the `DcsUnhook`.
+/// Otherwise only the accepted parts are forwarded through `hook`, `put` and
+/// `unhook`, just like a string which isn't buffered.
+///
+/// ```
+/// # use vte::{FilterPerform, Params, Parser, Perform, VteEventRef};
+/// # struct Printer(String);
+/// # impl Perform for Printer {
+/// #     fn print(&mut self, c: char) { self.0.push(c); }
+/// #     fn execute(&mut self, _byte: u8) {}
+/// #     fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+/// #     fn put(&mut self, _byte: u8) {}
+/// #     fn unhook(&mut self) {}
+/// #     fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+/// #     fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+/// #     fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// # }
+/// let mut performer = FilterPerform::new(Printer(String::new()), |event: &VteEventRef| {
+///     *event != VteEventRef::Print('b')
+/// });
+/// Parser::new().advance_slice(&mut performer, b"abc");
+/// assert_eq!(performer.into_inner().0, "ac");
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`VteEventRef`]: enum.VteEventRef.html
+#[derive(Clone)]
+pub struct FilterPerform<P: Perform, F: Fn(&VteEventRef) -> bool> {
+    performer: P,
+    filter: F,
+}
+
+impl<P: Perform, F: Fn(&VteEventRef) -> bool> FilterPerform<P, F> {
+    /// Wrap `performer`, forwarding only the actions accepted by `filter`
+    #[inline]
+    #[must_use]
+    pub fn new(performer: P, filter: F) -> FilterPerform<P, F> {
+        FilterPerform { performer, filter }
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+
+    #[inline]
+    fn accepts(&self, event: VteEventRef) -> bool {
+        (self.filter)(&event)
+    }
+}
+
+impl<P: Perform, F: Fn(&VteEventRef) -> bool> Perform for FilterPerform<P, F> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        if self.accepts(VteEventRef::Print(c)) {
+            self.performer.print(c);
+        }
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        if self.accepts(VteEventRef::Print(c)) {
+            self.performer.print_wide(c, cell_width);
+        }
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        if self.accepts(VteEventRef::Execute(byte)) {
+            self.performer.execute(byte);
+        }
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        if self.accepts(VteEventRef::Execute(c0 as u8)) {
+            self.performer.execute_c0(c0);
+        }
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        if self.accepts(VteEventRef::Execute(0x0d)) {
+            self.performer.carriage_return();
+        }
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        if self.accepts(VteEventRef::Execute(0x0a)) {
+            self.performer.linefeed();
+        }
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        if self.accepts(VteEventRef::Execute(0x08)) {
+            self.performer.backspace();
+        }
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        if self.accepts(VteEventRef::Execute(0x09)) {
+            self.performer.tab();
+        }
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        if self.accepts(VteEventRef::Execute(0x07)) {
+            self.performer.bell();
+        }
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        if self.accepts(VteEventRef::DcsHook { params, intermediates, ignore, final_byte }) {
+            self.performer.hook(params, intermediates, ignore, final_byte);
+        }
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        if self.accepts(VteEventRef::DcsPut(byte)) {
+            self.performer.put(byte);
+        }
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        if self.accepts(VteEventRef::DcsUnhook) {
+            self.performer.unhook();
+        }
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        let complete = self.accepts(VteEventRef::DcsHook { params, intermediates, ignore, final_byte })
+            && data.iter().all(|&byte| self.accepts(VteEventRef::DcsPut(byte)))
+            && self.accepts(VteEventRef::DcsUnhook);
+
+        if complete {
+            self.performer.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+        } else {
+            self.hook(params, intermediates, ignore, final_byte);
+            for &byte in data {
+                self.put(byte);
+            }
+            self.unhook();
+        }
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        if self.accepts(VteEventRef::OscDispatch { params, bell_terminated }) {
+            self.performer.osc_dispatch(params, bell_terminated);
+        }
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        if self.accepts(VteEventRef::SosDispatch(bytes)) {
+            self.performer.sos_dispatch(bytes);
+        }
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        if self.accepts(VteEventRef::PmDispatch(bytes)) {
+            self.performer.pm_dispatch(bytes);
+        }
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        if self.accepts(VteEventRef::ApcDispatch(bytes)) {
+            self.performer.apc_dispatch(bytes);
+        }
+    }
+
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        let event = VteEventRef::CsiDispatch { params, intermediates, ignore, final_byte: action, private };
+        if self.accepts(event) {
+            self.performer.csi_dispatch(params, intermediates, ignore, action, private);
+        }
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        if self.accepts(VteEventRef::EscDispatch { params, intermediates, ignore, byte }) {
+            self.performer.esc_dispatch(params, intermediates, ignore, byte);
+        }
+    }
+
+    /// Not a parser action, so it is always passed on
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        if self.accepts(VteEventRef::StateChange { from, to }) {
+            self.performer.on_state_change(from, to);
+        }
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        if self.accepts(VteEventRef::Error(byte)) {
+            self.performer.error(byte);
+        }
+    }
+}
+
+/// What a [`ConditionalEscapeStrip`] does with an action
+///
+/// [`ConditionalEscapeStrip`]: struct.ConditionalEscapeStrip.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StripAction {
+    /// Forward the action
+    PassThrough,
+
+    /// Drop the action
+    Strip,
+}
+
+/// A [`Perform`] which drops the actions a predicate strips
+///
+/// This is a [`FilterPerform`] with a predicate returning a [`StripAction`],
+/// and forwards device control strings the same way. [`strip_sgr`],
+/// [`strip_osc`] and [`strip_all_escapes`] create it with predicates for
+/// common cases.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// # use vte::{ConditionalEscapeStrip, Parser, TextStripper};
+/// let mut performer = ConditionalEscapeStrip::strip_all_escapes(TextStripper::new());
+/// Parser::new().advance_slice(&mut performer, b"\x1b[1mbold\x1b[m\r\n");
+/// assert_eq!(performer.inner().text(), "bold\r\n");
+/// # }
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`FilterPerform`]: struct.FilterPerform.html
+/// [`StripAction`]: enum.StripAction.html
+/// [`strip_sgr`]: struct.ConditionalEscapeStrip.html#method.strip_sgr
+/// [`strip_osc`]: struct.ConditionalEscapeStrip.html#method.strip_osc
+/// [`strip_all_escapes`]: struct.ConditionalEscapeStrip.html#method.strip_all_escapes
+#[derive(Clone)]
+pub struct ConditionalEscapeStrip<P: Perform, F: Fn(&VteEventRef) -> StripAction> {
+    performer: P,
+    predicate: F,
+}
+
+impl<P: Perform> ConditionalEscapeStrip<P, fn(&VteEventRef) -> StripAction> {
+    /// Wrap `performer`, stripping SGR sequences (`CSI Pm m`)
+    ///
+    /// Other CSI sequences ending in `m`, which have a private marker or
+    /// intermediates, are forwarded.
+    #[must_use]
+    pub fn strip_sgr(performer: P) -> ConditionalEscapeStrip<P, fn(&VteEventRef) -> StripAction> {
+        ConditionalEscapeStrip::new(performer, |event| match *event {
+            VteEventRef::CsiDispatch { intermediates: &[], final_byte: 'm', private: None, .. } => {
+                StripAction::Strip
+            },
+            _ => StripAction::PassThrough,
+        })
+    }
+
+    /// Wrap `performer`, stripping OSC strings
+    #[must_use]
+    pub fn strip_osc(performer: P) -> ConditionalEscapeStrip<P, fn(&VteEventRef) -> StripAction> {
+        ConditionalEscapeStrip::new(performer, |event| match *event {
+            VteEventRef::OscDispatch { .. } => StripAction::Strip,
+            _ => StripAction::PassThrough,
+        })
+    }
+
+    /// Wrap `performer`, stripping all escape sequences and strings
+    ///
+    /// Printed characters, controls, state changes and errors are forwarded.
+    #[must_use]
+    pub fn strip_all_escapes(performer: P) -> ConditionalEscapeStrip<P, fn(&VteEventRef) -> StripAction> {
+        ConditionalEscapeStrip::new(performer, |event| match *event {
+            VteEventRef::Print(_)
+            | VteEventRef::Execute(_)
+            | VteEventRef::StateChange { .. }
+            | VteEventRef::Error(_) => StripAction::PassThrough,
+            _ => StripAction::Strip,
+        })
+    }
+}
+
+impl<P: Perform, F: Fn(&VteEventRef) -> StripAction> ConditionalEscapeStrip<P, F> {
+    /// Wrap `performer`, dropping the actions `predicate` strips
+    #[inline]
+    #[must_use]
+    pub fn new(performer: P, predicate: F) -> ConditionalEscapeStrip<P, F> {
+        ConditionalEscapeStrip { performer, predicate }
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+
+    /// A `FilterPerform` of the wrapped `Perform` for forwarding an action
+    #[inline]
+    fn filter(&mut self) -> FilterPerform<&mut P, impl Fn(&VteEventRef) -> bool + '_> {
+        let ConditionalEscapeStrip { ref mut performer, ref predicate } = *self;
+        FilterPerform::new(performer, move |event| predicate(event) == StripAction::PassThrough)
+    }
+}
+
+impl<P: Perform, F: Fn(&VteEventRef) -> StripAction> Perform for ConditionalEscapeStrip<P, F> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.filter().print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.filter().print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        self.filter().execute(byte);
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        self.filter().execute_c0(c0);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.filter().carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.filter().linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        self.filter().backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        self.filter().tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.filter().bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.filter().hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.filter().put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.filter().unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.filter().dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.filter().osc_dispatch(params, bell_terminated);
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.filter().sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.filter().pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.filter().apc_dispatch(bytes);
+    }
+
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.filter().csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.filter().esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.filter().resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.filter().on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.filter().error(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConditionalEscapeStrip, FilterPerform, StripAction, VteEventRef};
+    use test_utils::{RecordedEvent, RecordingPerform};
+    use tests::describe;
+    use {Parser, Perform};
+
+    static INPUT: &[u8] = b"a\x1b]0;title\x07b\x1b[4m\x1b]8;;http://example.com\x1b\\c\x1bPqxy\x1b\\";
+
+    fn filtered<F: Fn(&VteEventRef) -> bool>(filter: F) -> FilterPerform<RecordingPerform, F> {
+        let mut performer = FilterPerform::new(RecordingPerform::new(), filter);
+        Parser::new().advance_slice(&mut performer, INPUT);
+        performer
+    }
+
+    #[test]
+    fn drops_osc() {
+        let performer = filtered(|e| !matches!(e, VteEventRef::OscDispatch { .. }));
+        let events = performer.inner().events();
+        assert!(!events.iter().any(|e| matches!(*e, RecordedEvent::OscDispatch { .. })));
+
+        // Everything else is forwarded unchanged
+        let mut expected = RecordingPerform::new();
+        Parser::new().advance_slice(&mut expected, INPUT);
+        let expected: Vec<_> = expected.events().iter()
+            .filter(|e| !matches!(**e, RecordedEvent::OscDispatch { .. }))
+            .cloned()
+            .collect();
+        assert_eq!(events, &expected[..]);
+    }
+
+    #[test]
+    fn filters_dcs_parts() {
+        let performer = filtered(|e| *e != VteEventRef::DcsPut(b'x'));
+        let calls = describe(performer.inner().events());
+        assert!(calls.contains(&"put 79".to_string()));
+        assert!(!calls.contains(&"put 78".to_string()));
+        assert!(calls.contains(&"unhook".to_string()));
+    }
+
+    #[test]
+    fn accepting_everything_forwards_everything() {
+        let performer = filtered(|_| true);
+
+        let mut expected = RecordingPerform::new();
+        Parser::new().advance_slice(&mut expected, INPUT);
+        assert_eq!(performer.inner().events(), expected.events());
+    }
+
+    fn stripped<P: Perform>(performer: &mut P) {
+        Parser::new().advance_slice(performer, INPUT);
+    }
+
+    #[test]
+    fn strip_sgr() {
+        let mut performer = ConditionalEscapeStrip::strip_sgr(RecordingPerform::new());
+        stripped(&mut performer);
+        Parser::new().advance_slice(&mut performer, b"\x1b[>4;2m\x1b[1;31m");
+        assert_eq!(describe(performer.inner().events()), vec![
+            "print 'a'",
+            "osc_dispatch [[48], [116, 105, 116, 108, 101]] true",
+            "print 'b'",
+            "osc_dispatch [[56], [], [104, 116, 116, 112, 58, 47, 47, 101, 120, 97, 109, 112, 108, 101, 46, 99, 111, 109]] false",
+            "esc_dispatch [] [] false 5c",
+            "print 'c'",
+            "hook [] [] false 'q'",
+            "put 78",
+            "put 79",
+            "unhook",
+            "esc_dispatch [] [] false 5c",
+            "csi_dispatch [[4], [2]] [] false 'm' private '>'",
+        ]);
+    }
+
+    #[test]
+    fn strip_osc() {
+        let mut performer = ConditionalEscapeStrip::strip_osc(RecordingPerform::new());
+        stripped(&mut performer);
+        let events = performer.inner().events();
+        assert!(!events.iter().any(|e| matches!(*e, RecordedEvent::OscDispatch { .. })));
+        assert!(events.iter().any(|e| matches!(*e, RecordedEvent::CsiDispatch { .. })));
+
+        // The same as filtering them out
+        let filter = filtered(|e| !matches!(e, VteEventRef::OscDispatch { .. }));
+        assert_eq!(events, filter.inner().events());
+    }
+
+    #[test]
+    fn strip_all_escapes() {
+        let mut performer = ConditionalEscapeStrip::strip_all_escapes(RecordingPerform::new());
+        stripped(&mut performer);
+        Parser::new().advance_slice(&mut performer, b"\x1b_apc\x1b\\\x07\r\x1b7");
+        assert_eq!(describe(performer.inner().events()), vec![
+            "print 'a'",
+            "print 'b'",
+            "print 'c'",
+            "execute 07",
+            "execute 0d",
+        ]);
+    }
+
+    #[test]
+    fn custom_predicate() {
+        let mut performer = ConditionalEscapeStrip::new(RecordingPerform::new(), |e: &VteEventRef| match *e {
+            VteEventRef::Print(c) if c != 'b' => StripAction::Strip,
+            _ => StripAction::PassThrough,
+        });
+        Parser::new().advance_slice(&mut performer, b"abc\x1b[m");
+        assert_eq!(describe(performer.inner().events()), vec!["print 'b'", "csi_dispatch [] [] false 'm'"]);
+    }
+}
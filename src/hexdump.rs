@@ -0,0 +1,356 @@
+//! Writing every action of the parser as an annotated line
+use std::fmt::{self, Write as FmtWrite};
+use std::io::{self, Write};
+
+use {Params, Parser, Perform};
+
+/// A [`Perform`] which writes a line for every action, for debugging
+///
+/// Each line starts with the name of the action, followed by its arguments,
+/// the position of the byte which triggered it and the bytes it was parsed
+/// from, separated by `|`:
+///
+/// ```text
+/// PRINT|U+0041 'A'|@ byte 5|41
+/// CSI_DISPATCH|params=[1]|inter=[]|private=none|ignore=false|final=0x6D|@ byte 9|1B 5B 31 6D
+/// ```
+///
+/// Positions and bytes are only known for input passed to [`advance`]; when
+/// the parser is advanced directly, the position is that of the last byte
+/// passed to `advance` and the bytes are left empty. Strings are quoted with
+/// anything but printable ASCII escaped, including `|`. State changes aren't
+/// written.
+///
+/// `Perform` methods can't fail, so the first error returned by the writer
+/// is kept and later lines are dropped; check [`io_error`] before calling
+/// [`finish`].
+///
+/// ```
+/// # use vte::{HexdumpPerform, Parser};
+/// let mut hexdump = HexdumpPerform::new(Vec::new());
+/// hexdump.advance(&mut Parser::new(), b"a\x1b[1m");
+///
+/// let output = String::from_utf8(hexdump.finish()).unwrap();
+/// assert_eq!(output, "PRINT|U+0061 'a'|@ byte 0|61\n\
+///     CSI_DISPATCH|params=[1]|inter=[]|private=none|ignore=false|final=0x6D|@ byte 4|1B 5B 31 6D\n");
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`advance`]: struct.HexdumpPerform.html#method.advance
+/// [`io_error`]: struct.HexdumpPerform.html#method.io_error
+/// [`finish`]: struct.HexdumpPerform.html#method.finish
+pub struct HexdumpPerform<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+
+    /// Position of the byte being parsed
+    position: u64,
+
+    /// Bytes parsed since the last line
+    pending: Vec<u8>,
+
+    /// Reused for formatting lines
+    buffer: String,
+}
+
+impl<W: Write> HexdumpPerform<W> {
+    /// Write the lines to `writer`
+    #[must_use]
+    pub fn new(writer: W) -> HexdumpPerform<W> {
+        HexdumpPerform {
+            writer,
+            error: None,
+            position: 0,
+            pending: Vec::new(),
+            buffer: String::new(),
+        }
+    }
+
+    /// Advance `parser` with `bytes`, writing the lines with their positions
+    /// and bytes
+    pub fn advance(&mut self, parser: &mut Parser, bytes: &[u8]) {
+        for &byte in bytes {
+            self.position = parser.byte_position();
+            self.pending.push(byte);
+            parser.advance(self, byte);
+        }
+    }
+
+    /// The first error returned by the writer, if any
+    #[inline]
+    #[must_use]
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Stop writing and return the writer
+    #[inline]
+    #[must_use]
+    pub fn finish(self) -> W {
+        self.writer
+    }
+
+    /// Write a line with `fields`, followed by the position and bytes
+    fn line(&mut self, fields: fmt::Arguments) {
+        if self.error.is_some() {
+            return;
+        }
+
+        self.buffer.clear();
+        let _ = write!(self.buffer, "{}|@ byte {}|", fields, self.position);
+        for (i, byte) in self.pending.drain(..).enumerate() {
+            if i > 0 {
+                self.buffer.push(' ');
+            }
+            let _ = write!(self.buffer, "{:02X}", byte);
+        }
+
+        if let Err(error) = writeln!(self.writer, "{}", self.buffer) {
+            self.error = Some(error);
+        }
+    }
+}
+
+/// Parameters as they are written in a sequence, like `[1;2:3]`
+struct ParamList<'a>(&'a Params);
+
+impl<'a> fmt::Display for ParamList<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, param) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_char(';')?;
+            }
+            for (j, value) in param.iter().enumerate() {
+                if j > 0 {
+                    f.write_char(':')?;
+                }
+                write!(f, "{}", value)?;
+            }
+        }
+        f.write_char(']')
+    }
+}
+
+/// Bytes in hex, like `[0x20 0x28]`
+struct ByteList<'a>(&'a [u8]);
+
+impl<'a> fmt::Display for ByteList<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+            write!(f, "0x{:02X}", byte)?;
+        }
+        f.write_char(']')
+    }
+}
+
+/// A quoted string, escaping `"`, `\`, `|` and anything but printable ASCII
+struct Text<'a>(&'a [u8]);
+
+impl<'a> fmt::Display for Text<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('"')?;
+        for &byte in self.0 {
+            match byte {
+                b'"' | b'\\' => write!(f, "\\{}", byte as char)?,
+                b'|' => f.write_str("\\x7c")?,
+                0x20..=0x7e => f.write_char(byte as char)?,
+                _ => write!(f, "\\x{:02x}", byte)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+/// OSC parameters as quoted strings, like `["0";"title"]`
+struct TextList<'a>(&'a [&'a [u8]]);
+
+impl<'a> fmt::Display for TextList<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, param) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_char(';')?;
+            }
+            write!(f, "{}", Text(param))?;
+        }
+        f.write_char(']')
+    }
+}
+
+impl<W: Write> Perform for HexdumpPerform<W> {
+    fn print(&mut self, c: char) {
+        self.line(format_args!("PRINT|U+{:04X} {:?}", c as u32, c));
+    }
+
+    fn execute(&mut self, byte: u8) {
+        self.line(format_args!("EXECUTE|0x{:02X}", byte));
+    }
+
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.line(format_args!(
+            "HOOK|params={}|inter={}|ignore={}|final=0x{:02X}",
+            ParamList(params),
+            ByteList(intermediates),
+            ignore,
+            final_byte,
+        ));
+    }
+
+    fn put(&mut self, byte: u8) {
+        self.line(format_args!("PUT|0x{:02X}", byte));
+    }
+
+    fn unhook(&mut self) {
+        self.line(format_args!("UNHOOK"));
+    }
+
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.line(format_args!(
+            "DCS_DISPATCH|params={}|inter={}|ignore={}|final=0x{:02X}|data={}",
+            ParamList(params),
+            ByteList(intermediates),
+            ignore,
+            final_byte,
+            Text(data),
+        ));
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.line(format_args!("OSC_DISPATCH|params={}|bell={}", TextList(params), bell_terminated));
+    }
+
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.line(format_args!("SOS_DISPATCH|data={}", Text(bytes)));
+    }
+
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.line(format_args!("PM_DISPATCH|data={}", Text(bytes)));
+    }
+
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.line(format_args!("APC_DISPATCH|data={}", Text(bytes)));
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        let private = match private {
+            Some(marker) => format!("0x{:02X}", marker),
+            None => "none".into(),
+        };
+        self.line(format_args!(
+            "CSI_DISPATCH|params={}|inter={}|private={}|ignore={}|final=0x{:02X}",
+            ParamList(params),
+            ByteList(intermediates),
+            private,
+            ignore,
+            action as u32,
+        ));
+    }
+
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.line(format_args!(
+            "ESC_DISPATCH|params={}|inter={}|ignore={}|final=0x{:02X}",
+            ParamList(params),
+            ByteList(intermediates),
+            ignore,
+            byte,
+        ));
+    }
+
+    fn error(&mut self, byte: u8) {
+        self.line(format_args!("ERROR|0x{:02X}", byte));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use super::HexdumpPerform;
+    use Parser;
+
+    fn dump(bytes: &[u8]) -> Vec<String> {
+        let mut hexdump = HexdumpPerform::new(Vec::new());
+        hexdump.advance(&mut Parser::new(), bytes);
+        assert!(hexdump.io_error().is_none());
+        String::from_utf8(hexdump.finish()).unwrap().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(dump(b"A\xc3\xa9\r\x1b(B\x1b[?25;1:2h"), vec![
+            "PRINT|U+0041 'A'|@ byte 0|41",
+            "PRINT|U+00E9 'é'|@ byte 2|C3 A9",
+            "EXECUTE|0x0D|@ byte 3|0D",
+            "ESC_DISPATCH|params=[]|inter=[0x28]|ignore=false|final=0x42|@ byte 6|1B 28 42",
+            "CSI_DISPATCH|params=[25;1:2]|inter=[]|private=0x3F|ignore=false|final=0x68|@ byte 16|1B 5B 3F 32 35 3B 31 3A 32 68",
+        ]);
+    }
+
+    #[test]
+    fn strings() {
+        let lines = dump(b"\x1b]0;a|b\"\x07\x1b_x\\y\x1b\\");
+        assert_eq!(lines, vec![
+            "OSC_DISPATCH|params=[\"0\";\"a\\x7cb\\\"\"]|bell=true|@ byte 8|1B 5D 30 3B 61 7C 62 22 07",
+            "APC_DISPATCH|data=\"x\\\\y\"|@ byte 14|1B 5F 78 5C 79 1B",
+            "ESC_DISPATCH|params=[]|inter=[]|ignore=false|final=0x5C|@ byte 15|5C",
+        ]);
+
+        // The `|` in the title doesn't split it
+        assert_eq!(lines[0].split('|').count(), 5);
+    }
+
+    #[test]
+    fn dcs() {
+        assert_eq!(dump(b"\x1bP1$qm\x1b\\"), vec![
+            "HOOK|params=[1]|inter=[0x24]|ignore=false|final=0x71|@ byte 4|1B 50 31 24 71",
+            "PUT|0x6D|@ byte 5|6D",
+            "UNHOOK|@ byte 6|1B",
+            "ESC_DISPATCH|params=[]|inter=[]|ignore=false|final=0x5C|@ byte 7|5C",
+        ]);
+    }
+
+    #[test]
+    fn without_advance() {
+        let mut hexdump = HexdumpPerform::new(Vec::new());
+        Parser::new().advance_slice(&mut hexdump, b"ab");
+        assert_eq!(hexdump.finish(), b"PRINT|U+0061 'a'|@ byte 0|\nPRINT|U+0062 'b'|@ byte 0|\n");
+    }
+
+    struct Failing;
+
+    impl Write for Failing {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("failed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn keeps_first_error() {
+        let mut hexdump = HexdumpPerform::new(Failing);
+        hexdump.advance(&mut Parser::new(), b"ab");
+        assert_eq!(hexdump.io_error().unwrap().to_string(), "failed");
+    }
+}
@@ -0,0 +1,263 @@
+//! Adapters for driving a [`Parser`] from `std::io` pipelines
+//!
+//! [`Parser`]: ../struct.Parser.html
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use std::task::{Context, Poll};
+
+#[cfg(feature = "tokio")]
+use tokio::io::AsyncWrite;
+
+use {Parser, Perform};
+
+/// Advance `parser` with `buf`, turning a panic of `performer` into an error
+fn advance<P: Perform>(parser: &mut Parser, performer: &mut P, buf: &[u8]) -> io::Result<usize> {
+    panic::catch_unwind(AssertUnwindSafe(|| parser.advance_slice(performer, buf)))
+        .map(|_| buf.len())
+        .map_err(|_| io::Error::other("Perform implementation panicked"))
+}
+
+/// Feeds all bytes written to it through a [`Parser`]
+///
+/// This allows hooking a parser up to anything that deals in [`Write`] sinks,
+/// for example `io::copy` or a `BufWriter`. Bytes are processed immediately
+/// on every `write` call; nothing is buffered by the writer itself.
+///
+/// [`Parser`]: ../struct.Parser.html
+/// [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+pub struct ParserWriter<P: Perform> {
+    parser: Parser,
+    performer: P,
+}
+
+impl<P: Perform> ParserWriter<P> {
+    /// Create a new writer dispatching to `performer` through a fresh parser
+    #[inline]
+    #[must_use]
+    pub fn new(performer: P) -> ParserWriter<P> {
+        ParserWriter::with_parser(Parser::new(), performer)
+    }
+
+    /// Create a new writer from an existing parser
+    #[must_use]
+    pub fn with_parser(parser: Parser, performer: P) -> ParserWriter<P> {
+        ParserWriter {
+            parser,
+            performer,
+        }
+    }
+
+    /// Get a reference to the parser
+    #[inline]
+    #[must_use]
+    pub fn parser(&self) -> &Parser {
+        &self.parser
+    }
+
+    /// Get a reference to the performer
+    #[inline]
+    #[must_use]
+    pub fn performer(&self) -> &P {
+        &self.performer
+    }
+
+    /// Get a mutable reference to the performer
+    #[inline]
+    pub fn performer_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Consume the writer, returning the parser and performer
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> (Parser, P) {
+        (self.parser, self.performer)
+    }
+}
+
+impl<P: Perform> Write for ParserWriter<P> {
+    /// Advance the parser with all of `buf`
+    ///
+    /// A panic in the [`Perform`] implementation is caught and reported as an
+    /// error. The parser may be left in the middle of a sequence afterwards.
+    ///
+    /// [`Perform`]: ../trait.Perform.html
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        advance(&mut self.parser, &mut self.performer, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Feeds all bytes written to it through a [`Parser`], asynchronously
+///
+/// This is the [`AsyncWrite`] counterpart of [`ParserWriter`], available
+/// with the `tokio` feature. Parsing never blocks, so every write completes
+/// immediately.
+///
+/// Shutting the writer down calls [`Parser::flush`], cancelling any
+/// unfinished sequence at the end of the stream. Flushing does not, since
+/// `tokio::io::copy` flushes whenever the reader has no data ready, which
+/// would cancel sequences split across reads.
+///
+/// [`Parser`]: ../struct.Parser.html
+/// [`Parser::flush`]: ../struct.Parser.html#method.flush
+/// [`ParserWriter`]: struct.ParserWriter.html
+/// [`AsyncWrite`]: https://docs.rs/tokio/1/tokio/io/trait.AsyncWrite.html
+#[cfg(feature = "tokio")]
+pub struct AsyncParserWriter<P: Perform + Send> {
+    parser: Parser,
+    performer: P,
+}
+
+#[cfg(feature = "tokio")]
+impl<P: Perform + Send> AsyncParserWriter<P> {
+    /// Create a new writer dispatching to `performer` through a fresh parser
+    #[inline]
+    #[must_use]
+    pub fn new(performer: P) -> AsyncParserWriter<P> {
+        AsyncParserWriter::with_parser(Parser::new(), performer)
+    }
+
+    /// Create a new writer from an existing parser
+    #[must_use]
+    pub fn with_parser(parser: Parser, performer: P) -> AsyncParserWriter<P> {
+        AsyncParserWriter {
+            parser,
+            performer,
+        }
+    }
+
+    /// Get a reference to the parser
+    #[inline]
+    #[must_use]
+    pub fn parser(&self) -> &Parser {
+        &self.parser
+    }
+
+    /// Get a reference to the performer
+    #[inline]
+    #[must_use]
+    pub fn performer(&self) -> &P {
+        &self.performer
+    }
+
+    /// Get a mutable reference to the performer
+    #[inline]
+    pub fn performer_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Consume the writer, returning the parser and performer
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> (Parser, P) {
+        (self.parser, self.performer)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<P: Perform + Send + Unpin> AsyncWrite for AsyncParserWriter<P> {
+    /// Advance the parser with all of `buf`
+    ///
+    /// A panic in the [`Perform`] implementation is caught and reported as an
+    /// error, like it is by [`ParserWriter`].
+    ///
+    /// [`Perform`]: ../trait.Perform.html
+    /// [`ParserWriter`]: struct.ParserWriter.html
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let writer = self.get_mut();
+        Poll::Ready(advance(&mut writer.parser, &mut writer.performer, buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<io::Result<()>> {
+        let writer = self.get_mut();
+        writer.parser.flush(&mut writer.performer);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufWriter, Write};
+
+    use super::ParserWriter;
+    use test_utils::RecordingPerform;
+    use tests::describe;
+    use {Params, Perform};
+
+    #[test]
+    fn pipe_through_buf_writer() {
+        let mut writer = BufWriter::new(ParserWriter::new(RecordingPerform::new()));
+        writer.write_all(b"hi\x1b[4").unwrap();
+        writer.write_all(b"m\x1b]0;title\x07").unwrap();
+
+        let writer = writer.into_inner().ok().unwrap();
+        let (_, recorder) = writer.into_inner();
+
+        assert_eq!(describe(recorder.events()), vec![
+            "print 'h'",
+            "print 'i'",
+            "csi_dispatch [[4]] [] false 'm'",
+            "osc_dispatch [[48], [116, 105, 116, 108, 101]] true",
+        ]);
+    }
+
+    /// Panics on every print
+    struct Panicking;
+
+    impl Perform for Panicking {
+        fn print(&mut self, _: char) { panic!("print"); }
+        fn execute(&mut self, _byte: u8) {}
+        fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+        fn put(&mut self, _byte: u8) {}
+        fn unhook(&mut self) {}
+        fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+        fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+        fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+    }
+
+    #[test]
+    fn perform_panic_is_error() {
+        let mut writer = ParserWriter::new(Panicking);
+        assert!(writer.write(b"\x1b[m").is_ok());
+        assert!(writer.write(b"a").is_err());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn async_writer() {
+        use tokio;
+        use tokio::io::AsyncWriteExt;
+
+        use super::AsyncParserWriter;
+        use test_utils::RecordedEvent;
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let mut writer = AsyncParserWriter::new(RecordingPerform::new());
+        let mut input: &[u8] = b"hi\x1b[4m\x1b]0;title\x07\x1b[1";
+        runtime.block_on(tokio::io::copy(&mut input, &mut writer)).unwrap();
+
+        // Only shutting down cancels the unfinished sequence
+        assert!(writer.performer().events().iter().all(|e| *e != RecordedEvent::Execute(0x18)));
+        runtime.block_on(writer.shutdown()).unwrap();
+        let recorder = writer.into_inner().1;
+
+        assert_eq!(describe(recorder.events()), vec![
+            "print 'h'",
+            "print 'i'",
+            "csi_dispatch [[4]] [] false 'm'",
+            "osc_dispatch [[48], [116, 105, 116, 108, 101]] true",
+            "execute 18",
+        ]);
+    }
+}
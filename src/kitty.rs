@@ -0,0 +1,407 @@
+//! Key events of the kitty keyboard protocol
+//!
+//! With the protocol enabled, terminals report keys as CSI sequences like
+//! `CSI key-code:shifted-key:base-layout-key ; modifiers:event-type ; text u`.
+//! [`KittyKeyEvent::parse`] decodes the parameters of such a sequence and
+//! [`KittyKeyEvent::encode`] produces one.
+//!
+//! Functional keys which keep their legacy encoding, like `CSI 1 ; 5 A` for
+//! ctrl+up or `CSI 3 ~` for delete, use the key codes from the private use
+//! area that the protocol assigns to them, so `key_code` identifies a key
+//! the same way for every final byte.
+//!
+//! Parameter values are limited to `u16::MAX` by [`Params`], so key codes and
+//! text outside of the basic multilingual plane can be encoded but not
+//! decoded.
+//!
+//! [`KittyKeyEvent::parse`]: struct.KittyKeyEvent.html#method.parse
+//! [`KittyKeyEvent::encode`]: struct.KittyKeyEvent.html#method.encode
+//! [`Params`]: ../struct.Params.html
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops;
+
+use Params;
+
+/// Functional keys with a legacy encoding, as key code, number before `~`
+/// and final byte of the `CSI 1 ; modifiers X` form
+///
+/// A key has either of the forms; zero marks the one it doesn't have.
+static LEGACY_KEYS: &[(u32, u32, u8)] = &[
+    (57348, 2, 0),     // Insert
+    (57349, 3, 0),     // Delete
+    (57350, 0, b'D'),  // Left
+    (57351, 0, b'C'),  // Right
+    (57352, 0, b'A'),  // Up
+    (57353, 0, b'B'),  // Down
+    (57354, 5, 0),     // Page up
+    (57355, 6, 0),     // Page down
+    (57356, 0, b'H'),  // Home
+    (57357, 0, b'F'),  // End
+    (57364, 0, b'P'),  // F1
+    (57365, 0, b'Q'),  // F2
+    (57366, 13, 0),    // F3
+    (57367, 0, b'S'),  // F4
+    (57368, 15, 0),    // F5
+    (57369, 17, 0),    // F6
+    (57370, 18, 0),    // F7
+    (57371, 19, 0),    // F8
+    (57372, 20, 0),    // F9
+    (57373, 21, 0),    // F10
+    (57374, 23, 0),    // F11
+    (57375, 24, 0),    // F12
+    (57427, 0, b'E'),  // Keypad begin
+];
+
+/// Alternative `~` forms of keys which are encoded with a final letter
+static TILDE_ALIASES: &[(u32, u32)] = &[
+    (7, 57356),   // Home
+    (8, 57357),   // End
+    (11, 57364),  // F1
+    (12, 57365),  // F2
+    (14, 57367),  // F4
+];
+
+/// Modifier keys which were held during a key event
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct KittyModifiers(u8);
+
+impl KittyModifiers {
+    pub const SHIFT: KittyModifiers = KittyModifiers(1);
+    pub const ALT: KittyModifiers = KittyModifiers(1 << 1);
+    pub const CTRL: KittyModifiers = KittyModifiers(1 << 2);
+    pub const SUPER: KittyModifiers = KittyModifiers(1 << 3);
+    pub const HYPER: KittyModifiers = KittyModifiers(1 << 4);
+    pub const META: KittyModifiers = KittyModifiers(1 << 5);
+    pub const CAPS_LOCK: KittyModifiers = KittyModifiers(1 << 6);
+    pub const NUM_LOCK: KittyModifiers = KittyModifiers(1 << 7);
+
+    /// No modifiers
+    #[inline]
+    #[must_use]
+    pub fn empty() -> KittyModifiers {
+        KittyModifiers(0)
+    }
+
+    /// Modifiers from the bit mask used by the protocol
+    #[inline]
+    #[must_use]
+    pub fn from_bits(bits: u8) -> KittyModifiers {
+        KittyModifiers(bits)
+    }
+
+    /// The bit mask used by the protocol, one less than the encoded value
+    #[inline]
+    #[must_use]
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// No modifier is set
+    #[inline]
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// All modifiers of `other` are set
+    #[inline]
+    #[must_use]
+    pub fn contains(self, other: KittyModifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for KittyModifiers {
+    type Output = KittyModifiers;
+
+    fn bitor(self, other: KittyModifiers) -> KittyModifiers {
+        KittyModifiers(self.0 | other.0)
+    }
+}
+
+/// Kind of a key event
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum KittyEventType {
+    #[default]
+    Press,
+    Repeat,
+    Release,
+}
+
+impl KittyEventType {
+    fn from_param(value: u16) -> Option<KittyEventType> {
+        match value {
+            0 | 1 => Some(KittyEventType::Press),
+            2 => Some(KittyEventType::Repeat),
+            3 => Some(KittyEventType::Release),
+            _ => None,
+        }
+    }
+
+    fn to_param(self) -> u32 {
+        match self {
+            KittyEventType::Press => 1,
+            KittyEventType::Repeat => 2,
+            KittyEventType::Release => 3,
+        }
+    }
+}
+
+/// A key event reported with the kitty keyboard protocol
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct KittyKeyEvent {
+    /// Unicode codepoint of the key, or the code the protocol assigns to a
+    /// functional key
+    pub key_code: u32,
+
+    /// Key code with shift held, if reported
+    pub shifted_key: Option<u32>,
+
+    /// Key code of the key in the standard US layout, if reported
+    pub base_layout_key: Option<u32>,
+
+    /// Modifiers held during the event
+    pub modifiers: KittyModifiers,
+
+    /// Press, repeat or release
+    pub event_type: KittyEventType,
+
+    /// Text generated by the event, if reported
+    pub text: Option<String>,
+}
+
+impl KittyKeyEvent {
+    /// Decode the parameters and final byte of a CSI sequence
+    ///
+    /// Returns `None` if the sequence is not a key event. Sequences with
+    /// a private marker or intermediates never are, so they should not be
+    /// passed in.
+    ///
+    /// ```
+    /// # use vte::kitty::{KittyEventType, KittyKeyEvent, KittyModifiers};
+    /// # use vte::Params;
+    /// # fn handle(params: &Params, action: char) {
+    /// if let Some(event) = KittyKeyEvent::parse(params, action as u8) {
+    ///     if event.key_code == u32::from('c') && event.modifiers.contains(KittyModifiers::CTRL) {
+    ///         // Interrupt
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn parse(params: &Params, final_byte: u8) -> Option<KittyKeyEvent> {
+        let key = params.get(0).unwrap_or(&[]);
+        let first = key.first().map(|&code| u32::from(code));
+
+        let key_code = match final_byte {
+            b'u' => first?,
+            b'~' => legacy_tilde_key(first?)?,
+            byte => {
+                // Only `1` or nothing may precede the final letter
+                if first.is_some_and(|code| code != 1) {
+                    return None;
+                }
+                legacy_letter_key(byte)?
+            },
+        };
+
+        let alternate = |index: usize| key.get(index).cloned().filter(|&code| code != 0).map(u32::from);
+
+        let modifiers_param = params.get(1).unwrap_or(&[]);
+        let modifiers = match modifiers_param.first() {
+            Some(&value) if value > 256 => return None,
+            Some(&value) => KittyModifiers(value.saturating_sub(1) as u8),
+            None => KittyModifiers::empty(),
+        };
+        let event_type = KittyEventType::from_param(modifiers_param.get(1).cloned().unwrap_or(1))?;
+
+        let text = match params.get(2) {
+            Some(codepoints) if final_byte == b'u' => {
+                Some(codepoints.iter().map(|&c| char::from_u32(u32::from(c))).collect::<Option<String>>()?)
+            },
+            Some(_) => return None,
+            None => None,
+        };
+
+        if params.len() > 3 {
+            return None;
+        }
+
+        Some(KittyKeyEvent {
+            key_code,
+            shifted_key: alternate(1),
+            base_layout_key: alternate(2),
+            modifiers,
+            event_type,
+            text,
+        })
+    }
+
+    /// Encode the event as a CSI sequence
+    ///
+    /// Functional keys are encoded in their legacy form, everything else
+    /// with the `u` final byte. Default parameters at the end are omitted.
+    ///
+    /// ```
+    /// # use vte::kitty::{KittyKeyEvent, KittyModifiers};
+    /// let event = KittyKeyEvent {
+    ///     key_code: u32::from('a'),
+    ///     modifiers: KittyModifiers::CTRL | KittyModifiers::SHIFT,
+    ///     ..KittyKeyEvent::default()
+    /// };
+    /// assert_eq!(event.encode(), b"\x1b[97;6u");
+    /// ```
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let legacy = LEGACY_KEYS.iter().find(|&&(code, _, _)| code == self.key_code);
+
+        let mut bytes = b"\x1b[".to_vec();
+        let (key, final_byte) = match legacy {
+            Some(&(_, number, 0)) => (number, b'~'),
+            Some(&(_, _, letter)) => (1, letter),
+            None => (self.key_code, b'u'),
+        };
+
+        let event_type = self.event_type != KittyEventType::Press;
+        let text = self.text.as_ref().filter(|_| legacy.is_none());
+        let modifiers = !self.modifiers.is_empty() || event_type || text.is_some();
+
+        // The key may be left out of letter forms without modifiers
+        if final_byte == b'u' || final_byte == b'~' || modifiers {
+            push_number(&mut bytes, key);
+        }
+
+        if legacy.is_none() {
+            match (self.shifted_key, self.base_layout_key) {
+                (None, None) => (),
+                (shifted, base) => {
+                    bytes.push(b':');
+                    if let Some(shifted) = shifted {
+                        push_number(&mut bytes, shifted);
+                    }
+                    if let Some(base) = base {
+                        bytes.push(b':');
+                        push_number(&mut bytes, base);
+                    }
+                },
+            }
+        }
+
+        if modifiers {
+            bytes.push(b';');
+            push_number(&mut bytes, u32::from(self.modifiers.0) + 1);
+            if event_type {
+                bytes.push(b':');
+                push_number(&mut bytes, self.event_type.to_param());
+            }
+        }
+
+        if let Some(text) = text {
+            bytes.push(b';');
+            for (index, c) in text.chars().enumerate() {
+                if index > 0 {
+                    bytes.push(b':');
+                }
+                push_number(&mut bytes, u32::from(c));
+            }
+        }
+
+        bytes.push(final_byte);
+        bytes
+    }
+}
+
+fn legacy_tilde_key(number: u32) -> Option<u32> {
+    LEGACY_KEYS.iter()
+        .find(|&&(_, tilde, _)| tilde == number && number != 0)
+        .map(|&(code, _, _)| code)
+        .or_else(|| TILDE_ALIASES.iter().find(|&&(tilde, _)| tilde == number).map(|&(_, code)| code))
+}
+
+fn legacy_letter_key(letter: u8) -> Option<u32> {
+    LEGACY_KEYS.iter()
+        .find(|&&(_, _, byte)| byte == letter && letter != 0)
+        .map(|&(code, _, _)| code)
+}
+
+fn push_number(bytes: &mut Vec<u8>, value: u32) {
+    let mut digits = [0u8; 10];
+    let mut start = digits.len();
+    let mut value = value;
+    loop {
+        start -= 1;
+        digits[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    bytes.extend_from_slice(&digits[start..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KittyEventType, KittyKeyEvent, KittyModifiers};
+    use test_utils::{RecordedEvent, RecordingPerform};
+    use Parser;
+
+    fn parse(bytes: &[u8]) -> Option<KittyKeyEvent> {
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_slice(&mut recorder, bytes);
+        recorder.events().iter().filter_map(|event| match *event {
+            RecordedEvent::CsiDispatch { ref params, final_byte, .. } => KittyKeyEvent::parse(params, final_byte as u8),
+            _ => None,
+        }).next()
+    }
+
+    #[test]
+    fn letter_keys() {
+        let event = parse(b"\x1b[97;5u").unwrap();
+        assert_eq!(event.key_code, 97);
+        assert_eq!(event.modifiers, KittyModifiers::CTRL);
+        assert_eq!(event.event_type, KittyEventType::Press);
+        assert_eq!(event.text, None);
+
+        let event = parse(b"\x1b[97:65;2:3;65u").unwrap();
+        assert_eq!(event.shifted_key, Some(65));
+        assert_eq!(event.base_layout_key, None);
+        assert_eq!(event.modifiers, KittyModifiers::SHIFT);
+        assert_eq!(event.event_type, KittyEventType::Release);
+        assert_eq!(event.text.as_ref().map(|text| &text[..]), Some("A"));
+
+        let event = parse(b"\x1b[1089::99;5u").unwrap();
+        assert_eq!((event.shifted_key, event.base_layout_key), (None, Some(99)));
+    }
+
+    #[test]
+    fn functional_keys() {
+        let up = parse(b"\x1b[A").unwrap();
+        assert_eq!((up.key_code, up.modifiers), (57352, KittyModifiers::empty()));
+
+        let up = parse(b"\x1b[1;3:2A").unwrap();
+        assert_eq!((up.key_code, up.modifiers, up.event_type), (57352, KittyModifiers::ALT, KittyEventType::Repeat));
+
+        assert_eq!(parse(b"\x1b[3;2~").unwrap().key_code, 57349);
+        assert_eq!(parse(b"\x1b[7~").unwrap().key_code, 57356);
+    }
+
+    #[test]
+    fn other_sequences() {
+        assert_eq!(parse(b"\x1b[2J"), None);
+        assert_eq!(parse(b"\x1b[12;5R"), None);
+        assert_eq!(parse(b"\x1b[2;3A"), None);
+        assert_eq!(parse(b"\x1b[99~"), None);
+        assert_eq!(parse(b"\x1b[97;1:4u"), None);
+        assert_eq!(parse(b"\x1b[97;1;97;1u"), None);
+    }
+
+    #[test]
+    fn encodes_defaults_compactly() {
+        let key = |key_code| KittyKeyEvent { key_code, ..KittyKeyEvent::default() };
+        assert_eq!(key(97).encode(), b"\x1b[97u");
+        assert_eq!(key(57352).encode(), b"\x1b[A");
+        assert_eq!(key(57349).encode(), b"\x1b[3~");
+        assert_eq!(KittyKeyEvent { text: Some("a".into()), ..key(97) }.encode(), b"\x1b[97;1;97u");
+    }
+}
@@ -24,26 +24,264 @@
 //!
 //! * UTF-8 Support for Input
 //! * OSC Strings can be terminated by 0x07
-//! * Only supports 7-bit codes. Some 8-bit codes are still supported, but they
-//!   no longer work in all states.
+//! * SOS, PM and APC strings are collected and dispatched instead of ignored
+//! * Only supports 7-bit codes by default. Some 8-bit codes are still
+//!   supported, but they no longer work in all states. Full 8-bit C1 control
+//!   support can be enabled with [`Parser::set_c1_controls`].
 //!
+//! # Features
+//!
+//! * `std` (default): Enables the [`io`] module, [`TerminalRecorder`],
+//!   [`HexdumpPerform`], [`Transcoder`] and [`EscapeSequenceSource`].
+//!   Implies `alloc`.
+//! * `alloc`: Buffers device control strings for performers which ask for
+//!   them in one piece through [`Perform::dcs_dispatch`], and enables
+//!   [`Parser::parse`]. Without it the parser only needs `core` and DCS data
+//!   is always streamed through `hook`, `put` and `unhook`.
+//! * `log`: Logs every state transition at trace level through the `log`
+//!   crate. `trace` is an alias kept for compatibility.
+//! * `simd`: Speeds up [`Parser::advance_slice`] on printable ASCII using SIMD
+//!   instructions where available.
+//! * `test-utils`: Enables the `test_utils` module with a [`Perform`] that
+//!   records every action, for use in tests. Implies `alloc`.
+//! * `tokio`: Adds [`io::AsyncParserWriter`], which implements tokio's
+//!   `AsyncWrite`. Implies `std`.
+//! * `serde`: Implements `Serialize` and `Deserialize` for [`State`],
+//!   [`Action`], [`Params`], `VteEvent` and `ParserState`.
+//! * `kitty`: Enables the [`kitty`] module for decoding and encoding key
+//!   events of the kitty keyboard protocol. Implies `alloc`.
+//! * `mouse`: Enables the [`mouse`] module for decoding the mouse events
+//!   reported by terminals.
+//! * `osc`: Enables the [`osc`] module for decoding the parameters of OSC
+//!   sequences like hyperlinks. Implies `alloc`.
+//! * `base64`: Adds [`osc::parse_osc52`], which decodes the clipboard
+//!   contents of OSC 52. Implies `osc`.
+//! * `unicode-width`: Makes the parser call [`Perform::print_wide`] with the
+//!   number of cells each character occupies.
+//! * `decoder`: Enables the [`decoder`] module, which decodes common
+//!   sequences into typed terminal commands. Implies `osc`.
+//! * `crossterm`: Enables [`compat::crossterm`], which translates parsed
+//!   terminal input into `crossterm` events. Implies `std` and `mouse`.
+//! * `arbitrary`: Implements `arbitrary::Arbitrary` for [`State`], [`Params`]
+//!   and `VteEvent`, generating values the parser could produce, for
+//!   structure-aware fuzzing. Implies `std`.
+//! * `metrics`: Makes every [`Parser`] record the lengths of the sequences it
+//!   completes, see [`Parser::metrics`]. This costs a counter increment per
+//!   byte and a few comparisons per sequence; without the feature the parser
+//!   does no extra work.
+//! * `proptest`: Builds the property based tests in _tests/proptests.rs_.
+//! * `wasm`: Enables the [`wasm`] module with `wasm-bindgen` bindings for
+//!   JavaScript. Implies `std`.
+//!
+//! [`State`]: enum.State.html
+//! [`Action`]: enum.Action.html
+//! [`Params`]: struct.Params.html
 //! [`Parser`]: struct.Parser.html
 //! [`Perform`]: trait.Perform.html
+//! [`Perform::dcs_dispatch`]: trait.Perform.html#method.dcs_dispatch
+//! [`Parser::advance_slice`]: struct.Parser.html#method.advance_slice
+//! [`Parser::parse`]: struct.Parser.html#method.parse
+//! [`Parser::set_c1_controls`]: struct.Parser.html#method.set_c1_controls
+//! [`Parser::metrics`]: struct.Parser.html#method.metrics
+//! [`io`]: io/index.html
+//! [`TerminalRecorder`]: struct.TerminalRecorder.html
+//! [`HexdumpPerform`]: struct.HexdumpPerform.html
+//! [`Transcoder`]: struct.Transcoder.html
+//! [`EscapeSequenceSource`]: trait.EscapeSequenceSource.html
+//! [`kitty`]: kitty/index.html
+//! [`mouse`]: mouse/index.html
+//! [`osc`]: osc/index.html
+//! [`decoder`]: decoder/index.html
+//! [`compat::crossterm`]: compat/crossterm/index.html
+//! [`wasm`]: wasm/index.html
+//! [`io::AsyncParserWriter`]: io/struct.AsyncParserWriter.html
 //! [Paul Williams' ANSI parser state machine]: http://vt100.net/emu/dec_ansi_parser
-extern crate utf8parse as utf8;
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 
-use std::mem;
+#[cfg(any(feature = "std", test))]
+extern crate core;
 
-mod table;
-mod definitions;
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+
+extern crate utf8parse;
+
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+
+#[cfg(feature = "tokio")]
+extern crate tokio;
 
-use definitions::{Action, State, unpack};
+#[cfg(feature = "base64")]
+extern crate base64;
 
-use table::{EXIT_ACTIONS, ENTRY_ACTIONS, STATE_CHANGE};
+#[cfg(feature = "crossterm")]
+extern crate crossterm;
+
+#[cfg(feature = "unicode-width")]
+extern crate unicode_width;
+
+#[cfg(feature = "wasm")]
+extern crate js_sys;
+
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use core::convert::TryFrom;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+mod table;
+#[cfg(test)]
+mod table_codegen;
+mod definitions;
+mod params;
+mod buffer;
+mod builder;
+mod c0;
+mod bound;
+mod capabilities;
+mod charset;
+mod editor;
+#[macro_use]
+mod composite;
+mod filter;
+mod metrics;
+mod repeat;
+mod sgr;
+mod utf8;
+#[cfg(any(feature = "alloc", test))]
+mod event;
+
+#[cfg(feature = "simd")]
+mod simd;
+
+#[cfg(feature = "alloc")]
+mod parser_state;
+#[cfg(feature = "alloc")]
+mod encode;
+#[cfg(feature = "alloc")]
+mod dcs;
+#[cfg(feature = "alloc")]
+mod modes;
+#[cfg(feature = "alloc")]
+mod observer;
+#[cfg(feature = "alloc")]
+mod tmux;
+#[cfg(feature = "alloc")]
+mod screen;
+#[cfg(feature = "alloc")]
+mod strip;
+#[cfg(feature = "alloc")]
+mod sync;
+#[cfg(feature = "alloc")]
+mod tabs;
+#[cfg(feature = "alloc")]
+mod lines;
+#[cfg(feature = "alloc")]
+mod reflow;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+#[cfg(feature = "std")]
+mod asciicast;
+#[cfg(feature = "std")]
+mod hexdump;
+#[cfg(feature = "std")]
+mod transcode;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "kitty")]
+pub mod kitty;
+#[cfg(feature = "mouse")]
+pub mod mouse;
+#[cfg(feature = "osc")]
+pub mod osc;
+#[cfg(feature = "decoder")]
+pub mod decoder;
+#[cfg(feature = "crossterm")]
+pub mod compat;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+pub use definitions::{Action, InvalidVariant, State, unpack};
+pub use table::Table;
+pub use params::{MAX_SUBPARAMS, Params, ParamsIter};
+pub use builder::{ParamOverflowMode, ParserBuilder, Utf8ErrorMode, Utf8Mode};
+pub use c0::C0;
+pub use bound::BoundParser;
+pub use capabilities::{Capabilities, Capability};
+pub use charset::{Charset, CharsetDecoder};
+pub use editor::LineEditor;
+#[cfg(feature = "alloc")]
+pub use capabilities::identify_sequence;
+pub use composite::CompositePerform;
+pub use filter::{ConditionalEscapeStrip, FilterPerform, StripAction, VteEventRef};
+pub use metrics::{MetricsPerform, PerformMetrics, SequenceCounter};
+pub use repeat::{RepeatTracker, MAX_REPEAT};
+#[cfg(feature = "metrics")]
+pub use metrics::ParserMetrics;
+pub use sgr::{NamedColor, SgrAttribute, SgrIter, TermColor, UnderlineStyle};
+pub use utf8::Utf8Accumulator;
+#[cfg(feature = "alloc")]
+pub use sgr::parse_sgr;
+#[cfg(feature = "std")]
+pub use asciicast::TerminalRecorder;
+#[cfg(feature = "std")]
+pub use hexdump::HexdumpPerform;
+#[cfg(feature = "std")]
+pub use transcode::{feed_event, EscapeSequenceSource, Transcoder};
+#[cfg(feature = "alloc")]
+pub use dcs::{DcsHandler, SixelDcsHandler};
+#[cfg(feature = "alloc")]
+pub use modes::{ModeState, PrivateMode, PrivateModeTracker};
+#[cfg(feature = "alloc")]
+pub use observer::ParserObserver;
+#[cfg(feature = "alloc")]
+pub use sync::{SynchronizedOutputPerform, DEFAULT_SYNC_TIMEOUT};
+#[cfg(feature = "alloc")]
+pub use tabs::{TabStopPerform, TabStops};
+#[cfg(feature = "alloc")]
+pub use lines::{LineBuffer, LineSink};
+#[cfg(feature = "alloc")]
+pub use reflow::{VtLine, VtLineBuffer};
+#[cfg(feature = "alloc")]
+pub use tmux::{TmuxPassthroughHandler, TmuxPassthroughParser};
+#[cfg(feature = "alloc")]
+pub use encode::{encode_csi, encode_esc, encode_osc, EncodeError};
+#[cfg(feature = "alloc")]
+pub use parser_state::ParserState;
+#[cfg(feature = "alloc")]
+pub use screen::{Attributes, Cell, ScreenBuffer, ScrollbackBuffer};
+#[cfg(feature = "alloc")]
+pub use strip::{strip_ansi, TextStripper};
+#[cfg(feature = "alloc")]
+pub use event::{ParsedEvent, VteEvent};
+
+use table::{C1_CHANGE, EXIT_ACTIONS, ENTRY_ACTIONS, STATE_CHANGE};
 
 impl State {
     /// Get exit action for this state
     #[inline(always)]
+    #[must_use]
     pub fn exit_action(&self) -> Action {
         unsafe {
             *EXIT_ACTIONS.get_unchecked(*self as usize)
@@ -52,6 +290,7 @@ impl State {
 
     /// Get entry action for this state
     #[inline(always)]
+    #[must_use]
     pub fn entry_action(&self) -> Action {
         unsafe {
             *ENTRY_ACTIONS.get_unchecked(*self as usize)
@@ -60,66 +299,380 @@ impl State {
 }
 
 
-const MAX_INTERMEDIATES: usize = 2;
+const MAX_INTERMEDIATES: usize = 8;
+/// Size of the fixed string buffer used without `alloc`
+#[cfg(not(feature = "alloc"))]
 const MAX_OSC_RAW: usize = 1024;
-const MAX_PARAMS: usize = 16;
+const MAX_PARAMS: usize = 32;
 
-struct VtUtf8Receiver<'a, P: Perform + 'a>(&'a mut P, &'a mut State);
+/// Kind of string being collected into the OSC buffer
+///
+/// SOS, PM and APC strings share the buffer with OSC strings but are not split
+/// into parameters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum StringKind {
+    Osc,
+    Sos,
+    Pm,
+    Apc,
+}
 
-impl<'a, P: Perform> utf8::Receiver for VtUtf8Receiver<'a, P> {
-    fn codepoint(&mut self, c: char) {
-        self.0.print(c);
-        *self.1 = State::Ground;
+impl StringKind {
+    /// Get the kind of string introduced by `ESC byte` or its C1 equivalent
+    #[inline]
+    fn from_introducer(byte: u8) -> StringKind {
+        match byte {
+            b'X' | 0x98 => StringKind::Sos,
+            b'^' | 0x9e => StringKind::Pm,
+            b'_' | 0x9f => StringKind::Apc,
+            _ => StringKind::Osc,
+        }
     }
+}
 
-    fn invalid_sequence(&mut self) {
-        self.0.print('�');
-        *self.1 = State::Ground;
-    }
+/// Escape sequence syntax understood by the [`Parser`]
+///
+/// [`Parser`]: struct.Parser.html
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParserMode {
+    /// ANSI compatible terminals such as the VT100 and its successors
+    #[default]
+    AnsiVt,
+
+    /// The VT52 and compatible terminals
+    ///
+    /// Escape sequences consist of `ESC` and a single final byte, which is
+    /// passed to [`Perform::esc_dispatch`] without parameters. Only the final
+    /// bytes `A`, `B`, `C` and `D` (cursor up, down, right and left), `F` and
+    /// `G` (enter and exit graphics mode), `H` (cursor home), `I` (reverse
+    /// line feed), `J` and `K` (erase to end of screen and line), `Z`
+    /// (identify), `=` and `>` (enter and exit alternate keypad mode) and `<`
+    /// (enter ANSI mode) are recognised; any other escape sequence is ignored.
+    ///
+    /// `ESC Y line column` (direct cursor address) is the only longer
+    /// sequence. It is dispatched with the final byte `Y` and a zero based
+    /// line and column as parameters.
+    ///
+    /// There are no CSI, OSC or other string sequences. Receiving `ESC <`
+    /// switches the parser to `AnsiVt` after dispatching it.
+    ///
+    /// [`Perform::esc_dispatch`]: trait.Perform.html#tymethod.esc_dispatch
+    Vt52,
 }
 
 /// Parser for raw _VTE_ protocol which delegates actions to a [`Perform`]
 ///
+/// The parser can be cloned to snapshot its state, including any partially
+/// parsed sequence or UTF-8 codepoint.
+///
 /// [`Perform`]: trait.Perform.html
+#[derive(Clone)]
 pub struct Parser {
     state: State,
     intermediates: [u8; MAX_INTERMEDIATES],
     intermediate_idx: usize,
-    params: [i64; MAX_PARAMS],
-    param: i64,
+    private_marker: Option<u8>,
+    params: Params,
+    param: u16,
     collecting_param: bool,
-    num_params: usize,
-    osc_raw: [u8; MAX_OSC_RAW],
+    collecting_subparam: bool,
+    osc_raw: buffer::BoundedByteBuffer,
     osc_params: [(usize, usize); MAX_PARAMS],
-    osc_idx: usize,
     osc_num_params: usize,
+    string_kind: StringKind,
+    #[cfg(feature = "alloc")]
+    dcs_data: buffer::BoundedByteBuffer,
+    #[cfg(feature = "alloc")]
+    dcs_final: u8,
+
+    /// The device control string is buffered for `dcs_dispatch` instead of
+    /// being streamed to the performer
+    #[cfg(feature = "alloc")]
+    dcs_buffered: bool,
+    #[cfg(feature = "alloc")]
+    dcs_handler: dcs::DcsHandlerSlot,
+    #[cfg(feature = "alloc")]
+    observer: observer::ObserverSlot,
     ignoring: bool,
-    utf8_parser: utf8::Parser,
+    c1_controls: bool,
+    mode: ParserMode,
+    utf8_mode: Utf8Mode,
+    utf8_error_mode: Utf8ErrorMode,
+    param_overflow_mode: ParamOverflowMode,
+
+    /// The parameters overflowed while entering the parameter state, which
+    /// is replaced by the ignore state
+    ignore_pending: bool,
+    max_params: usize,
+    max_subparams: usize,
+    max_intermediates: usize,
+    utf8: Utf8Accumulator,
+    byte_position: u64,
+    #[cfg(feature = "metrics")]
+    metrics: ParserMetrics,
+
+    /// Bytes of the OSC or device control string being collected, including
+    /// dropped ones
+    #[cfg(feature = "metrics")]
+    string_len: usize,
+}
+
+/// Outcome of [`Parser::advance_with_result`]
+///
+/// [`Parser::advance_with_result`]: struct.Parser.html#method.advance_with_result
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AdvanceResult {
+    /// The byte was consumed by the parser
+    ///
+    /// Callers implementing push-back would feed the byte again if this is
+    /// `false`. The parser re-parses bytes which interrupt a sequence itself,
+    /// so it currently always consumes every byte.
+    pub consumed: bool,
+
+    /// The byte cut an unfinished UTF-8 sequence short and was parsed again
+    /// from the ground state after the sequence had been reported as invalid
+    pub interrupted: bool,
 }
 
 impl Parser {
     /// Create a new Parser
+    ///
+    /// Use [`ParserBuilder`] to create a parser with a different
+    /// configuration.
+    ///
+    /// [`ParserBuilder`]: struct.ParserBuilder.html
+    #[must_use]
     pub fn new() -> Parser {
         Parser {
             state: State::Ground,
             intermediates: [0u8; MAX_INTERMEDIATES],
             intermediate_idx: 0,
-            params: [0i64; MAX_PARAMS],
+            private_marker: None,
+            params: Params::new(),
             param: 0,
             collecting_param: false,
-            num_params: 0,
-            osc_raw: [0; MAX_OSC_RAW],
+            collecting_subparam: false,
+            osc_raw: buffer::BoundedByteBuffer::new(buffer::DEFAULT_LIMIT),
             osc_params: [(0, 0); MAX_PARAMS],
-            osc_idx: 0,
             osc_num_params: 0,
+            string_kind: StringKind::Osc,
+            #[cfg(feature = "alloc")]
+            dcs_data: buffer::BoundedByteBuffer::new(buffer::DEFAULT_LIMIT),
+            #[cfg(feature = "alloc")]
+            dcs_final: 0,
+            #[cfg(feature = "alloc")]
+            dcs_buffered: false,
+            #[cfg(feature = "alloc")]
+            dcs_handler: dcs::DcsHandlerSlot::default(),
+            #[cfg(feature = "alloc")]
+            observer: observer::ObserverSlot::default(),
             ignoring: false,
-            utf8_parser: utf8::Parser::new(),
+            c1_controls: false,
+            mode: ParserMode::AnsiVt,
+            utf8_mode: Utf8Mode::Utf8,
+            utf8_error_mode: Utf8ErrorMode::Replace,
+            param_overflow_mode: ParamOverflowMode::Truncate,
+            ignore_pending: false,
+            max_params: MAX_PARAMS,
+            max_subparams: MAX_SUBPARAMS,
+            max_intermediates: MAX_INTERMEDIATES,
+            utf8: Utf8Accumulator::new(),
+            byte_position: 0,
+            #[cfg(feature = "metrics")]
+            metrics: ParserMetrics::default(),
+            #[cfg(feature = "metrics")]
+            string_len: 0,
+        }
+    }
+
+    /// Get the state the parser is currently in
+    ///
+    /// This can be used to observe parser progress in between calls to
+    /// [`advance`].
+    ///
+    /// [`advance`]: struct.Parser.html#method.advance
+    #[inline]
+    #[must_use]
+    pub fn current_state(&self) -> State {
+        self.state
+    }
+
+    /// Returns `true` if the parser is between sequences
+    ///
+    /// This is the case if it is in [`State::Ground`] and nothing is left
+    /// over from the bytes parsed so far: no incomplete UTF-8 character, no
+    /// parameter being collected and no escape held back in a device control
+    /// string. Input can be split after such a byte, for example to parse
+    /// the parts with different parsers. Parameters and intermediates of a
+    /// finished sequence are only cleared when the next one starts and
+    /// aren't pending.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use vte::{Parser, TextStripper};
+    /// let mut parser = Parser::new();
+    /// let mut stripper = TextStripper::new();
+    /// parser.advance_slice(&mut stripper, b"\x1b[1");
+    /// assert!(!parser.is_at_ground());
+    ///
+    /// parser.advance_slice(&mut stripper, b"m");
+    /// assert!(parser.is_at_ground());
+    /// # }
+    /// ```
+    ///
+    /// [`State::Ground`]: enum.State.html#variant.Ground
+    #[inline]
+    #[must_use]
+    pub fn is_at_ground(&self) -> bool {
+        #[cfg(feature = "alloc")]
+        {
+            if self.dcs_handler.escape_pending {
+                return false;
+            }
+        }
+
+        self.state == State::Ground
+            && self.utf8.is_empty()
+            && !self.collecting_param
+            && !self.collecting_subparam
+    }
+
+    /// Create a new Parser in VT52 mode
+    ///
+    /// See [`ParserMode::Vt52`].
+    ///
+    /// [`ParserMode::Vt52`]: enum.ParserMode.html#variant.Vt52
+    #[must_use]
+    pub fn new_vt52() -> Parser {
+        let mut parser = Parser::new();
+        parser.mode = ParserMode::Vt52;
+        parser
+    }
+
+    /// Get the escape sequence syntax the parser understands
+    #[inline]
+    #[must_use]
+    pub fn mode(&self) -> ParserMode {
+        self.mode
+    }
+
+    /// Change the escape sequence syntax the parser understands
+    ///
+    /// This is typically called by a terminal when it is asked to switch
+    /// modes, for example through `CSI ? 2 l`.
+    #[inline]
+    pub fn set_mode(&mut self, mode: ParserMode) {
+        self.mode = mode;
+    }
+
+    /// Enable or disable 8-bit C1 controls
+    ///
+    /// When enabled, IND, NEL, HTS, RI, SS2, SS3, DCS, SPA, EPA, SOS, DECID,
+    /// CSI, ST, OSC, PM and APC act like their two byte `ESC` equivalents, for
+    /// example `0x9b` is the same as `ESC [`. Escape sequence equivalents are
+    /// passed to [`Perform::esc_dispatch`] with their 7-bit final byte. The
+    /// other bytes in `0x80..=0x9f` are executed and cancel the current
+    /// sequence, like `CAN`.
+    ///
+    /// Bytes which are part of a UTF-8 encoded character printed in the ground
+    /// state are not affected, but any other byte in this range is treated as
+    /// a control. UTF-8 in OSC and other strings can therefore not be used
+    /// with C1 controls enabled. C1 controls have no effect in VT52 mode.
+    /// Disabled by default.
+    ///
+    /// [`Perform::esc_dispatch`]: trait.Perform.html#tymethod.esc_dispatch
+    #[inline]
+    pub fn set_c1_controls(&mut self, enabled: bool) {
+        self.c1_controls = enabled;
+    }
+
+    /// Returns `true` if 8-bit C1 controls are enabled
+    ///
+    /// See [`set_c1_controls`].
+    ///
+    /// [`set_c1_controls`]: struct.Parser.html#method.set_c1_controls
+    #[inline]
+    #[must_use]
+    pub fn c1_controls(&self) -> bool {
+        self.c1_controls
+    }
+
+    /// Install a handler which may claim device control strings
+    ///
+    /// See [`DcsHandler`]. A previously installed handler is replaced. Clones
+    /// of the parser and parsers created by `restore` have no handler; a
+    /// string the handler has already claimed is dropped by a clone.
+    ///
+    /// [`DcsHandler`]: trait.DcsHandler.html
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn set_dcs_handler(&mut self, handler: Box<dyn DcsHandler>) {
+        self.dcs_handler.handler = Some(handler);
+    }
+
+    /// Remove the DCS handler
+    ///
+    /// The rest of a string claimed by the handler is dropped.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn take_dcs_handler(&mut self) -> Option<Box<dyn DcsHandler>> {
+        self.dcs_handler.handler.take()
+    }
+
+    /// Create a new Parser which reports its progress to `observer`
+    ///
+    /// See [`ParserObserver`]. Clones of the parser and parsers created by
+    /// `restore` have no observer.
+    ///
+    /// [`ParserObserver`]: trait.ParserObserver.html
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn with_observer(observer: Box<dyn ParserObserver>) -> Parser {
+        let mut parser = Parser::new();
+        parser.set_observer(observer);
+        parser
+    }
+
+    /// Install an observer, replacing a previously installed one
+    ///
+    /// See [`with_observer`].
+    ///
+    /// [`with_observer`]: struct.Parser.html#method.with_observer
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn set_observer(&mut self, observer: Box<dyn ParserObserver>) {
+        self.observer.0 = Some(observer);
+    }
+
+    /// Remove the observer
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn take_observer(&mut self) -> Option<Box<dyn ParserObserver>> {
+        self.observer.0.take()
+    }
+
+    /// Store the collected value as a new parameter or as a subparameter of
+    /// the previous one
+    #[inline]
+    fn store_param(&mut self) {
+        if self.collecting_subparam {
+            self.params.extend_bounded(self.param, self.max_subparams);
+        } else {
+            self.params.push_bounded(self.param, self.max_params);
         }
+
+        self.param = 0;
+        self.collecting_param = false;
     }
 
+    /// Finish the parameter which is currently being collected, if any
     #[inline]
-    fn params(&self) -> &[i64] {
-        &self.params[..self.num_params]
+    fn finish_param(&mut self) {
+        if self.collecting_param || self.collecting_subparam {
+            self.store_param();
+        }
+        self.collecting_subparam = false;
     }
 
     #[inline]
@@ -127,22 +680,212 @@ impl Parser {
         &self.intermediates[..self.intermediate_idx]
     }
 
+    /// The parameters collected so far, including the one being collected
+    fn pending_params(&self) -> Params {
+        let mut params = self.params;
+        if self.collecting_subparam {
+            params.extend_bounded(self.param, self.max_subparams);
+        } else if self.collecting_param {
+            params.push_bounded(self.param, self.max_params);
+        }
+        params
+    }
+
+    /// A one line summary of the state, the parameters and the intermediates
+    ///
+    /// This is meant for error messages; the `Debug` output has more
+    /// details.
+    ///
+    /// ```
+    /// # use vte::{Parser, TextStripper};
+    /// let mut parser = Parser::new();
+    /// parser.advance_slice(&mut TextStripper::new(), b"\x1b[1;2");
+    /// assert_eq!(parser.dump_state(), "Parser { state: CsiParam, params: [[1], [2]], intermediates: [] }");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn dump_state(&self) -> String {
+        use core::fmt::Write;
+
+        let mut dump = String::new();
+        let _ = write!(
+            dump,
+            "Parser {{ state: {:?}, params: {:?}, intermediates: {:?} }}",
+            self.state,
+            self.pending_params(),
+            self.intermediates(),
+        );
+        dump
+    }
+
     /// Advance the parser state
     ///
     /// Requires a [`Perform`] in case `byte` triggers an action
     ///
     /// [`Perform`]: trait.Perform.html
     #[inline]
-    pub fn advance<P: Perform>(&mut self, performer: &mut P, byte: u8) {
+    pub fn advance<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        self.byte_position += 1;
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics.total_bytes += 1;
+        }
+        self.advance_byte(performer, byte);
+    }
+
+    /// Advance the parser state like [`advance`], reporting what happened to
+    /// `byte`
+    ///
+    /// ```
+    /// # use vte::{AdvanceResult, Params, Parser, Perform};
+    /// # struct Ignore;
+    /// # impl Perform for Ignore {
+    /// #     fn print(&mut self, _c: char) {}
+    /// #     fn execute(&mut self, _byte: u8) {}
+    /// #     fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    /// #     fn put(&mut self, _byte: u8) {}
+    /// #     fn unhook(&mut self) {}
+    /// #     fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    /// #     fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+    /// #     fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+    /// # }
+    /// # let mut performer = Ignore;
+    /// let mut parser = Parser::new();
+    /// parser.advance(&mut performer, 0xe2);
+    ///
+    /// let result = parser.advance_with_result(&mut performer, b'a');
+    /// assert_eq!(result, AdvanceResult { consumed: true, interrupted: true });
+    /// ```
+    ///
+    /// [`advance`]: struct.Parser.html#method.advance
+    #[inline]
+    pub fn advance_with_result<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) -> AdvanceResult {
+        // Anything but a continuation byte ends the sequence and is parsed
+        // again; invalid continuation bytes are replaced instead
+        let interrupted = self.state == State::Utf8 && !matches!(byte, 0x80..=0xbf);
+        self.advance(performer, byte);
+
+        AdvanceResult {
+            consumed: true,
+            interrupted,
+        }
+    }
+
+    /// Number of bytes passed to the parser
+    ///
+    /// Every call to [`advance`] counts one byte, including the bytes of
+    /// [`advance_slice`]. The counter is copied when the parser is cloned; a
+    /// parser created by `restore` starts at zero.
+    ///
+    /// [`advance`]: struct.Parser.html#method.advance
+    /// [`advance_slice`]: struct.Parser.html#method.advance_slice
+    #[inline]
+    #[must_use]
+    pub fn byte_position(&self) -> u64 {
+        self.byte_position
+    }
+
+    /// Lengths of the sequences completed since the parser was created or
+    /// [`reset_metrics`] was called
+    ///
+    /// The metrics are copied when the parser is cloned; a parser created by
+    /// `restore` starts without any.
+    ///
+    /// [`reset_metrics`]: struct.Parser.html#method.reset_metrics
+    #[cfg(feature = "metrics")]
+    #[inline]
+    #[must_use]
+    pub fn metrics(&self) -> &ParserMetrics {
+        &self.metrics
+    }
+
+    /// Set all [`metrics`] to zero
+    ///
+    /// A sequence which is partially parsed is still recorded when it
+    /// completes.
+    ///
+    /// [`metrics`]: struct.Parser.html#method.metrics
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn reset_metrics(&mut self) {
+        self.metrics = ParserMetrics::default();
+    }
+
+    #[cfg(feature = "simd")]
+    #[inline]
+    fn has_observer(&self) -> bool {
+        #[cfg(feature = "alloc")]
+        {
+            self.observer.0.is_some()
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            false
+        }
+    }
+
+    /// Advance the parser state without counting the byte
+    #[inline]
+    fn advance_byte<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        #[cfg(feature = "alloc")]
+        {
+            if let Some(ref mut observer) = self.observer.0 {
+                observer.on_byte(byte, self.state);
+            }
+        }
+
         // Utf8 characters are handled out-of-band.
         if let State::Utf8 = self.state {
             self.process_utf8(performer, byte);
             return;
         }
 
+        #[cfg(feature = "alloc")]
+        {
+            if self.dcs_handler.doubles_escapes && self.dcs_escape(performer, byte) {
+                return;
+            }
+        }
+
+        if self.state == State::Ground {
+            match (self.utf8_mode, byte) {
+                (Utf8Mode::Latin1, 0xa0..=0xff) => {
+                    print(performer, byte as char);
+                    return;
+                },
+                // Bytes which can't start a UTF-8 sequence; `0x80..=0x9f` are
+                // C1 controls instead
+                (Utf8Mode::Utf8, 0xa0..=0xc1) | (Utf8Mode::Utf8, 0xf5..=0xff) => {
+                    self.invalid_utf8(performer);
+                    return;
+                },
+                _ => (),
+            }
+        }
+
+        // VT52 escape sequences don't follow the ANSI state machine, apart
+        // from control characters
+        if self.mode == ParserMode::Vt52 && byte >= 0x20 {
+            match self.state {
+                State::Escape | State::EscapeIntermediate => {
+                    self.advance_vt52(performer, byte);
+                    return;
+                },
+                _ => (),
+            }
+        }
+
         // Handle state changes in the anywhere state before evaluating changes
         // for current state.
-        let mut change = STATE_CHANGE[State::Anywhere as usize][byte as usize];
+        let mut change = if self.c1_controls && self.mode == ParserMode::AnsiVt {
+            C1_CHANGE[byte as usize]
+        } else {
+            0
+        };
+
+        if change == 0 {
+            change = STATE_CHANGE[State::Anywhere as usize][byte as usize];
+        }
 
         if change == 0 {
             change = STATE_CHANGE[self.state as usize][byte as usize];
@@ -154,18 +897,330 @@ impl Parser {
         self.perform_state_change(performer, state, action, byte);
     }
 
+    /// Advance the parser state for every byte in `bytes`
+    ///
+    /// This is equivalent to calling [`advance`] for each byte in order.
+    ///
+    /// With the `simd` feature enabled, runs of printable ASCII in the ground
+    /// state are scanned ahead and printed without going through the state
+    /// machine.
+    ///
+    /// [`advance`]: struct.Parser.html#method.advance
+    #[cfg(not(feature = "simd"))]
+    #[inline]
+    pub fn advance_slice<P: Perform + ?Sized>(&mut self, performer: &mut P, bytes: &[u8]) {
+        for byte in bytes {
+            self.advance(performer, *byte);
+        }
+    }
+
+    /// Advance the parser state for every byte in `bytes`
+    ///
+    /// This is equivalent to calling [`advance`] for each byte in order.
+    ///
+    /// With the `simd` feature enabled, runs of printable ASCII in the ground
+    /// state are scanned ahead and printed without going through the state
+    /// machine.
+    ///
+    /// [`advance`]: struct.Parser.html#method.advance
+    #[cfg(feature = "simd")]
+    #[inline]
+    pub fn advance_slice<P: Perform + ?Sized>(&mut self, performer: &mut P, bytes: &[u8]) {
+        let mut i = 0;
+        while i < bytes.len() {
+            // An observer has to see every byte
+            if self.state == State::Ground && !self.has_observer() {
+                let len = simd::printable_ascii_len(&bytes[i..]);
+                for byte in &bytes[i..i + len] {
+                    print(performer, *byte as char);
+                }
+                self.byte_position += len as u64;
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.total_bytes += len as u64;
+                }
+
+                i += len;
+                if i == bytes.len() {
+                    break;
+                }
+            }
+
+            self.advance(performer, bytes[i]);
+            i += 1;
+        }
+    }
+
+    /// Advance the parser state for every byte of `s`
+    ///
+    /// The string is treated as a raw byte sequence, exactly like
+    /// `advance_slice(performer, s.as_bytes())`. Control characters in it are
+    /// parsed as such, so an `\x1b` in a string literal starts an escape
+    /// sequence.
+    ///
+    /// ```
+    /// # use vte::{Params, Parser, Perform};
+    /// # #[derive(Default)]
+    /// # struct Bold(bool, String);
+    /// # impl Perform for Bold {
+    /// #     fn print(&mut self, c: char) { if self.0 { self.1.push(c) } }
+    /// #     fn execute(&mut self, _byte: u8) {}
+    /// #     fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    /// #     fn put(&mut self, _byte: u8) {}
+    /// #     fn unhook(&mut self) {}
+    /// #     fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    /// #     fn csi_dispatch(&mut self, params: &Params, _intermediates: &[u8], _ignore: bool, c: char, _private: Option<u8>) {
+    /// #         if c == 'm' { self.0 = params.get(0) == Some(&[1]); }
+    /// #     }
+    /// #     fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+    /// # }
+    /// // Collects the text printed in bold
+    /// let mut performer = Bold::default();
+    /// Parser::new().advance_str(&mut performer, "normal \x1b[1mbold\x1b[0m normal");
+    /// assert_eq!(performer.1, "bold");
+    /// ```
+    #[inline]
+    pub fn advance_str<P: Perform + ?Sized>(&mut self, performer: &mut P, s: &str) {
+        self.advance_slice(performer, s.as_bytes());
+    }
+
+    /// Advance the parser state until it is back in [`State::Ground`]
+    ///
+    /// Bytes are parsed one at a time, stopping after the first one which
+    /// leaves the parser in `Ground`, and the bytes which weren't parsed are
+    /// returned. Each call therefore completes at most one escape sequence,
+    /// control or character, or parses all of `bytes` if they end in the
+    /// middle of one. A sequence split across several calls is completed by
+    /// the call which parses its last byte.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use vte::{Parser, TextStripper};
+    /// let mut stripper = TextStripper::new();
+    /// let mut parser = Parser::new();
+    ///
+    /// let mut bytes: &[u8] = b"\x1b[1mhi\x1b]0;title\x07";
+    /// let mut pieces = 0;
+    /// while !bytes.is_empty() {
+    ///     bytes = parser.advance_until_ground(&mut stripper, bytes);
+    ///     pieces += 1;
+    /// }
+    /// assert_eq!(pieces, 4);
+    /// assert_eq!(stripper.text(), "hi");
+    /// # }
+    /// ```
+    ///
+    /// [`State::Ground`]: enum.State.html#variant.Ground
+    pub fn advance_until_ground<'a, P: Perform + ?Sized>(&mut self, performer: &mut P, bytes: &'a [u8]) -> &'a [u8] {
+        for (i, byte) in bytes.iter().enumerate() {
+            self.advance(performer, *byte);
+            if self.state == State::Ground {
+                return &bytes[i + 1..];
+            }
+        }
+        &[]
+    }
+
+    /// Signal the end of input and cancel any unfinished sequence
+    ///
+    /// After this call the parser is in [`State::Ground`]. The action taken
+    /// depends on the state the parser was left in:
+    ///
+    /// * `Ground`: nothing happens.
+    /// * `Escape`, `EscapeIntermediate`, `CsiEntry`, `CsiParam`,
+    ///   `CsiIntermediate`, `CsiIgnore`, `DcsEntry`, `DcsParam`,
+    ///   `DcsIntermediate` and `DcsIgnore`: the sequence is discarded and
+    ///   `execute(0x18)` (CAN) is called.
+    /// * `DcsPassthrough`: the string is terminated as usual, calling
+    ///   `unhook`, or `dcs_dispatch` with the data received so far if it is
+    ///   buffered, followed by `execute(0x18)`.
+    /// * `OscString`: `osc_dispatch` is called with the parameters received so
+    ///   far, followed by `execute(0x18)`.
+    /// * `SosPmApcString`: `sos_dispatch`, `pm_dispatch` or `apc_dispatch` is
+    ///   called with the data received so far, followed by `execute(0x18)`.
+    /// * `Utf8`: the incomplete character is discarded without calling the
+    ///   [`Perform`].
+    ///
+    /// In every case except `Ground`, `on_state_change` reports the return to
+    /// `Ground`. This is the same as feeding a CAN byte, apart from the
+    /// handling of incomplete UTF-8.
+    ///
+    /// [`State::Ground`]: enum.State.html#variant.Ground
+    /// [`Perform`]: trait.Perform.html
+    pub fn flush<P: Perform + ?Sized>(&mut self, performer: &mut P) {
+        match self.state {
+            State::Ground => (),
+            State::Utf8 => {
+                performer.on_state_change(State::Utf8, State::Ground);
+                self.utf8.reset();
+                self.state = State::Ground;
+            },
+            _ => self.advance_byte(performer, 0x18),
+        }
+    }
+
+    /// Parse `bytes` and return the resulting actions as events
+    ///
+    /// This is a pull based alternative to implementing [`Perform`]. Parser
+    /// state is kept between calls, so a sequence split across several
+    /// calls is reported once it is complete.
+    ///
+    /// All events for `bytes` are collected eagerly into a `Vec` before the
+    /// iterator is returned. A lazily streaming variant is tracked
+    /// separately.
+    ///
+    /// ```
+    /// # use vte::{Parser, VteEvent};
+    /// let mut parser = Parser::new();
+    /// let mut events = parser.parse(b"hi");
+    /// assert_eq!(events.next(), Some(VteEvent::Print('h')));
+    /// assert_eq!(events.next(), Some(VteEvent::Print('i')));
+    /// assert_eq!(events.next(), None);
+    /// ```
+    ///
+    /// [`Perform`]: trait.Perform.html
+    #[cfg(feature = "alloc")]
+    pub fn parse(&mut self, bytes: &[u8]) -> impl Iterator<Item = VteEvent> {
+        let mut recorder = event::RecordingPerform::default();
+        self.advance_slice(&mut recorder, bytes);
+        recorder.events.into_iter()
+    }
+
+    /// Parse `bytes` and return the resulting actions with their position
+    ///
+    /// Like [`parse`], but every event carries the [`byte_position`] of the
+    /// byte which caused it. Bytes are always advanced one at a time.
+    ///
+    /// ```
+    /// # use vte::{Parser, VteEvent};
+    /// let mut parser = Parser::new();
+    /// let positions: Vec<_> = parser.parse_with_positions(b"a\x1b[m")
+    ///     .filter(|parsed| !matches!(parsed.event, VteEvent::StateChange { .. }))
+    ///     .map(|parsed| parsed.byte_position)
+    ///     .collect();
+    /// assert_eq!(positions, [0, 3]);
+    /// ```
+    ///
+    /// [`parse`]: struct.Parser.html#method.parse
+    /// [`byte_position`]: struct.Parser.html#method.byte_position
+    #[cfg(feature = "alloc")]
+    pub fn parse_with_positions(&mut self, bytes: &[u8]) -> impl Iterator<Item = ParsedEvent> {
+        let mut recorder = event::RecordingPerform::default();
+        let mut parsed = Vec::new();
+        for &byte in bytes {
+            self.advance(&mut recorder, byte);
+
+            let byte_position = self.byte_position - 1;
+            parsed.extend(recorder.events.drain(..).map(|event| ParsedEvent { event, byte_position }));
+        }
+        parsed.into_iter()
+    }
+
+    /// Parse `s` and return the resulting actions with their position
+    ///
+    /// Like [`parse_with_positions`], treating the string as a raw byte
+    /// sequence; see [`advance_str`].
+    ///
+    /// ```
+    /// # use vte::{Parser, VteEvent};
+    /// let mut parser = Parser::new();
+    /// let title = parser.parse_str("\x1b]2;vim\x07").find_map(|parsed| match parsed.event {
+    ///     VteEvent::OscDispatch { params, .. } => Some((params, parsed.byte_position)),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(title, Some((vec![b"2".to_vec(), b"vim".to_vec()], 7)));
+    /// ```
+    ///
+    /// [`parse_with_positions`]: struct.Parser.html#method.parse_with_positions
+    /// [`advance_str`]: struct.Parser.html#method.advance_str
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn parse_str(&mut self, s: &str) -> impl Iterator<Item = ParsedEvent> {
+        self.parse_with_positions(s.as_bytes())
+    }
+
+    /// Advance a VT52 escape sequence
+    ///
+    /// `EscapeIntermediate` is used while collecting the arguments of
+    /// `ESC Y`.
+    fn advance_vt52<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        let state = match (self.state, byte) {
+            (_, 0x7f) => return,
+            (State::Escape, b'Y') => State::EscapeIntermediate,
+            (State::Escape, b'A'..=b'D') |
+            (State::Escape, b'F'..=b'K') |
+            (State::Escape, b'Z') |
+            (State::Escape, b'<'..=b'>') => {
+                performer.esc_dispatch(&self.params, &[], false, byte);
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.record_sequence();
+                }
+                if byte == b'<' {
+                    self.mode = ParserMode::AnsiVt;
+                }
+                State::Ground
+            },
+            (State::EscapeIntermediate, _) => {
+                self.params.push(u16::from(byte - 0x20));
+                if self.params.len() < 2 {
+                    return;
+                }
+
+                performer.esc_dispatch(&self.params, &[], false, b'Y');
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.record_sequence();
+                }
+                State::Ground
+            },
+            _ => {
+                performer.error(byte);
+                State::Ground
+            },
+        };
+
+        performer.on_state_change(self.state, state);
+        self.state = state;
+    }
+
     #[inline]
     fn process_utf8<P>(&mut self, performer: &mut P, byte: u8)
-        where P: Perform
+        where P: Perform + ?Sized
     {
-        let mut receiver = VtUtf8Receiver(performer, &mut self.state);
-        let utf8_parser = &mut self.utf8_parser;
-        utf8_parser.advance(&mut receiver, byte);
+        match self.utf8.push(byte) {
+            None => return,
+            Some(c) if !self.utf8.interrupted() => {
+                print(performer, c);
+                performer.on_state_change(State::Utf8, State::Ground);
+                self.state = State::Ground;
+                return;
+            },
+            Some(_) => (),
+        }
+
+        // The incomplete sequence is replaced as a whole, then the byte which
+        // interrupted it is processed again unless it is a stray continuation
+        self.invalid_utf8(performer);
+        performer.on_state_change(State::Utf8, State::Ground);
+        self.state = State::Ground;
+
+        match byte {
+            0x80..=0xbf => self.invalid_utf8(performer),
+            _ => self.advance_byte(performer, byte),
+        }
+    }
+
+    #[inline]
+    fn invalid_utf8<P: Perform + ?Sized>(&mut self, performer: &mut P) {
+        if self.utf8_error_mode == Utf8ErrorMode::Replace {
+            print(performer, '\u{fffd}');
+        }
     }
 
     #[inline]
     fn perform_state_change<P>(&mut self, performer: &mut P, state: State, action: Action, byte: u8)
-        where P: Perform
+        where P: Perform + ?Sized
     {
         macro_rules! maybe_action {
             ($action:expr, $arg:expr) => {
@@ -178,70 +1233,306 @@ impl Parser {
             }
         }
 
+        #[cfg(feature = "log")]
+        trace!(
+            "vte: {:?} --[{:#04x}]--> {:?} action={:?}",
+            self.state,
+            byte,
+            if state == State::Anywhere { self.state } else { state },
+            action
+        );
+
         match state {
             State::Anywhere => {
                 // Just run the action
                 self.perform_action(performer, action, byte);
             },
             state => {
-                // Exit action for previous state
+                // Exit action for previous state; receives the byte which
+                // terminated the state
                 let exit_action = self.state.exit_action();
-                maybe_action!(exit_action, 0);
+                maybe_action!(exit_action, byte);
 
                 // Transition action
                 maybe_action!(action, byte);
 
-                // Entry action for new state
-                maybe_action!(state.entry_action(), 0);
+                // The parameters overflowed in the transition action
+                let state = match state {
+                    State::CsiParam | State::DcsParam if self.ignore_pending => {
+                        self.ignore_pending = false;
+                        if state == State::CsiParam { State::CsiIgnore } else { State::DcsIgnore }
+                    },
+                    state => state,
+                };
+
+                match state {
+                    State::CsiIgnore | State::DcsIgnore if self.state != state => {
+                        performer.error(byte);
+                    },
+                    _ => (),
+                }
+
+                // Entry action for new state; receives the byte which
+                // introduced the state
+                maybe_action!(state.entry_action(), byte);
 
                 // Assume the new state
+                if self.state != state {
+                    performer.on_state_change(self.state, state);
+                }
                 self.state = state;
             }
         }
     }
 
-    /// Separate method for osc_dispatch that borrows self as read-only
-    ///
-    /// The aliasing is needed here for multiple slices into self.osc_raw
+    /// Finish the last parameter of an OSC string
     #[inline]
-    fn osc_dispatch<P: Perform>(&self, performer: &mut P) {
-        let mut slices: [&[u8]; MAX_PARAMS] = unsafe { mem::uninitialized() };
+    fn finish_osc_param(&mut self) {
+        let param_idx = self.osc_num_params;
+        let idx = self.osc_raw.len();
+
+        match param_idx {
+            // Finish last parameter if not already maxed
+            MAX_PARAMS => (),
+
+            // First param is special - 0 to current byte index
+            0 => {
+                self.osc_params[param_idx] = (0, idx);
+                self.osc_num_params += 1;
+            },
 
-        for i in 0..self.osc_num_params {
-            let indices = self.osc_params[i];
-            slices[i] = &self.osc_raw[indices.0..indices.1];
+            // All other params depend on previous indexing
+            _ => {
+                let prev = self.osc_params[param_idx - 1];
+                let begin = prev.1;
+                self.osc_params[param_idx] = (begin, idx);
+                self.osc_num_params += 1;
+            }
+        }
+    }
+
+    /// Separate method for osc_dispatch that borrows self as read-only
+    ///
+    /// The aliasing is needed here for multiple slices into self.osc_raw
+    #[inline]
+    fn osc_dispatch<P: Perform + ?Sized>(&self, performer: &mut P, bell_terminated: bool) {
+        let mut slices: [&[u8]; MAX_PARAMS] = [&[]; MAX_PARAMS];
+
+        for (slice, indices) in slices.iter_mut().zip(&self.osc_params[..self.osc_num_params]) {
+            *slice = &self.osc_raw[indices.0..indices.1];
         }
 
         performer.osc_dispatch(
             &slices[..self.osc_num_params],
+            bell_terminated,
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn dcs_hook<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        self.dcs_final = byte;
+        self.dcs_data.clear();
+
+        let (claimed, doubles_escapes) = match self.dcs_handler.handler {
+            Some(ref mut handler) if !self.ignoring => {
+                let intermediates = &self.intermediates[..self.intermediate_idx];
+                let claimed = handler.hook(&self.params, intermediates, byte);
+                (claimed, claimed && handler.doubles_escapes())
+            },
+            _ => (false, false),
+        };
+        self.dcs_handler.claimed = claimed;
+        self.dcs_handler.doubles_escapes = doubles_escapes;
+
+        self.dcs_buffered = !claimed && performer.wants_dcs_dispatch();
+        if !claimed && !self.dcs_buffered {
+            performer.hook(&self.params, self.intermediates(), self.ignoring, byte);
+        }
+    }
+
+    /// Handle `ESC ESC` in a string claimed by a handler which doubles
+    /// escapes, returning `true` if `byte` was consumed
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn dcs_escape<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) -> bool {
+        if self.dcs_handler.escape_pending {
+            self.dcs_handler.escape_pending = false;
+            if byte == 0x1b {
+                self.dcs_put(performer, byte);
+                return true;
+            }
+
+            // The ESC started the string terminator or another sequence after
+            // all, which ends the string
+            self.perform_state_change(performer, State::Escape, Action::None, 0x1b);
+            false
+        } else if byte == 0x1b && self.state == State::DcsPassthrough {
+            self.dcs_handler.escape_pending = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn dcs_put<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        if self.dcs_handler.claimed {
+            if let Some(ref mut handler) = self.dcs_handler.handler {
+                handler.put(byte);
+            }
+        } else if self.dcs_buffered {
+            self.dcs_data.push(byte);
+        } else {
+            performer.put(byte);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn dcs_unhook<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        if self.dcs_handler.claimed {
+            self.dcs_handler.claimed = false;
+            self.dcs_handler.doubles_escapes = false;
+            if let Some(ref mut handler) = self.dcs_handler.handler {
+                handler.unhook();
+            }
+            return;
+        }
+
+        if !self.dcs_buffered {
+            return performer.unhook();
+        }
+        self.dcs_buffered = false;
+        performer.dcs_dispatch(
+            &self.params,
+            self.intermediates(),
+            self.ignoring,
+            &self.dcs_data,
+            self.dcs_final,
         );
+        if self.dcs_data.is_truncated() {
+            performer.error(byte);
+        }
+    }
+
+    /// Without a buffer, DCS data is streamed to the performer
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn dcs_hook<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        performer.hook(&self.params, self.intermediates(), self.ignoring, byte);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn dcs_put<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        performer.put(byte);
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn dcs_unhook<P: Perform + ?Sized>(&mut self, performer: &mut P, _byte: u8) {
+        performer.unhook();
+    }
+
+    /// Handle parameters overflowing at `byte`
+    #[cold]
+    #[inline(never)]
+    fn overflow_params<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        if self.params.is_truncated() {
+            return;
+        }
+
+        self.params.truncate();
+        match self.param_overflow_mode {
+            ParamOverflowMode::Truncate => (),
+            ParamOverflowMode::IgnoreSequence => self.ignore_params(performer, byte),
+            ParamOverflowMode::Callback => performer.error(byte),
+        }
+    }
+
+    /// Ignore the rest of a sequence whose parameters overflowed at `byte`
+    fn ignore_params<P: Perform + ?Sized>(&mut self, performer: &mut P, byte: u8) {
+        match self.state {
+            // The parameter state is entered after this action, which is
+            // redirected by `perform_state_change`
+            State::CsiEntry | State::DcsEntry => self.ignore_pending = true,
+            State::DcsParam => self.perform_state_change(performer, State::DcsIgnore, Action::None, byte),
+            _ => self.perform_state_change(performer, State::CsiIgnore, Action::None, byte),
+        }
+    }
+
+    /// Report a sequence with more intermediates or parameters than fit
+    #[inline]
+    fn report_overflow<P: Perform + ?Sized>(&self, performer: &mut P, byte: u8) {
+        let truncated = self.params.is_truncated() && self.param_overflow_mode == ParamOverflowMode::Truncate;
+        if self.ignoring || truncated {
+            performer.error(byte);
+        }
     }
 
     #[inline]
-    fn perform_action<P: Perform>(&mut self, performer: &mut P, action: Action, byte: u8) {
+    fn perform_action<P: Perform + ?Sized>(&mut self, performer: &mut P, action: Action, byte: u8) {
+        #[cfg(feature = "alloc")]
+        {
+            if let Some(ref mut observer) = self.observer.0 {
+                observer.on_action(action, byte);
+            }
+        }
+
         match action {
-            Action::Print => performer.print(byte as char),
-            Action::Execute => performer.execute(byte),
+            Action::Print => print(performer, byte as char),
+            Action::Execute => match byte {
+                0x07 => performer.bell(),
+                0x08 => performer.backspace(),
+                0x09 => performer.tab(),
+                0x0a => performer.linefeed(),
+                0x0d => performer.carriage_return(),
+                _ => match C0::try_from(byte) {
+                    Ok(c0) => performer.execute_c0(c0),
+                    Err(_) => performer.execute(byte),
+                },
+            },
             Action::Hook => {
-                performer.hook(
-                    self.params(),
-                    self.intermediates(),
-                    self.ignoring,
-                );
+                #[cfg(feature = "metrics")]
+                {
+                    self.string_len = 0;
+                }
+                self.finish_param();
+                self.report_overflow(performer, byte);
+                self.dcs_hook(performer, byte);
+            },
+            Action::Put => {
+                #[cfg(feature = "metrics")]
+                {
+                    self.string_len += 1;
+                }
+                self.dcs_put(performer, byte);
             },
-            Action::Put => performer.put(byte),
             Action::OscStart => {
-                self.osc_idx = 0;
+                #[cfg(feature = "metrics")]
+                {
+                    self.string_len = 0;
+                }
+                self.osc_raw.clear();
                 self.osc_num_params = 0;
+                self.string_kind = StringKind::from_introducer(byte);
             },
             Action::OscPut => {
-                let idx = self.osc_idx;
-                if idx == self.osc_raw.len() {
-                    return;
+                #[cfg(feature = "metrics")]
+                {
+                    self.string_len += 1;
                 }
+                let idx = self.osc_raw.len();
 
                 // Param separator
-                if byte == b';' {
+                if byte == b';' && self.string_kind == StringKind::Osc {
+                    // Separators after the limit are dropped with the bytes
+                    if self.osc_raw.is_full() {
+                        return self.osc_raw.set_truncated();
+                    }
+
                     let param_idx = self.osc_num_params;
                     match param_idx {
                         // Only process up to MAX_PARAMS
@@ -262,63 +1553,86 @@ impl Parser {
 
                     self.osc_num_params += 1;
                 } else {
-                    self.osc_raw[idx] = byte;
-                    self.osc_idx += 1;
+                    self.osc_raw.push(byte);
                 }
             },
             Action::OscEnd => {
-                let param_idx = self.osc_num_params;
-                let idx = self.osc_idx;
-
-                match param_idx {
-                    // Finish last parameter if not already maxed
-                    MAX_PARAMS => (),
-
-                    // First param is special - 0 to current byte index
-                    0 => {
-                        self.osc_params[param_idx] = (0, idx);
-                        self.osc_num_params += 1;
+                #[cfg(feature = "metrics")]
+                {
+                    match self.string_kind {
+                        StringKind::Osc => self.metrics.record_osc(self.string_len),
+                        _ => self.metrics.record_sequence(),
+                    }
+                }
+                match self.string_kind {
+                    StringKind::Osc => {
+                        self.finish_osc_param();
+                        self.osc_dispatch(performer, byte == 0x07);
                     },
+                    StringKind::Sos => performer.sos_dispatch(&self.osc_raw),
+                    StringKind::Pm => performer.pm_dispatch(&self.osc_raw),
+                    StringKind::Apc => performer.apc_dispatch(&self.osc_raw),
+                }
 
-                    // All other params depend on previous indexing
-                    _ => {
-                        let prev = self.osc_params[param_idx - 1];
-                        let begin = prev.1;
-                        self.osc_params[param_idx] = (begin, idx);
-                        self.osc_num_params += 1;
-                    }
+                // Reported once the string was dispatched, so the error can
+                // be attributed to it
+                if self.osc_raw.is_truncated() {
+                    performer.error(byte);
                 }
-                self.osc_dispatch(performer);
             },
-            Action::Unhook => performer.unhook(),
+            Action::Unhook => {
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.record_dcs(self.string_len);
+                }
+                self.dcs_unhook(performer, byte);
+            },
             Action::CsiDispatch => {
-                if self.collecting_param {
-                    let idx = self.num_params;
-                    self.params[idx] = self.param;
-                    self.num_params += 1;
+                self.finish_param();
+                self.report_overflow(performer, byte);
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.record_csi(self.params.len());
                 }
                 performer.csi_dispatch(
-                    self.params(),
+                    &self.params,
                     self.intermediates(),
                     self.ignoring,
-                    byte as char
+                    byte as char,
+                    self.private_marker
                 );
 
-                self.num_params = 0;
-                self.param = 0;
-                self.collecting_param = false;
+                self.params.clear();
             }
+            Action::EscDispatch if byte >= 0x80 => {
+                // C1 equivalent of an escape sequence, which never has
+                // parameters or intermediates
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.record_sequence();
+                }
+                performer.esc_dispatch(&Params::new(), &[], false, byte - 0x40);
+            },
             Action::EscDispatch => {
+                self.report_overflow(performer, byte);
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.record_sequence();
+                }
                 performer.esc_dispatch(
-                    self.params(),
+                    &self.params,
                     self.intermediates(),
                     self.ignoring,
                     byte
                 );
             },
             Action::Ignore | Action::None => (),
+            Action::Collect if self.state == State::CsiEntry && matches!(byte, 0x3c..=0x3f) => {
+                // Private marker, which only starts a CSI sequence
+                self.private_marker = Some(byte);
+            },
             Action::Collect => {
-                if self.intermediate_idx == MAX_INTERMEDIATES {
+                if self.intermediate_idx >= self.max_intermediates {
                     self.ignoring = true;
                 } else {
                     self.intermediates[self.intermediate_idx] = byte;
@@ -326,37 +1640,77 @@ impl Parser {
                 }
             },
             Action::Param => {
-                if byte == b';' {
-                    // Completed a param
-                    let idx = self.num_params;
-
-                    if idx == MAX_PARAMS {
-                        return;
-                    }
+                // The byte starts or ends a parameter which doesn't fit
+                if !self.collecting_param && !self.collecting_subparam && self.params.len() >= self.max_params {
+                    self.overflow_params(performer, byte);
+                }
 
-                    self.params[idx] = self.param;
-                    self.param = 0;
-                    self.num_params += 1;
-                    self.collecting_param = false;
+                if byte == b';' {
+                    // Completed a param; empty params default to zero
+                    self.store_param();
+                    self.collecting_subparam = false;
+                } else if byte == b':' {
+                    // Completed a value, subsequent ones are subparams
+                    self.store_param();
+                    self.collecting_subparam = true;
                 } else {
                     // Continue collecting bytes into param
                     self.param = self.param.saturating_mul(10);
-                    self.param = self.param.saturating_add((byte - b'0') as i64);
+                    self.param = self.param.saturating_add((byte - b'0') as u16);
                     self.collecting_param = true;
                 }
             },
             Action::Clear => {
                 self.intermediate_idx = 0;
-                self.num_params = 0;
+                self.private_marker = None;
+                self.params.clear();
+                self.param = 0;
+                self.collecting_param = false;
+                self.collecting_subparam = false;
                 self.ignoring = false;
             },
-            Action::BeginUtf8 => {
-                self.process_utf8(performer, byte);
-            },
+            Action::BeginUtf8 => self.process_utf8(performer, byte),
         }
     }
 }
 
+/// Print `c`, with its width if the `unicode-width` feature is enabled
+#[inline]
+fn print<P: Perform + ?Sized>(performer: &mut P, c: char) {
+    #[cfg(feature = "unicode-width")]
+    performer.print_wide(c, unicode_width::UnicodeWidthChar::width(c).unwrap_or(0) as u8);
+    #[cfg(not(feature = "unicode-width"))]
+    performer.print(c);
+}
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
+    }
+}
+
+/// Shows the sequence being parsed, leaving out the configuration
+///
+/// The parameters include the one being collected and the OSC length
+/// counts the bytes of the string collected so far.
+impl fmt::Debug for Parser {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let params = self.pending_params();
+        f.debug_struct("Parser")
+            .field("state", &self.state)
+            .field("mode", &self.mode)
+            .field("params_count", &params.len())
+            .field("params", &params)
+            .field("intermediates", &self.intermediates())
+            .field("private_marker", &self.private_marker)
+            .field("ignoring", &self.ignoring)
+            .field("osc_len", &self.osc_raw.len())
+            .field("utf8_remaining", &self.utf8.remaining())
+            .field("byte_position", &self.byte_position)
+            .finish()
+    }
+}
+
 /// Performs actions requested by the Parser
 ///
 /// Actions in this case mean, for example, handling a CSI escape sequence describing cursor
@@ -367,13 +1721,125 @@ impl Parser {
 /// a useful way in my own words for completeness, but the site should be
 /// referenced if something isn't clear. If the site disappears at some point in
 /// the future, consider checking archive.org.
+///
+/// `Perform` is implemented for mutable references and (with the `alloc`
+/// feature) boxes of any `Perform`, including trait objects, so a
+/// `Box<dyn Perform>` can be passed to the parser. Every call on a trait
+/// object is dispatched dynamically.
+///
+/// # Stability
+///
+/// New methods are only added to `Perform` with a default implementation,
+/// which may happen in any minor version. The default keeps the behaviour
+/// of existing implementations the same, like [`print_wide`] calling
+/// [`print`]. New required methods and changes to the signature of existing
+/// methods are breaking changes, made in major versions and described in
+/// _MIGRATING.md_.
+///
+/// An implementation which wraps another `Perform` has to forward the new
+/// methods as well, otherwise the wrapped `Perform` only sees their
+/// defaults.
+///
+/// [`print_wide`]: trait.Perform.html#method.print_wide
+/// [`print`]: trait.Perform.html#tymethod.print
 pub trait Perform {
     /// Draw a character to the screen and update states
-    fn print(&mut self, char);
+    fn print(&mut self, c: char);
+
+    /// Draw a character which occupies `cell_width` cells
+    ///
+    /// With the `unicode-width` feature the parser calls this method instead
+    /// of [`print`], with the width computed by the `unicode-width` crate:
+    /// 2 for East Asian wide characters like CJK ideographs, 0 for combining
+    /// marks and other zero width characters and 1 for the rest. Without the
+    /// feature it is never called by the parser. The default implementation
+    /// calls `print`.
+    ///
+    /// [`print`]: trait.Perform.html#tymethod.print
+    #[inline]
+    #[allow(unused_variables)]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.print(c);
+    }
+
+    /// Move the cursor to the first column, for CR (`0x0d`)
+    ///
+    /// The default implementation calls `execute_c0(C0::Cr)`.
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.execute_c0(C0::Cr);
+    }
+
+    /// Move the cursor down a line, scrolling at the bottom margin, for LF
+    /// (`0x0a`)
+    ///
+    /// VT (`0x0b`) and FF (`0x0c`), which terminals usually treat like LF,
+    /// are still passed to `execute_c0`. The default implementation calls
+    /// `execute_c0(C0::Lf)`.
+    #[inline]
+    fn linefeed(&mut self) {
+        self.execute_c0(C0::Lf);
+    }
+
+    /// Move the cursor left a column, for BS (`0x08`)
+    ///
+    /// The default implementation calls `execute_c0(C0::Bs)`.
+    #[inline]
+    fn backspace(&mut self) {
+        self.execute_c0(C0::Bs);
+    }
+
+    /// Move the cursor to the next tab stop, for HT (`0x09`)
+    ///
+    /// The default implementation calls `execute_c0(C0::Ht)`.
+    #[inline]
+    fn tab(&mut self) {
+        self.execute_c0(C0::Ht);
+    }
+
+    /// Ring the bell, for BEL (`0x07`) as a control function
+    ///
+    /// A BEL terminating an OSC string is not a control function and
+    /// doesn't ring the bell. The default implementation calls
+    /// `execute_c0(C0::Bel)`.
+    #[inline]
+    fn bell(&mut self) {
+        self.execute_c0(C0::Bel);
+    }
 
     /// Execute a C0 or C1 control function
+    ///
+    /// CAN (`0x18`) and SUB (`0x1a`) are executed in every state. They cancel
+    /// the sequence being parsed and return the parser to the ground state;
+    /// a terminal is expected to show an error character for SUB.
+    ///
+    /// C0 controls are passed to [`execute_c0`] instead, and CR, LF, BS, HT
+    /// and BEL to [`carriage_return`], [`linefeed`], [`backspace`], [`tab`]
+    /// and [`bell`]. These call `execute` unless they are overridden.
+    ///
+    /// [`execute_c0`]: trait.Perform.html#method.execute_c0
+    /// [`carriage_return`]: trait.Perform.html#method.carriage_return
+    /// [`linefeed`]: trait.Perform.html#method.linefeed
+    /// [`backspace`]: trait.Perform.html#method.backspace
+    /// [`tab`]: trait.Perform.html#method.tab
+    /// [`bell`]: trait.Perform.html#method.bell
     fn execute(&mut self, byte: u8);
 
+    /// Execute a C0 control function, the bytes `0x00` to `0x1f`
+    ///
+    /// This is [`execute`] with the byte as a [`C0`], so implementations
+    /// don't have to match on raw bytes. The named controls like [`bell`]
+    /// call it unless they are overridden, and C1 controls are still passed
+    /// to `execute`. The default implementation calls `execute(c0 as u8)`.
+    ///
+    /// [`execute`]: trait.Perform.html#tymethod.execute
+    /// [`C0`]: enum.C0.html
+    /// [`bell`]: trait.Perform.html#method.bell
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        self.execute(c0 as u8);
+    }
+
     /// Invoked when a final character arrives in first part of device control string
     ///
     /// The control function should be determined from the private marker, final character, and
@@ -381,9 +1847,36 @@ pub trait Perform {
     /// string; the handler function should subsequently be called by `put` for every character in
     /// the control string.
     ///
-    /// The `ignore` flag indicates that more than two intermediates arrived and
+    /// `final_byte` is the byte which ended the parameters and intermediates,
+    /// and together with the intermediates identifies the control function.
+    /// A private marker (`<`, `=`, `>` or `?`) is passed as the first
+    /// intermediate. Common device control strings are:
+    ///
+    /// | Sequence                 | Intermediates | Final byte | Function                        |
+    /// |--------------------------|---------------|------------|---------------------------------|
+    /// | `DCS P1 ; P2 ; P3 q`     |               | `q`        | Sixel graphics                  |
+    /// | `DCS P1 p`               |               | `p`        | ReGIS graphics                  |
+    /// | `DCS Pc ; Pl \|`         |               | `\|`       | DECUDK, user defined keys       |
+    /// | `DCS Pfn ; Pcn ; … {`    | optional      | `{`        | DECDLD, downloadable characters |
+    /// | `DCS $ q`                | `$`           | `q`        | DECRQSS, request a setting      |
+    /// | `DCS Ps $ r`             | `$`           | `r`        | DECRPSS, response to DECRQSS    |
+    /// | `DCS Ps $ t`             | `$`           | `t`        | DECRSPS, restore presentation   |
+    /// | `DCS + q`                | `+`           | `q`        | XTGETTCAP, request termcap      |
+    /// | `DCS + p`                | `+`           | `p`        | XTSETTCAP, set termcap          |
+    /// | `DCS = 1 s`, `DCS = 2 s` | `=`           | `s`        | Begin and end synchronized update |
+    /// | `DCS tmux ; … ST`        |               | `t`        | tmux passthrough, see [`TmuxPassthroughHandler`] |
+    ///
+    /// The `ignore` flag indicates that more than eight intermediates arrived and
     /// subsequent characters were ignored.
-    fn hook(&mut self, params: &[i64], intermediates: &[u8], ignore: bool);
+    ///
+    /// This is called as soon as the final byte arrives, unless the
+    /// performer asks for the complete string with [`wants_dcs_dispatch`].
+    /// The default implementation of [`dcs_dispatch`] calls it then.
+    ///
+    /// [`wants_dcs_dispatch`]: #method.wants_dcs_dispatch
+    /// [`dcs_dispatch`]: #method.dcs_dispatch
+    /// [`TmuxPassthroughHandler`]: struct.TmuxPassthroughHandler.html
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8);
 
     /// Pass bytes as part of a device control string to the handle chosen in `hook`. C0 controls
     /// will also be passed to the handler.
@@ -395,212 +1888,1522 @@ pub trait Perform {
     /// terminated.
     fn unhook(&mut self);
 
+    /// A complete device control string has been received
+    ///
+    /// `data` holds the passthrough bytes following the `final_byte`, without the
+    /// string terminator. This is only called if [`wants_dcs_dispatch`]
+    /// returns `true`, in which case the parser buffers the bytes until the
+    /// string is terminated. Without the `alloc` feature there is no buffer;
+    /// this method is never called and `hook`, `put` and `unhook` are invoked
+    /// as the data arrives instead.
+    ///
+    /// The default implementation calls `hook`, passes every byte of `data` to
+    /// `put` and finally calls `unhook`. Implementors which only care about the
+    /// complete string can override this method and `wants_dcs_dispatch`
+    /// instead.
+    ///
+    /// [`wants_dcs_dispatch`]: #method.wants_dcs_dispatch
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.hook(params, intermediates, ignore, final_byte);
+        for byte in data {
+            self.put(*byte);
+        }
+        self.unhook();
+    }
+
+    /// Returns `true` if device control strings should be buffered and passed
+    /// to [`dcs_dispatch`]
+    ///
+    /// This is asked when a string starts. The default returns `false`, so
+    /// the string is streamed through `hook`, `put` and `unhook` as it
+    /// arrives and nothing is allocated.
+    ///
+    /// [`dcs_dispatch`]: #method.dcs_dispatch
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        false
+    }
+
     /// Dispatch an operating system command
-    fn osc_dispatch(&mut self, params: &[&[u8]]);
+    ///
+    /// The string is split on every `;`, so `params[0]` is the command number
+    /// (`b"0"` for `OSC 0 ; title BEL`) and the remaining elements are its
+    /// arguments. Adjacent or trailing separators produce empty parameters
+    /// and an empty string is a single empty parameter. Bytes other than `;`
+    /// are passed through unchanged, apart from C0 controls, which are
+    /// ignored. At most `MAX_PARAMS` parameters are split off; the rest of
+    /// the string is dropped.
+    ///
+    /// `bell_terminated` is `true` if the string was terminated by `BEL`
+    /// rather than by `ST` (or cancelled). Replies to a query are usually
+    /// expected to use the same terminator.
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool);
+
+    /// Dispatch a start of string (`ESC X`) control string
+    ///
+    /// `bytes` is the body of the string, without the introducer and the
+    /// string terminator. The default implementation does nothing.
+    #[allow(unused_variables)]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {}
+
+    /// Dispatch a privacy message (`ESC ^`) control string
+    ///
+    /// `bytes` is the body of the string, without the introducer and the
+    /// string terminator. The default implementation does nothing.
+    #[allow(unused_variables)]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {}
+
+    /// Dispatch an application program command (`ESC _`) control string
+    ///
+    /// `bytes` is the body of the string, without the introducer and the
+    /// string terminator. The default implementation does nothing.
+    #[allow(unused_variables)]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {}
 
     /// A final character has arrived for a CSI sequence
     ///
-    /// The `ignore` flag indicates that more than two intermediates arrived and
+    /// The `ignore` flag indicates that more than eight intermediates arrived and
     /// subsequent characters were ignored.
-    fn csi_dispatch(&mut self, params: &[i64], intermediates: &[u8], ignore: bool, char);
+    ///
+    /// `private` is the private parameter prefix (`<`, `=`, `>` or `?`) the
+    /// sequence started with, if any. It is not part of `intermediates`, so
+    /// `CSI ? 25 h` is dispatched with `private` set to `Some(b'?')` and no
+    /// intermediates.
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    );
 
     /// The final character of an escape sequence has arrived.
     ///
-    /// The `ignore` flag indicates that more than two intermediates arrived and
+    /// `intermediates` holds every intermediate byte in order, for example
+    /// `[0x20]` for `ESC SP F` (S7C1T) and `[0x24, 0x28]` for `ESC $ ( C`.
+    /// `params` is empty apart from the row and column of the VT52 `ESC Y`.
+    ///
+    /// The `ignore` flag indicates that more than eight intermediates arrived and
     /// subsequent characters were ignored.
-    fn esc_dispatch(&mut self, params: &[i64], intermediates: &[u8], ignore: bool, byte: u8);
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8);
+
+    /// The window should be resized to `rows` and `cols` characters
+    ///
+    /// This is never called by the [`Parser`], which doesn't interpret
+    /// sequences; `CSI 8 ; rows ; cols t` is passed to `csi_dispatch` like any
+    /// other sequence. It is called by layers which decode sequences, like
+    /// [`TerminalDecoder`], so downstream code doesn't have to match the
+    /// sequence itself. A value of 0 keeps the current size of that
+    /// dimension. The default implementation does nothing.
+    ///
+    /// [`Parser`]: struct.Parser.html
+    /// [`TerminalDecoder`]: decoder/struct.TerminalDecoder.html
+    #[allow(unused_variables)]
+    fn resize_event(&mut self, rows: u16, cols: u16) {}
+
+    /// The parser has transitioned from one state to another
+    ///
+    /// This is not called when a byte leaves the parser in the same state. The
+    /// default implementation does nothing.
+    #[allow(unused_variables)]
+    fn on_state_change(&mut self, from: State, to: State) {}
+
+    /// A malformed sequence has been received
+    ///
+    /// Called with the offending byte when it causes a CSI or DCS sequence to
+    /// be ignored, or when an unrecognised VT52 escape sequence is received.
+    /// Sequences that are still dispatched, but with more intermediates or
+    /// parameters than the parser can hold, cause a single call with their
+    /// final byte before they are dispatched. Strings which were truncated to
+    /// the limits set with [`ParserBuilder::max_osc_len`] and
+    /// [`ParserBuilder::max_dcs_len`] cause a call with the byte that ended
+    /// them right after they are dispatched. The default implementation does
+    /// nothing.
+    ///
+    /// [`ParserBuilder::max_osc_len`]: struct.ParserBuilder.html#method.max_osc_len
+    /// [`ParserBuilder::max_dcs_len`]: struct.ParserBuilder.html#method.max_dcs_len
+    #[allow(unused_variables)]
+    fn error(&mut self, byte: u8) {}
+}
+
+/// Implement `Perform` by forwarding every method to `**self`
+macro_rules! forward_perform {
+    ($($ty:ty),*) => {$(
+        impl<P: Perform + ?Sized> Perform for $ty {
+            #[inline]
+            fn print(&mut self, c: char) {
+                (**self).print(c);
+            }
+
+            #[inline]
+            fn print_wide(&mut self, c: char, cell_width: u8) {
+                (**self).print_wide(c, cell_width);
+            }
+
+            #[inline]
+            fn execute(&mut self, byte: u8) {
+                (**self).execute(byte);
+            }
+
+            #[inline]
+            fn execute_c0(&mut self, c0: C0) {
+                (**self).execute_c0(c0);
+            }
+
+            #[inline]
+            fn carriage_return(&mut self) {
+                (**self).carriage_return();
+            }
+
+            #[inline]
+            fn linefeed(&mut self) {
+                (**self).linefeed();
+            }
+
+            #[inline]
+            fn backspace(&mut self) {
+                (**self).backspace();
+            }
+
+            #[inline]
+            fn tab(&mut self) {
+                (**self).tab();
+            }
+
+            #[inline]
+            fn bell(&mut self) {
+                (**self).bell();
+            }
+
+            #[inline]
+            fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+                (**self).hook(params, intermediates, ignore, final_byte);
+            }
+
+            #[inline]
+            fn put(&mut self, byte: u8) {
+                (**self).put(byte);
+            }
+
+            #[inline]
+            fn unhook(&mut self) {
+                (**self).unhook();
+            }
+
+            #[inline]
+            fn dcs_dispatch(
+                &mut self,
+                params: &Params,
+                intermediates: &[u8],
+                ignore: bool,
+                data: &[u8],
+                final_byte: u8,
+            ) {
+                (**self).dcs_dispatch(params, intermediates, ignore, data, final_byte);
+            }
+
+            #[inline]
+            fn wants_dcs_dispatch(&self) -> bool {
+                (**self).wants_dcs_dispatch()
+            }
+
+            #[inline]
+            fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+                (**self).osc_dispatch(params, bell_terminated);
+            }
+
+            #[inline]
+            fn sos_dispatch(&mut self, bytes: &[u8]) {
+                (**self).sos_dispatch(bytes);
+            }
+
+            #[inline]
+            fn pm_dispatch(&mut self, bytes: &[u8]) {
+                (**self).pm_dispatch(bytes);
+            }
+
+            #[inline]
+            fn apc_dispatch(&mut self, bytes: &[u8]) {
+                (**self).apc_dispatch(bytes);
+            }
+
+            #[inline]
+            fn csi_dispatch(
+                &mut self,
+                params: &Params,
+                intermediates: &[u8],
+                ignore: bool,
+                action: char,
+                private: Option<u8>,
+            ) {
+                (**self).csi_dispatch(params, intermediates, ignore, action, private);
+            }
+
+            #[inline]
+            fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+                (**self).esc_dispatch(params, intermediates, ignore, byte);
+            }
+
+            #[inline]
+            fn resize_event(&mut self, rows: u16, cols: u16) {
+                (**self).resize_event(rows, cols);
+            }
+
+            #[inline]
+            fn on_state_change(&mut self, from: State, to: State) {
+                (**self).on_state_change(from, to);
+            }
+
+            #[inline]
+            fn error(&mut self, byte: u8) {
+                (**self).error(byte);
+            }
+        }
+    )*};
 }
 
+// Mutable references and boxes forward to the `Perform` they point to, which
+// allows trait objects to be used; calls through `dyn Perform` are
+// dispatched dynamically.
+forward_perform!(&mut P);
+#[cfg(feature = "alloc")]
+forward_perform!(Box<P>);
+
 #[cfg(test)]
-mod tests {
-    use super::{Parser, Perform};
-    use std::i64;
+pub mod tests {
+    use super::{AdvanceResult, Params, Parser, ParserMode, Perform, State, C0, MAX_PARAMS};
+    use {CompositePerform, ParserBuilder};
+    use super::buffer;
+    use test_utils::{RecordedEvent, RecordingPerform};
 
-    static OSC_BYTES: &'static [u8] = &[0x1b, 0x5d, // Begin OSC
+    static OSC_BYTES: &[u8] = &[0x1b, 0x5d, // Begin OSC
         b'2', b';', b'j', b'w', b'i', b'l', b'm', b'@', b'j', b'w', b'i', b'l',
         b'm', b'-', b'd', b'e', b's', b'k', b':', b' ', b'~', b'/', b'c', b'o',
         b'd', b'e', b'/', b'a', b'l', b'a', b'c', b'r', b'i', b't', b't', b'y',
         0x07 // End OSC
     ];
 
-    #[derive(Default)]
-    struct OscDispatcher {
-        dispatched_osc: bool,
-        params: Vec<Vec<u8>>,
+    /// Describe recorded events as strings so call sequences can be compared
+    ///
+    /// State changes are left out.
+    pub fn describe(events: &[RecordedEvent]) -> Vec<String> {
+        events.iter().filter_map(|event| Some(match *event {
+            RecordedEvent::Print(c) => format!("print {:?}", c),
+            RecordedEvent::Execute(byte) => format!("execute {:02x}", byte),
+            RecordedEvent::DcsHook { ref params, ref intermediates, ignore, final_byte } => {
+                format!("hook {:?} {:?} {} {:?}", params, intermediates, ignore, final_byte as char)
+            },
+            RecordedEvent::DcsPut(byte) => format!("put {:02x}", byte),
+            RecordedEvent::DcsUnhook => "unhook".into(),
+            RecordedEvent::OscDispatch { ref params, bell_terminated } => {
+                format!("osc_dispatch {:?} {}", params, bell_terminated)
+            },
+            RecordedEvent::SosDispatch(ref bytes) => format!("sos_dispatch {:?}", bytes),
+            RecordedEvent::PmDispatch(ref bytes) => format!("pm_dispatch {:?}", bytes),
+            RecordedEvent::ApcDispatch(ref bytes) => format!("apc_dispatch {:?}", bytes),
+            RecordedEvent::CsiDispatch { ref params, ref intermediates, ignore, final_byte, private } => {
+                let mut call = format!("csi_dispatch {:?} {:?} {} {:?}", params, intermediates, ignore, final_byte);
+                if let Some(marker) = private {
+                    call.push_str(&format!(" private {:?}", marker as char));
+                }
+                call
+            },
+            RecordedEvent::EscDispatch { ref params, ref intermediates, ignore, byte } => {
+                format!("esc_dispatch {:?} {:?} {} {:02x}", params, intermediates, ignore, byte)
+            },
+            RecordedEvent::StateChange { .. } => return None,
+            RecordedEvent::Error(byte) => format!("error {:02x}", byte),
+        })).collect()
     }
 
-    // All empty bodies except osc_dispatch
-    impl Perform for OscDispatcher {
-        fn print(&mut self, _: char) {}
-        fn execute(&mut self, _byte: u8) {}
-        fn hook(&mut self, _params: &[i64], _intermediates: &[u8], _ignore: bool) {}
-        fn put(&mut self, _byte: u8) {}
-        fn unhook(&mut self) {}
-        fn osc_dispatch(&mut self, params: &[&[u8]]) {
-            // Set a flag so we know these assertions all run
-            self.dispatched_osc = true;
-            self.params = params.iter().map(|p| p.to_vec()).collect();
-        }
-        fn csi_dispatch(&mut self, _params: &[i64], _intermediates: &[u8], _ignore: bool, _c: char) {}
-        fn esc_dispatch(&mut self, _params: &[i64], _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+    /// Parameters of every OSC string which was dispatched
+    fn osc_params(recorder: &RecordingPerform) -> Vec<Vec<Vec<u8>>> {
+        recorder.events().iter().filter_map(|event| match *event {
+            RecordedEvent::OscDispatch { ref params, .. } => Some(params.clone()),
+            _ => None,
+        }).collect()
     }
 
-    #[derive(Default)]
-    struct CsiDispatcher {
-        dispatched_csi: bool,
-        params: Vec<Vec<i64>>,
+    /// Parameters of every CSI sequence which was dispatched
+    fn csi_params(recorder: &RecordingPerform) -> Vec<Vec<Vec<u16>>> {
+        recorder.events().iter().filter_map(|event| match *event {
+            RecordedEvent::CsiDispatch { ref params, .. } => {
+                Some(params.iter().map(|param| param.to_vec()).collect())
+            },
+            _ => None,
+        }).collect()
     }
 
-    impl Perform for CsiDispatcher {
-        fn print(&mut self, _: char) {}
-        fn execute(&mut self, _byte: u8) {}
-        fn hook(&mut self, _params: &[i64], _intermediates: &[u8], _ignore: bool) {}
-        fn put(&mut self, _byte: u8) {}
-        fn unhook(&mut self) {}
-        fn osc_dispatch(&mut self, _params: &[&[u8]]) { }
-        fn csi_dispatch(&mut self, params: &[i64], _intermediates: &[u8], _ignore: bool, _c: char) {
-            self.dispatched_csi = true;
-            self.params.push(params.to_vec());
-        }
-        fn esc_dispatch(&mut self, _params: &[i64], _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+    fn c1_calls(bytes: &[u8], c1_controls: bool) -> Vec<String> {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.set_c1_controls(c1_controls);
+        parser.advance_slice(&mut recorder, bytes);
+        describe(recorder.events())
     }
 
-
     #[test]
-    fn parse_osc() {
-        // Create dispatcher and check state
-        let mut dispatcher = OscDispatcher::default();
-        assert_eq!(dispatcher.dispatched_osc, false);
-
-        // Run parser using OSC_BYTES
-        let mut parser = Parser::new();
-        for byte in OSC_BYTES {
-            parser.advance(&mut dispatcher, *byte);
+    fn c1_escape_sequence_equivalents() {
+        for &c1 in &[0x84u8, 0x85, 0x88, 0x8d, 0x8e, 0x8f, 0x96, 0x97, 0x9a] {
+            let calls = c1_calls(&[b'a', c1, b'b'], true);
+            assert_eq!(calls, c1_calls(&[b'a', 0x1b, c1 - 0x40, b'b'], true));
+            assert_eq!(calls[1], format!("esc_dispatch [] [] false {:02x}", c1 - 0x40));
+
+            // Cancels a partial escape sequence
+            let calls = c1_calls(&[0x1b, b'(', c1], true);
+            assert_eq!(calls, vec![format!("esc_dispatch [] [] false {:02x}", c1 - 0x40)]);
+
+            // Executed when C1 controls are disabled
+            assert_eq!(c1_calls(&[c1], false), vec![format!("execute {:02x}", c1)]);
         }
+    }
 
-        // Check that flag is set and thus osc_dispatch assertions ran.
-        assert!(dispatcher.dispatched_osc);
-        assert_eq!(dispatcher.params.len(), 2);
-        assert_eq!(dispatcher.params[0], &OSC_BYTES[2..3]);
-        assert_eq!(dispatcher.params[1], &OSC_BYTES[4..(OSC_BYTES.len() - 1)]);
+    #[test]
+    fn c1_csi() {
+        assert_eq!(c1_calls(b"\x9b1;2H", true), c1_calls(b"\x1b[1;2H", true));
+        assert_eq!(c1_calls(b"\x9b?25h", true), vec!["csi_dispatch [[25]] [] false 'h' private '?'"]);
+        assert!(c1_calls(b"\x9b", false).is_empty());
     }
 
     #[test]
-    fn parse_empty_osc() {
-        // Create dispatcher and check state
-        let mut dispatcher = OscDispatcher::default();
-        assert_eq!(dispatcher.dispatched_osc, false);
+    fn c1_strings() {
+        let cases: &[(&[u8], &[u8])] = &[
+            (b"\x90qab\x9c", b"\x1bPqab\x1b\\"),
+            (b"\x9d0;t\x9c", b"\x1b]0;t\x1b\\"),
+            (b"\x98ab\x9c", b"\x1bXab\x1b\\"),
+            (b"\x9eab\x9c", b"\x1b^ab\x1b\\"),
+            (b"\x9fab\x9c", b"\x1b_ab\x1b\\"),
+        ];
 
-        // Run parser using OSC_BYTES
-        let mut parser = Parser::new();
-        for byte in &[0x1b, 0x5d, 0x07] {
-            parser.advance(&mut dispatcher, *byte);
+        for &(c1, esc) in cases {
+            // The 7-bit string terminator is additionally dispatched as `ESC \`
+            let mut expected = c1_calls(esc, true);
+            assert_eq!(expected.pop().unwrap(), "esc_dispatch [] [] false 5c");
+            assert_eq!(c1_calls(c1, true), expected, "{:?}", c1);
         }
 
-        // Check that flag is set and thus osc_dispatch assertions ran.
-        assert!(dispatcher.dispatched_osc);
+        assert_eq!(c1_calls(b"\x90qab\x9c", true), vec!["hook [] [] false 'q'", "put 61", "put 62", "unhook"]);
+        assert_eq!(c1_calls(b"\x9d0;t\x9c", true), vec!["osc_dispatch [[48], [116]] false"]);
+        assert_eq!(c1_calls(b"\x9fab\x9c", true), vec!["apc_dispatch [97, 98]"]);
     }
 
     #[test]
-    fn parse_osc_max_params() {
-        use MAX_PARAMS;
+    fn c1_keeps_utf8_in_ground() {
+        // 0x94 and 0x80 are C1 bytes, but also continuation bytes here
+        assert_eq!(c1_calls(b"\xe2\x94\x80", true), vec!["print '─'"]);
 
-        static INPUT: &'static [u8] = b"\x1b];;;;;;;;;;;;;;;;;\x1b";
+        // A stray continuation byte is a control
+        assert_eq!(c1_calls(b"\x9b4m", true), vec!["csi_dispatch [[4]] [] false 'm'"]);
+    }
 
-        // Create dispatcher and check state
-        let mut dispatcher = OscDispatcher::default();
-        assert_eq!(dispatcher.dispatched_osc, false);
+    #[test]
+    fn c1_controls_cancel_sequences() {
+        assert_eq!(c1_calls(b"\x1b[1\x80m", true), vec!["execute 80", "print 'm'"]);
+        assert_eq!(c1_calls(b"\x1b]0;t\x99", true), vec!["osc_dispatch [[48], [116]] false", "execute 99"]);
+        assert_eq!(c1_calls(b"\x1bPqa\x91", true), vec!["hook [] [] false 'q'", "put 61", "unhook", "execute 91"]);
 
-        // Run parser using OSC_BYTES
-        let mut parser = Parser::new();
-        for byte in INPUT {
-            parser.advance(&mut dispatcher, *byte);
+        // Without C1 controls the byte is part of the sequence
+        assert_eq!(c1_calls(b"\x1b]0;t\x99\x07", false), vec!["osc_dispatch [[48], [116, 153]] true"]);
+    }
+
+    fn vt52_calls(bytes: &[u8]) -> Vec<String> {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new_vt52();
+        parser.advance_slice(&mut recorder, bytes);
+        assert_eq!(parser.current_state(), State::Ground);
+        describe(recorder.events())
+    }
+
+    #[test]
+    fn vt52_escape_sequences() {
+        for &byte in b"ABCDFGHIJKZ=>" {
+            let calls = vt52_calls(&[b'a', 0x1b, byte, b'b']);
+            assert_eq!(calls, vec![
+                "print 'a'".to_string(),
+                format!("esc_dispatch [] [] false {:02x}", byte),
+                "print 'b'".to_string(),
+            ]);
         }
+    }
 
-        // Check that flag is set and thus osc_dispatch assertions ran.
-        assert!(dispatcher.dispatched_osc);
-        assert_eq!(dispatcher.params.len(), MAX_PARAMS);
-        for param in dispatcher.params.iter() {
-            assert_eq!(param.len(), 0);
+    #[test]
+    fn vt52_direct_cursor_address() {
+        // Line 3, column 10
+        assert_eq!(vt52_calls(b"\x1bY#*x"), vec!["esc_dispatch [[3], [10]] [] false 59", "print 'x'"]);
+
+        // Control characters are executed in between
+        assert_eq!(vt52_calls(b"\x1bY \x07 "), vec!["execute 07", "esc_dispatch [[0], [0]] [] false 59"]);
+    }
+
+    #[test]
+    fn vt52_ignores_ansi_sequences() {
+        assert_eq!(vt52_calls(b"\x1b[2J"), vec!["error 5b", "print '2'", "print 'J'"]);
+        assert_eq!(vt52_calls(b"\x1b]0;t\x07"), vec!["error 5d", "print '0'", "print ';'", "print 't'", "execute 07"]);
+        assert_eq!(vt52_calls(b"\x1bP\x1b\x1bA"), vec!["error 50", "esc_dispatch [] [] false 41"]);
+        assert_eq!(vt52_calls(b"\x1b\x18A"), vec!["execute 18", "print 'A'"]);
+    }
+
+    #[test]
+    fn vt52_enter_ansi_mode() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new_vt52();
+        assert_eq!(parser.mode(), ParserMode::Vt52);
+
+        parser.advance_slice(&mut recorder, b"\x1b<\x1b[2J");
+        assert_eq!(parser.mode(), ParserMode::AnsiVt);
+        assert_eq!(describe(recorder.events()), vec![
+            "esc_dispatch [] [] false 3c",
+            "csi_dispatch [[2]] [] false 'J'",
+        ]);
+
+        parser.set_mode(ParserMode::Vt52);
+        recorder.clear();
+        parser.advance_slice(&mut recorder, b"\x1bH");
+        assert_eq!(describe(recorder.events()), vec!["esc_dispatch [] [] false 48"]);
+    }
+
+    fn calls(bytes: &[u8]) -> Vec<String> {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, bytes);
+        describe(recorder.events())
+    }
+
+    /// Records bells separately from other controls
+    #[derive(Default)]
+    struct Bells {
+        calls: Vec<&'static str>,
+    }
+
+    impl Perform for Bells {
+        fn print(&mut self, _: char) {}
+        fn execute(&mut self, _byte: u8) {
+            self.calls.push("execute");
+        }
+        fn bell(&mut self) {
+            self.calls.push("bell");
         }
+        fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+        fn put(&mut self, _byte: u8) {}
+        fn unhook(&mut self) {}
+        fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {
+            self.calls.push("osc_dispatch");
+        }
+        fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+        fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+    }
+
+    /// Records printed characters with their width
+    #[cfg(feature = "unicode-width")]
+    #[derive(Default)]
+    struct Widths(Vec<(char, u8)>);
 
+    #[cfg(feature = "unicode-width")]
+    impl Perform for Widths {
+        fn print(&mut self, c: char) {
+            panic!("print {:?} without width", c);
+        }
+        fn print_wide(&mut self, c: char, cell_width: u8) {
+            self.0.push((c, cell_width));
+        }
+        fn execute(&mut self, _byte: u8) {}
+        fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+        fn put(&mut self, _byte: u8) {}
+        fn unhook(&mut self) {}
+        fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+        fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+        fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
     }
 
+    #[cfg(feature = "unicode-width")]
     #[test]
-    fn parse_csi_max_params() {
-        use MAX_PARAMS;
+    fn print_wide() {
+        let mut widths = Widths::default();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut widths, "a~\x1b[1m漢字\u{3042}e\u{301}\u{200b}é\u{1f600}".as_bytes());
+        parser.advance_slice(&mut widths, b"\xe2\x94x");
+        assert_eq!(widths.0, [
+            ('a', 1),
+            ('~', 1),
+            ('漢', 2),
+            ('字', 2),
+            ('\u{3042}', 2),
+            ('e', 1),
+            ('\u{301}', 0),
+            ('\u{200b}', 0),
+            ('é', 1),
+            ('\u{1f600}', 2),
+            ('\u{fffd}', 1),
+            ('x', 1),
+        ]);
+
+        // Wrappers pass the width on
+        let mut widths = CompositePerform(Widths::default(), Widths::default());
+        parser.advance_slice(&mut widths, "漢".as_bytes());
+        assert_eq!((widths.0).0, [('漢', 2)]);
+        assert_eq!((widths.1).0, [('漢', 2)]);
+    }
+
+    #[test]
+    fn bell() {
+        let mut bells = Bells::default();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut bells, b"\x07\n\x1b[1\x07m\x1b\x07c");
+        assert_eq!(bells.calls, vec!["bell", "execute", "bell", "bell"]);
+
+        // The terminator of an OSC string isn't a bell
+        let mut bells = Bells::default();
+        parser.advance_slice(&mut bells, b"\x1b]0;title\x07\x07");
+        assert_eq!(bells.calls, vec!["osc_dispatch", "bell"]);
+
+        // By default bells are executed
+        assert_eq!(calls(b"\x07\x1b]0;t\x07"), vec!["execute 07", "osc_dispatch [[48], [116]] true"]);
+    }
+
+    #[test]
+    fn can_and_sub_cancel_sequences() {
+        // The CSI sequence is dropped and its final byte is printed
+        assert_eq!(calls(b"\x1b[3\x18H"), vec!["execute 18", "print 'H'"]);
+        assert_eq!(calls(b"\x1b[3\x1aH"), vec!["execute 1a", "print 'H'"]);
+
+        for &byte in &[0x18, 0x1a] {
+            let execute = format!("execute {:x}", byte);
+            let with = |prefix: &[u8]| {
+                let mut input = prefix.to_vec();
+                input.extend_from_slice(&[byte, b'x']);
+                calls(&input)
+            };
+
+            for prefix in &[&b"\x1b"[..], b"\x1b#", b"\x1b[?1;", b"\x1b[1 ", b"\x1bP1$", b"\x1b[1?"] {
+                let mut expected = calls(prefix);
+                expected.extend(vec![execute.clone(), "print 'x'".into()]);
+                assert_eq!(with(prefix), expected, "{:?}", prefix);
+            }
+
+            // Strings end as if they were terminated
+            assert_eq!(with(b"\x1b]0;t"), vec![
+                "osc_dispatch [[48], [116]] false".to_string(), execute.clone(), "print 'x'".into(),
+            ]);
+            assert_eq!(with(b"\x1bPqa"), vec![
+                "hook [] [] false 'q'".to_string(), "put 61".into(), "unhook".into(), execute.clone(), "print 'x'".into(),
+            ]);
+
+            // An unfinished character is replaced first
+            assert_eq!(with(b"\xe2\x9c"), vec![
+                "print '\u{fffd}'".to_string(), execute.clone(), "print 'x'".into(),
+            ]);
+        }
+    }
+
+    #[test]
+    fn error_on_csi_ignore() {
+        // Private marker in parameter position
+        assert_eq!(calls(b"\x1b[1?2hx"), vec!["error 3f", "print 'x'"]);
+
+        // Parameter after an intermediate
+        assert_eq!(calls(b"\x1b[ 1q"), vec!["error 31"]);
+    }
+
+    #[test]
+    fn error_on_dcs_ignore() {
+        assert_eq!(calls(b"\x1bP1<q\x1b\\"), vec!["error 3c", "esc_dispatch [] [] false 5c"]);
+    }
+
+    #[test]
+    fn error_on_overflow() {
+        let mut params = b"\x1b[".to_vec();
+        for _ in 0..(MAX_PARAMS + 1) {
+            params.extend_from_slice(b"1;");
+        }
+        params.push(b'm');
+        let result = calls(&params);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], "error 6d");
+        assert!(result[1].starts_with("csi_dispatch"));
+
+        assert_eq!(calls(b"\x1b[ !\"#$%&'(q"), vec!["error 71", "csi_dispatch [] [32, 33, 34, 35, 36, 37, 38, 39] true 'q'"]);
+        assert_eq!(calls(b"\x1b !\"#$%&'(B"), vec!["error 42", "esc_dispatch [] [32, 33, 34, 35, 36, 37, 38, 39] true 42"]);
+    }
 
-        static INPUT: &'static [u8] = b"\x1b[;;;;;;;;;;;;;;;;;p";
+    #[test]
+    fn error_on_truncated_strings() {
+        let calls = |bytes: &[u8]| {
+            let mut recorder = RecordingPerform::new();
+            let mut parser = ParserBuilder::new().max_osc_len(4).build();
+            parser.advance_slice(&mut recorder, bytes);
+            describe(recorder.events())
+        };
+
+        // The error follows the dispatch of the string it belongs to
+        assert_eq!(calls(b"\x1b]0;abcd\x07"), vec!["osc_dispatch [[48], [97, 98, 99]] true", "error 07"]);
+        assert_eq!(calls(b"\x1b_abcde\x1b\\")[..2], ["apc_dispatch [97, 98, 99, 100]", "error 1b"]);
+        assert_eq!(calls(b"\x1b]0;a\x07"), vec!["osc_dispatch [[48], [97]] true"]);
+
+        // Separators past the limit are dropped like any other byte
+        assert_eq!(calls(b"\x1b]0;abc;d\x07"), vec!["osc_dispatch [[48], [97, 98, 99]] true", "error 07"]);
+    }
 
-        // Create dispatcher and check state
-        let mut dispatcher = CsiDispatcher::default();
-        assert!(!dispatcher.dispatched_csi);
+    #[test]
+    fn default_osc_limit() {
+        let mut osc = b"\x1b]".to_vec();
+        osc.resize(buffer::DEFAULT_LIMIT + 2, b'a');
+        osc.push(0x07);
+        let events = |bytes: &[u8]| {
+            let mut recorder = RecordingPerform::new();
+            Parser::new().advance_slice(&mut recorder, bytes);
+            recorder.events().iter()
+                .filter(|event| !matches!(**event, RecordedEvent::StateChange { .. }))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let dispatch = RecordedEvent::OscDispatch {
+            params: vec![vec![b'a'; buffer::DEFAULT_LIMIT]],
+            bell_terminated: true,
+        };
+        assert_eq!(events(&osc), vec![dispatch.clone()]);
+
+        osc.insert(2, b'a');
+        assert_eq!(events(&osc), vec![dispatch, RecordedEvent::Error(0x07)]);
+    }
 
+    /// Records the length of dispatched strings and the bytes of errors
+    #[cfg(feature = "alloc")]
+    #[derive(Default)]
+    struct StringLengths {
+        osc: Vec<usize>,
+        dcs: Vec<usize>,
+        errors: Vec<u8>,
+    }
 
-        // Run parser using OSC_BYTES
+    #[cfg(feature = "alloc")]
+    impl Perform for StringLengths {
+        fn print(&mut self, _: char) {}
+        fn execute(&mut self, _byte: u8) {}
+        fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+        fn put(&mut self, _byte: u8) {}
+        fn unhook(&mut self) {}
+        fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+            self.osc.push(params.iter().map(|param| param.len()).sum());
+        }
+        fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+        fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+        fn dcs_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, data: &[u8], _final_byte: u8) {
+            self.dcs.push(data.len());
+        }
+        fn wants_dcs_dispatch(&self) -> bool {
+            true
+        }
+        fn error(&mut self, byte: u8) {
+            self.errors.push(byte);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn long_strings_stay_bounded() {
+        const LEN: usize = 10 * 1024 * 1024;
+        let chunk = [b'a'; 4096];
+        let mut performer = StringLengths::default();
         let mut parser = Parser::new();
-        for byte in INPUT {
-            parser.advance(&mut dispatcher, *byte);
+
+        parser.advance_slice(&mut performer, b"\x1b]0;");
+        for _ in 0..LEN / chunk.len() {
+            parser.advance_slice(&mut performer, &chunk);
+        }
+        assert!(parser.osc_raw.capacity() <= buffer::DEFAULT_LIMIT + 64);
+        parser.advance(&mut performer, 0x07);
+        assert_eq!(performer.osc, vec![buffer::DEFAULT_LIMIT]);
+        assert_eq!(performer.errors, vec![0x07]);
+
+        parser.advance_slice(&mut performer, b"\x1bPq");
+        for _ in 0..LEN / chunk.len() {
+            parser.advance_slice(&mut performer, &chunk);
+        }
+        assert!(parser.dcs_data.capacity() <= buffer::DEFAULT_LIMIT + 64);
+        parser.advance_slice(&mut performer, b"\x1b\\");
+        assert_eq!(performer.dcs, vec![buffer::DEFAULT_LIMIT]);
+        assert_eq!(performer.errors, vec![0x07, 0x1b]);
+    }
+
+    #[test]
+    fn esc_intermediates() {
+        assert_eq!(calls(b"\x1b F"), vec!["esc_dispatch [] [32] false 46"]);
+        assert_eq!(calls(b"\x1b(B"), vec!["esc_dispatch [] [40] false 42"]);
+        assert_eq!(calls(b"\x1b$(C"), vec!["esc_dispatch [] [36, 40] false 43"]);
+    }
+
+    #[test]
+    fn csi_intermediates() {
+        assert_eq!(calls(b"\x1b[!p"), vec!["csi_dispatch [] [33] false 'p'"]);
+        assert_eq!(calls(b"\x1b[1 !p"), vec!["csi_dispatch [[1]] [32, 33] false 'p'"]);
+        assert_eq!(calls(b"\x1b[?1 !\"p"), vec!["csi_dispatch [[1]] [32, 33, 34] false 'p' private '?'"]);
+        assert_eq!(calls(b"\x1b[        p"), vec!["csi_dispatch [] [32, 32, 32, 32, 32, 32, 32, 32] false 'p'"]);
+        assert_eq!(calls(b"\x1b[         p"), vec!["error 70", "csi_dispatch [] [32, 32, 32, 32, 32, 32, 32, 32] true 'p'"]);
+    }
+
+    #[test]
+    fn csi_private_marker() {
+        assert_eq!(calls(b"\x1b[?25h"), vec!["csi_dispatch [[25]] [] false 'h' private '?'"]);
+        assert_eq!(calls(b"\x1b[>c"), vec!["csi_dispatch [] [] false 'c' private '>'"]);
+        assert_eq!(calls(b"\x1b[=1;2c"), vec!["csi_dispatch [[1], [2]] [] false 'c' private '='"]);
+        assert_eq!(calls(b"\x1b[<0;10;20M"), vec!["csi_dispatch [[0], [10], [20]] [] false 'M' private '<'"]);
+
+        // The marker doesn't carry over to the next sequence
+        assert_eq!(calls(b"\x1b[?1h\x1b[1h")[1], "csi_dispatch [[1]] [] false 'h'");
+
+        // DCS sequences keep it among their intermediates
+        assert_eq!(calls(b"\x1bP?1q\x1b\\")[0], "hook [[1]] [63] false 'q'");
+    }
+
+    #[test]
+    fn error_on_unknown_vt52_escape() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new_vt52();
+        parser.advance_slice(&mut recorder, b"\x1b[");
+        assert_eq!(describe(recorder.events()), vec!["error 5b"]);
+    }
+
+    fn flush_after(bytes: &[u8]) -> (State, Vec<String>) {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, bytes);
+        let state = parser.current_state();
+
+        recorder.clear();
+        parser.flush(&mut recorder);
+        assert_eq!(parser.current_state(), State::Ground);
+
+        (state, describe(recorder.events()))
+    }
+
+    #[test]
+    fn flush_cancels_sequences() {
+        let cases: &[(&[u8], State)] = &[
+            (b"\x1b", State::Escape),
+            (b"\x1b(", State::EscapeIntermediate),
+            (b"\x1b[", State::CsiEntry),
+            (b"\x1b[1", State::CsiParam),
+            (b"\x1b[1 ", State::CsiIntermediate),
+            (b"\x1b[1<", State::CsiIgnore),
+            (b"\x1bP", State::DcsEntry),
+            (b"\x1bP1", State::DcsParam),
+            (b"\x1bP ", State::DcsIntermediate),
+            (b"\x1bP1<", State::DcsIgnore),
+        ];
+
+        for &(bytes, expected) in cases {
+            let (state, calls) = flush_after(bytes);
+            assert_eq!(state, expected);
+            assert_eq!(calls, vec!["execute 18"], "{:?}", bytes);
         }
+    }
 
-        // Check that flag is set and thus csi_dispatch assertions ran.
-        assert!(dispatcher.dispatched_csi);
-        assert_eq!(dispatcher.params.len(), 1);
-        assert_eq!(dispatcher.params[0].len(), MAX_PARAMS);
+    #[test]
+    fn flush_dispatches_strings() {
+        let (state, calls) = flush_after(b"\x1bP1qab");
+        assert_eq!(state, State::DcsPassthrough);
+        assert_eq!(calls.last().unwrap(), "execute 18");
+        assert!(calls.contains(&"unhook".to_string()));
+
+        let (state, calls) = flush_after(b"\x1b]0;ti");
+        assert_eq!(state, State::OscString);
+        assert_eq!(calls, vec!["osc_dispatch [[48], [116, 105]] false", "execute 18"]);
+
+        let (state, calls) = flush_after(b"\x1b_ab");
+        assert_eq!(state, State::SosPmApcString);
+        assert_eq!(calls, vec!["apc_dispatch [97, 98]", "execute 18"]);
+    }
 
+    #[test]
+    fn flush_discards_partial_utf8() {
+        let (state, calls) = flush_after(b"\xe2\x94");
+        assert_eq!(state, State::Utf8);
+        assert!(calls.is_empty());
+
+        // Nothing to do in ground, and the parser is usable afterwards
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"\xe2");
+        parser.flush(&mut recorder);
+        parser.flush(&mut recorder);
+        parser.advance_slice(&mut recorder, b"\xe2\x94\x80");
+        assert_eq!(describe(recorder.events()), vec!["print '─'"]);
     }
 
     #[test]
-    fn parse_semi_set_underline() {
+    fn advance_until_ground_splits_sequences() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        let bytes: &[u8] = b"\x1b[1;2Ha\x1b]0;t\x1b\\\xe2\x94\x80\r";
+
+        let mut rest = parser.advance_until_ground(&mut recorder, bytes);
+        assert_eq!(rest, &bytes[6..]);
+        assert_eq!(describe(recorder.events()), vec!["csi_dispatch [[1], [2]] [] false 'H'"]);
+
+        rest = parser.advance_until_ground(&mut recorder, rest);
+        assert_eq!(rest, &bytes[7..]);
+
+        // The OSC is dispatched at the ESC of its terminator
+        recorder.clear();
+        rest = parser.advance_until_ground(&mut recorder, rest);
+        assert_eq!(rest, b"\xe2\x94\x80\r");
+        assert_eq!(describe(recorder.events()), vec![
+            "osc_dispatch [[48], [116]] false",
+            "esc_dispatch [] [] false 5c",
+        ]);
+
+        rest = parser.advance_until_ground(&mut recorder, rest);
+        assert_eq!(rest, b"\r");
+        rest = parser.advance_until_ground(&mut recorder, rest);
+        assert!(rest.is_empty());
+        assert_eq!(parser.advance_until_ground(&mut recorder, rest), b"");
+    }
+
+    #[test]
+    fn advance_until_ground_across_chunks() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+
+        assert!(parser.advance_until_ground(&mut recorder, b"\x1b[3").is_empty());
+        assert_eq!(parser.current_state(), State::CsiParam);
+        assert!(parser.advance_until_ground(&mut recorder, b"8;5").is_empty());
+        assert!(describe(recorder.events()).is_empty());
+
+        assert_eq!(parser.advance_until_ground(&mut recorder, b";1mx"), b"x");
+        assert_eq!(describe(recorder.events()), vec!["csi_dispatch [[38], [5], [1]] [] false 'm'"]);
+
+        recorder.clear();
+        assert!(parser.advance_until_ground(&mut recorder, b"\x1bP1qab").is_empty());
+        assert_eq!(parser.advance_until_ground(&mut recorder, b"c\x1b\\d"), b"d");
+        assert_eq!(parser.current_state(), State::Ground);
+    }
+
+    #[test]
+    fn clone_snapshots_partial_sequences() {
+        // Leave the parser mid-CSI with an intermediate and a partial param
+        static PREFIX: &[u8] = b"ab\x1b[12;3";
+        static SUFFIX: &[u8] = b"4 q\xe2\x94\x80\x1b]0;title\x07\x1b(B";
 
-        // Create dispatcher and check state
-        let mut dispatcher = CsiDispatcher::default();
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        for byte in PREFIX {
+            parser.advance(&mut recorder, *byte);
+        }
+
+        let mut cloned = parser.clone();
+        let mut cloned_recorder = RecordingPerform::new();
+        let mut original_recorder = RecordingPerform::new();
+
+        for byte in SUFFIX {
+            parser.advance(&mut original_recorder, *byte);
+            cloned.advance(&mut cloned_recorder, *byte);
+        }
+
+        assert_eq!(describe(original_recorder.events()), describe(cloned_recorder.events()));
+        assert_eq!(describe(original_recorder.events())[0], "csi_dispatch [[12], [34]] [32] false 'q'");
+    }
+
+    #[test]
+    fn clone_snapshots_partial_utf8() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+
+        // First two bytes of a three byte sequence
+        parser.advance(&mut recorder, 0xe2);
+        parser.advance(&mut recorder, 0x94);
+
+        let mut cloned = parser.clone();
+        parser.advance(&mut recorder, 0x80);
+
+        let mut cloned_recorder = RecordingPerform::new();
+        cloned.advance(&mut cloned_recorder, 0x80);
+
+        assert_eq!(describe(recorder.events()), vec!["print '─'"]);
+        assert_eq!(describe(recorder.events()), describe(cloned_recorder.events()));
+    }
+
+    #[test]
+    fn advance_slice_matches_advance() {
+        static INPUTS: &[&[u8]] = &[
+            b"plain text",
+            b"\x1b[1;31mred\x1b[0m\r\n",
+            b"\x1b]2;title\x07after",
+            b"\x1bP1$qm\x1b\\",
+            b"\x1b(0lqk\x1b(B",
+            b"\xe2\x94\x80\xc3\xa9\x1b[?25l",
+            b"\x1b[1;2;3;4;5;6;7;8;9;10;11;12;13;14;15;16;17;18;19;20;21;22;23;24;25;26;27;28;29;30;31;32;33;34H",
+        ];
+
+        for input in INPUTS {
+            let mut expected = RecordingPerform::new();
+            let mut parser = Parser::new();
+            for byte in *input {
+                parser.advance(&mut expected, *byte);
+            }
+
+            let mut actual = RecordingPerform::new();
+            let mut parser = Parser::new();
+            parser.advance_slice(&mut actual, input);
 
-        // Run parser using OSC_BYTES
+            assert_eq!(expected.events(), actual.events());
+        }
+    }
+
+    #[test]
+    fn on_state_change_reports_transitions() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"a\x1b[12mb\xc3\xa9");
+
+        let transitions: Vec<_> = recorder.events().iter().filter_map(|event| match *event {
+            RecordedEvent::StateChange { from, to } => Some((from, to)),
+            _ => None,
+        }).collect();
+        assert_eq!(transitions, vec![
+            (State::Ground, State::Escape),
+            (State::Escape, State::CsiEntry),
+            (State::CsiEntry, State::CsiParam),
+            (State::CsiParam, State::Ground),
+            (State::Ground, State::Utf8),
+            (State::Utf8, State::Ground),
+        ]);
+    }
+
+    #[test]
+    fn parse_dcs_params() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"\x1bP1;2qx\x1b\\");
+
+        assert_eq!(describe(recorder.events())[0], "hook [[1], [2]] [] false 'q'");
+
+        // The final byte selects the function
+        let mut recorder = RecordingPerform::new();
+        parser.advance_slice(&mut recorder, b"\x1bP1;1|17/7e\x1b\\");
+        assert_eq!(describe(recorder.events())[0], "hook [[1], [1]] [] false '|'");
+    }
+
+    #[test]
+    fn parse_apc() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"\x1b_hello\x1b\\");
+
+        assert_eq!(describe(recorder.events()), vec![
+            format!("apc_dispatch {:?}", b"hello"),
+            "esc_dispatch [] [] false 5c".into(),
+        ]);
+    }
+
+    #[test]
+    fn parse_sos_pm_apc_strings() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"\x1bXa;b\x1b\\\x1b^c;d\x1b\\\x1b_Gf=1;\x1b\\");
+
+        let calls: Vec<_> = describe(recorder.events()).iter()
+            .filter(|call| !call.starts_with("esc_dispatch"))
+            .cloned()
+            .collect();
+        assert_eq!(calls, vec![
+            format!("sos_dispatch {:?}", b"a;b"),
+            format!("pm_dispatch {:?}", b"c;d"),
+            format!("apc_dispatch {:?}", b"Gf=1;"),
+        ]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[derive(Default)]
+    pub struct DcsRecorder {
+        pub calls: Vec<String>,
+    }
+
+    #[cfg(feature = "alloc")]
+    impl Perform for DcsRecorder {
+        fn print(&mut self, _: char) {}
+        fn execute(&mut self, _byte: u8) {}
+        fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+        fn put(&mut self, _byte: u8) {}
+        fn unhook(&mut self) {}
+        fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+        fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+        fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+        fn dcs_dispatch(
+            &mut self,
+            params: &Params,
+            intermediates: &[u8],
+            ignore: bool,
+            data: &[u8],
+            final_byte: u8,
+        ) {
+            self.calls.push(format!("hook {:?} {:?} {} {:?}", params, intermediates, ignore, final_byte as char));
+            for byte in data {
+                self.calls.push(format!("put {:02x}", byte));
+            }
+            self.calls.push("unhook".into());
+            self.calls.push(format!("final {:?}", final_byte as char));
+        }
+        fn wants_dcs_dispatch(&self) -> bool {
+            true
+        }
+        fn error(&mut self, byte: u8) {
+            self.calls.push(format!("error {:02x}", byte));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn dcs_dispatch_matches_hook_put_unhook() {
+        static INPUT: &[u8] = b"\x1bP1$qm\x1b\\\x1bP0;1q#0;2;0;0;0#0~~~\x1b\\";
+
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, INPUT);
+
+        let mut dcs_recorder = DcsRecorder::default();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut dcs_recorder, INPUT);
+
+        let finals: Vec<_> = dcs_recorder.calls.iter()
+            .filter(|call| call.starts_with("final"))
+            .cloned()
+            .collect();
+        assert_eq!(finals, vec!["final 'q'", "final 'q'"]);
+
+        let expected: Vec<_> = describe(recorder.events()).iter()
+            .filter(|call| !call.starts_with("esc_dispatch"))
+            .cloned()
+            .collect();
+        dcs_recorder.calls.retain(|call| !call.starts_with("final"));
+        assert_eq!(expected, dcs_recorder.calls);
+        assert_eq!(expected[0], "hook [[1]] [36] false 'q'");
+        assert_eq!(expected[1], "put 6d");
+    }
+
+    #[test]
+    fn dcs_is_streamed_by_default() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"\x1bP1$qab");
+        let calls: Vec<_> = describe(recorder.events()).into_iter()
+            .filter(|call| !call.starts_with("state"))
+            .collect();
+        assert_eq!(calls, ["hook [[1]] [36] false 'q'", "put 61", "put 62"]);
+
+        // Only performers asking for the complete string wait for `ST`
+        #[cfg(feature = "alloc")]
+        {
+            let mut dcs_recorder = DcsRecorder::default();
+            let mut parser = Parser::new();
+            parser.advance_slice(&mut dcs_recorder, b"\x1bP1$qab");
+            assert!(dcs_recorder.calls.is_empty());
+            parser.advance_slice(&mut dcs_recorder, b"\x1b\\");
+            assert_eq!(dcs_recorder.calls[..3], ["hook [[1]] [36] false 'q'", "put 61", "put 62"]);
+        }
+    }
+
+    #[test]
+    fn parse_osc() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        for byte in OSC_BYTES {
+            parser.advance(&mut recorder, *byte);
+        }
+
+        let dispatched = osc_params(&recorder);
+        assert_eq!(dispatched.len(), 1);
+        assert_eq!(dispatched[0].len(), 2);
+        assert_eq!(dispatched[0][0], &OSC_BYTES[2..3]);
+        assert_eq!(dispatched[0][1], &OSC_BYTES[4..(OSC_BYTES.len() - 1)]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn trait_objects() {
+        let input = b"a\x1b[4m\x1b]0;t\x07\x1bPqx\x1b\\";
+        let expected = calls(input);
+
+        let mut recorder = RecordingPerform::new();
+        {
+            let mut boxed: Box<dyn Perform> = Box::new(&mut recorder);
+            Parser::new().advance_slice(&mut *boxed, input);
+        }
+        assert_eq!(describe(recorder.events()), expected);
+
+        let mut boxed = Box::new(RecordingPerform::new());
+        Parser::new().advance_slice(&mut boxed, input);
+        assert_eq!(describe(boxed.events()), expected);
+    }
+
+    #[test]
+    fn byte_position() {
+        let input = b"a\x1b[4m\xe2\x94\x80\x1b]0;t\x07\x1bPqx\x1b\\\xc3z";
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut RecordingPerform::new(), input);
+        assert_eq!(parser.byte_position(), input.len() as u64);
+
+        let mut clone = parser.clone();
+        assert_eq!(clone.byte_position(), input.len() as u64);
+
+        // Cancelling a sequence doesn't count as input
+        clone.advance(&mut RecordingPerform::new(), 0x1b);
+        clone.flush(&mut RecordingPerform::new());
+        assert_eq!(clone.byte_position(), input.len() as u64 + 1);
+        assert_eq!(parser.byte_position(), input.len() as u64);
+    }
+
+    #[test]
+    fn advance_str() {
+        let input = "\u{2500}\x1b[?25l\x1bP1$qm\x1b\\";
+        let mut expected = RecordingPerform::new();
+        Parser::new().advance_slice(&mut expected, input.as_bytes());
+
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_str(&mut recorder, input);
+        assert_eq!(recorder.events(), expected.events());
+
+        #[cfg(feature = "alloc")]
+        {
+            let parsed: Vec<_> = Parser::new().parse_str(input).map(|parsed| parsed.event).collect();
+            assert_eq!(parsed, expected.events());
+        }
+    }
+
+    #[test]
+    fn advance_with_result() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+
+        for &byte in b"a\xe2\x94" {
+            assert_eq!(parser.advance_with_result(&mut recorder, byte), AdvanceResult { consumed: true, interrupted: false });
+        }
+        assert_eq!(parser.advance_with_result(&mut recorder, 0x1b), AdvanceResult { consumed: true, interrupted: true });
+        assert_eq!(parser.byte_position(), 4);
+
+        // Invalid continuation bytes are part of the sequence
+        parser.advance_slice(&mut recorder, b"\\\xe0");
+        assert!(!parser.advance_with_result(&mut recorder, 0x80).interrupted);
+        assert_eq!(parser.current_state(), State::Ground);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn log_transitions() {
+        use std::cell::RefCell;
+        use std::sync::Once;
+
+        use log::{self, Log, Metadata, Record};
+
+        thread_local!(static LINES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) });
+
+        struct Capture;
+
+        impl Log for Capture {
+            fn enabled(&self, _metadata: &Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &Record) {
+                LINES.with(|lines| lines.borrow_mut().push(record.args().to_string()));
+            }
+
+            fn flush(&self) {}
+        }
+
+        static INIT: Once = Once::new();
+        static LOGGER: Capture = Capture;
+        INIT.call_once(|| {
+            log::set_logger(&LOGGER).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+
+        Parser::new().advance_slice(&mut RecordingPerform::new(), b"\x1b[4m");
+
+        LINES.with(|lines| {
+            assert_eq!(*lines.borrow(), vec![
+                "vte: Ground --[0x1b]--> Escape action=None",
+                "vte: Escape --[0x5b]--> CsiEntry action=None",
+                "vte: CsiEntry --[0x34]--> CsiParam action=Param",
+                "vte: CsiParam --[0x6d]--> Ground action=CsiDispatch",
+            ]);
+        });
+    }
+
+    #[test]
+    fn osc_terminators() {
+        let bel = calls(b"\x1b]52;c;?\x07");
+        let st = calls(b"\x1b]52;c;?\x1b\\");
+        assert_eq!(bel, vec!["osc_dispatch [[53, 50], [99], [63]] true"]);
+        assert_eq!(st[0], "osc_dispatch [[53, 50], [99], [63]] false");
+    }
+
+    #[test]
+    fn parse_empty_osc() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        for byte in &[0x1b, 0x5d, 0x07] {
+            parser.advance(&mut recorder, *byte);
+        }
+
+        assert_eq!(osc_params(&recorder).len(), 1);
+    }
+
+    #[test]
+    fn osc_param_splitting() {
+        fn split(input: &[u8]) -> Vec<Vec<u8>> {
+            let mut recorder = RecordingPerform::new();
+            Parser::new().advance_slice(&mut recorder, input);
+            osc_params(&recorder).remove(0)
+        }
+
+        let empty: &[u8] = b"";
+        assert_eq!(split(b"\x1b]\x07"), vec![empty]);
+        assert_eq!(split(b"\x1b]104\x07"), vec![&b"104"[..]]);
+        assert_eq!(split(b"\x1b]0;title\x07"), vec![&b"0"[..], b"title"]);
+        assert_eq!(split(b"\x1b]8;id=1;file:///\x1b\\"), vec![&b"8"[..], b"id=1", b"file:///"]);
+        assert_eq!(split(b"\x1b]8;;file:///\x07"), vec![&b"8"[..], b"", b"file:///"]);
+        assert_eq!(split(b"\x1b]0;title;\x07"), vec![&b"0"[..], b"title", b""]);
+
+        // Bytes which aren't valid UTF-8 are passed through
+        assert_eq!(split(b"\x1b]52;c;\xff\x80\xfe\x07"), vec![&b"52"[..], b"c", b"\xff\x80\xfe"]);
+    }
+
+    #[test]
+    fn parse_osc_max_params() {
+        static INPUT: &[u8] = b"\x1b];;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;\x1b";
+
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        for byte in INPUT {
+            parser.advance(&mut recorder, *byte);
+        }
+
+        let dispatched = osc_params(&recorder);
+        assert_eq!(dispatched.len(), 1);
+        assert_eq!(dispatched[0].len(), MAX_PARAMS);
+        for param in dispatched[0].iter() {
+            assert_eq!(param.len(), 0);
+        }
+    }
+
+    #[test]
+    fn parse_csi_max_params() {
+        static INPUT: &[u8] = b"\x1b[;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;p";
+
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        for byte in INPUT {
+            parser.advance(&mut recorder, *byte);
+        }
+
+        let dispatched = csi_params(&recorder);
+        assert_eq!(dispatched.len(), 1);
+        assert_eq!(dispatched[0].len(), MAX_PARAMS);
+    }
+
+    #[test]
+    fn parse_semi_set_underline() {
+        let mut recorder = RecordingPerform::new();
         let mut parser = Parser::new();
         for byte in b"\x1b[;4m" {
-            parser.advance(&mut dispatcher, *byte);
+            parser.advance(&mut recorder, *byte);
         }
 
-        // Check that flag is set and thus osc_dispatch assertions ran.
-        assert_eq!(dispatcher.params[0], &[0, 4]);
+        assert_eq!(csi_params(&recorder)[0], vec![vec![0], vec![4]]);
     }
 
     #[test]
-    fn parse_long_csi_param() {
-        // The important part is the parameter, which is (i64::MAX + 1)
-        static INPUT: &'static [u8] = b"\x1b[9223372036854775808m";
+    fn parse_csi_subparams() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"\x1b[38:2::255:128:0m");
 
-        let mut dispatcher = CsiDispatcher::default();
+        assert_eq!(csi_params(&recorder)[0], vec![vec![38, 2, 0, 255, 128, 0]]);
+    }
 
+    #[test]
+    fn parse_csi_mixed_subparams() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"\x1b[1;4:3;38:5:196;:2m");
+
+        assert_eq!(csi_params(&recorder)[0], vec![
+            vec![1],
+            vec![4, 3],
+            vec![38, 5, 196],
+            vec![0, 2],
+        ]);
+    }
+
+    #[test]
+    fn parse_long_csi_param() {
+        // The important part is the parameter, which is (u16::MAX + 1)
+        static INPUT: &[u8] = b"\x1b[65536m";
+
+        let mut recorder = RecordingPerform::new();
         let mut parser = Parser::new();
         for byte in INPUT {
-            parser.advance(&mut dispatcher, *byte);
+            parser.advance(&mut recorder, *byte);
         }
 
-        assert_eq!(dispatcher.params[0], &[i64::MAX as i64]);
+        assert_eq!(csi_params(&recorder)[0], vec![vec![u16::MAX]]);
+    }
+
+    #[test]
+    fn current_state_tracks_csi() {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        assert_eq!(parser.current_state(), State::Ground);
+
+        parser.advance(&mut recorder, 0x1b);
+        parser.advance(&mut recorder, b'[');
+        assert_eq!(parser.current_state(), State::CsiEntry);
 
+        for byte in b"1;2H" {
+            parser.advance(&mut recorder, *byte);
+        }
+        assert_eq!(parser.current_state(), State::Ground);
+        assert_eq!(csi_params(&recorder).len(), 1);
     }
+
     #[test]
     fn parse_osc_with_utf8_arguments() {
-        static INPUT: &'static [u8] = &[
+        static INPUT: &[u8] = &[
             0x0d, 0x1b, 0x5d, 0x32, 0x3b, 0x65, 0x63, 0x68, 0x6f, 0x20, 0x27,
             0xc2, 0xaf, 0x5c, 0x5f, 0x28, 0xe3, 0x83, 0x84, 0x29, 0x5f, 0x2f,
             0xc2, 0xaf, 0x27, 0x20, 0x26, 0x26, 0x20, 0x73, 0x6c, 0x65, 0x65,
             0x70, 0x20, 0x31, 0x07
         ];
 
-        // Create dispatcher and check state
-        let mut dispatcher = OscDispatcher { params: vec![], dispatched_osc: false };
-
-        // Run parser using OSC_BYTES
+        let mut recorder = RecordingPerform::new();
         let mut parser = Parser::new();
         for byte in INPUT {
-            parser.advance(&mut dispatcher, *byte);
+            parser.advance(&mut recorder, *byte);
         }
 
-        // Check that flag is set and thus osc_dispatch assertions ran.
-        assert_eq!(dispatcher.params[0], &[b'2']);
-        assert_eq!(dispatcher.params[1], &INPUT[5..(INPUT.len() - 1)]);
+        let dispatched = osc_params(&recorder);
+        assert_eq!(dispatched[0][0], b"2");
+        assert_eq!(dispatched[0][1], &INPUT[5..(INPUT.len() - 1)]);
+    }
+
+    #[derive(Default)]
+    struct C0Perform {
+        c0: Vec<C0>,
+        bytes: Vec<u8>,
+    }
+
+    impl Perform for C0Perform {
+        fn print(&mut self, _c: char) {}
+
+        fn execute(&mut self, byte: u8) {
+            self.bytes.push(byte);
+        }
+
+        fn execute_c0(&mut self, c0: C0) {
+            self.c0.push(c0);
+        }
+
+        fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+        fn put(&mut self, _byte: u8) {}
+        fn unhook(&mut self) {}
+        fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+        fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+        fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+    }
+
+    #[test]
+    fn execute_c0() {
+        let mut performer = C0Perform::default();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut performer, b"a\r\n\x00\x1b[1\x0em\x1f");
+        assert_eq!(performer.c0, [C0::Cr, C0::Lf, C0::Nul, C0::So, C0::Us]);
+        assert!(performer.bytes.is_empty());
+
+        // C1 controls are still executed as bytes
+        parser.set_c1_controls(true);
+        parser.advance_slice(&mut performer, b"\x80");
+        assert_eq!(performer.bytes, [0x80]);
+    }
+
+    #[test]
+    fn execute_c0_defaults_to_execute() {
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_slice(&mut recorder, b"\x07\x08");
+        assert_eq!(recorder.events(), &[RecordedEvent::Execute(0x07), RecordedEvent::Execute(0x08)]);
+    }
+
+    #[test]
+    fn wrappers_forward_execute_c0() {
+        let mut composite = CompositePerform(C0Perform::default(), C0Perform::default());
+        Parser::new().advance_slice(&mut composite, b"\t");
+        assert_eq!(composite.0.c0, [C0::Ht]);
+        assert_eq!(composite.1.c0, [C0::Ht]);
+    }
+
+    #[test]
+    fn debug_shows_pending_sequence() {
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut RecordingPerform::new(), b"\x1b[?1;2:3");
+        let debug = format!("{:?}", parser);
+        assert!(debug.starts_with("Parser { state: CsiParam, mode: AnsiVt, params_count: 2, params: [[1], [2, 3]], "));
+        assert!(debug.contains("intermediates: [], private_marker: Some(63), ignoring: false"));
+        assert!(debug.contains("utf8_remaining: 0, byte_position: 8"));
+
+        parser.advance_slice(&mut RecordingPerform::new(), b"m\x1b( \xe2");
+        let debug = format!("{:?}", parser);
+        assert!(debug.contains("state: EscapeIntermediate"));
+        assert!(debug.contains("params_count: 0, params: [], intermediates: [40, 32]"));
+
+        parser.advance_slice(&mut RecordingPerform::new(), b"B\xe2\x94");
+        assert!(format!("{:?}", parser).contains("state: Utf8"));
+        assert!(format!("{:?}", parser).contains("utf8_remaining: 1"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn dump_state() {
+        let mut parser = Parser::new();
+        assert_eq!(parser.dump_state(), "Parser { state: Ground, params: [], intermediates: [] }");
+
+        parser.advance_slice(&mut RecordingPerform::new(), b"\x1b[ 5;");
+        assert_eq!(parser.dump_state(), "Parser { state: CsiIgnore, params: [], intermediates: [32] }");
+    }
+
+    #[test]
+    fn is_at_ground() {
+        let mut recorder = RecordingPerform::new();
+        assert!(Parser::new().is_at_ground());
+
+        // In the middle of sequences and characters
+        for prefix in [&b"\x1b"[..], b"\x1b[", b"\x1b[1;2", b"\x1b[?", b"\x1b]0;x", b"\x1bP1$q", b"\xe2\x94"].iter() {
+            let mut parser = Parser::new();
+            parser.advance_slice(&mut recorder, prefix);
+            assert!(!parser.is_at_ground(), "{:?}", prefix);
+        }
+
+        // Pending bytes count even in `Ground`
+        let mut parser = Parser::new();
+        parser.collecting_param = true;
+        assert!(!parser.is_at_ground());
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, b"\xf0\x9f");
+        parser.state = State::Ground;
+        assert!(!parser.is_at_ground());
+
+        // After complete sequences, although their intermediates are kept
+        let mut parser = Parser::new();
+        for input in [&b"a"[..], b"\x1b(B", b"\x1b[1;2:3 q", b"\x1b]0;x\x07", b"\x1bP1$qm\x1b\\", "\u{2500}".as_bytes()].iter() {
+            parser.advance_slice(&mut recorder, input);
+            assert!(parser.is_at_ground(), "{:?}", input);
+        }
     }
 }
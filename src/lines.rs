@@ -0,0 +1,246 @@
+//! Collecting printed text into lines
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use {Params, Parser, Perform};
+
+/// Receives the lines collected by a [`LineBuffer`]
+///
+/// [`LineBuffer`]: struct.LineBuffer.html
+pub trait LineSink {
+    /// Handle a line, without its line ending
+    fn line(&mut self, line: &str);
+
+    /// Handle the text after the last line ending when the buffer is flushed
+    ///
+    /// This is only called if there is any text.
+    fn flush_incomplete(&mut self, _line: &str) {}
+}
+
+/// Collects the lines and the incomplete line
+impl LineSink for Vec<String> {
+    #[inline]
+    fn line(&mut self, line: &str) {
+        self.push(line.into());
+    }
+
+    #[inline]
+    fn flush_incomplete(&mut self, line: &str) {
+        self.push(line.into());
+    }
+}
+
+impl<S: LineSink + ?Sized> LineSink for &mut S {
+    #[inline]
+    fn line(&mut self, line: &str) {
+        (**self).line(line);
+    }
+
+    #[inline]
+    fn flush_incomplete(&mut self, line: &str) {
+        (**self).flush_incomplete(line);
+    }
+}
+
+/// A [`Perform`] which collects printed text into lines for a [`LineSink`]
+///
+/// A line ends with LF, CR LF, a CR which isn't followed by LF, FF or VT.
+/// Tabs are kept as `\t`; other controls and all escape sequences are
+/// dropped. The text after the last line ending is passed to
+/// [`LineSink::flush_incomplete`] by [`flush`], which should be called at
+/// the end of the input.
+///
+/// ```
+/// # use vte::{LineBuffer, Parser};
+/// let mut parser = Parser::new();
+/// let mut lines = LineBuffer::new(Vec::new());
+/// parser.advance_slice(&mut lines, b"\x1b[1mone\x1b[m\r\ntwo\rthree");
+/// assert_eq!(lines.current_line(), "three");
+///
+/// lines.flush(&mut parser);
+/// assert_eq!(lines.sink(), &["one", "two", "three"]);
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`LineSink`]: trait.LineSink.html
+/// [`LineSink::flush_incomplete`]: trait.LineSink.html#method.flush_incomplete
+/// [`flush`]: struct.LineBuffer.html#method.flush
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineBuffer<S: LineSink> {
+    sink: S,
+    line: String,
+
+    /// The line ended with CR, which may be the start of CR LF
+    cr_pending: bool,
+}
+
+impl<S: LineSink> LineBuffer<S> {
+    /// Pass the lines to `sink`
+    #[must_use]
+    pub fn new(sink: S) -> LineBuffer<S> {
+        LineBuffer { sink, line: String::new(), cr_pending: false }
+    }
+
+    /// The text after the last line ending
+    ///
+    /// This is empty right after a CR, since the line ended there.
+    #[inline]
+    #[must_use]
+    pub fn current_line(&self) -> &str {
+        &self.line
+    }
+
+    /// Flush `parser` and pass the current line to
+    /// [`LineSink::flush_incomplete`]
+    ///
+    /// A sequence which is unfinished is dropped by the parser.
+    ///
+    /// [`LineSink::flush_incomplete`]: trait.LineSink.html#method.flush_incomplete
+    pub fn flush(&mut self, parser: &mut Parser) {
+        parser.flush(self);
+        self.cr_pending = false;
+        if !self.line.is_empty() {
+            self.sink.flush_incomplete(&self.line);
+            self.line.clear();
+        }
+    }
+
+    /// The `LineSink`
+    #[inline]
+    #[must_use]
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// The `LineSink`
+    #[inline]
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Unwrap the `LineSink`, dropping the current line
+    #[inline]
+    #[must_use]
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    /// Pass the current line to the sink
+    fn end_line(&mut self) {
+        self.sink.line(&self.line);
+        self.line.clear();
+    }
+}
+
+impl<S: LineSink> Perform for LineBuffer<S> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.cr_pending = false;
+        self.line.push(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        let cr_pending = self.cr_pending;
+        self.cr_pending = false;
+        match byte {
+            // The CR already ended the line
+            0x0a if cr_pending => (),
+            0x0a..=0x0c => self.end_line(),
+            0x0d => {
+                self.end_line();
+                self.cr_pending = true;
+            },
+            0x09 => self.line.push('\t'),
+            _ => (),
+        }
+    }
+
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+    fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+
+    #[inline]
+    fn dcs_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _data: &[u8], _final_byte: u8) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineBuffer, LineSink};
+    use Parser;
+
+    fn lines(input: &[u8]) -> Vec<String> {
+        let mut parser = Parser::new();
+        let mut buffer = LineBuffer::new(Vec::new());
+        parser.advance_slice(&mut buffer, input);
+        buffer.flush(&mut parser);
+        buffer.into_sink()
+    }
+
+    #[test]
+    fn line_endings() {
+        assert_eq!(lines(b"a\nb\n"), ["a", "b"]);
+        assert_eq!(lines(b"a\r\nb\r\n\r\n"), ["a", "b", ""]);
+        assert_eq!(lines(b"a\rb\r\r"), ["a", "b", ""]);
+        assert_eq!(lines(b"a\x0cb\x0bc\n"), ["a", "b", "c"]);
+        assert_eq!(lines(b"a\r\nb\nc\rd\x0c\n\re"), ["a", "b", "c", "d", "", "", "e"]);
+    }
+
+    #[test]
+    fn cr_lf_split_across_calls() {
+        let mut parser = Parser::new();
+        let mut buffer = LineBuffer::new(Vec::new());
+        parser.advance_slice(&mut buffer, b"a\r");
+        assert_eq!(buffer.sink(), &["a"]);
+        assert_eq!(buffer.current_line(), "");
+
+        parser.advance_slice(&mut buffer, b"\nb");
+        assert_eq!(buffer.sink(), &["a"]);
+        assert_eq!(buffer.current_line(), "b");
+    }
+
+    #[test]
+    fn drops_sequences() {
+        assert_eq!(lines(b"\x1b]0;title\x07\x1b[31mred\x1b[m\tx\x07\x08\n"), ["red\tx"]);
+        assert_eq!(lines("\u{2500}\u{1f600}\n".as_bytes()), ["\u{2500}\u{1f600}"]);
+    }
+
+    #[derive(Default)]
+    struct Sink {
+        lines: Vec<String>,
+        incomplete: Vec<String>,
+    }
+
+    impl LineSink for Sink {
+        fn line(&mut self, line: &str) {
+            self.lines.push(line.into());
+        }
+
+        fn flush_incomplete(&mut self, line: &str) {
+            self.incomplete.push(line.into());
+        }
+    }
+
+    #[test]
+    fn flush_incomplete() {
+        let mut parser = Parser::new();
+        let mut buffer = LineBuffer::new(Sink::default());
+        parser.advance_slice(&mut buffer, b"done\npartial\x1b[1");
+        buffer.flush(&mut parser);
+        assert_eq!(buffer.sink().lines, ["done"]);
+        assert_eq!(buffer.sink().incomplete, ["partial"]);
+
+        // Nothing is left, and CR already ended the line
+        parser.advance_slice(&mut buffer, b"x\r");
+        buffer.flush(&mut parser);
+        buffer.flush(&mut parser);
+        assert_eq!(buffer.sink().lines, ["done", "x"]);
+        assert_eq!(buffer.sink().incomplete, ["partial"]);
+
+        // The line after a flush starts fresh
+        parser.advance_slice(&mut buffer, b"\ny\n");
+        assert_eq!(buffer.sink().lines, ["done", "x", "", "y"]);
+    }
+}
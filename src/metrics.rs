@@ -0,0 +1,568 @@
+//! Counting the actions passed to a `Perform` implementation
+use {Params, Parser, Perform, State, C0};
+
+/// Number of actions of every kind seen by a [`MetricsPerform`] or
+/// [`SequenceCounter`]
+///
+/// [`MetricsPerform`]: struct.MetricsPerform.html
+/// [`SequenceCounter`]: struct.SequenceCounter.html
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PerformMetrics {
+    /// Printed characters
+    pub print_count: u64,
+
+    /// Executed C0 and C1 controls
+    pub execute_count: u64,
+
+    /// Dispatched CSI sequences
+    pub csi_count: u64,
+
+    /// Dispatched OSC strings
+    pub osc_count: u64,
+
+    /// Dispatched escape sequences
+    pub esc_count: u64,
+
+    /// Device control strings, counted when they are hooked
+    pub dcs_hook_count: u64,
+
+    /// Malformed sequences reported through [`Perform::error`]
+    ///
+    /// [`Perform::error`]: trait.Perform.html#method.error
+    pub error_count: u64,
+
+    /// Bytes passed to [`MetricsPerform::advance`]
+    ///
+    /// [`MetricsPerform::advance`]: struct.MetricsPerform.html#method.advance
+    pub total_bytes: u64,
+}
+
+impl PerformMetrics {
+    /// Printed characters, controls and dispatched sequences
+    ///
+    /// Errors aren't included since the sequence they belong to is counted
+    /// as well.
+    #[must_use]
+    pub fn total_actions(&self) -> u64 {
+        self.print_count
+            + self.execute_count
+            + self.csi_count
+            + self.osc_count
+            + self.esc_count
+            + self.dcs_hook_count
+    }
+
+    /// Share of `count` in [`total_actions`] in percent, `0.0` without any
+    /// actions
+    ///
+    /// ```
+    /// # use vte::{Parser, SequenceCounter};
+    /// let mut counter = SequenceCounter::new();
+    /// Parser::new().advance_slice(&mut counter, b"\x1b[1mab\x1b[m");
+    ///
+    /// let report = counter.report();
+    /// assert_eq!(report.percentage(report.csi_count), 50.0);
+    /// ```
+    ///
+    /// [`total_actions`]: struct.PerformMetrics.html#method.total_actions
+    #[must_use]
+    pub fn percentage(&self, count: u64) -> f64 {
+        match self.total_actions() {
+            0 => 0.0,
+            total => count as f64 * 100.0 / total as f64,
+        }
+    }
+}
+
+/// A [`Perform`] which counts every action before forwarding it to `P`
+///
+/// The actions a stream consists of can be used to tune buffer sizes, for
+/// example. The parser doesn't tell its `Perform` how much input it has
+/// received, so bytes are only counted when they are passed through
+/// [`advance`].
+///
+/// ```no_run
+/// # use vte::{MetricsPerform, Params, Parser, Perform};
+/// # struct Ignore;
+/// # impl Perform for Ignore {
+/// #     fn print(&mut self, _c: char) {}
+/// #     fn execute(&mut self, _byte: u8) {}
+/// #     fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+/// #     fn put(&mut self, _byte: u8) {}
+/// #     fn unhook(&mut self) {}
+/// #     fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+/// #     fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+/// #     fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// # }
+/// let capture = std::fs::read("session.log").unwrap();
+///
+/// let mut parser = Parser::new();
+/// let mut performer = MetricsPerform::new(Ignore);
+/// performer.advance(&mut parser, &capture);
+///
+/// let metrics = performer.snapshot();
+/// println!(
+///     "{} bytes: {} characters, {} CSI, {} OSC, {} DCS",
+///     metrics.total_bytes, metrics.print_count, metrics.csi_count, metrics.osc_count, metrics.dcs_hook_count,
+/// );
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`advance`]: struct.MetricsPerform.html#method.advance
+#[derive(Debug, Default, Clone)]
+pub struct MetricsPerform<P: Perform> {
+    performer: P,
+    metrics: PerformMetrics,
+}
+
+impl<P: Perform> MetricsPerform<P> {
+    /// Wrap `performer`, starting with all counters at zero
+    #[must_use]
+    pub fn new(performer: P) -> MetricsPerform<P> {
+        MetricsPerform {
+            performer,
+            metrics: PerformMetrics::default(),
+        }
+    }
+
+    /// Advance `parser` with `bytes`, counting them in `total_bytes`
+    pub fn advance(&mut self, parser: &mut Parser, bytes: &[u8]) {
+        self.metrics.total_bytes += bytes.len() as u64;
+        parser.advance_slice(self, bytes);
+    }
+
+    /// The counters as they are now
+    #[inline]
+    #[must_use]
+    pub fn snapshot(&self) -> PerformMetrics {
+        self.metrics
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+}
+
+impl<P: Perform> Perform for MetricsPerform<P> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.metrics.print_count += 1;
+        self.performer.print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.metrics.print_count += 1;
+        self.performer.print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        self.metrics.execute_count += 1;
+        self.performer.execute(byte);
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        self.metrics.execute_count += 1;
+        self.performer.execute_c0(c0);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.metrics.execute_count += 1;
+        self.performer.carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.metrics.execute_count += 1;
+        self.performer.linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        self.metrics.execute_count += 1;
+        self.performer.backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        self.metrics.execute_count += 1;
+        self.performer.tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.metrics.execute_count += 1;
+        self.performer.bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.metrics.dcs_hook_count += 1;
+        self.performer.hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.performer.put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.performer.unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.metrics.dcs_hook_count += 1;
+        self.performer.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.metrics.osc_count += 1;
+        self.performer.osc_dispatch(params, bell_terminated);
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.apc_dispatch(bytes);
+    }
+
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.metrics.csi_count += 1;
+        self.performer.csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.metrics.esc_count += 1;
+        self.performer.esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.performer.on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.metrics.error_count += 1;
+        self.performer.error(byte);
+    }
+}
+
+/// A [`Perform`] which only counts the actions of every kind
+///
+/// This is [`MetricsPerform`] without a `Perform` to forward to, for
+/// measuring what input consists of.
+///
+/// ```no_run
+/// # use vte::{Parser, SequenceCounter};
+/// let capture = std::fs::read("session.log").unwrap();
+///
+/// let mut counter = SequenceCounter::new();
+/// counter.advance(&mut Parser::new(), &capture);
+///
+/// let report = counter.report();
+/// println!(
+///     "{:.1}% text, {:.1}% CSI, {:.1}% OSC",
+///     report.percentage(report.print_count),
+///     report.percentage(report.csi_count),
+///     report.percentage(report.osc_count),
+/// );
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`MetricsPerform`]: struct.MetricsPerform.html
+#[derive(Debug, Default, Clone)]
+pub struct SequenceCounter {
+    metrics: PerformMetrics,
+}
+
+impl SequenceCounter {
+    /// Create a counter with all counts at zero
+    #[must_use]
+    pub fn new() -> SequenceCounter {
+        SequenceCounter::default()
+    }
+
+    /// Advance `parser` with `bytes`, counting them in `total_bytes`
+    pub fn advance(&mut self, parser: &mut Parser, bytes: &[u8]) {
+        self.metrics.total_bytes += bytes.len() as u64;
+        parser.advance_slice(self, bytes);
+    }
+
+    /// The counts as they are now
+    #[inline]
+    #[must_use]
+    pub fn report(&self) -> PerformMetrics {
+        self.metrics
+    }
+
+    /// Set all counts to zero
+    #[inline]
+    pub fn reset(&mut self) {
+        self.metrics = PerformMetrics::default();
+    }
+}
+
+impl Perform for SequenceCounter {
+    #[inline]
+    fn print(&mut self, _c: char) {
+        self.metrics.print_count += 1;
+    }
+
+    #[inline]
+    fn execute(&mut self, _byte: u8) {
+        self.metrics.execute_count += 1;
+    }
+
+    #[inline]
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {
+        self.metrics.dcs_hook_count += 1;
+    }
+
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+
+    #[inline]
+    fn dcs_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _data: &[u8], _final_byte: u8) {
+        self.metrics.dcs_hook_count += 1;
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {
+        self.metrics.osc_count += 1;
+    }
+
+    #[inline]
+    fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {
+        self.metrics.csi_count += 1;
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {
+        self.metrics.esc_count += 1;
+    }
+
+    #[inline]
+    fn error(&mut self, _byte: u8) {
+        self.metrics.error_count += 1;
+    }
+}
+
+/// Lengths of the sequences a [`Parser`] has completed
+///
+/// With the `metrics` feature every parser keeps these, which can reveal
+/// input with unusually long sequences. They are read with
+/// [`Parser::metrics`] and set to zero with [`Parser::reset_metrics`].
+///
+/// ```
+/// # use vte::{Parser, SequenceCounter};
+/// let mut parser = Parser::new();
+/// parser.advance_slice(&mut SequenceCounter::new(), b"\x1b]0;title\x07\x1b[1;2H");
+///
+/// let metrics = parser.metrics();
+/// assert_eq!(metrics.max_osc_len, 7);
+/// assert_eq!(metrics.max_csi_param_count, 2);
+/// assert_eq!(metrics.total_sequences, 2);
+/// ```
+///
+/// [`Parser`]: struct.Parser.html
+/// [`Parser::metrics`]: struct.Parser.html#method.metrics
+/// [`Parser::reset_metrics`]: struct.Parser.html#method.reset_metrics
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ParserMetrics {
+    /// Longest OSC string, counting every byte between the introducer and
+    /// the terminator
+    ///
+    /// Bytes beyond the OSC buffer are counted even though they are dropped.
+    pub max_osc_len: usize,
+
+    /// Longest device control string, excluding its parameters and final
+    /// byte
+    pub max_dcs_len: usize,
+
+    /// Most parameters of a CSI sequence, excluding subparameters
+    pub max_csi_param_count: usize,
+
+    /// Dispatched CSI, OSC, ESC, SOS, PM and APC sequences and device
+    /// control strings
+    pub total_sequences: u64,
+
+    /// Bytes passed to the parser
+    pub total_bytes: u64,
+}
+
+#[cfg(feature = "metrics")]
+impl ParserMetrics {
+    #[inline]
+    pub(crate) fn record_csi(&mut self, param_count: usize) {
+        self.max_csi_param_count = self.max_csi_param_count.max(param_count);
+        self.total_sequences += 1;
+    }
+
+    #[inline]
+    pub(crate) fn record_osc(&mut self, len: usize) {
+        self.max_osc_len = self.max_osc_len.max(len);
+        self.total_sequences += 1;
+    }
+
+    #[inline]
+    pub(crate) fn record_dcs(&mut self, len: usize) {
+        self.max_dcs_len = self.max_dcs_len.max(len);
+        self.total_sequences += 1;
+    }
+
+    #[inline]
+    pub(crate) fn record_sequence(&mut self) {
+        self.total_sequences += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MetricsPerform, PerformMetrics, SequenceCounter};
+    #[cfg(feature = "metrics")]
+    use super::ParserMetrics;
+    use test_utils::RecordingPerform;
+    use Parser;
+    #[cfg(feature = "metrics")]
+    use ParserBuilder;
+
+    #[test]
+    fn counts_actions() {
+        let input = b"ab\r\n\x1b[1m\x1b[?25h\x1b]0;t\x07\x1b7\x1bPqx\x1b\\\x1b[1?h";
+        let mut performer = MetricsPerform::new(RecordingPerform::new());
+        performer.advance(&mut Parser::new(), input);
+
+        assert_eq!(performer.snapshot(), PerformMetrics {
+            print_count: 2,
+            execute_count: 2,
+            csi_count: 2,
+            osc_count: 1,
+            esc_count: 2,
+            dcs_hook_count: 1,
+            error_count: 1,
+            total_bytes: input.len() as u64,
+        });
+
+        // Everything is forwarded
+        let mut expected = RecordingPerform::new();
+        Parser::new().advance_slice(&mut expected, input);
+        assert_eq!(performer.inner().events(), expected.events());
+    }
+
+    #[test]
+    fn sequence_counter() {
+        let input = b"ab\r\n\x1b[1m\x1b[?25h\x1b]0;t\x07\x1b7\x1bPqx\x1b\\\x1b[1?h";
+        let mut counter = SequenceCounter::new();
+        counter.advance(&mut Parser::new(), input);
+
+        // The same as a `MetricsPerform`
+        let mut performer = MetricsPerform::new(RecordingPerform::new());
+        performer.advance(&mut Parser::new(), input);
+        let report = counter.report();
+        assert_eq!(report, performer.snapshot());
+
+        assert_eq!(report.total_actions(), 10);
+        assert_eq!(report.percentage(report.print_count), 20.0);
+        assert_eq!(report.percentage(report.dcs_hook_count), 10.0);
+
+        counter.reset();
+        assert_eq!(counter.report(), PerformMetrics::default());
+        assert_eq!(counter.report().percentage(0), 0.0);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn parser_metrics() {
+        let input = b"ab\x1b[1;2;3;4;5;6;7;8;9:1:2;10m\x1b]0;t\x07\x1b7\x1bPqxyz\x1b\\";
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut RecordingPerform::new(), input);
+
+        assert_eq!(*parser.metrics(), ParserMetrics {
+            max_osc_len: 3,
+            max_dcs_len: 3,
+            max_csi_param_count: 10,
+            total_sequences: 5,
+            total_bytes: input.len() as u64,
+        });
+
+        // Shorter sequences leave the maximums alone
+        parser.advance_slice(&mut RecordingPerform::new(), b"\x1b[1m");
+        assert_eq!(parser.metrics().max_csi_param_count, 10);
+        assert_eq!(parser.metrics().total_sequences, 6);
+
+        parser.reset_metrics();
+        assert_eq!(*parser.metrics(), ParserMetrics::default());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn parser_metrics_count_dropped_bytes() {
+        let mut input = b"\x1b]2;".to_vec();
+        input.extend(vec![b'a'; 4096]);
+        input.push(0x07);
+        let mut parser = ParserBuilder::new().max_osc_len(1024).build();
+        parser.advance_slice(&mut RecordingPerform::new(), &input);
+
+        assert_eq!(parser.metrics().max_osc_len, 4098);
+        assert_eq!(parser.metrics().total_sequences, 1);
+    }
+}
@@ -0,0 +1,257 @@
+//! Tracking the private modes set by DECSET and DECRST
+use alloc::collections::BTreeMap;
+
+use {Params, Perform};
+
+/// Whether a mode is set
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ModeState {
+    /// Set with `CSI ? Pm h`
+    On,
+
+    /// Reset with `CSI ? Pm l`
+    Off,
+}
+
+/// Codes of common private modes
+///
+/// ```
+/// # use vte::{Parser, PrivateMode, PrivateModeTracker};
+/// let mut modes = PrivateModeTracker::new();
+/// Parser::new().advance_slice(&mut modes, b"\x1b[?1049h\x1b[?25l");
+/// assert!(modes.is_set(PrivateMode::ALTERNATE_SCREEN_SAVE_CURSOR));
+/// assert!(!modes.is_set(PrivateMode::CURSOR_VISIBLE));
+/// ```
+#[derive(Debug)]
+pub enum PrivateMode {}
+
+impl PrivateMode {
+    /// DECCKM, cursor keys send application sequences
+    pub const APPLICATION_CURSOR_KEYS: u16 = 1;
+    /// DECCOLM, 132 columns
+    pub const COLUMNS_132: u16 = 3;
+    /// DECOM, cursor positions are relative to the scroll region
+    pub const ORIGIN: u16 = 6;
+    /// DECAWM, wrap at the end of the line
+    pub const AUTO_WRAP: u16 = 7;
+    /// X10 mouse reporting
+    pub const MOUSE_X10: u16 = 9;
+    /// Blinking cursor
+    pub const CURSOR_BLINK: u16 = 12;
+    /// DECTCEM, the cursor is shown
+    pub const CURSOR_VISIBLE: u16 = 25;
+    /// Report mouse presses and releases
+    pub const MOUSE_NORMAL: u16 = 1000;
+    /// Also report motion while a button is pressed
+    pub const MOUSE_BUTTON_EVENT: u16 = 1002;
+    /// Report all motion
+    pub const MOUSE_ANY_EVENT: u16 = 1003;
+    /// Report focus changes
+    pub const FOCUS_EVENTS: u16 = 1004;
+    /// UTF-8 encoded mouse positions
+    pub const MOUSE_UTF8: u16 = 1005;
+    /// SGR encoded mouse events
+    pub const MOUSE_SGR: u16 = 1006;
+    /// Scroll wheel sends cursor keys on the alternate screen
+    pub const ALTERNATE_SCROLL: u16 = 1007;
+    /// URXVT encoded mouse events
+    pub const MOUSE_URXVT: u16 = 1015;
+    /// Alternate screen buffer
+    pub const ALTERNATE_SCREEN: u16 = 1047;
+    /// Save the cursor when set and restore it when reset
+    pub const SAVE_CURSOR: u16 = 1048;
+    /// Alternate screen buffer, saving and restoring the cursor
+    pub const ALTERNATE_SCREEN_SAVE_CURSOR: u16 = 1049;
+    /// Pasted text is wrapped in `CSI 200 ~` and `CSI 201 ~`
+    pub const BRACKETED_PASTE: u16 = 2004;
+    /// Synchronized output, screen updates are held back while set
+    pub const SYNCHRONIZED_OUTPUT: u16 = 2026;
+}
+
+/// A [`Perform`] which tracks the private modes of a terminal
+///
+/// DECSET (`CSI ? Pm h`) and DECRST (`CSI ? Pm l`) set and reset modes, and
+/// XTSAVE (`CSI ? Pm s`) and XTRESTORE (`CSI ? Pm r`) save the current state
+/// of modes and restore it. A mode which was never set or reset has no
+/// state, since its default depends on the terminal; [`set_mode`] can be
+/// used to start from the defaults. A full reset (`ESC c`) forgets all
+/// modes.
+///
+/// Everything else is ignored, so the tracker is usually combined with
+/// another `Perform` through a [`CompositePerform`].
+///
+/// [`Perform`]: trait.Perform.html
+/// [`set_mode`]: struct.PrivateModeTracker.html#method.set_mode
+/// [`CompositePerform`]: struct.CompositePerform.html
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PrivateModeTracker {
+    modes: BTreeMap<u16, ModeState>,
+
+    /// The state of modes when they were saved; `None` if they had none
+    saved: BTreeMap<u16, Option<ModeState>>,
+}
+
+impl PrivateModeTracker {
+    /// Create a tracker which knows no modes
+    #[must_use]
+    pub fn new() -> PrivateModeTracker {
+        PrivateModeTracker::default()
+    }
+
+    /// The state of mode `code`, or `None` if it was never set or reset
+    #[inline]
+    #[must_use]
+    pub fn mode(&self, code: u16) -> Option<ModeState> {
+        self.modes.get(&code).copied()
+    }
+
+    /// Returns `true` if mode `code` is set
+    #[inline]
+    #[must_use]
+    pub fn is_set(&self, code: u16) -> bool {
+        self.mode(code) == Some(ModeState::On)
+    }
+
+    /// The state mode `code` had when it was last saved
+    ///
+    /// Returns `None` if it was never saved or had no state then.
+    #[inline]
+    #[must_use]
+    pub fn saved_mode(&self, code: u16) -> Option<ModeState> {
+        self.saved.get(&code).copied().flatten()
+    }
+
+    /// Change the state of mode `code`
+    #[inline]
+    pub fn set_mode(&mut self, code: u16, state: ModeState) {
+        self.modes.insert(code, state);
+    }
+
+    /// Every mode with a state, ordered by code
+    pub fn iter(&self) -> impl Iterator<Item = (u16, ModeState)> + '_ {
+        self.modes.iter().map(|(&code, &state)| (code, state))
+    }
+
+    fn save(&mut self, code: u16) {
+        let state = self.mode(code);
+        self.saved.insert(code, state);
+    }
+
+    fn restore(&mut self, code: u16) {
+        match self.saved.get(&code) {
+            Some(&Some(state)) => self.set_mode(code, state),
+            Some(&None) => {
+                self.modes.remove(&code);
+            },
+            None => (),
+        }
+    }
+}
+
+impl Perform for PrivateModeTracker {
+    fn print(&mut self, _c: char) {}
+    fn execute(&mut self, _byte: u8) {}
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        if ignore || !intermediates.is_empty() || private != Some(b'?') {
+            return;
+        }
+
+        for param in params {
+            let code = param[0];
+            match action {
+                'h' => self.set_mode(code, ModeState::On),
+                'l' => self.set_mode(code, ModeState::Off),
+                's' => self.save(code),
+                'r' => self.restore(code),
+                _ => return,
+            }
+        }
+    }
+
+    fn esc_dispatch(&mut self, _params: &Params, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if intermediates.is_empty() && byte == b'c' {
+            self.modes.clear();
+            self.saved.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModeState, PrivateMode, PrivateModeTracker};
+    use Parser;
+
+    fn track(bytes: &[u8]) -> PrivateModeTracker {
+        let mut modes = PrivateModeTracker::new();
+        Parser::new().advance_slice(&mut modes, bytes);
+        modes
+    }
+
+    #[test]
+    fn set_and_reset() {
+        let modes = track(b"\x1b[?1049h\x1b[?25l\x1b[?1000;1006h\x1b[?1000l");
+        assert_eq!(modes.mode(PrivateMode::ALTERNATE_SCREEN_SAVE_CURSOR), Some(ModeState::On));
+        assert_eq!(modes.mode(PrivateMode::CURSOR_VISIBLE), Some(ModeState::Off));
+        assert_eq!(modes.mode(PrivateMode::MOUSE_NORMAL), Some(ModeState::Off));
+        assert!(modes.is_set(PrivateMode::MOUSE_SGR));
+        assert!(!modes.is_set(PrivateMode::SYNCHRONIZED_OUTPUT));
+        assert_eq!(modes.mode(PrivateMode::SYNCHRONIZED_OUTPUT), None);
+        assert_eq!(modes.iter().collect::<Vec<_>>(), [
+            (25, ModeState::Off),
+            (1000, ModeState::Off),
+            (1006, ModeState::On),
+            (1049, ModeState::On),
+        ]);
+    }
+
+    #[test]
+    fn ansi_modes_are_ignored() {
+        let modes = track(b"\x1b[4h\x1b[>25h\x1b[?25$p\x1b[?2026 h");
+        assert_eq!(modes.iter().count(), 0);
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let mut modes = track(b"\x1b[?2004h\x1b[?2004;25s\x1b[?2004l\x1b[?25h");
+        assert_eq!(modes.saved_mode(PrivateMode::BRACKETED_PASTE), Some(ModeState::On));
+        assert_eq!(modes.saved_mode(PrivateMode::CURSOR_VISIBLE), None);
+
+        Parser::new().advance_slice(&mut modes, b"\x1b[?2004;25;7r");
+        assert_eq!(modes.mode(PrivateMode::BRACKETED_PASTE), Some(ModeState::On));
+        assert_eq!(modes.mode(PrivateMode::CURSOR_VISIBLE), None);
+        assert_eq!(modes.mode(PrivateMode::AUTO_WRAP), None);
+
+        // The saved state can be restored again
+        Parser::new().advance_slice(&mut modes, b"\x1b[?2004l\x1b[?2004r");
+        assert!(modes.is_set(PrivateMode::BRACKETED_PASTE));
+
+        // Saving again replaces the saved state
+        Parser::new().advance_slice(&mut modes, b"\x1b[?2004l\x1b[?2004s\x1b[?2004h\x1b[?2004r");
+        assert_eq!(modes.mode(PrivateMode::BRACKETED_PASTE), Some(ModeState::Off));
+    }
+
+    #[test]
+    fn defaults_and_reset() {
+        let mut modes = PrivateModeTracker::new();
+        modes.set_mode(PrivateMode::CURSOR_VISIBLE, ModeState::On);
+        modes.set_mode(PrivateMode::AUTO_WRAP, ModeState::On);
+        Parser::new().advance_slice(&mut modes, b"\x1b[?25s\x1b[?25l");
+        assert!(!modes.is_set(PrivateMode::CURSOR_VISIBLE));
+        assert!(modes.is_set(PrivateMode::AUTO_WRAP));
+
+        Parser::new().advance_slice(&mut modes, b"\x1bc\x1b[?25r");
+        assert_eq!(modes.iter().count(), 0);
+    }
+}
@@ -0,0 +1,326 @@
+//! Mouse events reported by terminals
+//!
+//! With mouse tracking enabled, terminals report mouse events in one of
+//! several encodings, selected by private modes:
+//!
+//! * The default X10 compatible encoding, `CSI M Cb Cx Cy`, where the button
+//!   and position are single characters offset by 32. The parser dispatches
+//!   `CSI M` on its own and prints the characters, which
+//!   [`parse_mouse_dec`] decodes. With mode 1005 the characters are UTF-8
+//!   encoded, which allows positions past column 223.
+//! * SGR, mode 1006, `CSI < Cb ; Cx ; Cy M` for presses and `m` for
+//!   releases, decoded by [`parse_mouse_csi`]
+//! * URXVT, mode 1015, `CSI Cb ; Cx ; Cy M` with the button offset by 32,
+//!   decoded by [`parse_mouse_csi`]
+//!
+//! Which events are reported depends on the tracking mode (X10, normal,
+//! button-event or any-event tracking), but not on the encoding.
+//!
+//! [`parse_mouse_dec`]: fn.parse_mouse_dec.html
+//! [`parse_mouse_csi`]: fn.parse_mouse_csi.html
+use core::ops;
+
+use Params;
+
+/// Button of a mouse event
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    WheelUp,
+    WheelDown,
+    WheelLeft,
+    WheelRight,
+
+    /// Buttons 8 to 11, usually back and forward
+    Other(u8),
+
+    /// No button, for motion without a pressed button and for releases in
+    /// the encodings which don't report the released button
+    None,
+}
+
+/// Modifier keys which were held during a mouse event
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MouseModifiers(u8);
+
+impl MouseModifiers {
+    pub const SHIFT: MouseModifiers = MouseModifiers(1);
+
+    /// Alt, which xterm calls meta
+    pub const ALT: MouseModifiers = MouseModifiers(1 << 1);
+    pub const CTRL: MouseModifiers = MouseModifiers(1 << 2);
+
+    /// No modifiers
+    #[inline]
+    #[must_use]
+    pub fn empty() -> MouseModifiers {
+        MouseModifiers(0)
+    }
+
+    /// No modifier is set
+    #[inline]
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// All modifiers of `other` are set
+    #[inline]
+    #[must_use]
+    pub fn contains(self, other: MouseModifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for MouseModifiers {
+    type Output = MouseModifiers;
+
+    fn bitor(self, other: MouseModifiers) -> MouseModifiers {
+        MouseModifiers(self.0 | other.0)
+    }
+}
+
+/// Kind of a mouse event
+///
+/// Wheel events are reported as presses.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseEventKind {
+    Press,
+    Release,
+    Motion,
+}
+
+/// A mouse event
+///
+/// The position is zero-based, unlike in the sequences.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    pub button: MouseButton,
+    pub modifiers: MouseModifiers,
+    pub column: u16,
+    pub row: u16,
+    pub kind: MouseEventKind,
+}
+
+impl MouseEvent {
+    /// Decode the button code `Cb` without offset
+    ///
+    /// `release` overrides the kind for the SGR encoding, which reports
+    /// releases by the final byte instead of a button.
+    fn decode(code: u16, column: u16, row: u16, release: bool) -> Option<MouseEvent> {
+        if code > 0xff || column == 0 || row == 0 {
+            return None;
+        }
+
+        let button = match (code & 0xc3, code & 0x20 != 0) {
+            (0x00, _) => MouseButton::Left,
+            (0x01, _) => MouseButton::Middle,
+            (0x02, _) => MouseButton::Right,
+            (0x03, _) => MouseButton::None,
+            (0x40, false) => MouseButton::WheelUp,
+            (0x41, false) => MouseButton::WheelDown,
+            (0x42, false) => MouseButton::WheelLeft,
+            (0x43, false) => MouseButton::WheelRight,
+            (bits @ 0x80..=0x83, _) => MouseButton::Other(bits as u8 - 0x80 + 8),
+            _ => return None,
+        };
+
+        let kind = if release || (button == MouseButton::None && code & 0x20 == 0) {
+            MouseEventKind::Release
+        } else if code & 0x20 != 0 {
+            MouseEventKind::Motion
+        } else {
+            MouseEventKind::Press
+        };
+
+        Some(MouseEvent {
+            button,
+            modifiers: MouseModifiers((code >> 2) as u8 & 0x07),
+            column: column - 1,
+            row: row - 1,
+            kind,
+        })
+    }
+}
+
+/// Decode a mouse event in the SGR or URXVT encoding
+///
+/// `params`, `private` and `final_byte` are those passed to
+/// [`Perform::csi_dispatch`]. Returns `None` for other sequences, including
+/// the `CSI M` of the X10 compatible encoding, see [`parse_mouse_dec`].
+///
+/// ```
+/// # use vte::mouse::{parse_mouse_csi, MouseButton, MouseEventKind};
+/// # use vte::{Params, Parser, Perform};
+/// struct Mouse(Vec<(MouseButton, MouseEventKind, u16, u16)>);
+///
+/// impl Perform for Mouse {
+///     fn csi_dispatch(&mut self, params: &Params, _: &[u8], _: bool, c: char, private: Option<u8>) {
+///         if let Some(event) = parse_mouse_csi(params, private, c as u8) {
+///             self.0.push((event.button, event.kind, event.column, event.row));
+///         }
+///     }
+///     # fn print(&mut self, _c: char) {}
+///     # fn execute(&mut self, _byte: u8) {}
+///     # fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+///     # fn put(&mut self, _byte: u8) {}
+///     # fn unhook(&mut self) {}
+///     # fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+///     # fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// }
+///
+/// let mut mouse = Mouse(Vec::new());
+/// Parser::new().advance_slice(&mut mouse, b"\x1b[<0;10;5M\x1b[<0;10;5m");
+/// assert_eq!(mouse.0, [
+///     (MouseButton::Left, MouseEventKind::Press, 9, 4),
+///     (MouseButton::Left, MouseEventKind::Release, 9, 4),
+/// ]);
+/// ```
+///
+/// [`Perform::csi_dispatch`]: ../trait.Perform.html#tymethod.csi_dispatch
+/// [`parse_mouse_dec`]: fn.parse_mouse_dec.html
+#[must_use]
+pub fn parse_mouse_csi(params: &Params, private: Option<u8>, final_byte: u8) -> Option<MouseEvent> {
+    if params.len() != 3 {
+        return None;
+    }
+
+    let code = params.get_or_default(0, 0, 0);
+    let column = params.get_or_default(1, 0, 0);
+    let row = params.get_or_default(2, 0, 0);
+
+    match (private, final_byte) {
+        (Some(b'<'), b'M') => MouseEvent::decode(code, column, row, false),
+        (Some(b'<'), b'm') => MouseEvent::decode(code, column, row, true),
+        (None, b'M') if code >= 32 => MouseEvent::decode(code - 32, column, row, false),
+        _ => None,
+    }
+}
+
+/// Decode a mouse event in the X10 compatible encoding
+///
+/// `data` are the three characters printed after `CSI M`, the button and
+/// the column and row. Each is offset by 32, so positions past column 223
+/// can only be reported with the UTF-8 extension of mode 1005. Without it,
+/// such a position is sent as a byte which isn't valid UTF-8 and printed as
+/// `U+FFFD`, which this rejects.
+///
+/// ```
+/// # use vte::mouse::{parse_mouse_dec, MouseButton, MouseEventKind};
+/// let event = parse_mouse_dec([' ', '*', '%']).unwrap();
+/// assert_eq!(event.button, MouseButton::Left);
+/// assert_eq!(event.kind, MouseEventKind::Press);
+/// assert_eq!((event.column, event.row), (9, 4));
+/// ```
+#[must_use]
+pub fn parse_mouse_dec(data: [char; 3]) -> Option<MouseEvent> {
+    let value = |c: char| match c as u32 {
+        value @ 32..=2047 => Some(value as u16 - 32),
+        _ => None,
+    };
+
+    MouseEvent::decode(value(data[0])?, value(data[1])?, value(data[2])?, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_mouse_csi, parse_mouse_dec, MouseButton, MouseEvent, MouseEventKind, MouseModifiers};
+    use test_utils::{RecordedEvent, RecordingPerform};
+    use Parser;
+
+    fn csi(bytes: &[u8]) -> Option<MouseEvent> {
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_slice(&mut recorder, bytes);
+        recorder.events().iter().filter_map(|event| match *event {
+            RecordedEvent::CsiDispatch { ref params, private, final_byte, .. } => {
+                Some(parse_mouse_csi(params, private, final_byte as u8))
+            },
+            _ => None,
+        }).next().unwrap()
+    }
+
+    fn event(button: MouseButton, kind: MouseEventKind, column: u16, row: u16) -> Option<MouseEvent> {
+        Some(MouseEvent { button, modifiers: MouseModifiers::empty(), column, row, kind })
+    }
+
+    #[test]
+    fn sgr() {
+        assert_eq!(csi(b"\x1b[<0;10;20M"), event(MouseButton::Left, MouseEventKind::Press, 9, 19));
+        assert_eq!(csi(b"\x1b[<2;10;20m"), event(MouseButton::Right, MouseEventKind::Release, 9, 19));
+        assert_eq!(csi(b"\x1b[<1;300;1M"), event(MouseButton::Middle, MouseEventKind::Press, 299, 0));
+
+        // Button-event and any-event tracking
+        assert_eq!(csi(b"\x1b[<32;3;4M"), event(MouseButton::Left, MouseEventKind::Motion, 2, 3));
+        assert_eq!(csi(b"\x1b[<35;3;4M"), event(MouseButton::None, MouseEventKind::Motion, 2, 3));
+
+        // Wheel and extra buttons
+        assert_eq!(csi(b"\x1b[<64;1;1M"), event(MouseButton::WheelUp, MouseEventKind::Press, 0, 0));
+        assert_eq!(csi(b"\x1b[<65;1;1M"), event(MouseButton::WheelDown, MouseEventKind::Press, 0, 0));
+        assert_eq!(csi(b"\x1b[<66;1;1M"), event(MouseButton::WheelLeft, MouseEventKind::Press, 0, 0));
+        assert_eq!(csi(b"\x1b[<67;1;1M"), event(MouseButton::WheelRight, MouseEventKind::Press, 0, 0));
+        assert_eq!(csi(b"\x1b[<128;1;1M"), event(MouseButton::Other(8), MouseEventKind::Press, 0, 0));
+        assert_eq!(csi(b"\x1b[<129;1;1m"), event(MouseButton::Other(9), MouseEventKind::Release, 0, 0));
+    }
+
+    #[test]
+    fn modifiers() {
+        let event = csi(b"\x1b[<20;1;1M").unwrap();
+        assert_eq!(event.button, MouseButton::Left);
+        assert_eq!(event.modifiers, MouseModifiers::SHIFT | MouseModifiers::CTRL);
+        assert!(!event.modifiers.contains(MouseModifiers::ALT));
+
+        let event = csi(b"\x1b[<73;1;1M").unwrap();
+        assert_eq!(event.button, MouseButton::WheelDown);
+        assert_eq!(event.modifiers, MouseModifiers::ALT);
+    }
+
+    #[test]
+    fn urxvt() {
+        assert_eq!(csi(b"\x1b[32;10;20M"), event(MouseButton::Left, MouseEventKind::Press, 9, 19));
+        assert_eq!(csi(b"\x1b[35;10;20M"), event(MouseButton::None, MouseEventKind::Release, 9, 19));
+        assert_eq!(csi(b"\x1b[64;10;20M"), event(MouseButton::Left, MouseEventKind::Motion, 9, 19));
+        assert_eq!(csi(b"\x1b[96;1;1M"), event(MouseButton::WheelUp, MouseEventKind::Press, 0, 0));
+    }
+
+    #[test]
+    fn other_sequences() {
+        // `DL` and the `CSI M` of the X10 encoding
+        assert_eq!(csi(b"\x1b[M"), None);
+        assert_eq!(csi(b"\x1b[5M"), None);
+
+        assert_eq!(csi(b"\x1b[<0;10M"), None);
+        assert_eq!(csi(b"\x1b[<0;0;1M"), None);
+        assert_eq!(csi(b"\x1b[10;10;10M"), None);
+        assert_eq!(csi(b"\x1b[?0;10;10M"), None);
+        assert_eq!(csi(b"\x1b[<0;1;1H"), None);
+    }
+
+    #[test]
+    fn x10() {
+        assert_eq!(parse_mouse_dec([' ', '!', '!']), event(MouseButton::Left, MouseEventKind::Press, 0, 0));
+        assert_eq!(parse_mouse_dec(['#', '!', '!']), event(MouseButton::None, MouseEventKind::Release, 0, 0));
+        assert_eq!(parse_mouse_dec(['@', '+', '5']), event(MouseButton::Left, MouseEventKind::Motion, 10, 20));
+        assert_eq!(parse_mouse_dec(['a', '!', '!']), event(MouseButton::WheelDown, MouseEventKind::Press, 0, 0));
+
+        // Mode 1005 encodes large positions as UTF-8
+        assert_eq!(parse_mouse_dec([' ', '\u{150}', '!']), event(MouseButton::Left, MouseEventKind::Press, 303, 0));
+
+        assert_eq!(parse_mouse_dec([' ', '\u{fffd}', '!']), None);
+        assert_eq!(parse_mouse_dec(['\x1f', '!', '!']), None);
+    }
+
+    #[test]
+    fn x10_through_parser() {
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_slice(&mut recorder, b"\x1b[M !!");
+
+        let mut data = recorder.events().iter().filter_map(|event| match *event {
+            RecordedEvent::Print(c) => Some(c),
+            _ => None,
+        });
+        let data = [data.next().unwrap(), data.next().unwrap(), data.next().unwrap()];
+        assert_eq!(parse_mouse_dec(data), event(MouseButton::Left, MouseEventKind::Press, 0, 0));
+    }
+}
@@ -0,0 +1,129 @@
+//! Instrumenting the parser without changing the `Perform`
+use alloc::boxed::Box;
+
+use {Action, State};
+
+/// Watches the parser work through its input
+///
+/// An observer is installed with [`Parser::with_observer`] or
+/// [`Parser::set_observer`]. It sees every byte before the parser acts on
+/// it, and every action of the state machine before it is performed, which
+/// makes it suitable for counters and tracing in production.
+///
+/// ```
+/// # use std::cell::Cell;
+/// # use std::rc::Rc;
+/// # use vte::{Action, Parser, ParserObserver, State, TextStripper};
+/// struct ByteCounter(Rc<Cell<usize>>);
+///
+/// impl ParserObserver for ByteCounter {
+///     fn on_byte(&mut self, _byte: u8, _state: State) {
+///         self.0.set(self.0.get() + 1);
+///     }
+///
+///     fn on_action(&mut self, _action: Action, _byte: u8) {}
+/// }
+///
+/// let bytes = Rc::new(Cell::new(0));
+/// let mut parser = Parser::with_observer(Box::new(ByteCounter(bytes.clone())));
+/// parser.advance_slice(&mut TextStripper::new(), b"\x1b[1mbold");
+/// assert_eq!(bytes.get(), 8);
+/// ```
+///
+/// [`Parser::with_observer`]: struct.Parser.html#method.with_observer
+/// [`Parser::set_observer`]: struct.Parser.html#method.set_observer
+pub trait ParserObserver {
+    /// `byte` was passed to the parser, which is in `state`
+    fn on_byte(&mut self, byte: u8, state: State);
+
+    /// The parser is about to perform `action` for `byte`
+    ///
+    /// Called for the exit, transition and entry actions of the state
+    /// machine. Characters of UTF-8 sequences are decoded outside of the
+    /// state machine and only reported through `on_byte`.
+    fn on_action(&mut self, action: Action, byte: u8);
+}
+
+/// Storage for the observer of a parser
+///
+/// The observer can't be cloned, so a clone of the parser has none.
+#[derive(Default)]
+pub(crate) struct ObserverSlot(pub(crate) Option<Box<dyn ParserObserver>>);
+
+impl Clone for ObserverSlot {
+    fn clone(&self) -> ObserverSlot {
+        ObserverSlot(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    use super::ParserObserver;
+    use test_utils::RecordingPerform;
+    use {Action, Parser, State};
+
+    #[derive(Default)]
+    struct Counts {
+        bytes: usize,
+        ground_bytes: usize,
+        actions: [usize; 16],
+    }
+
+    struct Counter(Rc<RefCell<Counts>>);
+
+    impl ParserObserver for Counter {
+        fn on_byte(&mut self, _byte: u8, state: State) {
+            let mut counts = self.0.borrow_mut();
+            counts.bytes += 1;
+            if state == State::Ground {
+                counts.ground_bytes += 1;
+            }
+        }
+
+        fn on_action(&mut self, action: Action, _byte: u8) {
+            self.0.borrow_mut().actions[action as usize] += 1;
+        }
+    }
+
+    #[test]
+    fn counts_bytes_and_actions() {
+        let counts = Rc::new(RefCell::new(Counts::default()));
+        let mut parser = Parser::with_observer(Box::new(Counter(counts.clone())));
+        let mut recorder = RecordingPerform::default();
+        parser.advance_slice(&mut recorder, b"ab\x1b[1;22mc\r\n\x1b]0;t\x07");
+
+        let counts = counts.borrow();
+        assert_eq!(counts.bytes, 18);
+        assert_eq!(counts.ground_bytes, 7);
+        assert_eq!(counts.actions[Action::Print as usize], 3);
+        assert_eq!(counts.actions[Action::Execute as usize], 2);
+        // Entering the escape state clears the sequence as well
+        assert_eq!(counts.actions[Action::Clear as usize], 3);
+        assert_eq!(counts.actions[Action::Param as usize], 4);
+        assert_eq!(counts.actions[Action::CsiDispatch as usize], 1);
+        assert_eq!(counts.actions[Action::OscStart as usize], 1);
+        assert_eq!(counts.actions[Action::OscPut as usize], 3);
+        assert_eq!(counts.actions[Action::OscEnd as usize], 1);
+    }
+
+    #[test]
+    fn clone_and_take() {
+        let counts = Rc::new(RefCell::new(Counts::default()));
+        let mut parser = Parser::with_observer(Box::new(Counter(counts.clone())));
+        let mut recorder = RecordingPerform::default();
+
+        let mut clone = parser.clone();
+        clone.advance_slice(&mut recorder, b"abc");
+        assert_eq!(counts.borrow().bytes, 0);
+
+        parser.advance_slice(&mut recorder, b"abc");
+        assert_eq!(counts.borrow().bytes, 3);
+
+        assert!(parser.take_observer().is_some());
+        parser.advance_slice(&mut recorder, b"abc");
+        assert_eq!(counts.borrow().bytes, 3);
+    }
+}
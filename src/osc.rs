@@ -0,0 +1,894 @@
+//! Decoding the parameters of OSC sequences
+//!
+//! [`parse_osc8`] decodes the hyperlinks of `OSC 8`, which for example
+//! `ls --hyperlink` prints around file names:
+//!
+//! ```text
+//! OSC 8 ; id=name ; file:///etc/hosts ST hosts OSC 8 ; ; ST
+//! ```
+//!
+//! A hyperlink-aware `Perform` keeps the URI of the open link and attaches
+//! it to the text it prints:
+//!
+//! ```
+//! # use vte::{Params, Parser, Perform};
+//! use vte::osc::{parse_osc8, Osc8};
+//!
+//! #[derive(Default)]
+//! struct Links {
+//!     uri: Option<String>,
+//!     links: Vec<(String, String)>,
+//! }
+//!
+//! impl Perform for Links {
+//!     fn print(&mut self, c: char) {
+//!         if let Some(ref uri) = self.uri {
+//!             match self.links.last_mut() {
+//!                 Some(&mut (ref link, ref mut text)) if link == uri => text.push(c),
+//!                 _ => self.links.push((uri.clone(), c.to_string())),
+//!             }
+//!         }
+//!     }
+//!
+//!     fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+//!         match parse_osc8(params) {
+//!             Some(Osc8::Open { uri, .. }) => self.uri = Some(uri),
+//!             Some(Osc8::Close) => self.uri = None,
+//!             None => (),
+//!         }
+//!     }
+//!     # fn execute(&mut self, _byte: u8) {}
+//!     # fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+//!     # fn put(&mut self, _byte: u8) {}
+//!     # fn unhook(&mut self) {}
+//!     # fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+//!     # fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+//! }
+//!
+//! let mut links = Links::default();
+//! Parser::new().advance_slice(&mut links, b"\x1b]8;;file:///etc/hosts\x1b\\hosts\x1b]8;;\x1b\\ x");
+//! assert_eq!(links.links, [("file:///etc/hosts".to_string(), "hosts".to_string())]);
+//! ```
+//!
+//! [`HyperlinkTracker`] does the same for any `Perform`, keeping the OSC 8
+//! sequences from it. [`TitleTracker`] similarly keeps the window title and
+//! icon name set by `OSC 0`, `OSC 1` and `OSC 2`.
+//!
+//! [`parse_osc8`]: fn.parse_osc8.html
+//! [`HyperlinkTracker`]: struct.HyperlinkTracker.html
+//! [`TitleTracker`]: struct.TitleTracker.html
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "base64")]
+use base64::alphabet;
+#[cfg(feature = "base64")]
+use base64::engine::{DecodePaddingMode, Engine, GeneralPurpose, GeneralPurposeConfig};
+
+use {Params, Perform, State, C0};
+
+/// A hyperlink sequence, `OSC 8 ; params ; uri ST`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Osc8 {
+    /// Start a link to `uri`
+    ///
+    /// Text printed until the next `Osc8` belongs to the link. Cells with
+    /// the same `id` and URI belong to the same link even if they aren't
+    /// adjacent, for example when a link wraps in a text editor.
+    Open {
+        id: Option<String>,
+        uri: String,
+    },
+
+    /// End the current link, sent with an empty URI
+    Close,
+}
+
+/// Decode the parameters of an `OSC 8` sequence
+///
+/// Returns `None` if `params` aren't those of `OSC 8`. The `id` is taken from
+/// the `:` separated `key=value` pairs in the second parameter; other keys
+/// are ignored. The URI is returned as sent, so percent-encoded characters
+/// stay encoded, and a URI containing `;` is joined again after the parser
+/// split it. Invalid UTF-8 is replaced by `U+FFFD`.
+#[must_use]
+pub fn parse_osc8(params: &[&[u8]]) -> Option<Osc8> {
+    let link = match params {
+        [b"8", link, _, ..] => link,
+        _ => return None,
+    };
+
+    let uri = join(&params[2..]);
+    if uri.is_empty() {
+        return Some(Osc8::Close);
+    }
+
+    let id = link.split(|&byte| byte == b':')
+        .find(|pair| pair.starts_with(b"id="))
+        .map(|pair| String::from_utf8_lossy(&pair[3..]).into_owned());
+
+    Some(Osc8::Open { id, uri })
+}
+
+/// The selection targeted by an `OSC 52` sequence
+#[cfg(feature = "base64")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ClipboardKind {
+    /// `c`, the clipboard
+    Clipboard,
+
+    /// `p`, the primary selection
+    Primary,
+
+    /// `q`, the secondary selection
+    Secondary,
+
+    /// `s`, the selection, which terminals map to primary or clipboard
+    Select,
+
+    /// `0` to `7`, one of the X11 cut buffers
+    CutBuffer(u8),
+}
+
+#[cfg(feature = "base64")]
+impl ClipboardKind {
+    fn from_byte(byte: u8) -> Option<ClipboardKind> {
+        match byte {
+            b'c' => Some(ClipboardKind::Clipboard),
+            b'p' => Some(ClipboardKind::Primary),
+            b'q' => Some(ClipboardKind::Secondary),
+            b's' => Some(ClipboardKind::Select),
+            b'0'..=b'7' => Some(ClipboardKind::CutBuffer(byte - b'0')),
+            _ => None,
+        }
+    }
+}
+
+/// A clipboard sequence, `OSC 52 ; selection ; data ST`
+#[cfg(feature = "base64")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Osc52Action {
+    /// Replace the contents of `clipboard` with `data`
+    ///
+    /// Empty `data` clears the clipboard.
+    Set {
+        clipboard: ClipboardKind,
+        data: Vec<u8>,
+    },
+
+    /// Report the contents of `clipboard`, sent with `?` as data
+    Query {
+        clipboard: ClipboardKind,
+    },
+}
+
+/// Standard base64, accepting data with and without padding
+#[cfg(feature = "base64")]
+const OSC52_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Decode the parameters of an `OSC 52` sequence
+///
+/// Returns `None` if `params` aren't those of `OSC 52` or the data isn't
+/// valid base64. The selection parameter may name several clipboards; like
+/// xterm, the first one recognised is used, and an empty selection means
+/// [`ClipboardKind::Select`]. The data is returned decoded, and may be any
+/// bytes, not only text.
+///
+/// ```
+/// use vte::osc::{parse_osc52, ClipboardKind, Osc52Action};
+///
+/// let set = Osc52Action::Set { clipboard: ClipboardKind::Clipboard, data: b"vte".to_vec() };
+/// assert_eq!(parse_osc52(&[b"52", b"c", b"dnRl"]), Some(set));
+///
+/// let query = Osc52Action::Query { clipboard: ClipboardKind::Primary };
+/// assert_eq!(parse_osc52(&[b"52", b"p", b"?"]), Some(query));
+/// ```
+///
+/// [`ClipboardKind::Select`]: enum.ClipboardKind.html#variant.Select
+#[cfg(feature = "base64")]
+#[must_use]
+pub fn parse_osc52(params: &[&[u8]]) -> Option<Osc52Action> {
+    let (selection, data) = match params {
+        [b"52", selection, data] => (selection, data),
+        _ => return None,
+    };
+
+    let clipboard = selection.iter()
+        .filter_map(|&byte| ClipboardKind::from_byte(byte))
+        .next()
+        .unwrap_or(ClipboardKind::Select);
+
+    if *data == b"?" {
+        return Some(Osc52Action::Query { clipboard });
+    }
+
+    let data = OSC52_BASE64.decode(data).ok()?;
+    Some(Osc52Action::Set { clipboard, data })
+}
+
+/// The hyperlink opened by the last `OSC 8` sequence
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HyperlinkState {
+    /// Target of the link, as sent
+    pub uri: String,
+
+    /// Identifier joining cells of the same link
+    pub id: Option<String>,
+}
+
+/// A [`Perform`] which tracks the current hyperlink before forwarding
+/// everything else to `P`
+///
+/// `OSC 8` sequences are decoded with [`parse_osc8`] and not passed on;
+/// `P` can't see them, but the link is available from
+/// [`current_hyperlink`] while `P` prints the text belonging to it. Opening
+/// a link replaces the current one.
+///
+/// ```
+/// # use vte::{Params, Parser, Perform};
+/// use vte::osc::HyperlinkTracker;
+///
+/// # struct Ignore;
+/// # impl Perform for Ignore {
+/// #     fn print(&mut self, _c: char) {}
+/// #     fn execute(&mut self, _byte: u8) {}
+/// #     fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+/// #     fn put(&mut self, _byte: u8) {}
+/// #     fn unhook(&mut self) {}
+/// #     fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+/// #     fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+/// #     fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// # }
+/// let mut parser = Parser::new();
+/// let mut tracker = HyperlinkTracker::new(Ignore);
+///
+/// parser.advance_slice(&mut tracker, b"\x1b]8;id=1;http://example.com\x1b\\");
+/// assert_eq!(tracker.current_hyperlink().unwrap().uri, "http://example.com");
+///
+/// parser.advance_slice(&mut tracker, b"link\x1b]8;;\x1b\\");
+/// assert_eq!(tracker.current_hyperlink(), None);
+/// ```
+///
+/// [`Perform`]: ../trait.Perform.html
+/// [`parse_osc8`]: fn.parse_osc8.html
+/// [`current_hyperlink`]: struct.HyperlinkTracker.html#method.current_hyperlink
+#[derive(Debug, Default, Clone)]
+pub struct HyperlinkTracker<P: Perform> {
+    performer: P,
+    hyperlink: Option<HyperlinkState>,
+}
+
+impl<P: Perform> HyperlinkTracker<P> {
+    /// Wrap `performer`, starting outside of any link
+    #[must_use]
+    pub fn new(performer: P) -> HyperlinkTracker<P> {
+        HyperlinkTracker { performer, hyperlink: None }
+    }
+
+    /// The link printed text belongs to, `None` after it was closed
+    #[inline]
+    #[must_use]
+    pub fn current_hyperlink(&self) -> Option<&HyperlinkState> {
+        self.hyperlink.as_ref()
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+}
+
+impl<P: Perform> Perform for HyperlinkTracker<P> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.performer.print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.performer.print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        self.performer.execute(byte);
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        self.performer.execute_c0(c0);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.performer.carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.performer.linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        self.performer.backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        self.performer.tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.performer.bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.performer.hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.performer.put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.performer.unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.performer.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        match parse_osc8(params) {
+            Some(Osc8::Open { id, uri }) => self.hyperlink = Some(HyperlinkState { uri, id }),
+            Some(Osc8::Close) => self.hyperlink = None,
+            None => self.performer.osc_dispatch(params, bell_terminated),
+        }
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.apc_dispatch(bytes);
+    }
+
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.performer.csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.performer.esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.performer.on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.performer.error(byte);
+    }
+}
+
+/// A change reported by a [`TitleTracker`]
+///
+/// [`TitleTracker`]: struct.TitleTracker.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TitleChange {
+    /// The window title, set by `OSC 0` and `OSC 2`
+    Title(String),
+
+    /// The icon name, set by `OSC 0` and `OSC 1`
+    IconName(String),
+}
+
+/// A [`Perform`] which tracks the window title and icon name before
+/// forwarding everything else to `P`
+///
+/// `OSC 0 ; text ST` sets both, `OSC 1 ; text ST` only the icon name and
+/// `OSC 2 ; text ST` only the title. These sequences are not passed on to
+/// `P`. Text which isn't valid UTF-8 is decoded lossily, and a `;` in it is
+/// kept. Every change is also passed to the callback set with
+/// [`on_change`]; `OSC 0` reports the icon name before the title.
+///
+/// ```
+/// # use vte::{Params, Parser, Perform};
+/// use vte::osc::{TitleChange, TitleTracker};
+///
+/// # struct Ignore;
+/// # impl Perform for Ignore {
+/// #     fn print(&mut self, _c: char) {}
+/// #     fn execute(&mut self, _byte: u8) {}
+/// #     fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+/// #     fn put(&mut self, _byte: u8) {}
+/// #     fn unhook(&mut self) {}
+/// #     fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+/// #     fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+/// #     fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// # }
+/// let mut parser = Parser::new();
+/// let mut tracker = TitleTracker::new(Ignore);
+/// tracker.on_change(|change| {
+///     if let TitleChange::Title(title) = change {
+///         println!("new title: {}", title);
+///     }
+/// });
+///
+/// parser.advance_slice(&mut tracker, b"\x1b]0;vim\x07\x1b]2;vim - notes.txt\x07");
+/// assert_eq!(tracker.title(), Some("vim - notes.txt"));
+/// assert_eq!(tracker.icon_name(), Some("vim"));
+/// ```
+///
+/// [`Perform`]: ../trait.Perform.html
+/// [`on_change`]: struct.TitleTracker.html#method.on_change
+pub struct TitleTracker<P: Perform> {
+    performer: P,
+    title: Option<String>,
+    icon_name: Option<String>,
+    callback: Option<Box<dyn FnMut(TitleChange)>>,
+}
+
+impl<P: Perform> TitleTracker<P> {
+    /// Wrap `performer`, starting without a title or icon name
+    #[must_use]
+    pub fn new(performer: P) -> TitleTracker<P> {
+        TitleTracker { performer, title: None, icon_name: None, callback: None }
+    }
+
+    /// Call `callback` with every change of the title or icon name
+    ///
+    /// This replaces the previous callback.
+    pub fn on_change<F: FnMut(TitleChange) + 'static>(&mut self, callback: F) {
+        self.callback = Some(Box::new(callback));
+    }
+
+    /// The last window title, `None` if none was set
+    #[inline]
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// The last icon name, `None` if none was set
+    #[inline]
+    #[must_use]
+    pub fn icon_name(&self) -> Option<&str> {
+        self.icon_name.as_deref()
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+
+    fn change(&mut self, change: TitleChange) {
+        match change {
+            TitleChange::Title(ref title) => self.title = Some(title.clone()),
+            TitleChange::IconName(ref name) => self.icon_name = Some(name.clone()),
+        }
+        if let Some(ref mut callback) = self.callback {
+            callback(change);
+        }
+    }
+}
+
+impl<P: Perform + fmt::Debug> fmt::Debug for TitleTracker<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TitleTracker")
+            .field("performer", &self.performer)
+            .field("title", &self.title)
+            .field("icon_name", &self.icon_name)
+            .finish()
+    }
+}
+
+impl<P: Perform> Perform for TitleTracker<P> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.performer.print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.performer.print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        self.performer.execute(byte);
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        self.performer.execute_c0(c0);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.performer.carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.performer.linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        self.performer.backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        self.performer.tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.performer.bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.performer.hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.performer.put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.performer.unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.performer.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        match params {
+            [b"0", text @ ..] if !text.is_empty() => {
+                let text = join(text);
+                self.change(TitleChange::IconName(text.clone()));
+                self.change(TitleChange::Title(text));
+            },
+            [b"1", text @ ..] if !text.is_empty() => self.change(TitleChange::IconName(join(text))),
+            [b"2", text @ ..] if !text.is_empty() => self.change(TitleChange::Title(join(text))),
+            _ => self.performer.osc_dispatch(params, bell_terminated),
+        }
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.apc_dispatch(bytes);
+    }
+
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.performer.csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.performer.esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.performer.on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.performer.error(byte);
+    }
+}
+
+/// Join OSC parameters, which may contain `;` themselves
+pub(crate) fn join(params: &[&[u8]]) -> String {
+    let mut text = Vec::new();
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            text.push(b';');
+        }
+        text.extend_from_slice(param);
+    }
+    String::from_utf8_lossy(&text).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::string::ToString;
+    use core::cell::RefCell;
+
+    use super::{parse_osc8, HyperlinkState, HyperlinkTracker, Osc8, TitleChange, TitleTracker};
+    #[cfg(feature = "base64")]
+    use super::{parse_osc52, ClipboardKind, Osc52Action};
+    use test_utils::RecordingPerform;
+    use tests::describe;
+    use Parser;
+
+    fn open(id: Option<&str>, uri: &str) -> Option<Osc8> {
+        Some(Osc8::Open { id: id.map(|id| id.to_string()), uri: uri.to_string() })
+    }
+
+    #[test]
+    fn without_id() {
+        assert_eq!(parse_osc8(&[b"8", b"", b"http://example.com"]), open(None, "http://example.com"));
+        assert_eq!(parse_osc8(&[b"8", b"k=v", b"http://example.com"]), open(None, "http://example.com"));
+    }
+
+    #[test]
+    fn with_id() {
+        assert_eq!(parse_osc8(&[b"8", b"id=1", b"file:///a"]), open(Some("1"), "file:///a"));
+        assert_eq!(parse_osc8(&[b"8", b"k=v:id=x:j=w", b"file:///a"]), open(Some("x"), "file:///a"));
+    }
+
+    #[test]
+    fn close() {
+        assert_eq!(parse_osc8(&[b"8", b"", b""]), Some(Osc8::Close));
+        assert_eq!(parse_osc8(&[b"8", b"id=1", b""]), Some(Osc8::Close));
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn osc52_kinds() {
+        let kinds = [
+            (&b"c"[..], ClipboardKind::Clipboard),
+            (b"p", ClipboardKind::Primary),
+            (b"q", ClipboardKind::Secondary),
+            (b"s", ClipboardKind::Select),
+            (b"0", ClipboardKind::CutBuffer(0)),
+            (b"7", ClipboardKind::CutBuffer(7)),
+            (b"", ClipboardKind::Select),
+            (b"xpc", ClipboardKind::Primary),
+        ];
+        for &(selection, clipboard) in &kinds {
+            let set = Osc52Action::Set { clipboard, data: b"text".to_vec() };
+            assert_eq!(parse_osc52(&[b"52", selection, b"dGV4dA=="]), Some(set));
+        }
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn osc52_data() {
+        let set = |data: &[u8]| Some(Osc52Action::Set { clipboard: ClipboardKind::Clipboard, data: data.to_vec() });
+        assert_eq!(parse_osc52(&[b"52", b"c", b"AP8KG4BUZXN0"]), set(b"\x00\xff\n\x1b\x80Test"));
+        assert_eq!(parse_osc52(&[b"52", b"c", b"dGV4dA"]), set(b"text"));
+        assert_eq!(parse_osc52(&[b"52", b"c", b""]), set(b""));
+        assert_eq!(parse_osc52(&[b"52", b"c", b"not base64!"]), None);
+        assert_eq!(parse_osc52(&[b"52", b"c"]), None);
+        assert_eq!(parse_osc52(&[b"8", b"c", b"dGV4dA=="]), None);
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn osc52_query() {
+        assert_eq!(parse_osc52(&[b"52", b"c", b"?"]), Some(Osc52Action::Query { clipboard: ClipboardKind::Clipboard }));
+        assert_eq!(parse_osc52(&[b"52", b"", b"?"]), Some(Osc52Action::Query { clipboard: ClipboardKind::Select }));
+        assert_eq!(parse_osc52(&[b"52", b"4", b"?"]), Some(Osc52Action::Query { clipboard: ClipboardKind::CutBuffer(4) }));
+    }
+
+    #[test]
+    fn percent_encoded_uri() {
+        let uri = "file:///home/user/My%20Files/a%3Bb";
+        assert_eq!(parse_osc8(&[b"8", b"", uri.as_bytes()]), open(None, uri));
+
+        // An unencoded `;` splits the URI into several parameters
+        assert_eq!(parse_osc8(&[b"8", b"", b"http://a/?x=1", b"y=2"]), open(None, "http://a/?x=1;y=2"));
+    }
+
+    #[test]
+    fn other_sequences() {
+        assert_eq!(parse_osc8(&[b"8", b""]), None);
+        assert_eq!(parse_osc8(&[b"8"]), None);
+        assert_eq!(parse_osc8(&[b"2", b"", b"title"]), None);
+        assert_eq!(parse_osc8(&[]), None);
+    }
+
+    #[test]
+    fn tracker() {
+        let mut parser = Parser::new();
+        let mut tracker = HyperlinkTracker::new(RecordingPerform::new());
+        assert_eq!(tracker.current_hyperlink(), None);
+
+        parser.advance_slice(&mut tracker, b"\x1b]8;id=a;http://example.com\x1b\\");
+        let link = HyperlinkState { uri: "http://example.com".to_string(), id: Some("a".to_string()) };
+        assert_eq!(tracker.current_hyperlink(), Some(&link));
+
+        for &byte in b"abc" {
+            parser.advance(&mut tracker, byte);
+            assert_eq!(tracker.current_hyperlink(), Some(&link));
+        }
+
+        parser.advance_slice(&mut tracker, b"\x1b]8;;\x07d");
+        assert_eq!(tracker.current_hyperlink(), None);
+
+        assert_eq!(describe(tracker.inner().events()), vec![
+            "esc_dispatch [] [] false 5c",
+            "print 'a'",
+            "print 'b'",
+            "print 'c'",
+            "print 'd'",
+        ]);
+    }
+
+    #[test]
+    fn tracker_forwards_other_osc() {
+        let mut parser = Parser::new();
+        let mut tracker = HyperlinkTracker::new(RecordingPerform::new());
+        parser.advance_slice(&mut tracker, b"\x1b]8;;file:///a\x07\x1b]0;t\x07\x1b]8;x\x07");
+
+        assert_eq!(tracker.current_hyperlink().map(|link| &*link.uri), Some("file:///a"));
+        assert_eq!(describe(tracker.into_inner().events()), vec![
+            "osc_dispatch [[48], [116]] true",
+            "osc_dispatch [[56], [120]] true",
+        ]);
+    }
+
+    #[test]
+    fn titles() {
+        let mut parser = Parser::new();
+        let mut tracker = TitleTracker::new(RecordingPerform::new());
+        assert_eq!((tracker.title(), tracker.icon_name()), (None, None));
+
+        parser.advance_slice(&mut tracker, b"\x1b]0;both\x07");
+        assert_eq!((tracker.title(), tracker.icon_name()), (Some("both"), Some("both")));
+
+        parser.advance_slice(&mut tracker, b"\x1b]1;icon\x1b\\");
+        assert_eq!((tracker.title(), tracker.icon_name()), (Some("both"), Some("icon")));
+
+        parser.advance_slice(&mut tracker, b"\x1b]2;a;b\x07");
+        assert_eq!((tracker.title(), tracker.icon_name()), (Some("a;b"), Some("icon")));
+
+        parser.advance_slice(&mut tracker, b"\x1b]2;\x07");
+        assert_eq!(tracker.title(), Some(""));
+
+        // Only the terminator of the OSC 1 reaches the inner `Perform`
+        assert_eq!(describe(tracker.inner().events()), vec!["esc_dispatch [] [] false 5c"]);
+    }
+
+    #[test]
+    fn title_callback() {
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let mut tracker = TitleTracker::new(RecordingPerform::new());
+        let seen = changes.clone();
+        tracker.on_change(move |change| seen.borrow_mut().push(change));
+
+        Parser::new().advance_slice(&mut tracker, b"\x1b]0;t\x07\x1b]1;i\x07\x1b]2;\xff\x07");
+        assert_eq!(*changes.borrow(), vec![
+            TitleChange::IconName("t".to_string()),
+            TitleChange::Title("t".to_string()),
+            TitleChange::IconName("i".to_string()),
+            TitleChange::Title("\u{fffd}".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn title_tracker_forwards_other_osc() {
+        let mut tracker = TitleTracker::new(RecordingPerform::new());
+        Parser::new().advance_slice(&mut tracker, b"\x1b]2\x07\x1b]8;;\x07\x1b]20;x\x07");
+
+        assert_eq!(tracker.title(), None);
+        assert_eq!(describe(tracker.into_inner().events()), vec![
+            "osc_dispatch [[50]] true",
+            "osc_dispatch [[56], [], []] true",
+            "osc_dispatch [[50, 48], [120]] true",
+        ]);
+    }
+}
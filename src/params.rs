@@ -0,0 +1,405 @@
+//! Fixed size parameter list for control sequences
+use core::{cmp, fmt};
+
+use MAX_PARAMS;
+
+/// Maximum number of subparameters stored for a single parameter
+pub const MAX_SUBPARAMS: usize = 8;
+
+/// Parameters of a CSI, DCS or ESC sequence
+///
+/// Every parameter consists of one or more values. Most sequences only use a
+/// single value per parameter, but some (SGR colors for example) may contain
+/// subparameters separated by colons; those are exposed as the additional
+/// values of the parameter slice.
+///
+/// Storage is fixed size so collecting parameters never allocates.
+#[derive(Clone, Copy)]
+pub struct Params {
+    /// Values of all parameters, each followed by its subparameters
+    values: [u16; MAX_PARAMS * MAX_SUBPARAMS],
+
+    /// Offset into `values` of the first value of each parameter
+    starts: [u8; MAX_PARAMS],
+
+    /// Number of values stored
+    values_len: usize,
+
+    /// Number of parameters
+    len: usize,
+
+    /// A parameter was dropped because the list was full, so subparameters
+    /// must be dropped too until the list is cleared
+    truncated: bool,
+}
+
+impl Params {
+    /// Create an empty parameter list
+    #[inline]
+    #[must_use]
+    pub fn new() -> Params {
+        Params {
+            values: [0; MAX_PARAMS * MAX_SUBPARAMS],
+            starts: [0; MAX_PARAMS],
+            values_len: 0,
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    /// Number of parameters
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no parameters
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over all parameters and their subparameters
+    #[inline]
+    #[must_use]
+    pub fn iter(&self) -> ParamsIter<'_> {
+        ParamsIter {
+            params: self,
+            index: 0,
+        }
+    }
+
+    /// Get the parameter at `index`, including its subparameters
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&[u16]> {
+        if index >= self.len {
+            return None;
+        }
+
+        let start = self.starts[index] as usize;
+        let end = if index + 1 < self.len {
+            self.starts[index + 1] as usize
+        } else {
+            self.values_len
+        };
+        Some(&self.values[start..end])
+    }
+
+    /// Get a single value or fall back to `default` if it is not present
+    ///
+    /// `sub` selects the subparameter; `0` is the parameter value itself.
+    ///
+    /// ```
+    /// # use vte::Params;
+    /// let params = Params::new();
+    /// assert_eq!(params.get_or_default(0, 0, 1), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get_or_default(&self, index: usize, sub: usize, default: u16) -> u16 {
+        self.get(index)
+            .and_then(|param| param.get(sub))
+            .cloned()
+            .unwrap_or(default)
+    }
+
+    /// Iterate over parameter values, substituting defaults
+    ///
+    /// Yields the value of every parameter, without subparameters, and of
+    /// every missing parameter up to the length of `defaults`. Missing values
+    /// and `0` are replaced by the default at the same index, since ECMA-48
+    /// treats zero as the default value. Parameters beyond the end of
+    /// `defaults` are yielded unchanged.
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")] {
+    /// # use vte::{Parser, VteEvent};
+    /// let mut parser = Parser::new();
+    /// for event in parser.parse(b"\x1b[0;5H") {
+    ///     if let VteEvent::CsiDispatch { params, .. } = event {
+    ///         let values: Vec<u16> = params.iter_defaults(&[1, 1, 1]).collect();
+    ///         assert_eq!(values, [1, 5, 1]);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter_defaults<'a>(&'a self, defaults: &'a [u16]) -> impl Iterator<Item = u16> + 'a {
+        let len = cmp::max(self.len, defaults.len());
+        (0..len).map(move |index| {
+            let value = self.get(index).map_or(0, |param| param[0]);
+            match defaults.get(index) {
+                Some(&default) if value == 0 => default,
+                _ => value,
+            }
+        })
+    }
+
+    /// Returns `true` if no more parameters can be added
+    #[inline]
+    pub(crate) fn is_full(&self) -> bool {
+        self.len == MAX_PARAMS
+    }
+
+    /// Returns `true` if parameters were dropped since the list was cleared
+    #[inline]
+    pub(crate) fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Remove all parameters
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.len = 0;
+        self.values_len = 0;
+        self.truncated = false;
+    }
+
+    /// Mark the list as missing parameters which didn't fit
+    #[inline]
+    pub(crate) fn truncate(&mut self) {
+        self.truncated = true;
+    }
+
+    /// Add a new parameter
+    ///
+    /// The value is dropped if the list is already full.
+    #[inline]
+    pub(crate) fn push(&mut self, value: u16) {
+        self.push_bounded(value, MAX_PARAMS);
+    }
+
+    /// Add a new parameter unless there are `limit` parameters already
+    #[inline]
+    pub(crate) fn push_bounded(&mut self, value: u16, limit: usize) {
+        if self.len >= limit || self.is_full() {
+            self.truncated = true;
+            return;
+        }
+
+        // At most `MAX_SUBPARAMS` values per parameter, so the start of
+        // the last one still fits into a `u8`
+        self.starts[self.len] = self.values_len as u8;
+        self.values[self.values_len] = value;
+        self.values_len += 1;
+        self.len += 1;
+    }
+
+    /// Add a subparameter to the last parameter
+    ///
+    /// The value is dropped if the parameter has no room left, or the
+    /// parameter itself was dropped.
+    #[cfg(any(feature = "alloc", test))]
+    #[inline]
+    pub(crate) fn extend(&mut self, value: u16) {
+        self.extend_bounded(value, MAX_SUBPARAMS);
+    }
+
+    /// Add a subparameter to the last parameter unless it has `limit`
+    /// values already, counting the parameter value itself
+    #[inline]
+    pub(crate) fn extend_bounded(&mut self, value: u16, limit: usize) {
+        if self.len == 0 || self.truncated {
+            return;
+        }
+
+        let values = self.values_len - self.starts[self.len - 1] as usize;
+        if values >= cmp::min(limit, MAX_SUBPARAMS) {
+            return;
+        }
+
+        self.values[self.values_len] = value;
+        self.values_len += 1;
+    }
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params::new()
+    }
+}
+
+impl PartialEq for Params {
+    fn eq(&self, other: &Params) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for Params {}
+
+impl fmt::Debug for Params {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a Params {
+    type Item = &'a [u16];
+    type IntoIter = ParamsIter<'a>;
+
+    fn into_iter(self) -> ParamsIter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the parameters of a [`Params`]
+///
+/// [`Params`]: struct.Params.html
+#[derive(Clone)]
+pub struct ParamsIter<'a> {
+    params: &'a Params,
+    index: usize,
+}
+
+impl<'a> Iterator for ParamsIter<'a> {
+    type Item = &'a [u16];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u16]> {
+        let param = self.params.get(self.index);
+        if param.is_some() {
+            self.index += 1;
+        }
+        param
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.params.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_SUBPARAMS, Params};
+    use MAX_PARAMS;
+
+    #[test]
+    fn empty() {
+        let params = Params::new();
+        assert_eq!(params.len(), 0);
+        assert!(params.is_empty());
+        assert_eq!(params.get(0), None);
+        assert_eq!(params.iter().next(), None);
+    }
+
+    #[test]
+    fn push_and_get() {
+        let mut params = Params::new();
+        params.push(12);
+        params.push(0);
+
+        assert_eq!(params.len(), 2);
+        assert_eq!(params.get(0), Some(&[12][..]));
+        assert_eq!(params.get(1), Some(&[0][..]));
+        assert_eq!(params.get(2), None);
+        assert_eq!(params.iter().collect::<Vec<_>>(), vec![&[12][..], &[0][..]]);
+    }
+
+    #[test]
+    fn get_or_default() {
+        let mut params = Params::new();
+        params.push(5);
+
+        assert_eq!(params.get_or_default(0, 0, 1), 5);
+        assert_eq!(params.get_or_default(0, 1, 1), 1);
+        assert_eq!(params.get_or_default(1, 0, 1), 1);
+    }
+
+    #[test]
+    fn iter_defaults() {
+        let empty = Params::new();
+        assert_eq!(empty.iter_defaults(&[]).count(), 0);
+        assert_eq!(empty.iter_defaults(&[1, 2]).collect::<Vec<_>>(), [1, 2]);
+
+        let mut params = Params::new();
+        params.push(0);
+        params.push(7);
+        params.extend(3);
+        assert_eq!(params.iter_defaults(&[1, 1]).collect::<Vec<_>>(), [1, 7]);
+
+        // Partially filled
+        assert_eq!(params.iter_defaults(&[1, 1, 9]).collect::<Vec<_>>(), [1, 7, 9]);
+
+        // Beyond the defaults, zero is kept
+        params.push(0);
+        assert_eq!(params.iter_defaults(&[1]).collect::<Vec<_>>(), [1, 7, 0]);
+    }
+
+    #[test]
+    fn push_beyond_capacity_is_dropped() {
+        let mut params = Params::new();
+        for i in 0..(MAX_PARAMS as u16 + 4) {
+            params.push(i);
+        }
+
+        assert!(params.is_full());
+        assert_eq!(params.len(), MAX_PARAMS);
+    }
+
+    #[test]
+    fn push_bounded() {
+        let mut params = Params::new();
+        params.push_bounded(1, 1);
+        params.push_bounded(2, 1);
+        params.extend(3);
+
+        assert_eq!(params.len(), 1);
+        assert_eq!(params.get(0), Some(&[1][..]));
+        assert!(params.is_truncated());
+    }
+
+    #[test]
+    fn extend_subparams() {
+        let mut params = Params::new();
+        params.push(38);
+        params.extend(2);
+        params.extend(0);
+        params.push(1);
+
+        assert_eq!(params.len(), 2);
+        assert_eq!(params.get(0), Some(&[38, 2, 0][..]));
+        assert_eq!(params.get(1), Some(&[1][..]));
+        assert_eq!(params.get_or_default(0, 1, 5), 2);
+    }
+
+    #[test]
+    fn extend_beyond_capacity_is_dropped() {
+        let mut params = Params::new();
+        params.push(0);
+        for i in 0..(MAX_SUBPARAMS as u16 + 4) {
+            params.extend(i);
+        }
+        assert_eq!(params.get(0).unwrap().len(), MAX_SUBPARAMS);
+
+        // Subparameters of a dropped parameter are dropped as well
+        let mut params = Params::new();
+        for i in 0..(MAX_PARAMS as u16) {
+            params.push(i);
+        }
+        params.extend(100);
+        params.push(200);
+        params.extend(300);
+
+        assert_eq!(params.get(MAX_PARAMS - 1), Some(&[MAX_PARAMS as u16 - 1, 100][..]));
+    }
+
+    #[test]
+    fn clear_and_eq() {
+        let mut a = Params::new();
+        a.push(1);
+        a.push(2);
+        a.clear();
+        a.push(3);
+
+        let mut b = Params::new();
+        b.push(3);
+
+        assert_eq!(a, b);
+        assert_eq!(format!("{:?}", a), "[[3]]");
+    }
+}
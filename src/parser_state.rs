@@ -0,0 +1,290 @@
+//! Saving and restoring the state of a `Parser`
+use alloc::vec::Vec;
+use core::cmp;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use {Parser, ParserMode, State, StringKind, MAX_INTERMEDIATES, MAX_PARAMS};
+
+/// Snapshot of a [`Parser`], including any partially received sequence
+///
+/// Unlike cloning the parser, a `ParserState` does not depend on the
+/// internal layout of the parser and can be serialized with the `serde`
+/// feature, so sessions can be checkpointed and restored by another
+/// process.
+///
+/// The 8-bit C1 control setting and the [`ParserMode`] are part of the
+/// state. Limits and the UTF-8 mode configured through a [`ParserBuilder`]
+/// are not; a restored parser uses the defaults.
+///
+/// [`Parser`]: struct.Parser.html
+/// [`ParserMode`]: enum.ParserMode.html
+/// [`ParserBuilder`]: struct.ParserBuilder.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParserState {
+    /// State of the state machine
+    state: State,
+
+    /// Collected intermediates, preceded by the private marker of a CSI
+    /// sequence
+    intermediates: Vec<u8>,
+
+    /// Sequence is ignored due to too many intermediates or parameters
+    ignoring: bool,
+
+    /// Completed parameters, each followed by its subparameters
+    params: Vec<Vec<u16>>,
+
+    /// Parameters were dropped because there were too many
+    params_truncated: bool,
+
+    /// Parameter which is currently being collected
+    param: u16,
+
+    /// Digits of `param` have been received
+    collecting_param: bool,
+
+    /// `param` is a subparameter of the last parameter
+    collecting_subparam: bool,
+
+    /// Byte introducing the OSC, SOS, PM or APC string which is collected
+    string_introducer: u8,
+
+    /// Collected string data, without OSC parameter separators
+    string: Vec<u8>,
+
+    /// Offset into `string` of every OSC parameter separator received
+    string_separators: Vec<usize>,
+
+    /// Collected DCS passthrough data
+    dcs_data: Vec<u8>,
+
+    /// Final byte of the DCS sequence
+    dcs_final: u8,
+
+    /// Bytes of an incomplete UTF-8 encoded character
+    utf8: Vec<u8>,
+
+    /// 8-bit C1 controls are enabled
+    c1_controls: bool,
+
+    /// Escape sequence syntax
+    mode: ParserMode,
+
+    /// DCS data is buffered for `dcs_dispatch` rather than streamed
+    #[cfg_attr(feature = "serde", serde(default))]
+    dcs_buffered: bool,
+}
+
+impl ParserState {
+    /// State the saved parser was in
+    #[inline]
+    #[must_use]
+    pub fn state(&self) -> State {
+        self.state
+    }
+}
+
+impl Parser {
+    /// Save the complete state of the parser
+    ///
+    /// See [`ParserState`].
+    ///
+    /// [`ParserState`]: struct.ParserState.html
+    #[must_use]
+    pub fn save(&self) -> ParserState {
+        let string_introducer = match self.string_kind {
+            StringKind::Osc => b']',
+            StringKind::Sos => b'X',
+            StringKind::Pm => b'^',
+            StringKind::Apc => b'_',
+        };
+
+        let utf8 = if self.state == State::Utf8 {
+            self.utf8.pending().to_vec()
+        } else {
+            Vec::new()
+        };
+
+        ParserState {
+            state: self.state,
+            intermediates: self.private_marker.iter().chain(self.intermediates()).cloned().collect(),
+            ignoring: self.ignoring,
+            params: self.params.iter().map(|param| param.to_vec()).collect(),
+            params_truncated: self.params.is_truncated(),
+            param: self.param,
+            collecting_param: self.collecting_param,
+            collecting_subparam: self.collecting_subparam,
+            string_introducer,
+            string: self.osc_raw.to_vec(),
+            string_separators: self.osc_params[..self.osc_num_params].iter()
+                .map(|&(_, end)| end)
+                .collect(),
+            dcs_data: self.dcs_data.to_vec(),
+            dcs_final: self.dcs_final,
+            utf8,
+            c1_controls: self.c1_controls,
+            mode: self.mode,
+            dcs_buffered: self.dcs_buffered,
+        }
+    }
+
+    /// Create a parser from a saved state
+    ///
+    /// Values which don't fit into the parser are dropped, so restoring a
+    /// state which was not created by [`save`] never panics.
+    ///
+    /// [`save`]: struct.Parser.html#method.save
+    #[must_use]
+    pub fn restore(state: ParserState) -> Parser {
+        let mut parser = Parser::new();
+
+        parser.state = match state.state {
+            State::Anywhere => State::Ground,
+            state => state,
+        };
+
+        let mut intermediates = &state.intermediates[..];
+        let csi = matches!(
+            parser.state,
+            State::CsiEntry | State::CsiParam | State::CsiIntermediate | State::CsiIgnore
+        );
+        match intermediates.first() {
+            Some(&byte) if csi && matches!(byte, 0x3c..=0x3f) => {
+                parser.private_marker = Some(byte);
+                intermediates = &intermediates[1..];
+            },
+            _ => (),
+        }
+
+        let intermediates = &intermediates[..cmp::min(intermediates.len(), MAX_INTERMEDIATES)];
+        parser.intermediates[..intermediates.len()].copy_from_slice(intermediates);
+        parser.intermediate_idx = intermediates.len();
+        parser.ignoring = state.ignoring;
+
+        for param in &state.params {
+            let mut values = param.iter();
+            parser.params.push(values.next().cloned().unwrap_or(0));
+            for value in values {
+                parser.params.extend(*value);
+            }
+        }
+        if state.params_truncated {
+            let len = parser.params.len();
+            parser.params.push_bounded(0, len);
+        }
+        parser.param = state.param;
+        parser.collecting_param = state.collecting_param;
+        parser.collecting_subparam = state.collecting_subparam;
+
+        parser.string_kind = StringKind::from_introducer(state.string_introducer);
+        parser.osc_raw.replace(state.string);
+
+        let mut begin = 0;
+        for &end in state.string_separators.iter().take(MAX_PARAMS) {
+            if end < begin || end > parser.osc_raw.len() {
+                break;
+            }
+            parser.osc_params[parser.osc_num_params] = (begin, end);
+            parser.osc_num_params += 1;
+            begin = end;
+        }
+
+        parser.dcs_data.replace(state.dcs_data);
+        parser.dcs_final = state.dcs_final;
+        parser.dcs_buffered = state.dcs_buffered;
+
+        if parser.state == State::Utf8 {
+            let mut complete = false;
+            for &byte in state.utf8.iter().take(4) {
+                complete |= parser.utf8.push(byte).is_some();
+            }
+
+            // Nothing to resume unless the bytes start a character
+            if complete || parser.utf8.is_empty() {
+                parser.utf8.reset();
+                parser.state = State::Ground;
+            }
+        }
+
+        parser.c1_controls = state.c1_controls;
+        parser.mode = state.mode;
+        parser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParserState;
+    use test_utils::RecordingPerform;
+    use tests::describe;
+    use {Parser, State};
+
+    fn round_trip(first: &[u8], second: &[u8]) -> (ParserState, Vec<String>) {
+        let mut recorder = RecordingPerform::new();
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut recorder, first);
+
+        let state = parser.save();
+        let mut restored = Parser::restore(state.clone());
+        assert_eq!(restored.save(), state);
+
+        recorder.clear();
+        restored.advance_slice(&mut recorder, second);
+
+        // The restored parser continues exactly like the original
+        let mut expected = RecordingPerform::new();
+        parser.advance_slice(&mut expected, second);
+        assert_eq!(recorder.events(), expected.events());
+
+        (state, describe(recorder.events()))
+    }
+
+    #[test]
+    fn restore_partial_csi() {
+        let (state, calls) = round_trip(b"\x1b[1;38:2:1", b"0;3 qx");
+        assert_eq!(state.state(), State::CsiParam);
+        assert_eq!(calls, vec!["csi_dispatch [[1], [38, 2, 10], [3]] [32] false 'q'", "print 'x'"]);
+    }
+
+    #[test]
+    fn restore_private_marker() {
+        let (_, calls) = round_trip(b"\x1b[?", b"25h");
+        assert_eq!(calls, vec!["csi_dispatch [[25]] [] false 'h' private '?'"]);
+
+        let (_, calls) = round_trip(b"\x1b[>1 ", b"q");
+        assert_eq!(calls, vec!["csi_dispatch [[1]] [32] false 'q' private '>'"]);
+    }
+
+    #[test]
+    fn restore_partial_strings() {
+        let (_, calls) = round_trip(b"\x1b]2;ti", b"tle\x07");
+        assert_eq!(calls, vec!["osc_dispatch [[50], [116, 105, 116, 108, 101]] true"]);
+
+        let (_, calls) = round_trip(b"\x1b_a;", b"b\x1b\\");
+        assert_eq!(calls[0], "apc_dispatch [97, 59, 98]");
+
+        // The hook was streamed before the state was saved
+        let (_, calls) = round_trip(b"\x1bP1$q", b"m\x1b\\");
+        assert_eq!(calls[..2], ["put 6d", "unhook"]);
+    }
+
+    #[test]
+    fn restore_partial_utf8() {
+        let (state, calls) = round_trip(b"\xe2\x94", b"\x80");
+        assert_eq!(state.state(), State::Utf8);
+        assert_eq!(calls, vec!["print '─'"]);
+    }
+
+    #[test]
+    fn restore_keeps_modes() {
+        let mut parser = Parser::new_vt52();
+        parser.set_c1_controls(true);
+
+        let restored = Parser::restore(parser.save());
+        assert_eq!(restored.mode(), parser.mode());
+        assert!(restored.c1_controls());
+    }
+}
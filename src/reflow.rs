@@ -0,0 +1,479 @@
+//! Lines of printed cells which can be re-flowed to a new width
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use {Cell, Params, Perform, SgrAttribute, SgrIter, State, C0};
+
+/// Distance of the tab stops
+const TAB_WIDTH: usize = 8;
+
+/// A line of a [`VtLineBuffer`]
+///
+/// A character two cells wide is stored in the first cell, and the second
+/// one holds `'\0'`, like in a [`ScreenBuffer`].
+///
+/// [`VtLineBuffer`]: struct.VtLineBuffer.html
+/// [`ScreenBuffer`]: struct.ScreenBuffer.html
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct VtLine {
+    pub cells: Vec<Cell>,
+
+    /// The line ended with a line feed, rather than wrapping at the end of
+    /// the line
+    pub hard_break: bool,
+}
+
+impl VtLine {
+    /// The characters of the line, without colors and attributes
+    #[must_use]
+    pub fn text(&self) -> String {
+        self.cells.iter().map(|cell| cell.ch).filter(|&c| c != '\0').collect()
+    }
+}
+
+/// A [`Perform`] which collects printed characters into lines `cols` cells
+/// wide before forwarding everything to `P`
+///
+/// Printing past the last column wraps to a new line, which is a soft wrap;
+/// LF, VT and FF end the line with a hard break. HT fills the line with
+/// spaces up to the next multiple of 8 and printed characters take the
+/// colors and attributes selected through SGR. Characters passed to `print`
+/// are one cell wide and those passed to `print_wide` take their width;
+/// those with a width of zero are dropped. The buffer only ever appends, so
+/// CR, BS, cursor movement and erasing are passed on to `P` without changing
+/// the lines.
+///
+/// [`reflow`] wraps the lines for a new width, joining soft wrapped lines
+/// and keeping the hard breaks; a resize event does the same. The lines are
+/// kept until they are cleared, including the line being printed to, which
+/// is the last one.
+///
+/// ```
+/// # use vte::{Parser, TextStripper, VtLineBuffer};
+/// let mut buffer = VtLineBuffer::new(TextStripper::new(), 4);
+/// Parser::new().advance_slice(&mut buffer, b"abcdef\r\ngh");
+/// let text = |buffer: &VtLineBuffer<_>| buffer.lines().iter().map(|line| line.text()).collect::<Vec<_>>();
+/// assert_eq!(text(&buffer), ["abcd", "ef", "gh"]);
+///
+/// buffer.reflow(3);
+/// assert_eq!(text(&buffer), ["abc", "def", "gh"]);
+/// assert!(!buffer.lines()[0].hard_break && buffer.lines()[1].hard_break);
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`reflow`]: struct.VtLineBuffer.html#method.reflow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VtLineBuffer<P: Perform> {
+    performer: P,
+    cols: u16,
+    lines: Vec<VtLine>,
+
+    /// Colors and attributes of printed characters
+    pen: Cell,
+}
+
+impl<P: Perform> VtLineBuffer<P> {
+    /// Wrap `performer`, wrapping lines at `cols` cells
+    ///
+    /// A width of zero is treated as one cell.
+    #[must_use]
+    pub fn new(performer: P, cols: u16) -> VtLineBuffer<P> {
+        VtLineBuffer { performer, cols: cols.max(1), lines: vec![VtLine::default()], pen: Cell::default() }
+    }
+
+    /// The width of the lines
+    #[inline]
+    #[must_use]
+    pub fn cols(&self) -> u16 {
+        self.cols
+    }
+
+    /// The lines, ending with the line being printed to
+    #[inline]
+    #[must_use]
+    pub fn lines(&self) -> &[VtLine] {
+        &self.lines
+    }
+
+    /// Remove all lines
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.lines.push(VtLine::default());
+    }
+
+    /// Wrap the lines at `new_cols` cells
+    ///
+    /// Lines which ended with a soft wrap are joined with the next one and
+    /// wrapped again, and hard breaks are kept, so reflowing back to the
+    /// old width restores the lines. A character two cells wide isn't split
+    /// across lines.
+    pub fn reflow(&mut self, new_cols: u16) {
+        self.cols = new_cols.max(1);
+
+        let mut lines = Vec::with_capacity(self.lines.len());
+        let mut logical = Vec::new();
+        for line in self.lines.drain(..) {
+            logical.extend(line.cells);
+            if line.hard_break {
+                wrap(&mut lines, &logical, self.cols.into(), true);
+                logical.clear();
+            }
+        }
+        wrap(&mut lines, &logical, self.cols.into(), false);
+        self.lines = lines;
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+
+    fn current(&mut self) -> &mut VtLine {
+        self.lines.last_mut().expect("there is always a current line")
+    }
+
+    /// Append `c`, `width` cells wide, wrapping first if it doesn't fit
+    fn write(&mut self, c: char, width: usize) {
+        if width == 0 {
+            return;
+        }
+
+        let cols = usize::from(self.cols);
+        let width = width.min(cols);
+        if self.current().cells.len() + width > cols {
+            self.lines.push(VtLine::default());
+        }
+
+        let pen = self.pen;
+        let cells = &mut self.current().cells;
+        cells.push(Cell { ch: c, ..pen });
+        cells.extend((1..width).map(|_| Cell { ch: '\0', ..pen }));
+    }
+
+    /// End the current line with a hard break
+    fn hard_break(&mut self) {
+        self.current().hard_break = true;
+        self.lines.push(VtLine::default());
+    }
+
+    /// Fill the line with spaces up to the next tab stop
+    fn tab(&mut self) {
+        let cols = usize::from(self.cols);
+        let pen = self.pen;
+        let cells = &mut self.current().cells;
+        let stop = ((cells.len() / TAB_WIDTH + 1) * TAB_WIDTH).min(cols);
+        while cells.len() < stop {
+            cells.push(Cell { ch: ' ', ..pen });
+        }
+    }
+}
+
+/// Append `cells` to `lines`, wrapped at `cols`, with a hard break after the
+/// last line if `hard_break` is set
+fn wrap(lines: &mut Vec<VtLine>, cells: &[Cell], cols: usize, hard_break: bool) {
+    let mut line = VtLine::default();
+    let mut start = 0;
+    while start < cells.len() {
+        // A wide character and the `'\0'` cells after it
+        let width = 1 + cells[start + 1..].iter().take_while(|cell| cell.ch == '\0').count();
+        if !line.cells.is_empty() && line.cells.len() + width > cols {
+            lines.push(line);
+            line = VtLine::default();
+        }
+
+        let kept = width.min(cols - line.cells.len());
+        line.cells.extend_from_slice(&cells[start..start + kept]);
+        start += width;
+    }
+
+    line.hard_break = hard_break;
+    lines.push(line);
+}
+
+impl<P: Perform> Perform for VtLineBuffer<P> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.write(c, 1);
+        self.performer.print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.write(c, cell_width.into());
+        self.performer.print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        self.performer.execute(byte);
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        if c0 == C0::Vt || c0 == C0::Ff {
+            self.hard_break();
+        }
+        self.performer.execute_c0(c0);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.performer.carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.hard_break();
+        self.performer.linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        self.performer.backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        VtLineBuffer::tab(self);
+        self.performer.tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.performer.bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.performer.hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.performer.put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.performer.unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.performer.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.performer.osc_dispatch(params, bell_terminated);
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.apc_dispatch(bytes);
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        if action == 'm' && !ignore && intermediates.is_empty() && private.is_none() {
+            if params.is_empty() {
+                self.pen.set_attribute(SgrAttribute::Reset);
+            }
+            for attribute in SgrIter::new(params) {
+                self.pen.set_attribute(attribute);
+            }
+        }
+        self.performer.csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.performer.esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.reflow(cols);
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.performer.on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.performer.error(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VtLineBuffer;
+    use test_utils::{RecordedEvent, RecordingPerform};
+    use {Attributes, NamedColor, Parser, Perform, TermColor};
+
+    fn track(cols: u16, bytes: &[u8]) -> VtLineBuffer<RecordingPerform> {
+        let mut buffer = VtLineBuffer::new(RecordingPerform::default(), cols);
+        Parser::new().advance_slice(&mut buffer, bytes);
+        buffer
+    }
+
+    fn text(buffer: &VtLineBuffer<RecordingPerform>) -> Vec<String> {
+        buffer.lines().iter().map(|line| line.text()).collect()
+    }
+
+    fn breaks(buffer: &VtLineBuffer<RecordingPerform>) -> Vec<bool> {
+        buffer.lines().iter().map(|line| line.hard_break).collect()
+    }
+
+    #[test]
+    fn hard_and_soft_breaks() {
+        let buffer = track(4, b"abcdefgh\r\nij\x0bk\x0c\tx");
+        assert_eq!(text(&buffer), ["abcd", "efgh", "ij", "k", "    ", "x"]);
+        assert_eq!(breaks(&buffer), [false, true, true, true, false, false]);
+        assert_eq!(text(&track(10, b"ab\tx\ty")), ["ab      x ", "y"]);
+
+        // The line is full, but only wraps when the next character is printed
+        let buffer = track(4, b"abcd");
+        assert_eq!(text(&buffer), ["abcd"]);
+        assert_eq!(breaks(&buffer), [false]);
+
+        assert_eq!(text(&track(4, b"")), [""]);
+        assert_eq!(text(&track(4, b"\n\n")), ["", "", ""]);
+    }
+
+    #[test]
+    fn reflow_80_to_40_and_back() {
+        let mut input = Vec::new();
+        for &c in b"abc" {
+            input.extend(vec![c; 80]);
+            input.extend(b"\r\n");
+        }
+        input.extend(vec![b'd'; 100]);
+
+        let mut buffer = track(80, &input);
+        let lines = buffer.lines().to_vec();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(breaks(&buffer), [true, true, true, false, false]);
+
+        buffer.reflow(40);
+        assert_eq!(buffer.cols(), 40);
+        assert_eq!(buffer.lines().len(), 9);
+        assert_eq!(breaks(&buffer), [false, true, false, true, false, true, false, false, false]);
+        assert_eq!(text(&buffer)[1], "a".repeat(40));
+        assert_eq!(text(&buffer)[8], "d".repeat(20));
+
+        buffer.reflow(80);
+        assert_eq!(buffer.lines(), &lines[..]);
+    }
+
+    #[test]
+    fn reflow_doubles_lines() {
+        let mut input = Vec::new();
+        for line in 0..10u8 {
+            input.extend(vec![b'a' + line; 80]);
+            input.push(b'\n');
+        }
+
+        let mut buffer = track(80, &input);
+        assert_eq!(buffer.lines().len(), 11);
+        buffer.reflow(40);
+        assert_eq!(buffer.lines().len(), 21);
+        assert_eq!(buffer.lines().iter().filter(|line| line.hard_break).count(), 10);
+    }
+
+    #[test]
+    fn reflow_joins_soft_wraps() {
+        let mut buffer = track(3, b"abcdefg\nhi");
+        assert_eq!(text(&buffer), ["abc", "def", "g", "hi"]);
+
+        buffer.reflow(5);
+        assert_eq!(text(&buffer), ["abcde", "fg", "hi"]);
+        assert_eq!(breaks(&buffer), [false, true, false]);
+
+        // Printing continues on the last line
+        Parser::new().advance_slice(&mut buffer, b"jklm");
+        assert_eq!(text(&buffer), ["abcde", "fg", "hijkl", "m"]);
+    }
+
+    #[test]
+    fn wide_characters() {
+        let mut buffer = track(3, b"a");
+        buffer.print_wide('\u{6f22}', 2);
+        buffer.print_wide('\u{301}', 0);
+        buffer.print_wide('\u{5b57}', 2);
+        assert_eq!(text(&buffer), ["a\u{6f22}", "\u{5b57}"]);
+        assert_eq!(buffer.lines()[0].cells[2].ch, '\0');
+
+        // Wide characters aren't split, and are cut to a single cell
+        buffer.reflow(2);
+        assert_eq!(text(&buffer), ["a", "\u{6f22}", "\u{5b57}"]);
+        buffer.reflow(1);
+        assert_eq!(text(&buffer), ["a", "\u{6f22}", "\u{5b57}"]);
+        assert_eq!(buffer.lines()[1].cells.len(), 1);
+    }
+
+    #[test]
+    fn attributes_and_forwarding() {
+        let mut buffer = track(10, b"\x1b[1;31ma\x1b[mb");
+        let cells = &buffer.lines()[0].cells;
+        assert_eq!(cells[0].fg, TermColor::Named(NamedColor::Red));
+        assert!(cells[0].attrs.contains(Attributes::BOLD));
+        assert_eq!(cells[1].fg, TermColor::Default);
+        let prints = buffer.inner().events().iter().filter(|event| matches!(event, RecordedEvent::Print(_)));
+        assert_eq!(prints.count(), 2);
+
+        buffer.resize_event(24, 1);
+        assert_eq!(buffer.cols(), 1);
+        assert_eq!(text(&buffer), ["a", "b"]);
+
+        buffer.clear();
+        assert_eq!(text(&buffer), [""]);
+    }
+}
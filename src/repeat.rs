@@ -0,0 +1,324 @@
+//! Expanding REP, which repeats the last printed character
+use {Params, Perform, State, C0};
+
+/// The most characters printed for a single REP
+pub const MAX_REPEAT: u16 = 32767;
+
+/// A [`Perform`] which expands REP (`CSI Ps b`) into printed characters
+/// before forwarding everything to `P`
+///
+/// REP prints the last graphic character `Ps` times, 1 if the parameter is
+/// missing. The character is passed to `print`, or to `print_wide` with its
+/// width if it was printed that way, so a wide character is repeated as a
+/// wide character. Characters with a width of zero, like combining marks,
+/// aren't repeated if they are passed to `print_wide`, or to `print` with
+/// the `unicode-width` feature; without it `print` can't tell them apart. A
+/// count of 0 and a REP before any character print nothing, and counts
+/// above [`MAX_REPEAT`] are clamped to it. REP isn't passed on; every other
+/// action is.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// # use vte::{Parser, RepeatTracker, TextStripper};
+/// let mut repeat = RepeatTracker::new(TextStripper::new());
+/// Parser::new().advance_slice(&mut repeat, b"ab\x1b[3b-\x1b[b");
+/// assert_eq!(repeat.inner().text(), "abbbb--");
+/// # }
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`MAX_REPEAT`]: constant.MAX_REPEAT.html
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RepeatTracker<P: Perform> {
+    performer: P,
+
+    /// The last character, with its width if it was passed to `print_wide`
+    last: Option<(char, Option<u8>)>,
+}
+
+impl<P: Perform> RepeatTracker<P> {
+    /// Wrap `performer`
+    #[inline]
+    #[must_use]
+    pub fn new(performer: P) -> RepeatTracker<P> {
+        RepeatTracker { performer, last: None }
+    }
+
+    /// The character REP would repeat, if any
+    #[inline]
+    #[must_use]
+    pub fn last_char(&self) -> Option<char> {
+        self.last.map(|(c, _)| c)
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+
+    /// Print the last character `count` times
+    fn repeat(&mut self, count: u16) {
+        let (c, width) = match self.last {
+            Some(last) => last,
+            None => return,
+        };
+
+        for _ in 0..count.min(MAX_REPEAT) {
+            match width {
+                Some(width) => self.performer.print_wide(c, width),
+                None => self.performer.print(c),
+            }
+        }
+    }
+}
+
+impl<P: Perform> Perform for RepeatTracker<P> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        if !is_zero_width(c) {
+            self.last = Some((c, None));
+        }
+        self.performer.print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        if cell_width > 0 {
+            self.last = Some((c, Some(cell_width)));
+        }
+        self.performer.print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        self.performer.execute(byte);
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        self.performer.execute_c0(c0);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.performer.carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.performer.linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        self.performer.backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        self.performer.tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.performer.bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.performer.hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.performer.put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.performer.unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.performer.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.performer.osc_dispatch(params, bell_terminated);
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.apc_dispatch(bytes);
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        if action == 'b' && !ignore && intermediates.is_empty() && private.is_none() {
+            self.repeat(params.get_or_default(0, 0, 1));
+        } else {
+            self.performer.csi_dispatch(params, intermediates, ignore, action, private);
+        }
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.performer.esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.performer.on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.performer.error(byte);
+    }
+}
+
+/// Whether `c` takes up no cells
+#[cfg(feature = "unicode-width")]
+fn is_zero_width(c: char) -> bool {
+    unicode_width::UnicodeWidthChar::width(c) == Some(0)
+}
+
+/// Zero width characters can't be recognized without `unicode-width`
+#[cfg(not(feature = "unicode-width"))]
+fn is_zero_width(_c: char) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RepeatTracker, MAX_REPEAT};
+    use test_utils::{RecordedEvent, RecordingPerform};
+    use tests::describe;
+    use {Parser, Perform};
+
+    fn track(bytes: &[u8]) -> RepeatTracker<RecordingPerform> {
+        let mut repeat = RepeatTracker::new(RecordingPerform::default());
+        Parser::new().advance_slice(&mut repeat, bytes);
+        repeat
+    }
+
+    fn printed(repeat: &RepeatTracker<RecordingPerform>) -> String {
+        repeat.inner().events().iter()
+            .filter_map(|event| match *event {
+                RecordedEvent::Print(c) => Some(c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn csi_calls(repeat: &RepeatTracker<RecordingPerform>) -> Vec<String> {
+        describe(repeat.inner().events()).into_iter().filter(|call| call.starts_with("csi_dispatch")).collect()
+    }
+
+    #[test]
+    fn repeats() {
+        let repeat = track(b"ab\x1b[3bc\x1b[b\r\n\x1b[1m\x1b[2b");
+        assert_eq!(printed(&repeat), "abbbbcccc");
+        assert_eq!(repeat.last_char(), Some('c'));
+
+        // REP itself isn't passed on, but other sequences ending in `b` are
+        assert_eq!(csi_calls(&repeat), ["csi_dispatch [[1]] [] false 'm'"]);
+        let repeat = track(b"a\x1b[?2b\x1b[2 b");
+        assert_eq!(printed(&repeat), "a");
+        assert_eq!(csi_calls(&repeat).len(), 2);
+    }
+
+    #[test]
+    fn wide_characters() {
+        let mut repeat = RepeatTracker::new(RecordingPerform::default());
+        repeat.print_wide('\u{6f22}', 2);
+        repeat.print_wide('\u{301}', 0);
+        Parser::new().advance_slice(&mut repeat, b"\x1b[2b");
+        assert_eq!(repeat.last_char(), Some('\u{6f22}'));
+        assert_eq!(repeat.inner().events().iter().filter(|event| **event == RecordedEvent::Print('\u{6f22}')).count(), 3);
+
+        // Without wide printing, CJK characters are repeated through `print`
+        assert_eq!(printed(&track("\u{5b57}\x1b[2b".as_bytes())), "\u{5b57}\u{5b57}\u{5b57}");
+    }
+
+    #[cfg(feature = "unicode-width")]
+    #[test]
+    fn combining_marks() {
+        let repeat = track("e\u{301}\x1b[2b".as_bytes());
+        assert_eq!(printed(&repeat), "e\u{301}ee");
+        assert_eq!(repeat.last_char(), Some('e'));
+
+        // Marks passed to `print` are recognized too
+        let mut repeat = RepeatTracker::new(RecordingPerform::default());
+        repeat.print('\u{5d0}');
+        repeat.print('\u{5b8}');
+        assert_eq!(repeat.last_char(), Some('\u{5d0}'));
+
+        // A mark before any character leaves nothing to repeat
+        assert_eq!(printed(&track("\u{64b}\u{200d}\x1b[3b".as_bytes())), "\u{64b}\u{200d}");
+    }
+
+    #[test]
+    fn nothing_to_repeat() {
+        assert_eq!(printed(&track(b"a\x1b[0b")), "a");
+        assert_eq!(printed(&track(b"\x1b[5b")), "");
+        assert_eq!(track(b"\x1b[5b").last_char(), None);
+    }
+
+    #[test]
+    fn clamped_count() {
+        assert_eq!(printed(&track(b"x\x1b[65535b")).len(), 1 + MAX_REPEAT as usize);
+        assert_eq!(printed(&track(b"x\x1b[32768b")).len(), 1 + MAX_REPEAT as usize);
+        assert_eq!(printed(&track(b"x\x1b[32767b")).len(), 1 + MAX_REPEAT as usize);
+    }
+}
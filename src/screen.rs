@@ -0,0 +1,779 @@
+//! A grid of character cells, drawn by terminal output
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use {Params, Perform, SgrAttribute, SgrIter, TermColor};
+
+/// Distance of the tab stops
+const TAB_WIDTH: usize = 8;
+
+/// Text attributes of a [`Cell`]
+///
+/// [`Cell`]: struct.Cell.html
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Attributes(u8);
+
+impl Attributes {
+    pub const BOLD: Attributes = Attributes(1);
+    pub const DIM: Attributes = Attributes(1 << 1);
+    pub const ITALIC: Attributes = Attributes(1 << 2);
+
+    /// Any style of underline
+    pub const UNDERLINE: Attributes = Attributes(1 << 3);
+    pub const BLINK: Attributes = Attributes(1 << 4);
+    pub const INVERSE: Attributes = Attributes(1 << 5);
+    pub const INVISIBLE: Attributes = Attributes(1 << 6);
+    pub const STRIKETHROUGH: Attributes = Attributes(1 << 7);
+
+    /// No attributes
+    #[inline]
+    #[must_use]
+    pub fn empty() -> Attributes {
+        Attributes(0)
+    }
+
+    /// No attribute is set
+    #[inline]
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// All attributes of `other` are set
+    #[inline]
+    #[must_use]
+    pub fn contains(self, other: Attributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Set the attributes of `other`
+    #[inline]
+    pub fn insert(&mut self, other: Attributes) {
+        self.0 |= other.0;
+    }
+
+    /// Clear the attributes of `other`
+    #[inline]
+    pub fn remove(&mut self, other: Attributes) {
+        self.0 &= !other.0;
+    }
+}
+
+/// A single cell of a [`ScreenBuffer`]
+///
+/// A character two cells wide is stored in the first cell, and the second
+/// one holds `'\0'`.
+///
+/// [`ScreenBuffer`]: struct.ScreenBuffer.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: TermColor,
+    pub bg: TermColor,
+    pub attrs: Attributes,
+}
+
+impl Default for Cell {
+    /// A space in the default colors and without attributes
+    fn default() -> Cell {
+        Cell { ch: ' ', fg: TermColor::Default, bg: TermColor::Default, attrs: Attributes::empty() }
+    }
+}
+
+impl Cell {
+    /// Apply an SGR attribute to the colors and attributes of the cell
+    pub(crate) fn set_attribute(&mut self, attribute: SgrAttribute) {
+        let pen = self;
+        match attribute {
+            SgrAttribute::Reset => *pen = Cell::default(),
+            SgrAttribute::Bold => pen.attrs.insert(Attributes::BOLD),
+            SgrAttribute::Dim => pen.attrs.insert(Attributes::DIM),
+            SgrAttribute::Italic => pen.attrs.insert(Attributes::ITALIC),
+            SgrAttribute::Underline(_) => pen.attrs.insert(Attributes::UNDERLINE),
+            SgrAttribute::Blink => pen.attrs.insert(Attributes::BLINK),
+            SgrAttribute::Inverse => pen.attrs.insert(Attributes::INVERSE),
+            SgrAttribute::Invisible => pen.attrs.insert(Attributes::INVISIBLE),
+            SgrAttribute::StrikeThrough => pen.attrs.insert(Attributes::STRIKETHROUGH),
+            SgrAttribute::NormalIntensity => {
+                pen.attrs.remove(Attributes::BOLD);
+                pen.attrs.remove(Attributes::DIM);
+            },
+            SgrAttribute::NoItalic => pen.attrs.remove(Attributes::ITALIC),
+            SgrAttribute::NoUnderline => pen.attrs.remove(Attributes::UNDERLINE),
+            SgrAttribute::NoBlink => pen.attrs.remove(Attributes::BLINK),
+            SgrAttribute::NoInverse => pen.attrs.remove(Attributes::INVERSE),
+            SgrAttribute::NoInvisible => pen.attrs.remove(Attributes::INVISIBLE),
+            SgrAttribute::NoStrikeThrough => pen.attrs.remove(Attributes::STRIKETHROUGH),
+            SgrAttribute::Foreground(color) => pen.fg = color,
+            SgrAttribute::Background(color) => pen.bg = color,
+            SgrAttribute::UnderlineColor(_) => (),
+        }
+    }
+}
+
+/// The lines scrolled off the top of a [`ScreenBuffer`]
+///
+/// At most `max_lines` lines are kept; when another one is pushed, the
+/// oldest is dropped.
+///
+/// ```
+/// # use vte::{Cell, ScrollbackBuffer};
+/// let line = |c| vec![Cell { ch: c, ..Cell::default() }];
+/// let mut scrollback = ScrollbackBuffer::new(2);
+/// scrollback.push_line(line('a'));
+/// scrollback.push_line(line('b'));
+/// assert!(!scrollback.capacity_exceeded());
+///
+/// scrollback.push_line(line('c'));
+/// assert!(scrollback.capacity_exceeded());
+/// assert_eq!(scrollback.scrollback().map(|line| line[0].ch).collect::<String>(), "bc");
+/// ```
+///
+/// [`ScreenBuffer`]: struct.ScreenBuffer.html
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ScrollbackBuffer {
+    max_lines: usize,
+    lines: VecDeque<Vec<Cell>>,
+
+    /// A line was dropped because the buffer was full
+    exceeded: bool,
+}
+
+impl ScrollbackBuffer {
+    /// Create an empty buffer keeping up to `max_lines` lines
+    #[must_use]
+    pub fn new(max_lines: usize) -> ScrollbackBuffer {
+        ScrollbackBuffer { max_lines, lines: VecDeque::new(), exceeded: false }
+    }
+
+    /// The maximum number of lines
+    #[inline]
+    #[must_use]
+    pub fn max_lines(&self) -> usize {
+        self.max_lines
+    }
+
+    /// The number of lines
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns `true` if there are no lines
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Add a line after the others, dropping the oldest one if the buffer is
+    /// full
+    pub fn push_line(&mut self, line: Vec<Cell>) {
+        if self.lines.len() == self.max_lines {
+            self.exceeded = true;
+            if self.lines.pop_front().is_none() {
+                return;
+            }
+        }
+        self.lines.push_back(line);
+    }
+
+    /// The lines, from the oldest to the newest
+    pub fn scrollback(&self) -> impl Iterator<Item = &[Cell]> + '_ {
+        self.lines.iter().map(|line| &line[..])
+    }
+
+    /// Returns `true` if lines were dropped because the buffer was full
+    #[inline]
+    #[must_use]
+    pub fn capacity_exceeded(&self) -> bool {
+        self.exceeded
+    }
+
+    /// Remove all lines
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.exceeded = false;
+    }
+}
+
+/// A [`Perform`] which draws terminal output into a grid of cells
+///
+/// This is the screen of a simple terminal emulator, `rows` by `cols` cells
+/// in size:
+///
+/// * Printed characters are drawn with the colors and attributes selected
+///   through SGR, wrapping at the end of the line. Characters passed to
+///   `print_wide` take their width; those with a width of zero are dropped.
+/// * CR, LF, VT, FF, BS and HT, which moves to the next multiple of 8.
+/// * CUU, CUD, CUF, CUB, CNL, CPL, CHA, HPA, VPA, CUP and HVP move the
+///   cursor, clamped to the screen.
+/// * ED and EL erase the screen or the line, filling it with spaces in the
+///   current background color. ED 3 clears the scrollback.
+/// * IND, NEL and RI. A line feed on the last row scrolls the screen up,
+///   moving the first row to the [`ScrollbackBuffer`], and RI on the first
+///   row scrolls it down.
+/// * A full reset, `ESC c`, clears the screen and the attributes, but not
+///   the scrollback.
+///
+/// Positions are counted from zero. A resize event changes the size of the
+/// screen, keeping the content at its top left. A screen created with `new`
+/// keeps no scrollback; use [`with_scrollback`] to keep the rows scrolled
+/// off the screen.
+///
+/// ```
+/// # use vte::{Attributes, NamedColor, Parser, ScreenBuffer, TermColor};
+/// let mut screen = ScreenBuffer::new(3, 10);
+/// Parser::new().advance_slice(&mut screen, b"hello\r\n\x1b[1;31mworld\x1b[1;3H\x1b[K");
+/// assert_eq!(screen.render_to_string(), "he\nworld");
+///
+/// let cell = screen.cell_at(1, 0);
+/// assert_eq!(cell.ch, 'w');
+/// assert_eq!(cell.fg, TermColor::Named(NamedColor::Red));
+/// assert!(cell.attrs.contains(Attributes::BOLD));
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`ScrollbackBuffer`]: struct.ScrollbackBuffer.html
+/// [`with_scrollback`]: struct.ScreenBuffer.html#method.with_scrollback
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScreenBuffer {
+    rows: usize,
+    cols: usize,
+    cells: Vec<Cell>,
+    cursor_row: usize,
+    cursor_col: usize,
+
+    /// The last column has been printed to, so the next character goes to
+    /// the next line
+    wrap_pending: bool,
+
+    /// Colors and attributes of printed characters
+    pen: Cell,
+    scrollback: ScrollbackBuffer,
+}
+
+impl ScreenBuffer {
+    /// Create an empty screen of `rows` by `cols` cells, with the cursor at
+    /// the top left
+    ///
+    /// A screen without rows or columns is treated as one cell.
+    #[must_use]
+    pub fn new(rows: usize, cols: usize) -> ScreenBuffer {
+        let (rows, cols) = (rows.max(1), cols.max(1));
+        let mut cells = Vec::new();
+        cells.resize(rows * cols, Cell::default());
+
+        ScreenBuffer {
+            rows,
+            cols,
+            cells,
+            cursor_row: 0,
+            cursor_col: 0,
+            wrap_pending: false,
+            pen: Cell::default(),
+            scrollback: ScrollbackBuffer::new(0),
+        }
+    }
+
+    /// Create an empty screen like [`new`] which keeps up to `max_lines`
+    /// rows scrolled off its top
+    ///
+    /// ```
+    /// # use vte::{Parser, ScreenBuffer};
+    /// let mut screen = ScreenBuffer::with_scrollback(2, 10, 100);
+    /// Parser::new().advance_slice(&mut screen, b"one\r\ntwo\r\nthree");
+    /// assert_eq!(screen.render_to_string(), "two\nthree");
+    ///
+    /// let first = screen.scrollback().scrollback().next().unwrap();
+    /// assert_eq!(first[0].ch, 'o');
+    /// ```
+    ///
+    /// [`new`]: struct.ScreenBuffer.html#method.new
+    #[must_use]
+    pub fn with_scrollback(rows: usize, cols: usize, max_lines: usize) -> ScreenBuffer {
+        ScreenBuffer { scrollback: ScrollbackBuffer::new(max_lines), ..ScreenBuffer::new(rows, cols) }
+    }
+
+    /// The rows scrolled off the top of the screen
+    #[inline]
+    #[must_use]
+    pub fn scrollback(&self) -> &ScrollbackBuffer {
+        &self.scrollback
+    }
+
+    /// The number of rows and columns
+    #[inline]
+    #[must_use]
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// The row and column of the cursor
+    #[inline]
+    #[must_use]
+    pub fn cursor_position(&self) -> (usize, usize) {
+        (self.cursor_row, self.cursor_col)
+    }
+
+    /// The cell at `row` and `col`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is outside of the screen.
+    #[inline]
+    #[must_use]
+    pub fn cell_at(&self, row: usize, col: usize) -> &Cell {
+        assert!(row < self.rows && col < self.cols, "cell ({}, {}) is outside of the screen", row, col);
+        &self.cells[row * self.cols + col]
+    }
+
+    /// The cells of `row`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row is outside of the screen.
+    #[inline]
+    #[must_use]
+    pub fn line(&self, row: usize) -> &[Cell] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// The text on the screen, without colors and attributes
+    ///
+    /// Rows are separated by `\n`. Spaces at the end of rows and empty rows
+    /// at the end of the screen are left out.
+    #[must_use]
+    pub fn render_to_string(&self) -> String {
+        let mut text = String::new();
+        let mut empty_rows = 0;
+        for row in 0..self.rows {
+            let line = self.line(row);
+            let len = line.iter().rposition(|cell| cell.ch != ' ' && cell.ch != '\0').map_or(0, |i| i + 1);
+            if len == 0 {
+                empty_rows += 1;
+                continue;
+            }
+
+            // Separate this row from the previous one and the empty rows
+            // between them
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            for _ in 0..empty_rows {
+                text.push('\n');
+            }
+            empty_rows = 0;
+
+            text.extend(line[..len].iter().map(|cell| cell.ch).filter(|&c| c != '\0'));
+        }
+        text
+    }
+
+    /// Change the size of the screen
+    ///
+    /// The content is kept at the top left, cut off or padded with empty
+    /// cells, and the cursor is clamped to the new size. The scrollback is
+    /// kept as it is.
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        let scrollback = mem::take(&mut self.scrollback);
+        let mut screen = ScreenBuffer { pen: self.pen, scrollback, ..ScreenBuffer::new(rows, cols) };
+        let copied = self.cols.min(screen.cols);
+        for row in 0..self.rows.min(screen.rows) {
+            let start = row * screen.cols;
+            screen.cells[start..start + copied].copy_from_slice(&self.line(row)[..copied]);
+        }
+        screen.move_to(self.cursor_row, self.cursor_col);
+        *self = screen;
+    }
+
+    /// Move the cursor to `row` and `col`, clamped to the screen
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.cursor_row = row.min(self.rows - 1);
+        self.cursor_col = col.min(self.cols - 1);
+        self.wrap_pending = false;
+    }
+
+    fn move_to_col(&mut self, col: usize) {
+        self.move_to(self.cursor_row, col);
+    }
+
+    /// An empty cell in the current background color
+    fn blank(&self) -> Cell {
+        Cell { bg: self.pen.bg, ..Cell::default() }
+    }
+
+    /// Fill the cells from `start` to `end`, counted over the whole screen,
+    /// with blanks
+    fn erase(&mut self, start: usize, end: usize) {
+        let blank = self.blank();
+        for cell in &mut self.cells[start..end] {
+            *cell = blank;
+        }
+        self.wrap_pending = false;
+    }
+
+    /// IND: move down, scrolling up on the last row
+    fn index(&mut self) {
+        if self.cursor_row + 1 == self.rows {
+            if self.scrollback.max_lines() > 0 {
+                self.scrollback.push_line(self.line(0).to_vec());
+            }
+
+            let len = self.cells.len();
+            self.cells.copy_within(self.cols.., 0);
+            self.erase(len - self.cols, len);
+        } else {
+            self.move_to(self.cursor_row + 1, self.cursor_col);
+        }
+    }
+
+    /// RI: move up, scrolling down on the first row
+    fn reverse_index(&mut self) {
+        if self.cursor_row == 0 {
+            let len = self.cells.len();
+            self.cells.copy_within(..len - self.cols, self.cols);
+            self.erase(0, self.cols);
+        } else {
+            self.move_to(self.cursor_row - 1, self.cursor_col);
+        }
+    }
+
+    /// Draw `c` at the cursor and move past it
+    fn write(&mut self, c: char, width: usize) {
+        if width == 0 {
+            return;
+        }
+
+        // Characters which don't fit on the rest of the line wrap too
+        let width = width.min(self.cols);
+        if self.wrap_pending || self.cursor_col + width > self.cols {
+            self.index();
+            self.move_to_col(0);
+        }
+
+        let start = self.cursor_row * self.cols + self.cursor_col;
+        self.cells[start] = Cell { ch: c, ..self.pen };
+        for cell in &mut self.cells[start + 1..start + width] {
+            *cell = Cell { ch: '\0', ..self.pen };
+        }
+
+        let next = self.cursor_col + width;
+        if next == self.cols {
+            self.cursor_col = self.cols - 1;
+            self.wrap_pending = true;
+        } else {
+            self.cursor_col = next;
+        }
+    }
+
+    /// ED: erase after the cursor, before it or everything
+    fn erase_display(&mut self, mode: u16) {
+        let cursor = self.cursor_row * self.cols + self.cursor_col;
+        match mode {
+            0 => self.erase(cursor, self.cells.len()),
+            1 => self.erase(0, cursor + 1),
+            2 => self.erase(0, self.cells.len()),
+            3 => self.scrollback.clear(),
+            _ => (),
+        }
+    }
+
+    /// EL: erase after the cursor, before it or the whole line
+    fn erase_line(&mut self, mode: u16) {
+        let line = self.cursor_row * self.cols;
+        let cursor = line + self.cursor_col;
+        match mode {
+            0 => self.erase(cursor, line + self.cols),
+            1 => self.erase(line, cursor + 1),
+            2 => self.erase(line, line + self.cols),
+            _ => (),
+        }
+    }
+}
+
+impl Perform for ScreenBuffer {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.write(c, 1);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.write(c, cell_width.into());
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            0x0a..=0x0c => self.index(),
+            0x0d => self.move_to_col(0),
+            0x08 => {
+                let col = self.cursor_col.saturating_sub(1);
+                self.move_to_col(col);
+            },
+            0x09 => {
+                let col = (self.cursor_col / TAB_WIDTH + 1) * TAB_WIDTH;
+                self.move_to_col(col);
+            },
+            // IND and NEL as C1 controls
+            0x84 => self.index(),
+            0x85 => {
+                self.index();
+                self.move_to_col(0);
+            },
+            0x8d => self.reverse_index(),
+            _ => (),
+        }
+    }
+
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        if ignore || !intermediates.is_empty() || private.is_some() {
+            return;
+        }
+
+        let arg = |index: usize| usize::from(params.get_or_default(index, 0, 1).max(1));
+        match action {
+            'A' => self.move_to(self.cursor_row.saturating_sub(arg(0)), self.cursor_col),
+            'B' | 'e' => self.move_to(self.cursor_row.saturating_add(arg(0)), self.cursor_col),
+            'C' | 'a' => self.move_to_col(self.cursor_col.saturating_add(arg(0))),
+            'D' => self.move_to_col(self.cursor_col.saturating_sub(arg(0))),
+            'E' => self.move_to(self.cursor_row.saturating_add(arg(0)), 0),
+            'F' => self.move_to(self.cursor_row.saturating_sub(arg(0)), 0),
+            'G' | '`' => self.move_to_col(arg(0) - 1),
+            'd' => self.move_to(arg(0) - 1, self.cursor_col),
+            'H' | 'f' => self.move_to(arg(0) - 1, arg(1) - 1),
+            'J' => self.erase_display(params.get_or_default(0, 0, 0)),
+            'K' => self.erase_line(params.get_or_default(0, 0, 0)),
+            'm' if params.is_empty() => self.pen.set_attribute(SgrAttribute::Reset),
+            'm' => {
+                for attribute in SgrIter::new(params) {
+                    self.pen.set_attribute(attribute);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn esc_dispatch(&mut self, _params: &Params, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if !intermediates.is_empty() {
+            return;
+        }
+
+        match byte {
+            b'D' => self.index(),
+            b'E' => {
+                self.index();
+                self.move_to_col(0);
+            },
+            b'M' => self.reverse_index(),
+            b'c' => {
+                let scrollback = mem::take(&mut self.scrollback);
+                *self = ScreenBuffer { scrollback, ..ScreenBuffer::new(self.rows, self.cols) };
+            },
+            _ => (),
+        }
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.resize(rows.into(), cols.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Attributes, Cell, ScreenBuffer, ScrollbackBuffer};
+    use {NamedColor, Parser, Perform, TermColor};
+
+    fn draw(rows: usize, cols: usize, bytes: &[u8]) -> ScreenBuffer {
+        let mut screen = ScreenBuffer::new(rows, cols);
+        Parser::new().advance_slice(&mut screen, bytes);
+        screen
+    }
+
+    #[test]
+    fn printing_and_wrapping() {
+        let screen = draw(3, 4, b"abcd");
+        assert_eq!(screen.render_to_string(), "abcd");
+        assert_eq!(screen.cursor_position(), (0, 3));
+
+        let screen = draw(3, 4, b"abcdef\r\ngh\tx");
+        assert_eq!(screen.render_to_string(), "abcd\nef\ngh x");
+        assert_eq!(screen.cursor_position(), (2, 3));
+        assert_eq!(screen.cell_at(2, 2).ch, ' ');
+
+        // Overwriting and backspace
+        let screen = draw(3, 10, b"abc\x08\x08x\rY");
+        assert_eq!(screen.render_to_string(), "Yxc");
+
+        assert_eq!(draw(3, 10, b"").render_to_string(), "");
+        assert_eq!(draw(3, 10, b"\n\nc\x1b[Ha").render_to_string(), "a\n\nc");
+    }
+
+    #[test]
+    fn cursor_movement() {
+        let screen = draw(5, 10, b"\x1b[2;3Ha\x1b[2Bb\x1b[3Dc\x1b[Ad\x1b[4Ce");
+        assert_eq!(screen.render_to_string(), "\n  a\n  d    e\n c b");
+        assert_eq!(screen.cursor_position(), (2, 8));
+
+        let screen = draw(5, 10, b"\x1b[100;100Hz\x1b[8Gy\x1b[2dx\x1b[Ew\x1b[Fv");
+        assert_eq!(screen.render_to_string(), "\nv       x\nw\n\n       y z");
+        assert_eq!(draw(5, 10, b"\x1b[100A\x1b[100Dq").cell_at(0, 0).ch, 'q');
+    }
+
+    #[test]
+    fn erasing() {
+        let fill = b"abcde\r\nfghij\r\nklmno\x1b[2;3H";
+        let erase = |sequence: &[u8]| {
+            let mut screen = draw(3, 5, fill);
+            Parser::new().advance_slice(&mut screen, sequence);
+            screen.render_to_string()
+        };
+
+        assert_eq!(erase(b"\x1b[J"), "abcde\nfg");
+        assert_eq!(erase(b"\x1b[1J"), "\n   ij\nklmno");
+        assert_eq!(erase(b"\x1b[2J"), "");
+        assert_eq!(erase(b"\x1b[K"), "abcde\nfg\nklmno");
+        assert_eq!(erase(b"\x1b[1K"), "abcde\n   ij\nklmno");
+        assert_eq!(erase(b"\x1b[2K"), "abcde\n\nklmno");
+
+        // Erased cells take the background color, but not the other
+        // attributes
+        let screen = draw(3, 5, b"\x1b[1;44mab\x1b[D\x1b[K");
+        assert_eq!(screen.cell_at(0, 0), &Cell {
+            ch: 'a',
+            bg: TermColor::Named(NamedColor::Blue),
+            attrs: Attributes::BOLD,
+            ..Cell::default()
+        });
+        assert_eq!(screen.cell_at(0, 1), &Cell { bg: TermColor::Named(NamedColor::Blue), ..Cell::default() });
+    }
+
+    #[test]
+    fn sgr() {
+        let screen = draw(2, 10, b"\x1b[1;4;38;5;208;48;2;1;2;3ma\x1b[22;39mb\x1b[7;49mc\x1b[md");
+        let cell = screen.cell_at(0, 0);
+        assert_eq!(cell.fg, TermColor::Indexed(208));
+        assert_eq!(cell.bg, TermColor::Rgb(1, 2, 3));
+        assert!(cell.attrs.contains(Attributes::BOLD));
+        assert!(cell.attrs.contains(Attributes::UNDERLINE));
+
+        let cell = screen.cell_at(0, 1);
+        assert_eq!(cell.fg, TermColor::Default);
+        assert_eq!(cell.attrs, Attributes::UNDERLINE);
+
+        let cell = screen.cell_at(0, 2);
+        assert_eq!(cell.bg, TermColor::Default);
+        assert!(cell.attrs.contains(Attributes::INVERSE));
+        assert_eq!(screen.cell_at(0, 3), &Cell { ch: 'd', ..Cell::default() });
+    }
+
+    #[test]
+    fn scrolling() {
+        let screen = draw(3, 5, b"1\n2\n3\n4\r\n5");
+        assert_eq!(screen.render_to_string(), "  3\n   4\n5");
+
+        let screen = draw(3, 5, b"1\r\n2\r\n3\x1b[H\x1bMa\x1bD\x1bEb");
+        assert_eq!(screen.render_to_string(), "a\n1\nb");
+        assert_eq!(draw(2, 3, b"abcdefg").render_to_string(), "def\ng");
+    }
+
+    #[test]
+    fn wide_characters() {
+        let mut screen = draw(2, 5, b"ab");
+        screen.print_wide('\u{6f22}', 2);
+        screen.print_wide('\u{301}', 0);
+        screen.print_wide('\u{5b57}', 2);
+        assert_eq!(screen.render_to_string(), "ab\u{6f22}\n\u{5b57}");
+        assert_eq!(screen.cell_at(0, 3).ch, '\0');
+        assert_eq!(screen.cursor_position(), (1, 2));
+    }
+
+    #[test]
+    fn reset_and_resize() {
+        let mut screen = draw(3, 5, b"\x1b[31mabcde\r\nfg");
+        screen.resize(2, 3);
+        assert_eq!(screen.size(), (2, 3));
+        assert_eq!(screen.render_to_string(), "abc\nfg");
+        assert_eq!(screen.cursor_position(), (1, 2));
+
+        screen.resize_event(4, 6);
+        Parser::new().advance_slice(&mut screen, b"h");
+        assert_eq!(screen.render_to_string(), "abc\nfgh");
+        assert_eq!(screen.cell_at(1, 2).fg, TermColor::Named(NamedColor::Red));
+
+        Parser::new().advance_slice(&mut screen, b"\x1bcx");
+        assert_eq!(screen.render_to_string(), "x");
+        assert_eq!(screen.cell_at(0, 0), &Cell { ch: 'x', ..Cell::default() });
+    }
+
+    fn text(line: &[Cell]) -> String {
+        line.iter().map(|cell| cell.ch).collect::<String>().trim_end().into()
+    }
+
+    #[test]
+    fn scrollback_buffer() {
+        let mut scrollback = ScrollbackBuffer::new(3);
+        assert!(scrollback.is_empty());
+        for c in "abcde".chars() {
+            scrollback.push_line(vec![Cell { ch: c, ..Cell::default() }]);
+            assert!(scrollback.len() <= 3);
+        }
+        assert_eq!(scrollback.len(), 3);
+        assert!(scrollback.capacity_exceeded());
+        assert_eq!(scrollback.scrollback().map(text).collect::<Vec<_>>(), ["c", "d", "e"]);
+
+        scrollback.clear();
+        assert!(scrollback.is_empty());
+        assert!(!scrollback.capacity_exceeded());
+
+        // Without lines, every pushed line is dropped
+        let mut scrollback = ScrollbackBuffer::new(0);
+        scrollback.push_line(vec![Cell::default()]);
+        assert!(scrollback.is_empty());
+        assert!(scrollback.capacity_exceeded());
+    }
+
+    #[test]
+    fn scrolling_into_scrollback() {
+        let mut screen = ScreenBuffer::with_scrollback(2, 4, 3);
+        Parser::new().advance_slice(&mut screen, b"1\r\n2\r\n3\r\n4");
+        assert_eq!(screen.render_to_string(), "3\n4");
+        assert_eq!(screen.scrollback().scrollback().map(text).collect::<Vec<_>>(), ["1", "2"]);
+        assert!(!screen.scrollback().capacity_exceeded());
+
+        // The oldest lines are evicted first
+        Parser::new().advance_slice(&mut screen, b"\r\n5\r\n6abcdefg");
+        assert_eq!(screen.render_to_string(), "6abc\ndefg");
+        assert_eq!(screen.scrollback().scrollback().map(text).collect::<Vec<_>>(), ["3", "4", "5"]);
+        assert_eq!(screen.scrollback().len(), 3);
+        assert!(screen.scrollback().capacity_exceeded());
+
+        // Scrolling down, resizing and resetting keep the scrollback
+        Parser::new().advance_slice(&mut screen, b"\x1b[H\x1bM\x1bc");
+        screen.resize(3, 10);
+        assert_eq!(screen.scrollback().len(), 3);
+
+        Parser::new().advance_slice(&mut screen, b"\x1b[3J");
+        assert!(screen.scrollback().is_empty());
+        assert_eq!(screen.scrollback().max_lines(), 3);
+
+        // Without scrollback nothing is kept
+        assert!(draw(2, 4, b"1\n2\n3\n").scrollback().is_empty());
+    }
+}
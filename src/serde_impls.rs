@@ -0,0 +1,341 @@
+//! Serde support for parser types
+//!
+//! Byte strings are encoded as base64 strings for human-readable formats and
+//! as plain bytes otherwise.
+use core::fmt;
+
+#[cfg(any(feature = "alloc", test))]
+use alloc::string::String;
+#[cfg(any(feature = "alloc", test))]
+use alloc::vec::Vec;
+
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use Params;
+
+impl Serialize for Params {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Params {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Params, D::Error> {
+        deserializer.deserialize_seq(ParamsVisitor)
+    }
+}
+
+/// Parameters which don't fit are dropped, like they are by the parser
+struct ParamsVisitor;
+
+impl<'de> Visitor<'de> for ParamsVisitor {
+    type Value = Params;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of parameters")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Params, A::Error> {
+        let mut params = Params::new();
+        while seq.next_element_seed(ParamSeed(&mut params))?.is_some() {}
+        Ok(params)
+    }
+}
+
+/// Deserialize a single parameter and its subparameters into `Params`
+struct ParamSeed<'a>(&'a mut Params);
+
+impl<'de, 'a> DeserializeSeed<'de> for ParamSeed<'a> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ParamSeed<'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a non-empty list of parameter values")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        match seq.next_element()? {
+            Some(value) => self.0.push(value),
+            None => return Err(de::Error::invalid_length(0, &self)),
+        }
+
+        while let Some(value) = seq.next_element()? {
+            self.0.extend(value);
+        }
+
+        Ok(())
+    }
+}
+
+/// `char` as its codepoint
+#[cfg(any(feature = "alloc", test))]
+pub mod codepoint {
+    use serde::de::{self, Unexpected};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(c: &char, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(*c as u32)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<char, D::Error> {
+        let point = u32::deserialize(deserializer)?;
+        ::core::char::from_u32(point).ok_or_else(|| {
+            de::Error::invalid_value(Unexpected::Unsigned(point.into()), &"a unicode codepoint")
+        })
+    }
+}
+
+/// Byte string, encoded as base64 for human-readable formats
+#[cfg(any(feature = "alloc", test))]
+pub mod bytes {
+    use alloc::vec::Vec;
+
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&super::encode_base64(bytes))
+        } else {
+            serializer.serialize_bytes(bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(super::BytesVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(super::BytesVisitor)
+        }
+    }
+}
+
+/// List of byte strings, see `bytes`
+#[cfg(any(feature = "alloc", test))]
+pub mod byte_lists {
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "super::bytes")] Vec<u8>);
+
+    #[derive(Serialize)]
+    struct WrapperRef<'a>(#[serde(with = "super::bytes")] &'a [u8]);
+
+    pub fn serialize<S: Serializer>(lists: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(lists.iter().map(|bytes| WrapperRef(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error> {
+        let lists = Vec::<Wrapper>::deserialize(deserializer)?;
+        Ok(lists.into_iter().map(|bytes| bytes.0).collect())
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+struct BytesVisitor;
+
+#[cfg(any(feature = "alloc", test))]
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a byte string or base64 string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Vec<u8>, E> {
+        decode_base64(value).ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 with padding
+#[cfg(any(feature = "alloc", test))]
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(any(feature = "alloc", test))]
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.as_bytes();
+    if !encoded.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() / 4 * 3);
+    let chunks = encoded.len() / 4;
+    for (index, chunk) in encoded.chunks(4).enumerate() {
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && index + 1 != chunks) {
+            return None;
+        }
+
+        let mut n = 0u32;
+        for &c in &chunk[..4 - padding] {
+            let value = BASE64.iter().position(|&b| b == c)?;
+            n = (n << 6) | value as u32;
+        }
+        n <<= 6 * padding as u32;
+
+        let decoded = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        bytes.extend_from_slice(&decoded[..3 - padding]);
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate bincode;
+    extern crate serde_json;
+
+    use super::{decode_base64, encode_base64};
+    use event::VteEvent;
+    use {Action, Params, State};
+
+    #[test]
+    fn base64() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"", ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"\x00\xff\x10", "AP8Q"),
+        ];
+
+        for &(bytes, encoded) in cases {
+            assert_eq!(encode_base64(bytes), encoded);
+            assert_eq!(decode_base64(encoded).unwrap(), bytes);
+        }
+
+        assert_eq!(decode_base64("Zg="), None);
+        assert_eq!(decode_base64("Zg==Zg=="), None);
+        assert_eq!(decode_base64("Z!=="), None);
+    }
+
+    fn events() -> Vec<VteEvent> {
+        let mut params = Params::new();
+        params.push(38);
+        params.extend(5);
+        params.push(1);
+
+        vec![
+            VteEvent::Print('─'),
+            VteEvent::Execute(0x07),
+            VteEvent::DcsHook { params, intermediates: vec![b'$'], ignore: false, final_byte: b'q' },
+            VteEvent::DcsPut(b'q'),
+            VteEvent::DcsUnhook,
+            VteEvent::OscDispatch { params: vec![b"0".to_vec(), b"title".to_vec()], bell_terminated: true },
+            VteEvent::SosDispatch(b"sos".to_vec()),
+            VteEvent::PmDispatch(vec![]),
+            VteEvent::ApcDispatch(vec![0, 0xff]),
+            VteEvent::CsiDispatch { params, intermediates: vec![], ignore: true, final_byte: 'm', private: Some(b'?') },
+            VteEvent::EscDispatch { params: Params::new(), intermediates: vec![b'('], ignore: false, byte: b'B' },
+            VteEvent::StateChange { from: State::Ground, to: State::CsiEntry },
+            VteEvent::Error(b'?'),
+        ]
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let events = events();
+        let json = serde_json::to_string(&events).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<VteEvent>>(&json).unwrap(), events);
+
+        let action = serde_json::to_string(&Action::CsiDispatch).unwrap();
+        assert_eq!(action, "\"CsiDispatch\"");
+        assert_eq!(serde_json::from_str::<Action>(&action).unwrap(), Action::CsiDispatch);
+    }
+
+    #[test]
+    fn json_representation() {
+        assert_eq!(serde_json::to_string(&VteEvent::Print('a')).unwrap(), r#"{"Print":97}"#);
+        assert_eq!(
+            serde_json::to_string(&VteEvent::OscDispatch { params: vec![b"0".to_vec(), b"title".to_vec()], bell_terminated: true }).unwrap(),
+            r#"{"OscDispatch":{"params":["MA==","dGl0bGU="],"bell_terminated":true}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&VteEvent::StateChange { from: State::Ground, to: State::Utf8 }).unwrap(),
+            r#"{"StateChange":{"from":"Ground","to":"Utf8"}}"#
+        );
+
+        let mut params = Params::new();
+        params.push(38);
+        params.extend(5);
+        assert_eq!(serde_json::to_string(&params).unwrap(), "[[38,5]]");
+    }
+
+    #[test]
+    fn json_rejects_invalid_values() {
+        assert!(serde_json::from_str::<VteEvent>(r#"{"Print":55296}"#).is_err());
+        assert!(serde_json::from_str::<VteEvent>(r#"{"SosDispatch":"!"}"#).is_err());
+        assert!(serde_json::from_str::<Params>("[[]]").is_err());
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let events = events();
+        let encoded = bincode::serialize(&events).unwrap();
+        assert_eq!(bincode::deserialize::<Vec<VteEvent>>(&encoded).unwrap(), events);
+
+        let action = bincode::serialize(&Action::Print).unwrap();
+        assert_eq!(bincode::deserialize::<Action>(&action).unwrap(), Action::Print);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn parser_state_round_trip() {
+        use Parser;
+        use test_utils::RecordingPerform;
+
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut RecordingPerform::new(), b"\x1b]0;ti\xe2\x94");
+        let state = parser.save();
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<::ParserState>(&json).unwrap(), state);
+
+        let encoded = bincode::serialize(&state).unwrap();
+        assert_eq!(bincode::deserialize::<::ParserState>(&encoded).unwrap(), state);
+    }
+}
@@ -0,0 +1,519 @@
+//! Decoding the attributes of SGR sequences
+#[cfg(any(feature = "alloc", test))]
+use alloc::vec::Vec;
+use core::fmt;
+
+use {Params, ParamsIter};
+
+/// One of the 8 standard colors or their bright variants
+///
+/// The discriminants are the indices of the colors in the 256 color palette.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NamedColor {
+    Black = 0,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+/// The named colors in the order of their indices
+const NAMED_COLORS: [NamedColor; 16] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+];
+
+impl NamedColor {
+    /// The color with palette index `index`, or `None` if it is above 15
+    #[inline]
+    #[must_use]
+    pub fn from_index(index: u8) -> Option<NamedColor> {
+        NAMED_COLORS.get(index as usize).copied()
+    }
+
+    /// The bright variant of a standard color; bright colors stay the same
+    #[inline]
+    #[must_use]
+    pub fn to_bright(self) -> NamedColor {
+        NAMED_COLORS[self as usize | 8]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            NamedColor::Black | NamedColor::BrightBlack => "black",
+            NamedColor::Red | NamedColor::BrightRed => "red",
+            NamedColor::Green | NamedColor::BrightGreen => "green",
+            NamedColor::Yellow | NamedColor::BrightYellow => "yellow",
+            NamedColor::Blue | NamedColor::BrightBlue => "blue",
+            NamedColor::Magenta | NamedColor::BrightMagenta => "magenta",
+            NamedColor::Cyan | NamedColor::BrightCyan => "cyan",
+            NamedColor::White | NamedColor::BrightWhite => "white",
+        }
+    }
+}
+
+impl fmt::Display for NamedColor {
+    /// The lowercase name, like `red` or `bright red`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if *self as u8 >= 8 {
+            f.write_str("bright ")?;
+        }
+        f.write_str(self.name())
+    }
+}
+
+/// A color set through SGR
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TermColor {
+    /// The default foreground or background color of the terminal, set with
+    /// `39`, `49` or `59`
+    #[default]
+    Default,
+
+    /// One of the 16 colors set with `30` to `37`, `90` to `97` and the
+    /// matching background attributes
+    Named(NamedColor),
+
+    /// A color of the 256 color palette, set with `38;5;n`
+    Indexed(u8),
+
+    /// A direct color, set with `38;2;r;g;b`
+    Rgb(u8, u8, u8),
+}
+
+impl TermColor {
+    /// The color with index `index` of the 256 color palette
+    ///
+    /// The first 16 indices are named colors.
+    ///
+    /// ```
+    /// # use vte::{NamedColor, TermColor};
+    /// assert_eq!(TermColor::from_indexed(9), TermColor::Named(NamedColor::BrightRed));
+    /// assert_eq!(TermColor::from_indexed(208), TermColor::Indexed(208));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn from_indexed(index: u8) -> TermColor {
+        match NamedColor::from_index(index) {
+            Some(color) => TermColor::Named(color),
+            None => TermColor::Indexed(index),
+        }
+    }
+
+    /// Returns `true` for the default color of the terminal
+    #[inline]
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == TermColor::Default
+    }
+}
+
+impl fmt::Display for TermColor {
+    /// The name of default and named colors, and the hex code of other
+    /// colors like `#ff8700`
+    ///
+    /// Palette colors are shown in the colors of xterm's default palette.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (r, g, b) = match *self {
+            TermColor::Default => return f.write_str("default"),
+            TermColor::Named(color) => return color.fmt(f),
+            TermColor::Indexed(index) => match NamedColor::from_index(index) {
+                Some(color) => return color.fmt(f),
+                None => palette_rgb(index),
+            },
+            TermColor::Rgb(r, g, b) => (r, g, b),
+        };
+        write!(f, "#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+/// The color of the 6x6x6 color cube (`16` to `231`) or the grayscale ramp
+/// (`232` to `255`) in xterm's default palette
+fn palette_rgb(index: u8) -> (u8, u8, u8) {
+    const LEVELS: [u8; 6] = [0, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+    if index >= 232 {
+        let gray = 8 + 10 * (index - 232);
+        return (gray, gray, gray);
+    }
+
+    let cube = index - 16;
+    (LEVELS[(cube / 36) as usize], LEVELS[(cube / 6 % 6) as usize], LEVELS[(cube % 6) as usize])
+}
+
+/// Style of an underline, selected with `4:n`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnderlineStyle {
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+/// A text attribute set through SGR
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SgrAttribute {
+    /// Reset all attributes, `0`
+    Reset,
+    Bold,
+    Dim,
+    Italic,
+
+    /// `4`, `4:1` to `4:5` or `21` for a double underline
+    Underline(UnderlineStyle),
+    Blink,
+    Inverse,
+    Invisible,
+    StrikeThrough,
+
+    /// Neither bold nor dim, `22`
+    NormalIntensity,
+    NoItalic,
+
+    /// `24` or `4:0`
+    NoUnderline,
+    NoBlink,
+    NoInverse,
+    NoInvisible,
+    NoStrikeThrough,
+
+    Foreground(TermColor),
+    Background(TermColor),
+
+    /// `58`, a color for underlines other than the foreground color
+    ///
+    /// [`TermColor::Default`], set with `59`, draws underlines in the
+    /// foreground color again.
+    ///
+    /// [`TermColor::Default`]: enum.TermColor.html#variant.Default
+    UnderlineColor(TermColor),
+}
+
+/// Decode the parameters of an SGR sequence
+///
+/// `CSI m` resets all attributes. Unknown attributes and colors with values
+/// out of range are skipped, see [`SgrIter`].
+///
+/// ```
+/// # use vte::{parse_sgr, Parser, SgrAttribute, TermColor, VteEvent};
+/// let mut parser = Parser::new();
+/// for event in parser.parse(b"\x1b[1;38;5;208m") {
+///     if let VteEvent::CsiDispatch { params, final_byte: 'm', .. } = event {
+///         assert_eq!(parse_sgr(&params), [
+///             SgrAttribute::Bold,
+///             SgrAttribute::Foreground(TermColor::Indexed(208)),
+///         ]);
+///     }
+/// }
+/// ```
+///
+/// [`SgrIter`]: struct.SgrIter.html
+#[cfg(any(feature = "alloc", test))]
+#[must_use]
+pub fn parse_sgr(params: &Params) -> Vec<SgrAttribute> {
+    if params.is_empty() {
+        return [SgrAttribute::Reset].to_vec();
+    }
+
+    SgrIter::new(params).collect()
+}
+
+/// Iterator decoding the attributes of an SGR sequence
+///
+/// Colors may take several parameters, like `38;2;r;g;b`, which are consumed
+/// together. The parameters following the last decoded attribute are
+/// available through [`remainder`], for example to pass attributes the
+/// iterator doesn't know on to other code.
+///
+/// Unlike [`parse_sgr`], this yields nothing if there are no parameters.
+///
+/// [`remainder`]: struct.SgrIter.html#method.remainder
+/// [`parse_sgr`]: fn.parse_sgr.html
+#[derive(Clone)]
+pub struct SgrIter<'a> {
+    params: ParamsIter<'a>,
+}
+
+impl<'a> SgrIter<'a> {
+    /// Decode the attributes in `params`
+    #[inline]
+    #[must_use]
+    pub fn new(params: &'a Params) -> SgrIter<'a> {
+        SgrIter { params: params.iter() }
+    }
+
+    /// The parameters which haven't been decoded yet
+    #[inline]
+    #[must_use]
+    pub fn remainder(&self) -> ParamsIter<'a> {
+        self.params.clone()
+    }
+
+    /// Decode a single attribute from the next parameters
+    ///
+    /// Returns `None` for an unknown or malformed attribute, consuming its
+    /// parameters.
+    fn decode(&mut self, param: &[u16]) -> Option<SgrAttribute> {
+        let attribute = match param[0] {
+            0 => SgrAttribute::Reset,
+            1 => SgrAttribute::Bold,
+            2 => SgrAttribute::Dim,
+            3 => SgrAttribute::Italic,
+            4 => match param.get(1) {
+                None | Some(1) => SgrAttribute::Underline(UnderlineStyle::Single),
+                Some(0) => SgrAttribute::NoUnderline,
+                Some(2) => SgrAttribute::Underline(UnderlineStyle::Double),
+                Some(3) => SgrAttribute::Underline(UnderlineStyle::Curly),
+                Some(4) => SgrAttribute::Underline(UnderlineStyle::Dotted),
+                Some(5) => SgrAttribute::Underline(UnderlineStyle::Dashed),
+                Some(_) => return None,
+            },
+            5 | 6 => SgrAttribute::Blink,
+            7 => SgrAttribute::Inverse,
+            8 => SgrAttribute::Invisible,
+            9 => SgrAttribute::StrikeThrough,
+            21 => SgrAttribute::Underline(UnderlineStyle::Double),
+            22 => SgrAttribute::NormalIntensity,
+            23 => SgrAttribute::NoItalic,
+            24 => SgrAttribute::NoUnderline,
+            25 => SgrAttribute::NoBlink,
+            27 => SgrAttribute::NoInverse,
+            28 => SgrAttribute::NoInvisible,
+            29 => SgrAttribute::NoStrikeThrough,
+            value @ 30..=37 => SgrAttribute::Foreground(named_color(value - 30)),
+            value @ 40..=47 => SgrAttribute::Background(named_color(value - 40)),
+            value @ 90..=97 => SgrAttribute::Foreground(named_color(value - 90 + 8)),
+            value @ 100..=107 => SgrAttribute::Background(named_color(value - 100 + 8)),
+            39 => SgrAttribute::Foreground(TermColor::Default),
+            49 => SgrAttribute::Background(TermColor::Default),
+            59 => SgrAttribute::UnderlineColor(TermColor::Default),
+            38 => SgrAttribute::Foreground(self.extended_color(param)?),
+            48 => SgrAttribute::Background(self.extended_color(param)?),
+            58 => SgrAttribute::UnderlineColor(self.extended_color(param)?),
+            _ => return None,
+        };
+
+        Some(attribute)
+    }
+
+    /// Decode the color of `38`, `48` or `58`
+    ///
+    /// The color is given either as subparameters, like `38:5:n` or
+    /// `38:2::r:g:b`, or as the following parameters, like `38;5;n` or
+    /// `38;2;r;g;b`. The color space id of the ITU T.416 form `38:2:id:r:g:b`
+    /// is ignored; the form without it is accepted too.
+    fn extended_color(&mut self, param: &[u16]) -> Option<TermColor> {
+        if param.len() > 1 {
+            return match param[1..] {
+                [5, index, ..] => Some(TermColor::Indexed(color_value(index)?)),
+                [2, _, r, g, b, ..] | [2, r, g, b] => {
+                    Some(TermColor::Rgb(color_value(r)?, color_value(g)?, color_value(b)?))
+                },
+                _ => None,
+            };
+        }
+
+        match self.params.next()?[0] {
+            5 => Some(TermColor::Indexed(color_value(self.params.next()?[0])?)),
+            2 => {
+                let r = self.params.next()?[0];
+                let g = self.params.next()?[0];
+                let b = self.params.next()?[0];
+                Some(TermColor::Rgb(color_value(r)?, color_value(g)?, color_value(b)?))
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Iterator for SgrIter<'a> {
+    type Item = SgrAttribute;
+
+    fn next(&mut self) -> Option<SgrAttribute> {
+        while let Some(param) = self.params.next() {
+            if let Some(attribute) = self.decode(param) {
+                return Some(attribute);
+            }
+        }
+        None
+    }
+}
+
+/// A named color from an index known to be below 16
+#[inline]
+fn named_color(index: u16) -> TermColor {
+    TermColor::from_indexed(index as u8)
+}
+
+#[inline]
+fn color_value(value: u16) -> Option<u8> {
+    if value <= u16::from(u8::MAX) {
+        Some(value as u8)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{parse_sgr, NamedColor, SgrAttribute, SgrIter, TermColor, UnderlineStyle};
+    use test_utils::{RecordedEvent, RecordingPerform};
+    use {Params, Parser};
+
+    fn params(bytes: &[u8]) -> Params {
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_slice(&mut recorder, bytes);
+        recorder.events().iter().filter_map(|event| match *event {
+            RecordedEvent::CsiDispatch { ref params, .. } => Some(*params),
+            _ => None,
+        }).next().unwrap()
+    }
+
+    fn sgr(bytes: &[u8]) -> Vec<SgrAttribute> {
+        parse_sgr(&params(bytes))
+    }
+
+    #[test]
+    fn attributes() {
+        assert_eq!(sgr(b"\x1b[m"), vec![SgrAttribute::Reset]);
+        assert_eq!(sgr(b"\x1b[0;1;2;3;4;5;7;8;9m"), vec![
+            SgrAttribute::Reset,
+            SgrAttribute::Bold,
+            SgrAttribute::Dim,
+            SgrAttribute::Italic,
+            SgrAttribute::Underline(UnderlineStyle::Single),
+            SgrAttribute::Blink,
+            SgrAttribute::Inverse,
+            SgrAttribute::Invisible,
+            SgrAttribute::StrikeThrough,
+        ]);
+        assert_eq!(sgr(b"\x1b[22;23;24;25;27;28;29m"), vec![
+            SgrAttribute::NormalIntensity,
+            SgrAttribute::NoItalic,
+            SgrAttribute::NoUnderline,
+            SgrAttribute::NoBlink,
+            SgrAttribute::NoInverse,
+            SgrAttribute::NoInvisible,
+            SgrAttribute::NoStrikeThrough,
+        ]);
+
+        // Unknown attributes are skipped
+        assert_eq!(sgr(b"\x1b[1;73;3m"), vec![SgrAttribute::Bold, SgrAttribute::Italic]);
+    }
+
+    #[test]
+    fn underline_styles() {
+        assert_eq!(sgr(b"\x1b[4:0;4:1;4:2;4:3;4:4;4:5;21;4:9m"), vec![
+            SgrAttribute::NoUnderline,
+            SgrAttribute::Underline(UnderlineStyle::Single),
+            SgrAttribute::Underline(UnderlineStyle::Double),
+            SgrAttribute::Underline(UnderlineStyle::Curly),
+            SgrAttribute::Underline(UnderlineStyle::Dotted),
+            SgrAttribute::Underline(UnderlineStyle::Dashed),
+            SgrAttribute::Underline(UnderlineStyle::Double),
+        ]);
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(sgr(b"\x1b[31;42;97;100;39;49;59m"), vec![
+            SgrAttribute::Foreground(TermColor::Named(NamedColor::Red)),
+            SgrAttribute::Background(TermColor::Named(NamedColor::Green)),
+            SgrAttribute::Foreground(TermColor::Named(NamedColor::BrightWhite)),
+            SgrAttribute::Background(TermColor::Named(NamedColor::BrightBlack)),
+            SgrAttribute::Foreground(TermColor::Default),
+            SgrAttribute::Background(TermColor::Default),
+            SgrAttribute::UnderlineColor(TermColor::Default),
+        ]);
+        assert_eq!(sgr(b"\x1b[38;5;208;1;48;2;10;20;30;58;5;1m"), vec![
+            SgrAttribute::Foreground(TermColor::Indexed(208)),
+            SgrAttribute::Bold,
+            SgrAttribute::Background(TermColor::Rgb(10, 20, 30)),
+            SgrAttribute::UnderlineColor(TermColor::Indexed(1)),
+        ]);
+        assert_eq!(sgr(b"\x1b[38:2::1:2:3;48:2:4:5:6;58:5:7;58:2:0:8:9:10m"), vec![
+            SgrAttribute::Foreground(TermColor::Rgb(1, 2, 3)),
+            SgrAttribute::Background(TermColor::Rgb(4, 5, 6)),
+            SgrAttribute::UnderlineColor(TermColor::Indexed(7)),
+            SgrAttribute::UnderlineColor(TermColor::Rgb(8, 9, 10)),
+        ]);
+
+        // Out of range and truncated colors are skipped
+        assert_eq!(sgr(b"\x1b[38;5;256;1m"), vec![SgrAttribute::Bold]);
+        assert_eq!(sgr(b"\x1b[1;48;2;1m"), vec![SgrAttribute::Bold]);
+        assert_eq!(sgr(b"\x1b[38:2:1m"), vec![]);
+    }
+
+    #[test]
+    fn remainder() {
+        let params = params(b"\x1b[1;38;2;1;2;3;4m");
+        let mut iter = SgrIter::new(&params);
+
+        assert_eq!(iter.next(), Some(SgrAttribute::Bold));
+        assert_eq!(iter.remainder().count(), 6);
+
+        // All parameters of the color are consumed
+        assert_eq!(iter.next(), Some(SgrAttribute::Foreground(TermColor::Rgb(1, 2, 3))));
+        assert_eq!(iter.remainder().collect::<Vec<_>>(), vec![&[4][..]]);
+
+        assert_eq!(SgrIter::new(&Params::new()).next(), None);
+    }
+
+    #[test]
+    fn named_colors() {
+        for index in 0..16 {
+            let color = NamedColor::from_index(index).unwrap();
+            assert_eq!(color as u8, index);
+            assert_eq!(TermColor::from_indexed(index), TermColor::Named(color));
+        }
+        assert_eq!(NamedColor::from_index(16), None);
+        assert_eq!(TermColor::from_indexed(16), TermColor::Indexed(16));
+
+        assert_eq!(NamedColor::Blue.to_bright(), NamedColor::BrightBlue);
+        assert_eq!(NamedColor::BrightBlue.to_bright(), NamedColor::BrightBlue);
+        assert!(TermColor::default().is_default());
+        assert!(!TermColor::Indexed(0).is_default());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(TermColor::Default.to_string(), "default");
+        assert_eq!(TermColor::Named(NamedColor::Magenta).to_string(), "magenta");
+        assert_eq!(TermColor::Named(NamedColor::BrightCyan).to_string(), "bright cyan");
+        assert_eq!(TermColor::Indexed(1).to_string(), "red");
+        assert_eq!(TermColor::Indexed(16).to_string(), "#000000");
+        assert_eq!(TermColor::Indexed(208).to_string(), "#ff8700");
+        assert_eq!(TermColor::Indexed(231).to_string(), "#ffffff");
+        assert_eq!(TermColor::Indexed(232).to_string(), "#080808");
+        assert_eq!(TermColor::Indexed(255).to_string(), "#eeeeee");
+        assert_eq!(TermColor::Rgb(1, 0xab, 255).to_string(), "#01abff");
+    }
+}
@@ -0,0 +1,87 @@
+//! Vectorized scanning for runs of printable ASCII
+//!
+//! Only `core::arch` intrinsics are used, since they are available on stable
+//! Rust. Targets without SSE2 fall back to a scalar loop.
+
+/// Check whether `byte` is printable ASCII, excluding DEL
+#[inline(always)]
+fn is_printable(byte: u8) -> bool {
+    (0x20..0x7f).contains(&byte)
+}
+
+/// Get the length of the run of printable ASCII at the start of `bytes`
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+#[inline]
+pub fn printable_ascii_len(bytes: &[u8]) -> usize {
+    use core::arch::x86_64::{
+        __m128i, _mm_and_si128, _mm_cmpgt_epi8, _mm_cmplt_epi8, _mm_loadu_si128,
+        _mm_movemask_epi8, _mm_set1_epi8,
+    };
+
+    const LANES: usize = 16;
+
+    let mut offset = 0;
+    while offset + LANES <= bytes.len() {
+        // Bytes are compared as signed integers, so anything above 0x7f is
+        // negative and thus not greater than 0x1f.
+        let mask = unsafe {
+            let chunk = _mm_loadu_si128(bytes[offset..].as_ptr() as *const __m128i);
+            let above_controls = _mm_cmpgt_epi8(chunk, _mm_set1_epi8(0x1f));
+            let below_del = _mm_cmplt_epi8(chunk, _mm_set1_epi8(0x7f));
+            _mm_movemask_epi8(_mm_and_si128(above_controls, below_del)) as u32
+        };
+
+        if mask != 0xffff {
+            return offset + (!mask).trailing_zeros() as usize;
+        }
+
+        offset += LANES;
+    }
+
+    offset + scalar_printable_ascii_len(&bytes[offset..])
+}
+
+/// Get the length of the run of printable ASCII at the start of `bytes`
+#[cfg(not(all(target_arch = "x86_64", target_feature = "sse2")))]
+#[inline]
+pub fn printable_ascii_len(bytes: &[u8]) -> usize {
+    scalar_printable_ascii_len(bytes)
+}
+
+#[inline]
+fn scalar_printable_ascii_len(bytes: &[u8]) -> usize {
+    bytes.iter().position(|byte| !is_printable(*byte)).unwrap_or(bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{printable_ascii_len, scalar_printable_ascii_len};
+
+    #[test]
+    fn matches_scalar() {
+        let mut input = [b'a'; 70];
+        assert_eq!(printable_ascii_len(&input), 70);
+
+        for position in 0..input.len() {
+            for &byte in &[0x00, 0x1b, 0x1f, 0x7f, 0x80, 0xc3, 0xff] {
+                let previous = input[position];
+                input[position] = byte;
+
+                for start in 0..position + 1 {
+                    let slice = &input[start..];
+                    assert_eq!(printable_ascii_len(slice), scalar_printable_ascii_len(slice));
+                    assert_eq!(printable_ascii_len(slice), position - start);
+                }
+
+                input[position] = previous;
+            }
+        }
+    }
+
+    #[test]
+    fn boundaries() {
+        assert_eq!(printable_ascii_len(b""), 0);
+        assert_eq!(printable_ascii_len(b" ~\x7f"), 2);
+        assert_eq!(printable_ascii_len(b"\x1f"), 0);
+    }
+}
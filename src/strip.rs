@@ -0,0 +1,129 @@
+//! Removing escape sequences from terminal output
+use alloc::string::String;
+
+use {Params, Parser, Perform};
+
+/// A [`Perform`] which collects the printed text and drops everything else
+///
+/// Line feeds, carriage returns and tabs are kept as `\n`, `\r` and `\t`, so
+/// the text keeps its lines. Other controls and all escape sequences,
+/// including the strings of OSC and DCS sequences, are dropped.
+///
+/// ```
+/// # use vte::{Parser, TextStripper};
+/// let mut stripper = TextStripper::new();
+/// Parser::new().advance_slice(&mut stripper, b"\x1b[1mbold\x1b[0m\r\n");
+/// assert_eq!(stripper.text(), "bold\r\n");
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TextStripper {
+    text: String,
+}
+
+impl TextStripper {
+    /// Create a stripper which has collected no text
+    #[must_use]
+    pub fn new() -> TextStripper {
+        TextStripper::default()
+    }
+
+    /// The text collected so far
+    #[inline]
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Take the collected text
+    #[inline]
+    #[must_use]
+    pub fn into_text(self) -> String {
+        self.text
+    }
+}
+
+impl Perform for TextStripper {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.text.push('\r');
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.text.push('\n');
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        self.text.push('\t');
+    }
+
+    fn execute(&mut self, _byte: u8) {}
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+    fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+
+    #[inline]
+    fn dcs_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _data: &[u8], _final_byte: u8) {}
+}
+
+/// Remove all escape sequences from `input`, returning the printed text
+///
+/// See [`TextStripper`] for what is kept. Invalid UTF-8 is replaced by
+/// `U+FFFD`, and a sequence which is unfinished at the end of `input` is
+/// dropped.
+///
+/// ```
+/// # use vte::strip_ansi;
+/// assert_eq!(strip_ansi(b"\x1b]0;title\x07\x1b[31mred\x1b[m text"), "red text");
+/// ```
+///
+/// [`TextStripper`]: struct.TextStripper.html
+#[must_use]
+pub fn strip_ansi(input: &[u8]) -> String {
+    let mut stripper = TextStripper::new();
+    let mut parser = Parser::new();
+    parser.advance_slice(&mut stripper, input);
+    parser.flush(&mut stripper);
+    stripper.into_text()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_ansi;
+
+    #[test]
+    fn colors() {
+        assert_eq!(strip_ansi(b"\x1b[1;31merror\x1b[0m: \x1b[38;5;208mwarning\x1b[39m"), "error: warning");
+        assert_eq!(strip_ansi(b"\x1b[38:2::255:0:0mrgb\x1b[m"), "rgb");
+    }
+
+    #[test]
+    fn cursor_movement() {
+        assert_eq!(strip_ansi(b"\x1b[2J\x1b[H\x1b[10;20Hat\x1b[3A\x1b7\x1b8 \x1bMup"), "at up");
+        assert_eq!(strip_ansi(b"one\r\ntwo\tthree\x08\x07"), "one\r\ntwo\tthree");
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(strip_ansi(b"\x1b]0;title\x07\x1b]8;;http://a\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip_ansi(b"\x1bPq#0;2;0;0;0#0~~\x1b\\\x1b_Gf=1;\x1b\\sixel"), "sixel");
+    }
+
+    #[test]
+    fn unfinished() {
+        assert_eq!(strip_ansi(b"a\x1b[1"), "a");
+        assert_eq!(strip_ansi(b"a\xe2\x94"), "a");
+        assert_eq!(strip_ansi("\u{2500}\u{1f600}".as_bytes()), "\u{2500}\u{1f600}");
+    }
+}
@@ -0,0 +1,393 @@
+//! Holding back screen updates during synchronized output
+use core::time::Duration;
+
+use event::RecordingPerform;
+use {Params, Perform, State, VteEvent, C0};
+
+/// How long output is held back if the end of the update never arrives
+pub const DEFAULT_SYNC_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// Call `$method` on the buffer while synchronizing and on the `Perform`
+/// otherwise
+macro_rules! route {
+    ($self:ident.$method:ident($($arg:expr),*)) => {
+        if $self.synchronized {
+            $self.buffer.$method($($arg),*);
+        } else {
+            $self.performer.$method($($arg),*);
+        }
+    };
+}
+
+/// A [`Perform`] which holds back the actions of synchronized updates
+///
+/// Programs wrap a screen update in `DCS = 1 s ST` and `DCS = 2 s ST` so the
+/// terminal doesn't draw it half-finished. Outside of an update every action
+/// is forwarded to `P`. Between the markers actions are recorded instead,
+/// and when the update ends they are passed to `flush` in one call, together
+/// with `P`; [`VteEvent::replay`] performs them on it. The markers themselves
+/// are not passed on.
+///
+/// An update which doesn't end within the [timeout] is flushed anyway. The
+/// adapter has no clock of its own, so the time which passes has to be
+/// reported with [`tick`].
+///
+/// ```
+/// # use vte::{Parser, SynchronizedOutputPerform, TextStripper, VteEvent};
+/// let mut sync = SynchronizedOutputPerform::new(TextStripper::new(), |stripper, events: &[VteEvent]| {
+///     for event in events {
+///         event.replay(stripper);
+///     }
+/// });
+/// let mut parser = Parser::new();
+///
+/// parser.advance_slice(&mut sync, b"a\x1bP=1s\x1b\\b");
+/// assert_eq!(sync.inner().text(), "a");
+///
+/// parser.advance_slice(&mut sync, b"\x1bP=2s\x1b\\");
+/// assert_eq!(sync.inner().text(), "ab");
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`VteEvent::replay`]: enum.VteEvent.html#method.replay
+/// [timeout]: struct.SynchronizedOutputPerform.html#method.set_timeout
+/// [`tick`]: struct.SynchronizedOutputPerform.html#method.tick
+pub struct SynchronizedOutputPerform<P: Perform, F: FnMut(&mut P, &[VteEvent])> {
+    performer: P,
+    flush: F,
+    buffer: RecordingPerform,
+    synchronized: bool,
+
+    /// The current DCS is a marker, so its data isn't passed on
+    in_marker: bool,
+    timeout: Duration,
+    elapsed: Duration,
+}
+
+impl<P: Perform, F: FnMut(&mut P, &[VteEvent])> SynchronizedOutputPerform<P, F> {
+    /// Wrap `performer`, passing held back actions to `flush`
+    ///
+    /// The timeout is [`DEFAULT_SYNC_TIMEOUT`].
+    ///
+    /// [`DEFAULT_SYNC_TIMEOUT`]: constant.DEFAULT_SYNC_TIMEOUT.html
+    #[must_use]
+    pub fn new(performer: P, flush: F) -> SynchronizedOutputPerform<P, F> {
+        SynchronizedOutputPerform {
+            performer,
+            flush,
+            buffer: RecordingPerform::default(),
+            synchronized: false,
+            in_marker: false,
+            timeout: DEFAULT_SYNC_TIMEOUT,
+            elapsed: Duration::from_secs(0),
+        }
+    }
+
+    /// Set how long an update may take before it is flushed anyway
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Report that `elapsed` has passed since the last call
+    ///
+    /// Flushes the current update if it has been held back for longer than
+    /// the timeout.
+    pub fn tick(&mut self, elapsed: Duration) {
+        if !self.synchronized {
+            return;
+        }
+
+        self.elapsed += elapsed;
+        if self.elapsed >= self.timeout {
+            self.end_update();
+        }
+    }
+
+    /// An update has started and not ended yet
+    #[inline]
+    #[must_use]
+    pub fn is_synchronized(&self) -> bool {
+        self.synchronized
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`, dropping any held back actions
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+
+    /// Flush the held back actions and forward new ones again
+    fn end_update(&mut self) {
+        self.synchronized = false;
+        (self.flush)(&mut self.performer, &self.buffer.events);
+        self.buffer.events.clear();
+    }
+
+    /// Start or end an update if the DCS is one of the markers
+    fn marker(&mut self, params: &Params, intermediates: &[u8], final_byte: u8) -> bool {
+        if intermediates != b"=" || final_byte != b's' {
+            return false;
+        }
+
+        match params.get(0) {
+            Some(&[1]) => {
+                self.synchronized = true;
+                self.elapsed = Duration::from_secs(0);
+            },
+            Some(&[2]) if self.synchronized => self.end_update(),
+            Some(&[2]) => (),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl<P: Perform, F: FnMut(&mut P, &[VteEvent])> Perform for SynchronizedOutputPerform<P, F> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        route!(self.print(c));
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        route!(self.print_wide(c, cell_width));
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        route!(self.execute(byte));
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        route!(self.execute_c0(c0));
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        route!(self.carriage_return());
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        route!(self.linefeed());
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        route!(self.backspace());
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        route!(self.tab());
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        route!(self.bell());
+    }
+
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        if !ignore && self.marker(params, intermediates, final_byte) {
+            self.in_marker = true;
+            return;
+        }
+        route!(self.hook(params, intermediates, ignore, final_byte));
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        if !self.in_marker {
+            route!(self.put(byte));
+        }
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        if self.in_marker {
+            self.in_marker = false;
+        } else {
+            route!(self.unhook());
+        }
+    }
+
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        if !ignore && self.marker(params, intermediates, final_byte) {
+            return;
+        }
+        route!(self.dcs_dispatch(params, intermediates, ignore, data, final_byte));
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        route!(self.osc_dispatch(params, bell_terminated));
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        route!(self.sos_dispatch(bytes));
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        route!(self.pm_dispatch(bytes));
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        route!(self.apc_dispatch(bytes));
+    }
+
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        route!(self.csi_dispatch(params, intermediates, ignore, action, private));
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        route!(self.esc_dispatch(params, intermediates, ignore, byte));
+    }
+
+    /// Not a parser action, so it is always passed on immediately
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        route!(self.on_state_change(from, to));
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        route!(self.error(byte));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+    use core::time::Duration;
+
+    use super::SynchronizedOutputPerform;
+    use test_utils::RecordingPerform;
+    use tests::describe;
+    use {Parser, VteEvent};
+
+    /// An adapter which replays flushed events and counts the flushes
+    fn synchronized(
+        flushes: &Rc<Cell<usize>>,
+    ) -> SynchronizedOutputPerform<RecordingPerform, impl FnMut(&mut RecordingPerform, &[VteEvent])> {
+        let flushes = flushes.clone();
+        SynchronizedOutputPerform::new(RecordingPerform::default(), move |recorder, events: &[VteEvent]| {
+            flushes.set(flushes.get() + 1);
+            for event in events {
+                event.replay(recorder);
+            }
+        })
+    }
+
+    #[test]
+    fn hidden_until_end() {
+        let flushes = Rc::new(Cell::new(0));
+        let mut sync = synchronized(&flushes);
+        let mut parser = Parser::new();
+
+        parser.advance_slice(&mut sync, b"a\x1bP=1s\x1b\\\x1b[1mb\r\n\x1b]0;t\x07");
+        assert!(sync.is_synchronized());
+        assert_eq!(describe(sync.inner().events()), vec!["print 'a'"]);
+
+        parser.advance_slice(&mut sync, b"\x1bP=2s\x1b\\c");
+        assert!(!sync.is_synchronized());
+        assert_eq!(flushes.get(), 1);
+        assert_eq!(describe(sync.inner().events()), vec![
+            "print 'a'",
+            "esc_dispatch [] [] false 5c",
+            "csi_dispatch [[1]] [] false 'm'",
+            "print 'b'",
+            "execute 0d",
+            "execute 0a",
+            "osc_dispatch [[48], [116]] true",
+            "esc_dispatch [] [] false 5c",
+            "print 'c'",
+        ]);
+    }
+
+    #[test]
+    fn timeout() {
+        let flushes = Rc::new(Cell::new(0));
+        let mut sync = synchronized(&flushes);
+        sync.set_timeout(Duration::from_millis(100));
+        let mut parser = Parser::new();
+
+        sync.tick(Duration::from_secs(1));
+        parser.advance_slice(&mut sync, b"\x1bP=1sx\x1b\\a");
+        sync.tick(Duration::from_millis(60));
+        assert!(sync.is_synchronized());
+        assert!(describe(sync.inner().events()).is_empty());
+
+        sync.tick(Duration::from_millis(60));
+        assert!(!sync.is_synchronized());
+        assert_eq!(flushes.get(), 1);
+        assert_eq!(describe(sync.inner().events()), vec!["esc_dispatch [] [] false 5c", "print 'a'"]);
+
+        // A late end marker has nothing left to flush
+        parser.advance_slice(&mut sync, b"b\x1bP=2s\x1b\\");
+        assert_eq!(flushes.get(), 1);
+        assert_eq!(describe(sync.inner().events()).len(), 4);
+    }
+
+    #[test]
+    fn other_dcs() {
+        let flushes = Rc::new(Cell::new(0));
+        let mut sync = synchronized(&flushes);
+        Parser::new().advance_slice(&mut sync, b"\x1bP=3s\x1b\\\x1bP1sx\x1b\\\x1bP=1s\x1b\\\x1bP=1s\x1b\\");
+        assert!(sync.is_synchronized());
+        assert_eq!(describe(sync.inner().events()), vec![
+            "hook [[3]] [61] false 's'",
+            "unhook",
+            "esc_dispatch [] [] false 5c",
+            "hook [[1]] [] false 's'",
+            "put 78",
+            "unhook",
+            "esc_dispatch [] [] false 5c",
+        ]);
+    }
+}
@@ -0,0 +1,430 @@
+//! Tracking the tab stops of a terminal
+use alloc::vec::Vec;
+
+use {Params, Perform, State, C0};
+
+/// Distance of the tab stops a terminal starts with
+const DEFAULT_TAB_WIDTH: u16 = 8;
+
+/// The tab stops of a line of `cols` columns
+///
+/// Columns are counted from zero. A new `TabStops` has a stop every 8
+/// columns, at 8, 16 and so on.
+///
+/// ```
+/// # use vte::TabStops;
+/// let mut stops = TabStops::new(20);
+/// assert_eq!(stops.next_stop(0), Some(8));
+///
+/// stops.set(3);
+/// stops.clear(16);
+/// assert_eq!(stops.next_stop(0), Some(3));
+/// assert_eq!(stops.next_stop(8), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TabStops {
+    stops: Vec<bool>,
+}
+
+impl TabStops {
+    /// Create the default tab stops of a line of `cols` columns
+    #[must_use]
+    pub fn new(cols: u16) -> TabStops {
+        let mut stops = TabStops { stops: Vec::new() };
+        stops.resize(cols);
+        stops
+    }
+
+    /// Number of columns
+    #[inline]
+    #[must_use]
+    pub fn cols(&self) -> u16 {
+        self.stops.len() as u16
+    }
+
+    /// The first stop after `col`, or `None` if there is none left
+    #[must_use]
+    pub fn next_stop(&self, col: u16) -> Option<u16> {
+        let start = col as usize + 1;
+        self.stops.get(start..)?
+            .iter()
+            .position(|&stop| stop)
+            .map(|offset| (start + offset) as u16)
+    }
+
+    /// Returns `true` if there is a stop at `col`
+    #[inline]
+    #[must_use]
+    pub fn is_set(&self, col: u16) -> bool {
+        self.stops.get(col as usize).copied().unwrap_or(false)
+    }
+
+    /// Set a stop at `col`; columns past the end are ignored
+    #[inline]
+    pub fn set(&mut self, col: u16) {
+        if let Some(stop) = self.stops.get_mut(col as usize) {
+            *stop = true;
+        }
+    }
+
+    /// Remove the stop at `col`
+    #[inline]
+    pub fn clear(&mut self, col: u16) {
+        if let Some(stop) = self.stops.get_mut(col as usize) {
+            *stop = false;
+        }
+    }
+
+    /// Remove every stop
+    #[inline]
+    pub fn clear_all(&mut self) {
+        self.stops.iter_mut().for_each(|stop| *stop = false);
+    }
+
+    /// Change the number of columns
+    ///
+    /// Existing stops are kept and new columns get the default stops.
+    pub fn resize(&mut self, cols: u16) {
+        let old = self.stops.len();
+        self.stops.resize(cols as usize, false);
+        for col in old..self.stops.len() {
+            self.stops[col] = col > 0 && col % DEFAULT_TAB_WIDTH as usize == 0;
+        }
+    }
+}
+
+/// A [`Perform`] which maintains the [`TabStops`] of the terminal before
+/// forwarding everything to `P`
+///
+/// HTS (`ESC H`) sets a stop at the cursor and TBC (`CSI g`) clears the stop
+/// at the cursor or, with parameter 3, all stops. To know where the cursor
+/// is, the adapter follows its column through printed characters, CR, BS,
+/// HT and the sequences CUF, CUB, CHA, HPA, CUP and HVP. Characters passed
+/// to `print` are assumed to be one column wide and those passed to
+/// `print_wide` take their width, and printing past the last column wraps
+/// to the next line. A resize event resizes the tab stops.
+///
+/// ```
+/// # use vte::{Parser, TabStopPerform, TextStripper};
+/// let mut tabs = TabStopPerform::new(TextStripper::new(), 80);
+/// Parser::new().advance_slice(&mut tabs, b"\x1b[3g\x1b[5G\x1bH\r\x1b[20G\x1bH");
+///
+/// assert_eq!(tabs.tab_stops().next_stop(0), Some(4));
+/// assert_eq!(tabs.tab_stops().next_stop(4), Some(19));
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`TabStops`]: struct.TabStops.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabStopPerform<P: Perform> {
+    performer: P,
+    tab_stops: TabStops,
+    col: u16,
+
+    /// The last column has been printed to, so the next character goes to
+    /// the next line
+    wrap_pending: bool,
+}
+
+impl<P: Perform> TabStopPerform<P> {
+    /// Wrap `performer` for a terminal `cols` columns wide
+    #[must_use]
+    pub fn new(performer: P, cols: u16) -> TabStopPerform<P> {
+        TabStopPerform { performer, tab_stops: TabStops::new(cols), col: 0, wrap_pending: false }
+    }
+
+    /// The current tab stops
+    #[inline]
+    #[must_use]
+    pub fn tab_stops(&self) -> &TabStops {
+        &self.tab_stops
+    }
+
+    /// The column the cursor is in, counted from zero
+    #[inline]
+    #[must_use]
+    pub fn cursor_col(&self) -> u16 {
+        self.col
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    #[must_use]
+    pub fn inner(&self) -> &P {
+        &self.performer
+    }
+
+    /// The wrapped `Perform`
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform`
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+
+    fn last_col(&self) -> u16 {
+        self.tab_stops.cols().saturating_sub(1)
+    }
+
+    /// Move the cursor past a character `width` columns wide
+    fn advance(&mut self, width: u16) {
+        if width == 0 {
+            return;
+        }
+        if self.wrap_pending {
+            self.move_to(0);
+        }
+
+        let next = self.col.saturating_add(width);
+        if next > self.last_col() {
+            self.col = self.last_col();
+            self.wrap_pending = true;
+        } else {
+            self.col = next;
+        }
+    }
+
+    /// Move the cursor to `col`, which is clamped to the line
+    fn move_to(&mut self, col: u16) {
+        self.col = col.min(self.last_col());
+        self.wrap_pending = false;
+    }
+}
+
+impl<P: Perform> Perform for TabStopPerform<P> {
+    #[inline]
+    fn print(&mut self, c: char) {
+        self.advance(1);
+        self.performer.print(c);
+    }
+
+    #[inline]
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.advance(cell_width.into());
+        self.performer.print_wide(c, cell_width);
+    }
+
+    #[inline]
+    fn execute(&mut self, byte: u8) {
+        self.performer.execute(byte);
+    }
+
+    #[inline]
+    fn execute_c0(&mut self, c0: C0) {
+        self.performer.execute_c0(c0);
+    }
+
+    #[inline]
+    fn carriage_return(&mut self) {
+        self.move_to(0);
+        self.performer.carriage_return();
+    }
+
+    #[inline]
+    fn linefeed(&mut self) {
+        self.performer.linefeed();
+    }
+
+    #[inline]
+    fn backspace(&mut self) {
+        let col = self.col.saturating_sub(1);
+        self.move_to(col);
+        self.performer.backspace();
+    }
+
+    #[inline]
+    fn tab(&mut self) {
+        let col = self.tab_stops.next_stop(self.col).unwrap_or_else(|| self.last_col());
+        self.move_to(col);
+        self.performer.tab();
+    }
+
+    #[inline]
+    fn bell(&mut self) {
+        self.performer.bell();
+    }
+
+    #[inline]
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.performer.hook(params, intermediates, ignore, final_byte);
+    }
+
+    #[inline]
+    fn put(&mut self, byte: u8) {
+        self.performer.put(byte);
+    }
+
+    #[inline]
+    fn unhook(&mut self) {
+        self.performer.unhook();
+    }
+
+    #[inline]
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.performer.dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.performer.wants_dcs_dispatch()
+    }
+
+    #[inline]
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.performer.osc_dispatch(params, bell_terminated);
+    }
+
+    #[inline]
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.sos_dispatch(bytes);
+    }
+
+    #[inline]
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.pm_dispatch(bytes);
+    }
+
+    #[inline]
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.performer.apc_dispatch(bytes);
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        if !ignore && intermediates.is_empty() && private.is_none() {
+            let count = params.get_or_default(0, 0, 1).max(1);
+            match action {
+                'C' => self.move_to(self.col.saturating_add(count)),
+                'D' => self.move_to(self.col.saturating_sub(count)),
+                'G' | '`' => self.move_to(count - 1),
+                'H' | 'f' => self.move_to(params.get_or_default(1, 0, 1).max(1) - 1),
+                'g' => match params.get_or_default(0, 0, 0) {
+                    0 => self.tab_stops.clear(self.col),
+                    3 => self.tab_stops.clear_all(),
+                    _ => (),
+                },
+                _ => (),
+            }
+        }
+        self.performer.csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    #[inline]
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        if byte == b'H' && intermediates.is_empty() {
+            self.tab_stops.set(self.col);
+        }
+        self.performer.esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    #[inline]
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.tab_stops.resize(cols);
+        self.move_to(self.col);
+        self.performer.resize_event(rows, cols);
+    }
+
+    #[inline]
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.performer.on_state_change(from, to);
+    }
+
+    #[inline]
+    fn error(&mut self, byte: u8) {
+        self.performer.error(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TabStopPerform, TabStops};
+    use test_utils::RecordingPerform;
+    use {Parser, Perform};
+
+    fn stops(tab_stops: &TabStops) -> Vec<u16> {
+        (0..tab_stops.cols()).filter(|&col| tab_stops.is_set(col)).collect()
+    }
+
+    fn track(cols: u16, bytes: &[u8]) -> TabStopPerform<RecordingPerform> {
+        let mut tabs = TabStopPerform::new(RecordingPerform::default(), cols);
+        Parser::new().advance_slice(&mut tabs, bytes);
+        tabs
+    }
+
+    #[test]
+    fn default_stops() {
+        let tab_stops = TabStops::new(80);
+        assert_eq!(stops(&tab_stops), [8, 16, 24, 32, 40, 48, 56, 64, 72]);
+        assert_eq!(tab_stops.next_stop(0), Some(8));
+        assert_eq!(tab_stops.next_stop(7), Some(8));
+        assert_eq!(tab_stops.next_stop(8), Some(16));
+        assert_eq!(tab_stops.next_stop(72), None);
+        assert_eq!(tab_stops.next_stop(200), None);
+        assert_eq!(TabStops::new(0).next_stop(0), None);
+
+        let mut tab_stops = TabStops::new(10);
+        tab_stops.resize(20);
+        assert_eq!(stops(&tab_stops), [8, 16]);
+    }
+
+    #[test]
+    fn custom_stops() {
+        let mut tab_stops = TabStops::new(20);
+        tab_stops.set(3);
+        tab_stops.set(19);
+        tab_stops.set(20);
+        tab_stops.clear(8);
+        assert_eq!(stops(&tab_stops), [3, 16, 19]);
+
+        let tabs = track(40, b"\x1b[3g\x1b[5G\x1bHab\x1bH\t\x1bH");
+        assert_eq!(stops(tabs.tab_stops()), [4, 6, 39]);
+        assert_eq!(tabs.cursor_col(), 39);
+    }
+
+    #[test]
+    fn clear() {
+        let tabs = track(40, b"\t\t\x1b[g\t\x1b[0g");
+        assert_eq!(stops(tabs.tab_stops()), [8, 32]);
+
+        let tabs = track(40, b"\x1b[3g\t");
+        assert!(stops(tabs.tab_stops()).is_empty());
+        assert_eq!(tabs.cursor_col(), 39);
+
+        // Other parameters and private sequences don't clear anything
+        let tabs = track(40, b"\x1b[2g\x1b[?3g\x1b[3 g");
+        assert_eq!(stops(tabs.tab_stops()), [8, 16, 24, 32]);
+    }
+
+    #[test]
+    fn cursor() {
+        assert_eq!(track(80, b"abc\x08\x08").cursor_col(), 1);
+        assert_eq!(track(80, b"\x1b[10Cx\x1b[3D").cursor_col(), 8);
+        assert_eq!(track(80, b"\x1b[5;12H\rab").cursor_col(), 2);
+        assert_eq!(track(80, b"\x1b[200`").cursor_col(), 79);
+        assert_eq!(track(4, b"abcd").cursor_col(), 3);
+        assert_eq!(track(4, b"abcde").cursor_col(), 1);
+
+        let mut tabs = track(80, b"a");
+        tabs.print_wide('\u{6f22}', 2);
+        tabs.print_wide('\u{301}', 0);
+        assert_eq!(tabs.cursor_col(), 3);
+    }
+}
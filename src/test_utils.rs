@@ -0,0 +1,52 @@
+//! Utilities for testing code which uses the parser
+//!
+//! Only available with the `test-utils` feature, which is meant to be enabled
+//! for tests alone:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! vte = { version = "0.4", features = ["test-utils"] }
+//! ```
+//!
+//! [`RecordingPerform`] records every action so the parser output can be
+//! compared against the expected [`RecordedEvent`]s.
+//!
+//! ```
+//! use vte::Parser;
+//! use vte::test_utils::{RecordedEvent, RecordingPerform};
+//!
+//! let mut recorder = RecordingPerform::new();
+//! let mut parser = Parser::new();
+//! parser.advance_slice(&mut recorder, b"a\x07");
+//!
+//! assert_eq!(recorder.events(), &[RecordedEvent::Print('a'), RecordedEvent::Execute(0x07)]);
+//!
+//! recorder.clear();
+//! assert!(recorder.events().is_empty());
+//! ```
+//!
+//! [`RecordingPerform`]: struct.RecordingPerform.html
+//! [`RecordedEvent`]: enum.RecordedEvent.html
+pub use event::{RecordingPerform, VteEvent as RecordedEvent};
+
+impl RecordingPerform {
+    /// Create a recorder without any events
+    #[inline]
+    #[must_use]
+    pub fn new() -> RecordingPerform {
+        RecordingPerform::default()
+    }
+
+    /// All events recorded so far, in order
+    #[inline]
+    #[must_use]
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// Forget all recorded events
+    #[inline]
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
@@ -0,0 +1,363 @@
+//! Unwrapping sequences which tmux passes through to the outer terminal
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::{Ref, RefCell, RefMut};
+
+use {DcsHandler, Params, Parser, Perform, State, C0};
+
+/// Data following the final byte of `DCS tmux ;`
+const PREFIX: &[u8] = b"mux;";
+
+/// A [`DcsHandler`] which parses the sequences tmux passes through
+///
+/// tmux wraps sequences meant for the terminal it runs in as
+/// `DCS tmux ; <data> ST`, doubling every ESC in the data. The parser sees
+/// this as a DCS with the final byte `t` and the data `mux;<data>`. The
+/// handler claims every DCS with the final byte `t` and no parameters or
+/// intermediates, and feeds the unescaped data of those starting with
+/// `mux;` to an inner parser with its own `Perform`. The data of other
+/// claimed strings is dropped.
+///
+/// The inner parser keeps its state between strings, so a sequence may be
+/// split across several of them.
+///
+/// [`DcsHandler`]: trait.DcsHandler.html
+pub struct TmuxPassthroughHandler<P: Perform> {
+    parser: Parser,
+    performer: P,
+
+    /// Number of bytes of `PREFIX` received, or `None` if the claimed string
+    /// doesn't start with it
+    prefix: Option<usize>,
+}
+
+impl<P: Perform> TmuxPassthroughHandler<P> {
+    /// Create a handler passing the unwrapped sequences to `performer`
+    #[must_use]
+    pub fn new(performer: P) -> TmuxPassthroughHandler<P> {
+        TmuxPassthroughHandler {
+            parser: Parser::new(),
+            performer,
+            prefix: None,
+        }
+    }
+
+    /// The `Perform` of the inner parser
+    #[inline]
+    #[must_use]
+    pub fn performer(&self) -> &P {
+        &self.performer
+    }
+
+    /// The `Perform` of the inner parser
+    #[inline]
+    pub fn performer_mut(&mut self) -> &mut P {
+        &mut self.performer
+    }
+
+    /// Unwrap the `Perform` of the inner parser
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.performer
+    }
+}
+
+impl<P: Perform> DcsHandler for TmuxPassthroughHandler<P> {
+    fn hook(&mut self, params: &Params, intermediates: &[u8], final_byte: u8) -> bool {
+        self.prefix = Some(0);
+        final_byte == b't' && params.is_empty() && intermediates.is_empty()
+    }
+
+    fn put(&mut self, byte: u8) {
+        self.prefix = match self.prefix {
+            Some(len) if len < PREFIX.len() => Some(len + 1).filter(|_| PREFIX[len] == byte),
+            Some(len) => {
+                self.parser.advance(&mut self.performer, byte);
+                Some(len)
+            },
+            None => None,
+        };
+    }
+
+    fn unhook(&mut self) {}
+
+    fn doubles_escapes(&self) -> bool {
+        true
+    }
+}
+
+/// A parser which also parses the sequences tmux passes through
+///
+/// Sequences wrapped by tmux are unwrapped by a [`TmuxPassthroughHandler`]
+/// and passed to the same `Perform` as the rest of the input, in order. This
+/// lets a program running inside tmux, or a tool reading its output, see the
+/// sequences the outer terminal would receive.
+///
+/// ```
+/// # use vte::{Params, Perform, TmuxPassthroughParser};
+/// #[derive(Default)]
+/// struct Sgr(Vec<u16>);
+///
+/// impl Perform for Sgr {
+///     fn csi_dispatch(
+///         &mut self,
+///         params: &Params,
+///         _intermediates: &[u8],
+///         _ignore: bool,
+///         c: char,
+///         _private: Option<u8>,
+///     ) {
+///         if c == 'm' {
+///             self.0.extend(params.iter().map(|param| param[0]));
+///         }
+///     }
+///     # fn print(&mut self, _c: char) {}
+///     # fn execute(&mut self, _byte: u8) {}
+///     # fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+///     # fn put(&mut self, _byte: u8) {}
+///     # fn unhook(&mut self) {}
+///     # fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+///     # fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// }
+///
+/// let mut parser = TmuxPassthroughParser::new(Sgr::default());
+/// parser.advance(b"\x1b[1m\x1bPtmux;\x1b\x1b[31m\x1b\\\x1b[0m");
+/// assert_eq!(parser.into_inner().0, [1, 31, 0]);
+/// ```
+///
+/// [`TmuxPassthroughHandler`]: struct.TmuxPassthroughHandler.html
+pub struct TmuxPassthroughParser<P: Perform + 'static> {
+    parser: Parser,
+    performer: Rc<RefCell<P>>,
+}
+
+impl<P: Perform + 'static> TmuxPassthroughParser<P> {
+    /// Create a parser passing all actions to `performer`
+    #[must_use]
+    pub fn new(performer: P) -> TmuxPassthroughParser<P> {
+        let performer = Rc::new(RefCell::new(performer));
+
+        let mut parser = Parser::new();
+        parser.set_dcs_handler(Box::new(TmuxPassthroughHandler::new(Shared(performer.clone()))));
+
+        TmuxPassthroughParser {
+            parser,
+            performer,
+        }
+    }
+
+    /// Advance the parser with `bytes`
+    #[inline]
+    pub fn advance(&mut self, bytes: &[u8]) {
+        self.parser.advance_slice(&mut Shared(self.performer.clone()), bytes);
+    }
+
+    /// The outer parser
+    #[inline]
+    #[must_use]
+    pub fn parser(&self) -> &Parser {
+        &self.parser
+    }
+
+    /// The `Perform`
+    #[inline]
+    #[must_use]
+    pub fn performer(&self) -> Ref<'_, P> {
+        self.performer.borrow()
+    }
+
+    /// The `Perform`
+    #[inline]
+    pub fn performer_mut(&mut self) -> RefMut<'_, P> {
+        self.performer.borrow_mut()
+    }
+
+    /// Unwrap the `Perform`
+    #[must_use]
+    pub fn into_inner(mut self) -> P {
+        // Drop the only other reference, held by the handler
+        self.parser.take_dcs_handler();
+        match Rc::try_unwrap(self.performer) {
+            Ok(performer) => performer.into_inner(),
+            Err(_) => unreachable!("performer is still shared"),
+        }
+    }
+}
+
+/// Lends a `Perform` to the outer and the inner parser
+///
+/// The inner parser is only advanced by the handler, which isn't called
+/// while the outer parser calls its `Perform`, so they never borrow it at
+/// the same time.
+struct Shared<P: Perform>(Rc<RefCell<P>>);
+
+impl<P: Perform> Perform for Shared<P> {
+    fn print(&mut self, c: char) {
+        self.0.borrow_mut().print(c);
+    }
+
+    fn print_wide(&mut self, c: char, cell_width: u8) {
+        self.0.borrow_mut().print_wide(c, cell_width);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        self.0.borrow_mut().execute(byte);
+    }
+
+    fn execute_c0(&mut self, c0: C0) {
+        self.0.borrow_mut().execute_c0(c0);
+    }
+
+    fn carriage_return(&mut self) {
+        self.0.borrow_mut().carriage_return();
+    }
+
+    fn linefeed(&mut self) {
+        self.0.borrow_mut().linefeed();
+    }
+
+    fn backspace(&mut self) {
+        self.0.borrow_mut().backspace();
+    }
+
+    fn tab(&mut self) {
+        self.0.borrow_mut().tab();
+    }
+
+    fn bell(&mut self) {
+        self.0.borrow_mut().bell();
+    }
+
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.0.borrow_mut().hook(params, intermediates, ignore, final_byte);
+    }
+
+    fn put(&mut self, byte: u8) {
+        self.0.borrow_mut().put(byte);
+    }
+
+    fn unhook(&mut self) {
+        self.0.borrow_mut().unhook();
+    }
+
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.0.borrow_mut().dcs_dispatch(params, intermediates, ignore, data, final_byte);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        self.0.borrow().wants_dcs_dispatch()
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        self.0.borrow_mut().osc_dispatch(params, bell_terminated);
+    }
+
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.0.borrow_mut().sos_dispatch(bytes);
+    }
+
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.0.borrow_mut().pm_dispatch(bytes);
+    }
+
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.0.borrow_mut().apc_dispatch(bytes);
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.0.borrow_mut().csi_dispatch(params, intermediates, ignore, action, private);
+    }
+
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.0.borrow_mut().esc_dispatch(params, intermediates, ignore, byte);
+    }
+
+    fn resize_event(&mut self, rows: u16, cols: u16) {
+        self.0.borrow_mut().resize_event(rows, cols);
+    }
+
+    fn on_state_change(&mut self, from: State, to: State) {
+        self.0.borrow_mut().on_state_change(from, to);
+    }
+
+    fn error(&mut self, byte: u8) {
+        self.0.borrow_mut().error(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TmuxPassthroughHandler, TmuxPassthroughParser};
+    use tests::describe;
+    use test_utils::RecordingPerform;
+    use {DcsHandler, Params};
+
+    fn unwrap(input: &[u8]) -> Vec<String> {
+        let mut parser = TmuxPassthroughParser::new(RecordingPerform::new());
+        parser.advance(input);
+        describe(parser.into_inner().events())
+    }
+
+    #[test]
+    fn passthrough_csi() {
+        assert_eq!(unwrap(b"\x1bPtmux;\x1b\x1b[31m\x1b\\"), vec![
+            "csi_dispatch [[31]] [] false 'm'",
+            "esc_dispatch [] [] false 5c",
+        ]);
+    }
+
+    #[test]
+    fn passthrough_order() {
+        assert_eq!(unwrap(b"a\x1bPtmux;\x1b\x1b]0;t\x07b\x1b\\c"), vec![
+            "print 'a'",
+            "osc_dispatch [[48], [116]] true",
+            "print 'b'",
+            "esc_dispatch [] [] false 5c",
+            "print 'c'",
+        ]);
+    }
+
+    #[test]
+    fn other_strings_are_dropped() {
+        // No prefix
+        assert_eq!(unwrap(b"\x1bPtx\x1b\x1b[1m\x1b\\"), vec!["esc_dispatch [] [] false 5c"]);
+
+        // Other device control strings reach the `Perform`
+        assert_eq!(unwrap(b"\x1bP1tx\x1b\\")[..3], ["hook [[1]] [] false 't'", "put 78", "unhook"]);
+    }
+
+    #[test]
+    fn undoubled_escape_ends_string() {
+        assert_eq!(unwrap(b"\x1bPtmux;x\x1b[1m"), vec!["print 'x'", "csi_dispatch [[1]] [] false 'm'"]);
+    }
+
+    #[test]
+    fn handler() {
+        let mut handler = TmuxPassthroughHandler::new(RecordingPerform::new());
+        assert!(handler.doubles_escapes());
+        assert!(!handler.hook(&Params::default(), &[], b'q'));
+
+        assert!(handler.hook(&Params::default(), &[], b't'));
+        for &byte in b"mux;\x1b[31m" {
+            handler.put(byte);
+        }
+        handler.unhook();
+        assert_eq!(describe(handler.performer().events()), vec!["csi_dispatch [[31]] [] false 'm'"]);
+    }
+}
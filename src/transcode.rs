@@ -0,0 +1,453 @@
+//! Writing parsed events as escape sequences again
+use std::io::{self, Write};
+
+use {encode_csi, encode_esc, encode_osc, EncodeError, Params, Parser, Perform, VteEvent};
+
+/// A value which can be written as the escape sequences producing it
+///
+/// This is the inverse of parsing: writing a [`VteEvent`] and parsing the
+/// bytes reports the event again. Strings are written with their `ST`,
+/// which the parser reports as an escape sequence after the string, and
+/// the events of a device control string have to be written together.
+/// State changes write nothing and errors the byte which caused them.
+/// Events which can't be encoded cause an error of kind `InvalidInput`.
+///
+/// ```
+/// # use vte::{EscapeSequenceSource, Parser, VteEvent};
+/// let events: Vec<_> = Parser::new().parse(b"\x1b[?25h\x1b]0;title\x07")
+///     .filter(|event| !matches!(*event, VteEvent::StateChange { .. }))
+///     .collect();
+///
+/// let mut bytes = Vec::new();
+/// events.write_to(&mut bytes).unwrap();
+/// assert_eq!(bytes, b"\x1b[?25h\x1b]0;title\x07");
+/// ```
+///
+/// [`VteEvent`]: enum.VteEvent.html
+pub trait EscapeSequenceSource {
+    /// Write the escape sequences to `writer`
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+impl EscapeSequenceSource for VteEvent {
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let bytes = encode(self, true).map_err(invalid_input)?;
+        writer.write_all(&bytes)
+    }
+}
+
+/// Writes the values one after the other
+impl<S: EscapeSequenceSource> EscapeSequenceSource for [S] {
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.iter().try_for_each(|source| source.write_to(writer))
+    }
+}
+
+impl<S: EscapeSequenceSource> EscapeSequenceSource for Vec<S> {
+    #[inline]
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self[..].write_to(writer)
+    }
+}
+
+/// Write `source` as escape sequences and advance `parser` with them
+///
+/// Nothing is parsed if `source` can't be encoded.
+///
+/// ```
+/// # use vte::{feed_event, Parser, TextStripper, VteEvent};
+/// let mut stripper = TextStripper::new();
+/// feed_event(&mut Parser::new(), &VteEvent::Print('a'), &mut stripper).unwrap();
+/// assert_eq!(stripper.text(), "a");
+/// ```
+pub fn feed_event<P, S>(parser: &mut Parser, source: &S, performer: &mut P) -> io::Result<()>
+    where P: Perform + ?Sized, S: EscapeSequenceSource + ?Sized
+{
+    let mut bytes = Vec::new();
+    source.write_to(&mut bytes)?;
+    parser.advance_slice(performer, &bytes);
+    Ok(())
+}
+
+fn invalid_input(error: EncodeError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, error)
+}
+
+/// A [`Perform`] which passes every action to a closure as a [`VteEvent`]
+/// and writes the escape sequences of the events it returns
+///
+/// The closure may return the event unchanged, replace it or return `None`
+/// to drop it, for example to filter cursor movement or change the text. The
+/// events are encoded with [`encode_csi`], [`encode_osc`] and
+/// [`encode_esc`]. The `ST` ending a string is parsed as an escape sequence
+/// of its own, so strings are written without it, and DCS data is written
+/// as it is passed to `put`. State changes and errors aren't passed to the
+/// closure, since they have no bytes of their own.
+///
+/// If the closure returns every event unchanged, the output is the input
+/// as long as it is in the form the encoders produce: no empty parameters
+/// and no C1 controls, for example.
+///
+/// `Perform` methods can't fail, so the first error is kept and later events
+/// are dropped; check [`io_error`] before calling [`finish`]. Events which
+/// can't be encoded cause an error of kind `InvalidInput`.
+///
+/// ```
+/// # use vte::{Parser, Transcoder, VteEvent};
+/// // Drop cursor positioning and upper-case the text
+/// let mut transcoder = Transcoder::new(Vec::new(), |event| match event {
+///     VteEvent::CsiDispatch { final_byte: 'H', .. } => None,
+///     VteEvent::Print(c) => Some(VteEvent::Print(c.to_ascii_uppercase())),
+///     event => Some(event),
+/// });
+/// Parser::new().advance_slice(&mut transcoder, b"\x1b[1mhi\x1b[5;1H\x1b]0;title\x07");
+/// assert_eq!(transcoder.finish(), b"\x1b[1mHI\x1b]0;title\x07");
+/// ```
+///
+/// [`Perform`]: trait.Perform.html
+/// [`VteEvent`]: enum.VteEvent.html
+/// [`encode_csi`]: fn.encode_csi.html
+/// [`encode_osc`]: fn.encode_osc.html
+/// [`encode_esc`]: fn.encode_esc.html
+/// [`io_error`]: struct.Transcoder.html#method.io_error
+/// [`finish`]: struct.Transcoder.html#method.finish
+pub struct Transcoder<W: Write, F: FnMut(VteEvent) -> Option<VteEvent>> {
+    writer: W,
+    transform: F,
+    error: Option<io::Error>,
+}
+
+impl<W: Write, F: FnMut(VteEvent) -> Option<VteEvent>> Transcoder<W, F> {
+    /// Write the events returned by `transform` to `writer`
+    #[must_use]
+    pub fn new(writer: W, transform: F) -> Transcoder<W, F> {
+        Transcoder { writer, transform, error: None }
+    }
+
+    /// The first error, if any
+    #[inline]
+    #[must_use]
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Stop writing and return the writer
+    #[inline]
+    #[must_use]
+    pub fn finish(self) -> W {
+        self.writer
+    }
+
+    /// Transform `event` and write the result
+    fn emit(&mut self, event: VteEvent) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let event = match (self.transform)(event) {
+            Some(event) => event,
+            None => return,
+        };
+        let result = match encode(&event, false) {
+            Ok(bytes) => self.writer.write_all(&bytes),
+            Err(error) => Err(invalid_input(error)),
+        };
+        if let Err(error) = result {
+            self.error = Some(error);
+        }
+    }
+}
+
+/// The bytes of `event`, with the `ST` ending strings if `terminated` is set
+fn encode(event: &VteEvent, terminated: bool) -> Result<Vec<u8>, EncodeError> {
+    let bytes = match *event {
+        VteEvent::Print(c) => {
+            let mut bytes = [0; 4];
+            c.encode_utf8(&mut bytes).as_bytes().to_vec()
+        },
+        VteEvent::Execute(byte) | VteEvent::DcsPut(byte) => vec![byte],
+        VteEvent::DcsHook { ref params, ref intermediates, final_byte, .. } => {
+            // A DCS is written like a CSI sequence with another introducer
+            let mut bytes = encode_csi(&param_list(params), intermediates, final_byte)?;
+            bytes[1] = b'P';
+            bytes
+        },
+        VteEvent::OscDispatch { ref params, bell_terminated } => {
+            let params: Vec<&[u8]> = params.iter().map(|param| &param[..]).collect();
+            let mut bytes = encode_osc(&params)?;
+            if bell_terminated || !terminated {
+                bytes.truncate(bytes.len() - 2);
+            }
+            if bell_terminated {
+                bytes.push(0x07);
+            }
+            bytes
+        },
+        VteEvent::SosDispatch(ref data) => string(b'X', data, terminated),
+        VteEvent::PmDispatch(ref data) => string(b'^', data, terminated),
+        VteEvent::ApcDispatch(ref data) => string(b'_', data, terminated),
+        VteEvent::CsiDispatch { ref params, ref intermediates, final_byte, private, .. } => {
+            let mut all = Vec::with_capacity(intermediates.len() + 1);
+            all.extend(private);
+            all.extend_from_slice(intermediates);
+            encode_csi(&param_list(params), &all, final_byte as u32 as u8)?
+        },
+        VteEvent::EscDispatch { ref intermediates, byte, .. } => encode_esc(intermediates, byte)?,
+        VteEvent::DcsUnhook if terminated => b"\x1b\\".to_vec(),
+        VteEvent::Error(byte) if terminated => vec![byte],
+        VteEvent::DcsUnhook | VteEvent::StateChange { .. } | VteEvent::Error(_) => Vec::new(),
+    };
+    Ok(bytes)
+}
+
+fn param_list(params: &Params) -> Vec<&[u16]> {
+    params.iter().collect()
+}
+
+/// An SOS, PM or APC string, with the `ST` if `terminated` is set
+fn string(introducer: u8, data: &[u8], terminated: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(data.len() + 4);
+    bytes.push(0x1b);
+    bytes.push(introducer);
+    bytes.extend_from_slice(data);
+    if terminated {
+        bytes.extend_from_slice(b"\x1b\\");
+    }
+    bytes
+}
+
+impl<W: Write, F: FnMut(VteEvent) -> Option<VteEvent>> Perform for Transcoder<W, F> {
+    fn print(&mut self, c: char) {
+        self.emit(VteEvent::Print(c));
+    }
+
+    fn execute(&mut self, byte: u8) {
+        self.emit(VteEvent::Execute(byte));
+    }
+
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, final_byte: u8) {
+        self.emit(VteEvent::DcsHook { params: *params, intermediates: intermediates.to_vec(), ignore, final_byte });
+    }
+
+    fn put(&mut self, byte: u8) {
+        self.emit(VteEvent::DcsPut(byte));
+    }
+
+    fn unhook(&mut self) {
+        self.emit(VteEvent::DcsUnhook);
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        let params = params.iter().map(|param| param.to_vec()).collect();
+        self.emit(VteEvent::OscDispatch { params, bell_terminated });
+    }
+
+    fn sos_dispatch(&mut self, bytes: &[u8]) {
+        self.emit(VteEvent::SosDispatch(bytes.to_vec()));
+    }
+
+    fn pm_dispatch(&mut self, bytes: &[u8]) {
+        self.emit(VteEvent::PmDispatch(bytes.to_vec()));
+    }
+
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.emit(VteEvent::ApcDispatch(bytes.to_vec()));
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.emit(VteEvent::CsiDispatch {
+            params: *params,
+            intermediates: intermediates.to_vec(),
+            ignore,
+            final_byte: action,
+            private,
+        });
+    }
+
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.emit(VteEvent::EscDispatch { params: *params, intermediates: intermediates.to_vec(), ignore, byte });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{feed_event, EscapeSequenceSource, Transcoder};
+    use test_utils::RecordingPerform;
+    use {Params, Parser, VteEvent};
+
+    fn transcode<F: FnMut(VteEvent) -> Option<VteEvent>>(input: &[u8], transform: F) -> Vec<u8> {
+        let mut transcoder = Transcoder::new(Vec::new(), transform);
+        Parser::new().advance_slice(&mut transcoder, input);
+        assert!(transcoder.io_error().is_none());
+        transcoder.finish()
+    }
+
+    #[test]
+    fn identity() {
+        let corpus: &[&[u8]] = &[
+            b"plain text\r\n\t\x08\x07",
+            "h\u{e9}llo \u{2500}\u{1f600}".as_bytes(),
+            b"\x1b[m\x1b[0m\x1b[1;31m\x1b[38:2:0:255:128:0m\x1b[48;5;208m",
+            b"\x1b[?25h\x1b[?1049l\x1b[>c\x1b[2 q\x1b[1;24r\x1b[65535A",
+            b"\x1b7\x1b8\x1b(B\x1b#8\x1bc\x1b=",
+            b"\x1b]0;title\x07\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\",
+            b"\x1bP1$qm\x1b\\\x1bPq#0;2;0;0;0~-\x1b\\",
+            b"\x1bXsos\x1b\\\x1b^pm\x1b\\\x1b_apc\x1b\\",
+        ];
+
+        for &input in corpus {
+            assert_eq!(transcode(input, Some), input, "{:?}", String::from_utf8_lossy(input));
+        }
+    }
+
+    #[test]
+    fn transform() {
+        // Drop cursor movement and replace line feeds
+        let output = transcode(b"a\x1b[5Ab\x1b[31m\nc", |event| match event {
+            VteEvent::CsiDispatch { final_byte: 'A', .. } => None,
+            VteEvent::Execute(b'\n') => Some(VteEvent::Print('!')),
+            event => Some(event),
+        });
+        assert_eq!(output, b"ab\x1b[31m!c");
+    }
+
+    #[test]
+    fn invalid_events() {
+        let mut transcoder = Transcoder::new(Vec::new(), |event| match event {
+            VteEvent::Print('b') => Some(VteEvent::EscDispatch {
+                params: Default::default(),
+                intermediates: vec![],
+                ignore: false,
+                byte: b'[',
+            }),
+            event => Some(event),
+        });
+        Parser::new().advance_slice(&mut transcoder, b"abc");
+        assert_eq!(transcoder.io_error().unwrap().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(transcoder.finish(), b"a");
+    }
+
+    /// Feed `source` to a parser, returning the events without state changes
+    /// and the `ST` after a string
+    fn round_trip<S: EscapeSequenceSource + ?Sized>(source: &S) -> Vec<VteEvent> {
+        let mut recorder = RecordingPerform::new();
+        feed_event(&mut Parser::new(), source, &mut recorder).unwrap();
+
+        let mut events: Vec<_> = recorder.events.into_iter()
+            .filter(|event| !matches!(*event, VteEvent::StateChange { .. }))
+            .collect();
+        if let [_, .., VteEvent::EscDispatch { byte: b'\\', .. }] = events[..] {
+            events.pop();
+        }
+        events
+    }
+
+    fn params(values: &[&[u16]]) -> Params {
+        let mut params = Params::new();
+        for param in values {
+            params.push(param[0]);
+            for &value in &param[1..] {
+                params.extend(value);
+            }
+        }
+        params
+    }
+
+    #[test]
+    fn every_event_round_trips() {
+        let mut events = Vec::new();
+        events.extend(" ~aZ\u{e9}\u{2500}\u{1f600}\u{fffd}".chars().map(VteEvent::Print));
+        events.extend((0x00..0x20).filter(|&byte| byte != 0x1b).map(VteEvent::Execute));
+
+        let param_lists: &[&[&[u16]]] = &[&[], &[&[0]], &[&[1], &[2]], &[&[38, 2, 0, 1, 2, 3]], &[&[65535]]];
+        for &values in param_lists {
+            for &intermediates in &[&b""[..], b" ", b"$"] {
+                for &private in &[None, Some(b'?'), Some(b'>')] {
+                    for &final_byte in &['m', 'h', 'q', '@', '~'] {
+                        events.push(VteEvent::CsiDispatch {
+                            params: params(values),
+                            intermediates: intermediates.to_vec(),
+                            ignore: false,
+                            final_byte,
+                            private,
+                        });
+                    }
+                }
+            }
+        }
+
+        for &intermediates in &[&b""[..], b"(", b"#", b" %"] {
+            for byte in 0x30..0x7f {
+                if intermediates.is_empty() && b"PX[]^_".contains(&byte) {
+                    continue;
+                }
+                events.push(VteEvent::EscDispatch {
+                    params: Params::new(),
+                    intermediates: intermediates.to_vec(),
+                    ignore: false,
+                    byte,
+                });
+            }
+        }
+
+        let osc_params: &[&[&[u8]]] = &[&[b""], &[b"0", b"title"], &[b"8", b"", b"https://example.com"], &[b"2", "t\u{ee}tle".as_bytes()]];
+        for &params in osc_params {
+            for &bell_terminated in &[true, false] {
+                let params = params.iter().map(|param| param.to_vec()).collect();
+                events.push(VteEvent::OscDispatch { params, bell_terminated });
+            }
+        }
+
+        for &data in &[&b""[..], b"data", "sp ace \u{e9}".as_bytes()] {
+            events.push(VteEvent::SosDispatch(data.to_vec()));
+            events.push(VteEvent::PmDispatch(data.to_vec()));
+            events.push(VteEvent::ApcDispatch(data.to_vec()));
+        }
+
+        for event in events {
+            assert_eq!(round_trip(&event), ::std::slice::from_ref(&event), "{}", event);
+        }
+    }
+
+    #[test]
+    fn device_control_strings_round_trip() {
+        let hooks = [(params(&[&[1]]), &b"$"[..], b'q'), (params(&[]), b"", b'q'), (params(&[&[0], &[1]]), b"+", b'p')];
+        for (params, intermediates, final_byte) in hooks.iter().cloned() {
+            let mut events = vec![VteEvent::DcsHook { params, intermediates: intermediates.to_vec(), ignore: false, final_byte }];
+            events.extend(b"#0;2;0;0;0~-".iter().cloned().map(VteEvent::DcsPut));
+            events.push(VteEvent::DcsUnhook);
+            assert_eq!(round_trip(&events), events);
+        }
+    }
+
+    #[test]
+    fn write_errors_and_states() {
+        let mut bytes = Vec::new();
+        let invalid = VteEvent::EscDispatch { params: Params::new(), intermediates: vec![], ignore: false, byte: b'[' };
+        let error = invalid.write_to(&mut bytes).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        // The slice is written up to the event which can't be encoded
+        let events = vec![VteEvent::Print('a'), invalid, VteEvent::Print('b')];
+        assert!(events.write_to(&mut bytes).is_err());
+        assert_eq!(bytes, b"a");
+
+        let mut recorder = RecordingPerform::new();
+        assert!(feed_event(&mut Parser::new(), &events, &mut recorder).is_err());
+        assert!(recorder.events.is_empty());
+
+        bytes.clear();
+        let state = VteEvent::StateChange { from: ::State::Ground, to: ::State::Escape };
+        vec![state, VteEvent::Error(0x1b)].write_to(&mut bytes).unwrap();
+        assert_eq!(bytes, b"\x1b");
+    }
+}
@@ -0,0 +1,239 @@
+//! Decoding UTF-8 one byte at a time
+use utf8parse;
+
+/// Receiver collecting the result of a single byte
+struct Output {
+    c: Option<char>,
+    invalid: bool,
+}
+
+impl utf8parse::Receiver for Output {
+    fn codepoint(&mut self, c: char) {
+        self.c = Some(c);
+    }
+
+    fn invalid_sequence(&mut self) {
+        self.invalid = true;
+    }
+}
+
+/// The UTF-8 decoder of the parser
+///
+/// Bytes are passed in one at a time, and a character is returned as soon as
+/// its last byte arrives. Overlong encodings, surrogates and code points past
+/// `U+10FFFF` are malformed, following [RFC 3629].
+///
+/// This is not a general purpose UTF-8 decoder. It never allocates and
+/// keeps the bytes of an incomplete character between calls, which suits
+/// streams like terminal output; for complete buffers use
+/// `core::str::from_utf8` or `String::from_utf8_lossy` instead.
+///
+/// Malformed input is replaced by `U+FFFD`, once per incomplete character
+/// and for every byte which can't start one. A byte which doesn't continue
+/// the incomplete character may start the next one, so it isn't consumed:
+/// [`interrupted`] returns `true` after `push` returned `U+FFFD` for the
+/// incomplete character, and the byte has to be pushed again.
+///
+/// ```
+/// # use vte::Utf8Accumulator;
+/// let mut utf8 = Utf8Accumulator::new();
+/// let mut text = String::new();
+/// for &byte in b"\xe2\x94\x80 \xe2\x94a\xff" {
+///     text.extend(utf8.push(byte));
+///     if utf8.interrupted() {
+///         text.extend(utf8.push(byte));
+///     }
+/// }
+/// assert_eq!(text, "\u{2500} \u{fffd}a\u{fffd}");
+/// ```
+///
+/// [RFC 3629]: https://tools.ietf.org/html/rfc3629
+/// [`interrupted`]: struct.Utf8Accumulator.html#method.interrupted
+#[derive(Clone)]
+pub struct Utf8Accumulator {
+    parser: utf8parse::Parser,
+
+    /// Bytes of the incomplete character
+    pending: [u8; 4],
+    pending_len: usize,
+
+    interrupted: bool,
+}
+
+impl Utf8Accumulator {
+    /// Create a decoder expecting the first byte of a character
+    #[inline]
+    #[must_use]
+    pub fn new() -> Utf8Accumulator {
+        Utf8Accumulator {
+            parser: utf8parse::Parser::new(),
+            pending: [0; 4],
+            pending_len: 0,
+            interrupted: false,
+        }
+    }
+
+    /// Decode the next byte
+    ///
+    /// Returns the character ending with `byte`, `U+FFFD` if the input is
+    /// malformed, or `None` if the character is incomplete.
+    #[inline]
+    pub fn push(&mut self, byte: u8) -> Option<char> {
+        let mut output = Output { c: None, invalid: false };
+        self.parser.advance(&mut output, byte);
+
+        if output.invalid {
+            self.interrupted = self.pending_len > 0;
+            self.reset();
+            return Some('\u{fffd}');
+        }
+
+        self.interrupted = false;
+        if output.c.is_some() {
+            self.pending_len = 0;
+        } else if self.pending_len < self.pending.len() {
+            self.pending[self.pending_len] = byte;
+            self.pending_len += 1;
+        }
+
+        output.c
+    }
+
+    /// Returns `true` if the last byte interrupted an incomplete character
+    ///
+    /// `push` returned `U+FFFD` for the incomplete character and didn't
+    /// consume the byte.
+    #[inline]
+    #[must_use]
+    pub fn interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    /// The bytes of the incomplete character
+    #[inline]
+    #[must_use]
+    pub fn pending(&self) -> &[u8] {
+        &self.pending[..self.pending_len]
+    }
+
+    /// The number of bytes still missing from the incomplete character
+    ///
+    /// This is zero if no character is incomplete.
+    #[inline]
+    #[must_use]
+    pub fn remaining(&self) -> u8 {
+        let len = match self.pending().first() {
+            None => return 0,
+            Some(0xc0..=0xdf) => 2,
+            Some(0xe0..=0xef) => 3,
+            Some(_) => 4,
+        };
+        len - self.pending_len as u8
+    }
+
+    /// Returns `true` if no character is incomplete
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pending_len == 0
+    }
+
+    /// Discard the incomplete character
+    #[inline]
+    pub fn reset(&mut self) {
+        self.parser = utf8parse::Parser::new();
+        self.pending_len = 0;
+    }
+}
+
+impl Default for Utf8Accumulator {
+    fn default() -> Utf8Accumulator {
+        Utf8Accumulator::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::Utf8Accumulator;
+
+    /// Decode `bytes`, pushing interrupting bytes again
+    fn decode(bytes: &[u8]) -> String {
+        let mut utf8 = Utf8Accumulator::new();
+        let mut text = String::new();
+        for &byte in bytes {
+            text.extend(utf8.push(byte));
+            if utf8.interrupted() {
+                text.extend(utf8.push(byte));
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(decode(b"a\x7f"), "a\x7f");
+        assert_eq!(decode("\u{80}\u{7ff}".as_bytes()), "\u{80}\u{7ff}");
+        assert_eq!(decode("\u{800}\u{d7ff}\u{e000}\u{ffff}".as_bytes()), "\u{800}\u{d7ff}\u{e000}\u{ffff}");
+        assert_eq!(decode("\u{10000}\u{10ffff}".as_bytes()), "\u{10000}\u{10ffff}");
+    }
+
+    #[test]
+    fn incomplete() {
+        let mut utf8 = Utf8Accumulator::new();
+        assert_eq!(utf8.push(0xf0), None);
+        assert_eq!(utf8.push(0x9f), None);
+        assert_eq!(utf8.push(0x98), None);
+        assert_eq!(utf8.pending(), [0xf0, 0x9f, 0x98]);
+        assert_eq!(utf8.remaining(), 1);
+        assert!(!utf8.is_empty());
+
+        assert_eq!(utf8.push(0x80), Some('\u{1f600}'));
+        assert!(utf8.is_empty());
+        assert_eq!(utf8.remaining(), 0);
+
+        assert_eq!(utf8.push(0xe2), None);
+        assert_eq!(utf8.remaining(), 2);
+        utf8.reset();
+        assert!(utf8.pending().is_empty());
+        assert_eq!(utf8.push(b'a'), Some('a'));
+    }
+
+    #[test]
+    fn overlong() {
+        // `/` as 2, 3 and 4 bytes
+        assert_eq!(decode(b"\xc0\xaf"), "\u{fffd}\u{fffd}");
+        assert_eq!(decode(b"\xe0\x80\xaf"), "\u{fffd}\u{fffd}\u{fffd}");
+        assert_eq!(decode(b"\xf0\x80\x80\xaf"), "\u{fffd}\u{fffd}\u{fffd}\u{fffd}");
+    }
+
+    #[test]
+    fn out_of_range() {
+        // Surrogates
+        assert_eq!(decode(b"\xed\xa0\x80"), "\u{fffd}\u{fffd}\u{fffd}");
+
+        // Past U+10FFFF
+        assert_eq!(decode(b"\xf4\x90\x80\x80"), "\u{fffd}\u{fffd}\u{fffd}\u{fffd}");
+        assert_eq!(decode(b"\xf5\x80"), "\u{fffd}\u{fffd}");
+    }
+
+    #[test]
+    fn interrupted() {
+        let mut utf8 = Utf8Accumulator::new();
+        assert_eq!(utf8.push(0xe2), None);
+        assert_eq!(utf8.push(0x94), None);
+        assert_eq!(utf8.push(0x1b), Some('\u{fffd}'));
+        assert!(utf8.interrupted());
+        assert!(utf8.is_empty());
+        assert_eq!(utf8.push(0x1b), Some('\x1b'));
+        assert!(!utf8.interrupted());
+
+        // A lead byte starts the next character
+        assert_eq!(decode(b"\xe2\xc3\xa9"), "\u{fffd}\u{e9}");
+
+        // Stray continuation bytes are replaced one by one
+        assert_eq!(decode(b"\x80\xbf"), "\u{fffd}\u{fffd}");
+        assert!(!Utf8Accumulator::new().interrupted());
+    }
+}
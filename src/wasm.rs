@@ -0,0 +1,209 @@
+//! Bindings for using the parser from JavaScript through `wasm-bindgen`
+//!
+//! [`WasmParser`] reports every action by calling a JavaScript function with
+//! a plain object. Its `type` field names the action, the other fields carry
+//! its arguments:
+//!
+//! | `type`         | Fields                                                        |
+//! |----------------|---------------------------------------------------------------|
+//! | `print`        | `char` (string)                                               |
+//! | `execute`      | `byte`                                                        |
+//! | `dcs_dispatch` | `params`, `intermediates`, `ignore`, `data`, `finalByte`      |
+//! | `osc_dispatch` | `params` (array of `Uint8Array`), `bellTerminated`            |
+//! | `sos_dispatch`, `pm_dispatch`, `apc_dispatch` | `bytes`                        |
+//! | `csi_dispatch` | `params`, `intermediates`, `ignore`, `action` (string), `private` (number or `null`) |
+//! | `esc_dispatch` | `params`, `intermediates`, `ignore`, `byte`                   |
+//! | `error`        | `byte`                                                        |
+//!
+//! CSI, DCS and ESC `params` are arrays of parameters, each an array of its
+//! value followed by its subparameters. Byte strings are `Uint8Array`s.
+//! Device control strings are always reported in one piece and state
+//! changes are not reported.
+//!
+//! [`WasmParser`]: struct.WasmParser.html
+use js_sys::{Array, Function, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use {Params, Parser, Perform};
+
+/// A [`Parser`] which reports its actions to a JavaScript callback
+///
+/// ```js
+/// const parser = new WasmParser();
+/// parser.on_event((event) => console.log(event.type, event));
+/// parser.advance(new TextEncoder().encode("\x1b[1mbold"));
+/// ```
+///
+/// [`Parser`]: ../struct.Parser.html
+#[wasm_bindgen]
+pub struct WasmParser {
+    parser: Parser,
+    performer: JsPerform,
+}
+
+#[wasm_bindgen]
+impl WasmParser {
+    /// Create a parser without a callback
+    #[wasm_bindgen(constructor)]
+    #[must_use]
+    pub fn new() -> WasmParser {
+        WasmParser {
+            parser: Parser::new(),
+            performer: JsPerform {
+                callback: None,
+                error: None,
+            },
+        }
+    }
+
+    /// Set the function which is called with every event
+    pub fn on_event(&mut self, callback: Function) {
+        self.performer.callback = Some(callback);
+    }
+
+    /// Advance the parser with `bytes`
+    ///
+    /// If the callback throws, the rest of `bytes` is still parsed but not
+    /// reported, and the first exception is rethrown.
+    pub fn advance(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        self.parser.advance_slice(&mut self.performer, bytes);
+        self.performer.error.take().map_or(Ok(()), Err)
+    }
+
+    /// Cancel any unfinished sequence, see [`Parser::flush`]
+    ///
+    /// [`Parser::flush`]: ../struct.Parser.html#method.flush
+    pub fn flush(&mut self) -> Result<(), JsValue> {
+        self.parser.flush(&mut self.performer);
+        self.performer.error.take().map_or(Ok(()), Err)
+    }
+}
+
+impl Default for WasmParser {
+    fn default() -> WasmParser {
+        WasmParser::new()
+    }
+}
+
+/// Turns actions into JavaScript objects
+struct JsPerform {
+    callback: Option<Function>,
+
+    /// First exception thrown by the callback during the current call
+    error: Option<JsValue>,
+}
+
+impl JsPerform {
+    /// Call the callback with an event of kind `kind` and `fields`
+    fn emit(&mut self, kind: &str, fields: &[(&str, JsValue)]) {
+        let callback = match self.callback {
+            Some(ref callback) if self.error.is_none() => callback,
+            _ => return,
+        };
+
+        let event = Object::new();
+        let _ = Reflect::set(&event, &"type".into(), &kind.into());
+        for &(name, ref value) in fields {
+            let _ = Reflect::set(&event, &name.into(), value);
+        }
+
+        if let Err(error) = callback.call1(&JsValue::NULL, &event) {
+            self.error = Some(error);
+        }
+    }
+}
+
+fn params_array(params: &Params) -> JsValue {
+    params.iter()
+        .map(|param| param.iter().map(|&value| JsValue::from(value)).collect::<Array>())
+        .collect::<Array>()
+        .into()
+}
+
+fn bytes(bytes: &[u8]) -> JsValue {
+    Uint8Array::from(bytes).into()
+}
+
+impl Perform for JsPerform {
+    fn print(&mut self, c: char) {
+        self.emit("print", &[("char", c.to_string().into())]);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        self.emit("execute", &[("byte", byte.into())]);
+    }
+
+    // Device control strings are buffered for `dcs_dispatch` instead
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+
+    fn dcs_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        data: &[u8],
+        final_byte: u8,
+    ) {
+        self.emit("dcs_dispatch", &[
+            ("params", params_array(params)),
+            ("intermediates", bytes(intermediates)),
+            ("ignore", ignore.into()),
+            ("data", bytes(data)),
+            ("finalByte", final_byte.into()),
+        ]);
+    }
+
+    #[inline]
+    fn wants_dcs_dispatch(&self) -> bool {
+        true
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        let params: Array = params.iter().map(|param| bytes(param)).collect();
+        self.emit("osc_dispatch", &[("params", params.into()), ("bellTerminated", bell_terminated.into())]);
+    }
+
+    fn sos_dispatch(&mut self, data: &[u8]) {
+        self.emit("sos_dispatch", &[("bytes", bytes(data))]);
+    }
+
+    fn pm_dispatch(&mut self, data: &[u8]) {
+        self.emit("pm_dispatch", &[("bytes", bytes(data))]);
+    }
+
+    fn apc_dispatch(&mut self, data: &[u8]) {
+        self.emit("apc_dispatch", &[("bytes", bytes(data))]);
+    }
+
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+        private: Option<u8>,
+    ) {
+        self.emit("csi_dispatch", &[
+            ("params", params_array(params)),
+            ("intermediates", bytes(intermediates)),
+            ("ignore", ignore.into()),
+            ("action", action.to_string().into()),
+            ("private", private.map_or(JsValue::NULL, JsValue::from)),
+        ]);
+    }
+
+    fn esc_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, byte: u8) {
+        self.emit("esc_dispatch", &[
+            ("params", params_array(params)),
+            ("intermediates", bytes(intermediates)),
+            ("ignore", ignore.into()),
+            ("byte", byte.into()),
+        ]);
+    }
+
+    fn error(&mut self, byte: u8) {
+        self.emit("error", &[("byte", byte.into())]);
+    }
+}
@@ -0,0 +1,46 @@
+//! Parsing recordings of `TerminalRecorder` as asciicast v2
+#![cfg(feature = "std")]
+
+extern crate serde_json;
+extern crate vte;
+
+use std::time::Instant;
+
+use serde_json::Value;
+use vte::{Parser, TerminalRecorder};
+
+#[test]
+fn recording_is_valid_asciicast() {
+    let start_time = Instant::now();
+    let mut recorder = TerminalRecorder::new(Vec::new(), start_time, 80, 24).unwrap();
+
+    let mut parser = Parser::new();
+    for chunk in &[&b"$ echo \"hi\"\r\n"[..], b"\x1b[32mhi\x1b[0m\t\\\r\n", b"\x07\xe2\x9c\x93"] {
+        parser.advance_slice(&mut recorder, chunk);
+    }
+
+    assert!(recorder.io_error().is_none());
+    let recording = String::from_utf8(recorder.finish()).unwrap();
+    let mut lines = recording.lines();
+
+    let header: Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert_eq!(header["version"], 2);
+    assert_eq!(header["width"], 80);
+    assert_eq!(header["height"], 24);
+
+    let mut output = String::new();
+    let mut last_time = 0.;
+    for line in lines {
+        let event: Vec<Value> = serde_json::from_str(line).unwrap();
+        assert_eq!(event.len(), 3);
+
+        let time = event[0].as_f64().unwrap();
+        assert!(time >= last_time, "{} after {}", time, last_time);
+        last_time = time;
+
+        assert_eq!(event[1], "o");
+        output.push_str(event[2].as_str().unwrap());
+    }
+
+    assert_eq!(output, "$ echo \"hi\"\r\nhi\t\\\r\n\x07\u{2713}");
+}
@@ -0,0 +1,87 @@
+//! Dispatch of the C0 controls with named `Perform` methods
+extern crate vte;
+
+use vte::{Params, Parser, Perform};
+
+/// Records the method every control is passed to
+#[derive(Default)]
+struct Controls(Vec<String>);
+
+impl Perform for Controls {
+    fn print(&mut self, c: char) {
+        self.0.push(format!("print {:?}", c));
+    }
+    fn execute(&mut self, byte: u8) {
+        self.0.push(format!("execute {:02x}", byte));
+    }
+    fn carriage_return(&mut self) {
+        self.0.push("carriage_return".into());
+    }
+    fn linefeed(&mut self) {
+        self.0.push("linefeed".into());
+    }
+    fn backspace(&mut self) {
+        self.0.push("backspace".into());
+    }
+    fn tab(&mut self) {
+        self.0.push("tab".into());
+    }
+    fn bell(&mut self) {
+        self.0.push("bell".into());
+    }
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+    fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+}
+
+fn calls(bytes: &[u8]) -> Vec<String> {
+    let mut controls = Controls::default();
+    Parser::new().advance_slice(&mut controls, bytes);
+    controls.0
+}
+
+#[test]
+fn named_controls() {
+    assert_eq!(calls(b"\r"), ["carriage_return"]);
+    assert_eq!(calls(b"\n"), ["linefeed"]);
+    assert_eq!(calls(b"\x08"), ["backspace"]);
+    assert_eq!(calls(b"\t"), ["tab"]);
+    assert_eq!(calls(b"\x07"), ["bell"]);
+}
+
+#[test]
+fn other_controls_are_executed() {
+    assert_eq!(calls(b"\x0b\x0c\x00\x0e"), ["execute 0b", "execute 0c", "execute 00", "execute 0e"]);
+}
+
+#[test]
+fn controls_inside_sequences() {
+    // Controls are executed in the middle of CSI and escape sequences
+    assert_eq!(calls(b"a\x1b[1\r\nm\x1b\tc"), ["print 'a'", "carriage_return", "linefeed", "tab"]);
+}
+
+/// Only implements the required methods
+struct Executed(Vec<u8>);
+
+impl Perform for Executed {
+    fn print(&mut self, _c: char) {}
+    fn execute(&mut self, byte: u8) {
+        self.0.push(byte);
+    }
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    fn csi_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _c: char, _private: Option<u8>) {}
+    fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+}
+
+#[test]
+fn defaults_execute() {
+    let mut executed = Executed(Vec::new());
+    Parser::new().advance_slice(&mut executed, b"\r\n\x08\t\x07\x0b");
+    assert_eq!(executed.0, b"\r\n\x08\t\x07\x0b");
+}
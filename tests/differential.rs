@@ -0,0 +1,375 @@
+//! Differential tests against the `vtparse` crate
+//!
+//! `vtparse` is the parser of wezterm, and implements the DEC ANSI parser
+//! state machine described by Paul Williams at
+//! <https://vt100.net/emu/dec_ansi_parser> independently of this crate, like
+//! the C library of the same name. The same bytes are passed to both parsers,
+//! and the actions and ground state after every byte have to match.
+//!
+//! Both parsers are recorded in a common format which leaves out what only
+//! one of them reports:
+//!
+//! - the `ignore` flags, since `vtparse` keeps 2 intermediates and `vte` 8
+//! - DCS parameters, which `vtparse` pads with zeroes for every separator
+//! - SOS and PM strings, which `vtparse` drops
+//!
+//! The remaining differences are intentional and documented with the tests
+//! which skip them.
+extern crate vte;
+extern crate vtparse;
+
+use vte::{Params, Parser, Perform, State};
+use vtparse::{CsiParam, VTActor, VTParser};
+
+/// Render parameters as `1;2:3`
+fn params_string<'a, I>(params: I) -> String
+where
+    I: IntoIterator<Item = &'a [u16]>,
+{
+    params
+        .into_iter()
+        .map(|param| param.iter().map(|value| value.to_string()).collect::<Vec<_>>().join(":"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn csi_string(private: Option<u8>, params: &str, intermediates: &[u8], action: char) -> String {
+    let private = private.map(|byte| (byte as char).to_string()).unwrap_or_default();
+    format!("csi {}{} {:?} {:?}", private, params, intermediates, action)
+}
+
+fn osc_string(params: &[&[u8]]) -> String {
+    // `vtparse` dispatches an empty string without parameters
+    let params: &[&[u8]] = if params.is_empty() { &[b""] } else { params };
+    format!("osc {:?}", params)
+}
+
+/// Records the actions of `vte`
+#[derive(Default)]
+struct VteActions(Vec<String>);
+
+impl Perform for VteActions {
+    fn print(&mut self, c: char) {
+        self.0.push(format!("print {:?}", c));
+    }
+    fn execute(&mut self, byte: u8) {
+        self.0.push(format!("execute {:02x}", byte));
+    }
+    fn hook(&mut self, _params: &Params, intermediates: &[u8], _ignore: bool, final_byte: u8) {
+        self.0.push(format!("hook {:?} {:02x}", intermediates, final_byte));
+    }
+    fn put(&mut self, byte: u8) {
+        self.0.push(format!("put {:02x}", byte));
+    }
+    fn unhook(&mut self) {
+        self.0.push("unhook".into());
+    }
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        self.0.push(osc_string(params));
+    }
+    fn apc_dispatch(&mut self, bytes: &[u8]) {
+        self.0.push(format!("apc {:?}", bytes));
+    }
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, c: char, private: Option<u8>) {
+        self.0.push(csi_string(private, &params_string(params), intermediates, c));
+    }
+    fn esc_dispatch(&mut self, _params: &Params, intermediates: &[u8], _ignore: bool, byte: u8) {
+        self.0.push(format!("esc {:?} {:02x}", intermediates, byte));
+    }
+}
+
+/// Records the actions of `vtparse`
+#[derive(Default)]
+struct ReferenceActions {
+    actions: Vec<String>,
+
+    /// Record C1 controls with an escape sequence equivalent like `vte`
+    c1_escapes: bool,
+}
+
+impl VTActor for ReferenceActions {
+    fn print(&mut self, c: char) {
+        self.actions.push(format!("print {:?}", c));
+    }
+
+    fn execute_c0_or_c1(&mut self, control: u8) {
+        match control {
+            0x84 | 0x85 | 0x88 | 0x8d..=0x8f | 0x96 | 0x97 | 0x9a if self.c1_escapes => {
+                self.actions.push(format!("esc [] {:02x}", control - 0x40));
+            },
+            _ => self.actions.push(format!("execute {:02x}", control)),
+        }
+    }
+
+    fn dcs_hook(&mut self, mode: u8, _params: &[i64], intermediates: &[u8], _ignored: bool) {
+        self.actions.push(format!("hook {:?} {:02x}", intermediates, mode));
+    }
+
+    fn dcs_put(&mut self, byte: u8) {
+        self.actions.push(format!("put {:02x}", byte));
+    }
+
+    fn dcs_unhook(&mut self) {
+        self.actions.push("unhook".into());
+    }
+
+    fn esc_dispatch(&mut self, _params: &[i64], intermediates: &[u8], _ignored: bool, byte: u8) {
+        self.actions.push(format!("esc {:?} {:02x}", intermediates, byte));
+    }
+
+    fn csi_dispatch(&mut self, params: &[CsiParam], _truncated: bool, byte: u8) {
+        // Private markers and intermediates are mixed into the parameters,
+        // and omitted parameters are left out
+        let mut private = None;
+        let mut intermediates = Vec::new();
+        let mut values = Vec::new();
+        let mut param = Vec::new();
+        let mut value = None;
+        for (i, csi_param) in params.iter().enumerate() {
+            match *csi_param {
+                CsiParam::P(byte @ 0x3c..=0x3f) if i == 0 => private = Some(byte),
+                CsiParam::P(byte @ 0x20..=0x2f) => intermediates.push(byte),
+                CsiParam::Integer(integer) => value = Some(integer.min(u16::MAX as i64) as u16),
+                CsiParam::P(b':') => param.push(value.take().unwrap_or(0)),
+                CsiParam::P(b';') => {
+                    param.push(value.take().unwrap_or(0));
+                    values.push(std::mem::take(&mut param));
+                },
+                CsiParam::P(byte) => panic!("unexpected parameter byte {:02x}", byte),
+            }
+        }
+        if value.is_some() || !param.is_empty() {
+            param.push(value.unwrap_or(0));
+            values.push(param);
+        }
+
+        let params = params_string(values.iter().map(Vec::as_slice));
+        self.actions.push(csi_string(private, &params, &intermediates, byte as char));
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]]) {
+        self.actions.push(osc_string(params));
+    }
+
+    fn apc_dispatch(&mut self, data: Vec<u8>) {
+        self.actions.push(format!("apc {:?}", data));
+    }
+}
+
+/// Actions of both parsers and whether they were in the ground state after
+/// every byte
+#[derive(Debug, PartialEq)]
+struct Run {
+    actions: Vec<String>,
+    ground: Vec<bool>,
+}
+
+fn run_vte(bytes: &[u8], c1_controls: bool) -> Run {
+    let mut actions = VteActions::default();
+    let mut parser = Parser::new();
+    parser.set_c1_controls(c1_controls);
+
+    let mut ground = Vec::new();
+    for &byte in bytes {
+        parser.advance(&mut actions, byte);
+        ground.push(parser.current_state() == State::Ground);
+    }
+    Run { actions: actions.0, ground }
+}
+
+fn run_reference(bytes: &[u8], c1_escapes: bool) -> Run {
+    let mut actions = ReferenceActions { c1_escapes, ..ReferenceActions::default() };
+    let mut parser = VTParser::new();
+
+    let mut ground = Vec::new();
+    for &byte in bytes {
+        parser.parse_byte(byte, &mut actions);
+        ground.push(parser.is_ground());
+    }
+    Run { actions: actions.actions, ground }
+}
+
+/// Bytes leading from the ground state to every other state
+const STATES: &[(&str, &[u8])] = &[
+    ("Ground", b""),
+    ("Escape", b"\x1b"),
+    ("EscapeIntermediate", b"\x1b("),
+    ("CsiEntry", b"\x1b["),
+    ("CsiParam", b"\x1b[1;2"),
+    ("CsiParam with private marker", b"\x1b[?1"),
+    ("CsiIntermediate", b"\x1b[1 "),
+    ("CsiIgnore", b"\x1b[1?"),
+    ("DcsEntry", b"\x1bP"),
+    ("DcsParam", b"\x1bP1;2"),
+    ("DcsIntermediate", b"\x1bP1 "),
+    ("DcsIgnore", b"\x1bP1?"),
+    ("DcsPassthrough", b"\x1bP1q"),
+    ("OscString", b"\x1b]0;"),
+    ("SosPmApcString (SOS)", b"\x1bX"),
+    ("SosPmApcString (PM)", b"\x1b^"),
+    ("SosPmApcString (APC)", b"\x1b_"),
+];
+
+/// Pass `byte` to both parsers in every state but `skip`, followed by a
+/// string terminator and a printable character to see where they ended up
+fn assert_byte_from_every_state(byte: u8, c1_controls: bool, skip: &[&str]) {
+    for &(state, prefix) in STATES {
+        if skip.contains(&state) {
+            continue;
+        }
+
+        let mut bytes = prefix.to_vec();
+        bytes.push(byte);
+        bytes.extend_from_slice(b"\x1b\\x");
+
+        assert_eq!(
+            run_vte(&bytes, c1_controls),
+            run_reference(&bytes, c1_controls),
+            "byte {:02x} in {}",
+            byte,
+            state
+        );
+    }
+}
+
+#[test]
+fn seven_bit_bytes() {
+    for byte in 0x00..=0x7f {
+        // `vtparse` keeps C0 controls in APC strings, which are ignored in
+        // all strings apart from DCS by the state machine
+        if byte < 0x20 && byte != 0x18 && byte != 0x1a && byte != 0x1b {
+            let mut bytes = b"\x1b_a".to_vec();
+            bytes.push(byte);
+            bytes.extend_from_slice(b"b\x1b\\");
+            assert_eq!(run_vte(&bytes, false).actions, ["apc [97, 98]", "esc [] 5c"]);
+            assert_byte_from_every_state(byte, false, &["SosPmApcString (APC)"]);
+        } else {
+            assert_byte_from_every_state(byte, false, &[]);
+        }
+    }
+}
+
+#[test]
+fn c1_controls() {
+    for byte in 0x80..=0x9f {
+        // String and control sequence introducers in their own state start a
+        // new sequence in `vte`, whereas `vtparse` continues the current one
+        let skip: &[&str] = match byte {
+            0x90 => &["DcsEntry"],
+            0x98 | 0x9e => &["SosPmApcString (SOS)", "SosPmApcString (PM)", "SosPmApcString (APC)"],
+            0x9b => &["CsiEntry"],
+            0x9d => &["OscString"],
+            // `vtparse` only recognizes `ESC _` as APC, and ignores 8-bit APC
+            // strings like SOS and PM
+            0x9f => continue,
+            _ => &[],
+        };
+
+        assert_byte_from_every_state(byte, true, skip);
+    }
+
+    // Without C1 controls the introducers are ignored in the ground state,
+    // and the other controls are executed like C0 controls
+    for byte in 0x80..=0x9f {
+        let execute = format!("execute {:02x}", byte);
+        let expected = match byte {
+            0x90 | 0x9b | 0x9d..=0x9f => vec!["print 'a'", "print 'b'"],
+            _ => vec!["print 'a'", &execute, "print 'b'"],
+        };
+        assert_eq!(run_vte(&[b'a', byte, b'b'], false).actions, expected);
+    }
+}
+
+#[test]
+fn eight_bit_bytes() {
+    // Printed as UTF-8 in the ground state and OSC strings, see `utf8`, and
+    // kept in APC strings by `vte` but dropped by `vtparse`
+    let skip = ["Ground", "OscString", "SosPmApcString (APC)"];
+    for byte in 0xa0..=0xff {
+        assert_byte_from_every_state(byte, false, &skip);
+        assert_byte_from_every_state(byte, true, &skip);
+    }
+}
+
+#[test]
+fn introducers_restart_sequences() {
+    for &(introducer, esc) in &[(0x90u8, b'P'), (0x98, b'X'), (0x9b, b'['), (0x9d, b']'), (0x9e, b'^')] {
+        let mut c1 = vec![0x1b, esc, introducer];
+        c1.extend_from_slice(b"1m\x1b\\");
+        let mut seven_bit = vec![0x1b, esc, 0x1b, esc];
+        seven_bit.extend_from_slice(b"1m\x1b\\");
+
+        // Apart from the ESC dispatched by the first `ESC \`
+        assert_eq!(run_vte(&c1, true).actions, run_vte(&seven_bit, true).actions);
+    }
+}
+
+#[test]
+fn utf8() {
+    let text = "a\u{e9}\u{2500}\u{1f600}\u{10ffff}";
+    let mut bytes = text.as_bytes().to_vec();
+    bytes.extend_from_slice(b"\x1b]0;");
+    bytes.extend_from_slice(text.as_bytes());
+    bytes.extend_from_slice(b"\x07");
+    assert_eq!(run_vte(&bytes, false), run_reference(&bytes, false));
+
+    // Invalid UTF-8 is replaced by `vte`, but dropped by `vtparse`
+    assert_eq!(run_vte(b"\xff", false).actions, ["print '\u{fffd}'"]);
+    assert!(run_reference(b"\xff", false).actions.is_empty());
+
+    // `vtparse` also drops the byte which interrupts an incomplete character
+    assert_eq!(run_vte(b"\xe2\x1b[m", false).actions, ["print '\u{fffd}'", "csi  [] 'm'"]);
+    assert_eq!(run_reference(b"\xe2\x1b[m", false).actions, ["print '\u{fffd}'", "print '['", "print 'm'"]);
+}
+
+/// Escape sequences as emitted by common programs
+const CORPUS: &[&[u8]] = &[
+    // SGR with 256 and true colors, also with colons
+    b"\x1b[0;1;4;38;5;208;48;2;10;20;30mcolors\x1b[m",
+    b"\x1b[4:3m\x1b[58:2::255:0:0mcurly\x1b[59m\x1b[24m",
+    // Cursor movement, scrolling regions and erasing
+    b"\x1b[H\x1b[2J\x1b[3;20r\x1b[10;5H\x1b[K\x1b[1A\x1b[2B\x1b[3C\x1b[4D\x1b[r",
+    b"\x1b7\x1b[?1049h\x1b[22;0;0t\x1b[?1049l\x1b[23;0;0t\x1b8",
+    // Private modes and device reports
+    b"\x1b[?25l\x1b[?1h\x1b=\x1b[?2004h\x1b[?1000;1006h\x1b[c\x1b[>c\x1b[6n\x1b[?u",
+    b"\x1b[>4;1m\x1b[=1;1u\x1b[>1u\x1b[<u\x1b[?12$p\x1b[ q\x1b[2 q\x1b[!p",
+    // Character sets and single escapes
+    b"\x1b(B\x1b)0\x0e\x0f\x1b#8\x1bM\x1bD\x1bE\x1bH\x1bc\x1b%G\x1b>",
+    // Input: keys, mouse and pastes
+    b"\x1bOA\x1b[1;5C\x1b[3~\x1b[15;2~\x1b[97;5u\x1b[<0;10;20M\x1b[<0;10;20m\x1b[200~text\x1b[201~",
+    // OSC strings with BEL and ST
+    b"\x1b]0;title\x07\x1b]2;\xe2\x94\x80 name\x1b\\\x1b]8;id=1;https://example.com\x1b\\link\x1b]8;;\x1b\\",
+    b"\x1b]52;c;aGVsbG8=\x07\x1b]4;1;rgb:ff/00/00\x07\x1b]10;?\x07\x1b]133;A\x07\x1b]7;file://host/tmp\x1b\\",
+    // Device control strings: DECRQSS, sixel and tmux passthrough
+    b"\x1bP$qm\x1b\\\x1bP1$r0m\x1b\\\x1bPq#0;2;0;0;0#0~~@@\x1b\\",
+    b"\x1bPtmux;\x1b\x1b]0;nested\x07\x1b\\\x1bP+q544e\x1b\\",
+    // APC graphics
+    b"\x1b_Gf=24,s=1,v=1;AAAA\x1b\\",
+    // Cancelled and interrupted sequences
+    b"\x1b[1;2\x18x\x1b]0;t\x1ay\x1b[1\x1b[2m\x1bP1q\x1b[3m",
+    b"\x1b[1;2\r\n\tm\x1b[1;2:3;4m\x1b[;5H\x1b[1;m",
+    // Text with controls
+    b"$ ls -l\r\ntotal 0\r\n\x07\x08\x08  done\t\xe2\x9c\x93\r\n",
+];
+
+#[test]
+fn corpus() {
+    for &bytes in CORPUS {
+        assert_eq!(run_vte(bytes, false), run_reference(bytes, false), "{:?}", String::from_utf8_lossy(bytes));
+    }
+}
+
+#[test]
+fn vim_recording() {
+    // The recording cuts off some characters, which the parsers handle
+    // differently as shown in `utf8`
+    let text = String::from_utf8_lossy(include_bytes!("../benches/data/vim.log"));
+    let bytes = text.as_bytes();
+    let vte = run_vte(bytes, false);
+    let reference = run_reference(bytes, false);
+
+    if let Some(i) = (0..bytes.len()).find(|&i| vte.ground[i] != reference.ground[i]) {
+        panic!("ground state differs after byte {}", i);
+    }
+    assert_eq!(vte.actions, reference.actions);
+}
@@ -0,0 +1,61 @@
+//! Round trips of kitty keyboard protocol events through the parser
+#![cfg(feature = "kitty")]
+
+extern crate vte;
+
+use vte::kitty::{KittyEventType, KittyKeyEvent, KittyModifiers};
+use vte::{Params, Parser, Perform};
+
+/// Decodes every CSI sequence as a key event
+#[derive(Default)]
+struct Keys(Vec<Option<KittyKeyEvent>>);
+
+impl Perform for Keys {
+    fn print(&mut self, _c: char) {}
+    fn execute(&mut self, _byte: u8) {}
+    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _final_byte: u8) {}
+    fn put(&mut self, _byte: u8) {}
+    fn unhook(&mut self) {}
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+    fn esc_dispatch(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char, private: Option<u8>) {
+        assert!(intermediates.is_empty() && !ignore && private.is_none());
+        self.0.push(KittyKeyEvent::parse(params, action as u8));
+    }
+}
+
+#[test]
+fn round_trip() {
+    let events = vec![
+        KittyKeyEvent { key_code: u32::from('a'), ..KittyKeyEvent::default() },
+        KittyKeyEvent {
+            key_code: u32::from('a'),
+            shifted_key: Some(u32::from('A')),
+            modifiers: KittyModifiers::SHIFT,
+            text: Some("A".into()),
+            ..KittyKeyEvent::default()
+        },
+        KittyKeyEvent {
+            key_code: 0x441,
+            base_layout_key: Some(u32::from('c')),
+            modifiers: KittyModifiers::CTRL | KittyModifiers::CAPS_LOCK,
+            event_type: KittyEventType::Repeat,
+            ..KittyKeyEvent::default()
+        },
+        KittyKeyEvent { key_code: 13, event_type: KittyEventType::Release, ..KittyKeyEvent::default() },
+        KittyKeyEvent { key_code: 57352, modifiers: KittyModifiers::ALT, ..KittyKeyEvent::default() },
+        KittyKeyEvent { key_code: 57349, ..KittyKeyEvent::default() },
+        KittyKeyEvent { key_code: 57375, modifiers: KittyModifiers::NUM_LOCK, ..KittyKeyEvent::default() },
+        KittyKeyEvent { key_code: u32::from('e'), text: Some("\u{e9}t\u{e9}".into()), ..KittyKeyEvent::default() },
+    ];
+
+    let mut keys = Keys::default();
+    let mut parser = Parser::new();
+    for event in &events {
+        parser.advance_slice(&mut keys, &event.encode());
+    }
+
+    let decoded: Vec<_> = keys.0.into_iter().map(Option::unwrap).collect();
+    assert_eq!(decoded, events);
+}
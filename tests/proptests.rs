@@ -0,0 +1,189 @@
+//! Property based tests of invariants which hold for any input or event
+//!
+//! Run with `cargo test --features proptest`. The number of cases defaults
+//! to 256; set `PROPTEST_CASES=100000` for a thorough local run.
+#![cfg(feature = "proptest")]
+
+extern crate proptest;
+extern crate vte;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use vte::test_utils::{RecordedEvent, RecordingPerform};
+use vte::{feed_event, Params, Parser, State, VteEvent, MAX_SUBPARAMS};
+
+/// Bytes which are mostly escape sequences, strings and UTF-8
+fn input() -> impl Strategy<Value = Vec<u8>> {
+    let byte = prop_oneof![
+        any::<u8>(),
+        Just(0x1b),
+        Just(b'['),
+        Just(b']'),
+        Just(b'P'),
+        Just(b';'),
+        Just(0x07),
+        Just(0x9c),
+        0x30..0x3au8,
+        0x80..0xc0u8,
+    ];
+    vec(byte, 0..512)
+}
+
+/// `Params` with the `values` of every parameter
+///
+/// Only the parser creates `Params`, so they are taken from a CSI.
+fn params_of(values: &[Vec<u16>]) -> Params {
+    let params: Vec<String> = values.iter()
+        .map(|param| param.iter().map(u16::to_string).collect::<Vec<_>>().join(":"))
+        .collect();
+    let bytes = format!("\x1b[{}m", params.join(";"));
+    Parser::new().parse(bytes.as_bytes())
+        .find_map(|event| match event {
+            VteEvent::CsiDispatch { params, .. } => Some(params),
+            _ => None,
+        })
+        .expect("a CSI")
+}
+
+/// Up to 32 parameters, with up to `subparams` values each
+fn params(subparams: usize) -> impl Strategy<Value = Params> {
+    vec(vec(any::<u16>(), 1..=subparams), 0..=32).prop_map(|values| params_of(&values))
+}
+
+/// Up to 8 intermediates, as many as the parser collects
+fn intermediates() -> impl Strategy<Value = Vec<u8>> {
+    vec(0x20..0x30u8, 0..=8)
+}
+
+/// Bytes of a string, which can't contain C0 controls or `ST`
+fn string() -> impl Strategy<Value = Vec<u8>> {
+    vec(prop_oneof![0x20..0x9cu8, 0x9d..=0xffu8], 0..64)
+}
+
+/// Events which can be written as escape sequences, as groups which parse
+/// back into the same events
+///
+/// A device control string hook is followed by its data and the unhook,
+/// since the parser only reports the parts of complete strings. Ignored
+/// sequences, state changes and errors aren't written as they were parsed
+/// and aren't generated.
+fn events() -> impl Strategy<Value = Vec<VteEvent>> {
+    let print = prop_oneof![
+        proptest::char::range(' ', '~'),
+        proptest::char::range('\u{a0}', char::MAX),
+    ].prop_map(VteEvent::Print);
+    let execute = prop_oneof![0x00..0x1bu8, 0x1c..0x20u8].prop_map(VteEvent::Execute);
+    let csi = (params(MAX_SUBPARAMS), intermediates(), proptest::option::of(b'<'..=b'?'), 0x40..=0x7eu8)
+        .prop_map(|(params, intermediates, private, final_byte)| VteEvent::CsiDispatch {
+            params,
+            intermediates,
+            ignore: false,
+            final_byte: char::from(final_byte),
+            private,
+        });
+    let esc = (intermediates(), 0x30..=0x7eu8)
+        .prop_filter("starts a string", |&(ref intermediates, byte)| {
+            !intermediates.is_empty() || !b"PX[]^_".contains(&byte)
+        })
+        .prop_map(|(intermediates, byte)| VteEvent::EscDispatch { params: Params::new(), intermediates, ignore: false, byte });
+    let osc = (vec(string().prop_map(|param| param.into_iter().filter(|&byte| byte != b';').collect()), 1..=8), any::<bool>())
+        .prop_map(|(params, bell_terminated)| VteEvent::OscDispatch { params, bell_terminated });
+    let dcs = (params(1), intermediates(), 0x40..=0x7eu8, vec(0x20..0x7fu8, 0..32))
+        .prop_map(|(params, intermediates, final_byte, data)| {
+            let mut events = vec![VteEvent::DcsHook { params, intermediates, ignore: false, final_byte }];
+            events.extend(data.into_iter().map(VteEvent::DcsPut));
+            events.push(VteEvent::DcsUnhook);
+            events
+        });
+
+    prop_oneof![
+        print.prop_map(|event| vec![event]),
+        execute.prop_map(|event| vec![event]),
+        csi.prop_map(|event| vec![event]),
+        esc.prop_map(|event| vec![event]),
+        osc.prop_map(|event| vec![event]),
+        string().prop_map(|data| vec![VteEvent::SosDispatch(data)]),
+        string().prop_map(|data| vec![VteEvent::PmDispatch(data)]),
+        string().prop_map(|data| vec![VteEvent::ApcDispatch(data)]),
+        dcs,
+    ]
+}
+
+proptest! {
+    #[test]
+    fn events_round_trip(events in events()) {
+        let mut recorder = RecordingPerform::new();
+        prop_assert!(feed_event(&mut Parser::new(), &events, &mut recorder).is_ok());
+
+        let mut parsed: Vec<_> = recorder.events().iter()
+            .filter(|event| !matches!(**event, RecordedEvent::StateChange { .. }))
+            .cloned()
+            .collect();
+        // The `ST` after a string is reported as an escape sequence
+        if let [_, .., RecordedEvent::EscDispatch { byte: b'\\', .. }] = parsed[..] {
+            parsed.pop();
+        }
+        prop_assert_eq!(parsed, events);
+    }
+
+    #[test]
+    fn flush_returns_to_ground(bytes in input()) {
+        let mut parser = Parser::new();
+        parser.advance_slice(&mut RecordingPerform::new(), &bytes);
+        parser.flush(&mut RecordingPerform::new());
+        prop_assert_eq!(parser.current_state(), State::Ground);
+    }
+
+    #[test]
+    fn at_most_one_print_or_execute_per_byte(bytes in input()) {
+        let mut parser = Parser::new();
+        let mut recorder = RecordingPerform::new();
+
+        for (index, &byte) in bytes.iter().enumerate() {
+            parser.advance(&mut recorder, byte);
+
+            let calls = recorder.events().iter()
+                .filter(|event| matches!(**event, RecordedEvent::Print(_) | RecordedEvent::Execute(_)))
+                .count();
+            prop_assert!(calls <= index + 1, "{} calls for {} bytes", calls, index + 1);
+        }
+    }
+
+    #[test]
+    fn hooks_are_paired(bytes in input()) {
+        let mut parser = Parser::new();
+        let mut recorder = RecordingPerform::new();
+        parser.advance_slice(&mut recorder, &bytes);
+        parser.flush(&mut recorder);
+
+        let mut hooked = false;
+        for event in recorder.events() {
+            match *event {
+                RecordedEvent::DcsHook { .. } => {
+                    prop_assert!(!hooked, "hook while hooked");
+                    hooked = true;
+                },
+                RecordedEvent::DcsPut(_) => prop_assert!(hooked, "put without hook"),
+                RecordedEvent::DcsUnhook => {
+                    prop_assert!(hooked, "unhook without hook");
+                    hooked = false;
+                },
+                _ => (),
+            }
+        }
+        prop_assert!(!hooked, "hook without unhook");
+    }
+
+    #[test]
+    fn advance_slice_matches_advance(bytes in input()) {
+        let mut expected = RecordingPerform::new();
+        let mut parser = Parser::new();
+        for &byte in &bytes {
+            parser.advance(&mut expected, byte);
+        }
+
+        let mut recorder = RecordingPerform::new();
+        Parser::new().advance_slice(&mut recorder, &bytes);
+        prop_assert_eq!(recorder.events(), expected.events());
+    }
+}
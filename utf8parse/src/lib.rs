@@ -3,7 +3,14 @@
 //! This module implements a table-driven UTF-8 parser which should
 //! theoretically contain the minimal number of branches (1). The only branch is
 //! on the `Action` returned from unpacking a transition.
-use std::char;
+//!
+//! The parser only depends on `core` and can be used in `no_std` environments.
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(test)]
+extern crate core;
+
+use core::char;
 
 mod types;
 use self::types::{State, Action, unpack};
@@ -14,7 +21,7 @@ use self::table::TRANSITIONS;
 /// Handles codepoint and invalid sequence events from the parser.
 pub trait Receiver {
     /// Called whenever a codepoint is parsed successfully
-    fn codepoint(&mut self, char);
+    fn codepoint(&mut self, c: char);
 
     /// Called when an invalid_sequence is detected
     fn invalid_sequence(&mut self);
@@ -23,6 +30,7 @@ pub trait Receiver {
 /// A parser for Utf8 Characters
 ///
 /// Repeatedly call `advance` with bytes to emit Utf8 characters
+#[derive(Clone)]
 pub struct Parser {
     point: u32,
     state: State,